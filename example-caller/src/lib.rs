@@ -0,0 +1,99 @@
+//! Example program swapping through the dex over cpi, out of token accounts owned by its
+//! authority pda, and reading back what it got.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use chudex::{
+    client,
+    cpi::{self, ExchangeAccounts},
+    utils::assert_msg,
+};
+
+pub const AUTHORITY_SEED: &[u8] = b"example_authority";
+
+/// Instruction data, swapping amount_in of the authority's src tokens.
+///
+/// Accounts:
+/// dex program
+/// authority (pda of "example_authority")
+/// then the dex's Exchange accounts after the user
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Swap {
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+use solana_program::entrypoint;
+#[cfg(not(feature = "no-entrypoint"))]
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let swap =
+        Swap::try_from_slice(instruction_data).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let chudex_program = next_account_info(accounts_iter)?;
+    let authority = next_account_info(accounts_iter)?;
+    let exchange_accounts = ExchangeAccounts {
+        program: chudex_program.clone(),
+        user: authority.clone(),
+        user_token_src: next_account_info(accounts_iter)?.clone(),
+        user_token_dst: next_account_info(accounts_iter)?.clone(),
+        pool: next_account_info(accounts_iter)?.clone(),
+        pool_vault_src: next_account_info(accounts_iter)?.clone(),
+        pool_vault_dst: next_account_info(accounts_iter)?.clone(),
+        token_program_src: next_account_info(accounts_iter)?.clone(),
+        token_program_dst: next_account_info(accounts_iter)?.clone(),
+        mint_src: next_account_info(accounts_iter)?.clone(),
+        mint_dst: next_account_info(accounts_iter)?.clone(),
+        remaining_accounts: accounts_iter.cloned().collect(),
+    };
+
+    // pda verification
+
+    // authority pda
+    let (authority_key, authority_bump) =
+        Pubkey::find_program_address(&[AUTHORITY_SEED], program_id);
+    let authority_seeds = &[AUTHORITY_SEED, &[authority_bump]];
+    assert_msg(
+        *authority.key == authority_key,
+        ProgramError::InvalidSeeds,
+        "authority pda aint right",
+    )?;
+
+    // LOGIC
+
+    cpi::exchange(
+        exchange_accounts,
+        swap.amount_in,
+        swap.min_amount_out,
+        false,
+        &[authority_seeds],
+    )?;
+
+    // the dex reports what we got, no need to reread the token accounts
+    let result = client::exchange_result(chudex_program.key)?;
+    msg!(
+        "Swapped {} for {}, fee {}",
+        result.amount_in,
+        result.amount_out,
+        result.fee
+    );
+
+    Ok(())
+}
@@ -0,0 +1,404 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use chudex::{
+    client::ExchangeResult,
+    curve,
+    error::ChudexError,
+    instruction::ChudexInstruction,
+    processor::Processor,
+    state::{Factory, GlobalConfig, LpMetadata, MintIndex, Pool, RegistryPage},
+};
+use chudex_example_caller::{process_instruction, Swap, AUTHORITY_SEED};
+use solana_program_test::{processor, tokio, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    bpf_loader_upgradeable,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program, sysvar,
+    transaction::{Transaction, TransactionError},
+    transaction_context::TransactionReturnData,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account,
+};
+
+/// Processes `instructions`, returning the transaction's return data.
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<Option<TransactionReturnData>, TransactionError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    result.result?;
+    Ok(result.metadata.unwrap().return_data)
+}
+
+async fn create_mint(context: &mut ProgramTestContext, decimals: u8) -> Pubkey {
+    let mint = Keypair::new();
+    let payer = context.payer.pubkey();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    process(
+        context,
+        &[
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint(
+                &spl_token::id(),
+                &mint.pubkey(),
+                &payer,
+                None,
+                decimals,
+            )
+            .unwrap(),
+        ],
+        &[&mint],
+    )
+    .await
+    .unwrap();
+    mint.pubkey()
+}
+
+/// Creates `owner`'s token account for `mint` holding `amount`.
+async fn create_token_account(
+    context: &mut ProgramTestContext,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    amount: u64,
+) -> Pubkey {
+    let payer = context.payer.pubkey();
+    let account = get_associated_token_address(owner, mint);
+    process(
+        context,
+        &[
+            create_associated_token_account(&payer, owner, mint, &spl_token::id()),
+            spl_token::instruction::mint_to(&spl_token::id(), mint, &account, &payer, &[], amount)
+                .unwrap(),
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+    account
+}
+
+async fn token_balance(context: &mut ProgramTestContext, account: &Pubkey) -> u64 {
+    let account = context
+        .banks_client
+        .get_account(*account)
+        .await
+        .unwrap()
+        .unwrap();
+    spl_token::state::Account::unpack(&account.data)
+        .unwrap()
+        .amount
+}
+
+/// The dex and the example caller, and a pool of the dex with liquidity.
+struct Programs {
+    chudex_id: Pubkey,
+    caller_id: Pubkey,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    pool: Pubkey,
+    pool_vault_a: Pubkey,
+    pool_vault_b: Pubkey,
+}
+
+impl Programs {
+    /// The caller's Swap of a for b, out of the caller authority's token accounts.
+    fn swap_ix(
+        &self,
+        authority_token_a: Pubkey,
+        authority_token_b: Pubkey,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Instruction {
+        let (authority, _) = Pubkey::find_program_address(&[AUTHORITY_SEED], &self.caller_id);
+        Instruction {
+            program_id: self.caller_id,
+            accounts: vec![
+                AccountMeta::new_readonly(self.chudex_id, false),
+                AccountMeta::new_readonly(authority, false),
+                AccountMeta::new(authority_token_a, false),
+                AccountMeta::new(authority_token_b, false),
+                AccountMeta::new(self.pool, false),
+                AccountMeta::new(self.pool_vault_a, false),
+                AccountMeta::new(self.pool_vault_b, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(self.mint_a, false),
+                AccountMeta::new_readonly(self.mint_b, false),
+            ],
+            data: Swap {
+                amount_in,
+                min_amount_out,
+            }
+            .try_to_vec()
+            .unwrap(),
+        }
+    }
+
+    /// What the pool gives for `amount_in` of a at its current reserves, and the fee.
+    async fn quote(&self, context: &mut ProgramTestContext, amount_in: u64) -> (u64, u64) {
+        let pool_account = context
+            .banks_client
+            .get_account(self.pool)
+            .await
+            .unwrap()
+            .unwrap();
+        let pool_state = Pool::try_from_slice(&pool_account.data).unwrap();
+        curve::swap(
+            amount_in,
+            pool_state.reserve_a,
+            pool_state.reserve_b,
+            pool_state.fee,
+            pool_state.fee_decimals,
+        )
+        .unwrap()
+    }
+}
+
+/// Starts the dex and the example caller, with a pool holding a billion of each mint
+/// deposited by the payer.
+async fn start() -> (ProgramTestContext, Programs) {
+    let chudex_id = Pubkey::new_unique();
+    let caller_id = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "chudex",
+        chudex_id,
+        processor!(Processor::process_instruction),
+    );
+    program_test.add_program(
+        "chudex_example_caller",
+        caller_id,
+        processor!(process_instruction),
+    );
+    // the config admin has to be chudex's upgrade authority
+    let admin = Keypair::new();
+    program_test.add_account(
+        admin.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            ..Account::default()
+        },
+    );
+    let program_data =
+        Pubkey::find_program_address(&[chudex_id.as_ref()], &bpf_loader_upgradeable::id()).0;
+    let mut program_data_bytes = vec![3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+    program_data_bytes.extend_from_slice(admin.pubkey().as_ref());
+    program_test.add_account(
+        program_data,
+        Account {
+            lamports: 1_000_000_000,
+            data: program_data_bytes,
+            owner: bpf_loader_upgradeable::id(),
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+
+    // more decimals makes a the pool's first mint
+    let mint_a = create_mint(&mut context, 9).await;
+    let mint_b = create_mint(&mut context, 6).await;
+    let user_token_a = create_token_account(&mut context, &payer, &mint_a, 1_000_000_000).await;
+    let user_token_b = create_token_account(&mut context, &payer, &mint_b, 1_000_000_000).await;
+
+    let find = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &chudex_id).0;
+    let config = find(&[GlobalConfig::SEED]);
+    let pool = find(&[b"chudex_pool", mint_a.as_ref(), mint_b.as_ref()]);
+    let pool_mint = find(&[b"chudex_pool_mint", pool.as_ref()]);
+    let pool_vault_a = get_associated_token_address(&pool, &mint_a);
+    let pool_vault_b = get_associated_token_address(&pool, &mint_b);
+    let page = 0u64.to_le_bytes();
+
+    // config and pool
+    process(
+        &mut context,
+        &[
+            Instruction {
+                program_id: chudex_id,
+                accounts: vec![
+                    AccountMeta::new(admin.pubkey(), true),
+                    AccountMeta::new(config, false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                    AccountMeta::new_readonly(program_data, false),
+                ],
+                data: ChudexInstruction::InitializeConfig {
+                    fee_tiers: vec![30],
+                }
+                .try_to_vec()
+                .unwrap(),
+            },
+            Instruction {
+                program_id: chudex_id,
+                accounts: vec![
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new(pool, false),
+                    AccountMeta::new(pool_vault_a, false),
+                    AccountMeta::new(pool_vault_b, false),
+                    AccountMeta::new_readonly(mint_a, false),
+                    AccountMeta::new_readonly(mint_b, false),
+                    AccountMeta::new(pool_mint, false),
+                    AccountMeta::new_readonly(spl_token::id(), false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                    AccountMeta::new_readonly(sysvar::rent::id(), false),
+                    AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                    AccountMeta::new_readonly(spl_token::id(), false),
+                    AccountMeta::new_readonly(spl_token::id(), false),
+                    AccountMeta::new_readonly(config, false),
+                    AccountMeta::new(find(&[Factory::SEED]), false),
+                    AccountMeta::new(find(&[RegistryPage::SEED, &page]), false),
+                    AccountMeta::new(find(&[MintIndex::SEED, mint_a.as_ref()]), false),
+                    AccountMeta::new(find(&[RegistryPage::SEED, mint_a.as_ref(), &page]), false),
+                    AccountMeta::new(find(&[MintIndex::SEED, mint_b.as_ref()]), false),
+                    AccountMeta::new(find(&[RegistryPage::SEED, mint_b.as_ref(), &page]), false),
+                    AccountMeta::new(find(&[LpMetadata::SEED, pool_mint.as_ref()]), false),
+                ],
+                data: ChudexInstruction::InitializePool {
+                    fee_tier: 30,
+                    dynamic_fee: None,
+                    allowlist: None,
+                    hook_program: None,
+                }
+                .try_to_vec()
+                .unwrap(),
+            },
+        ],
+        &[&admin],
+    )
+    .await
+    .unwrap();
+
+    // liquidity
+    process(
+        &mut context,
+        &[Instruction {
+            program_id: chudex_id,
+            accounts: vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new(user_token_a, false),
+                AccountMeta::new(user_token_b, false),
+                AccountMeta::new(get_associated_token_address(&payer, &pool_mint), false),
+                AccountMeta::new(pool, false),
+                AccountMeta::new(pool_vault_a, false),
+                AccountMeta::new(pool_vault_b, false),
+                AccountMeta::new(pool_mint, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+                AccountMeta::new_readonly(mint_a, false),
+                AccountMeta::new_readonly(mint_b, false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+            data: ChudexInstruction::Deposit {
+                token_a_amount: 1_000_000_000,
+                max_token_b_amount: 1_000_000_000,
+                native_sol: false,
+            }
+            .try_to_vec()
+            .unwrap(),
+        }],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    (
+        context,
+        Programs {
+            chudex_id,
+            caller_id,
+            mint_a,
+            mint_b,
+            pool,
+            pool_vault_a,
+            pool_vault_b,
+        },
+    )
+}
+
+#[tokio::test]
+async fn swap_through_cpi() {
+    let (mut context, programs) = start().await;
+    let (expected_out, expected_fee) = programs.quote(&mut context, 10_000_000).await;
+    assert!(expected_out > 0);
+
+    // the caller swaps a for b out of its pda's token accounts
+    let (authority, _) = Pubkey::find_program_address(&[AUTHORITY_SEED], &programs.caller_id);
+    let authority_token_a =
+        create_token_account(&mut context, &authority, &programs.mint_a, 10_000_000).await;
+    let authority_token_b =
+        create_token_account(&mut context, &authority, &programs.mint_b, 0).await;
+    let swap = programs.swap_ix(authority_token_a, authority_token_b, 10_000_000, 1);
+    let return_data = process(&mut context, &[swap], &[]).await.unwrap();
+
+    assert_eq!(token_balance(&mut context, &authority_token_a).await, 0);
+    assert_eq!(
+        token_balance(&mut context, &authority_token_b).await,
+        expected_out
+    );
+
+    // the dex's result is the last return data, the caller doesn't set its own
+    let return_data = return_data.unwrap();
+    assert_eq!(return_data.program_id, programs.chudex_id);
+    assert_eq!(
+        ExchangeResult::try_from_slice(&return_data.data).unwrap(),
+        ExchangeResult {
+            amount_in: 10_000_000,
+            amount_out: expected_out,
+            fee: expected_fee,
+        }
+    );
+}
+
+#[tokio::test]
+async fn dex_rejection_fails_the_caller() {
+    let (mut context, programs) = start().await;
+    let (expected_out, _) = programs.quote(&mut context, 10_000_000).await;
+
+    let (authority, _) = Pubkey::find_program_address(&[AUTHORITY_SEED], &programs.caller_id);
+    let authority_token_a =
+        create_token_account(&mut context, &authority, &programs.mint_a, 10_000_000).await;
+    let authority_token_b =
+        create_token_account(&mut context, &authority, &programs.mint_b, 0).await;
+    let swap = programs.swap_ix(
+        authority_token_a,
+        authority_token_b,
+        10_000_000,
+        expected_out + 1,
+    );
+    let result = process(&mut context, &[swap], &[]).await;
+    assert_eq!(
+        result.unwrap_err(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(ChudexError::ExchangeAmountBelowMinimum as u32)
+        )
+    );
+    assert_eq!(
+        token_balance(&mut context, &authority_token_a).await,
+        10_000_000
+    );
+}
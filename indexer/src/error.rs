@@ -0,0 +1,23 @@
+use solana_client::client_error::ClientError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum IndexerError {
+    #[error("Sqlite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("Io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Json error: {0}")]
+    Json(#[from] serde_json::Error),
+    /// Boxed, a client error is many times the size of the others.
+    #[error("Rpc error: {0}")]
+    Rpc(Box<ClientError>),
+    #[error("Invalid transaction dump: {0}")]
+    InvalidDump(String),
+}
+
+impl From<ClientError> for IndexerError {
+    fn from(e: ClientError) -> Self {
+        IndexerError::Rpc(Box::new(e))
+    }
+}
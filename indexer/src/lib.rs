@@ -0,0 +1,12 @@
+pub mod error;
+pub mod source;
+pub mod store;
+
+/// Log messages of one confirmed transaction.
+#[derive(Debug, Clone)]
+pub struct TransactionLogs {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub logs: Vec<String>,
+}
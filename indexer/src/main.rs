@@ -0,0 +1,63 @@
+use std::{env, path::PathBuf, process, str::FromStr};
+
+use chudex_indexer::{error::IndexerError, source, store::Store};
+use solana_program::pubkey::Pubkey;
+
+const USAGE: &str =
+    "usage: chudex-indexer --program-id <PUBKEY> --db <PATH> (--json <PATH> | --rpc <URL>)";
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+fn run() -> Result<(), IndexerError> {
+    let mut program_id = None;
+    let mut db = None;
+    let mut json = None;
+    let mut rpc = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args.next();
+        match (arg.as_str(), value) {
+            ("--program-id", Some(v)) => program_id = Some(v),
+            ("--db", Some(v)) => db = Some(PathBuf::from(v)),
+            ("--json", Some(v)) => json = Some(PathBuf::from(v)),
+            ("--rpc", Some(v)) => rpc = Some(v),
+            _ => usage(),
+        }
+    }
+
+    let program_id = program_id
+        .map(|id| Pubkey::from_str(&id).unwrap_or_else(|_| usage()))
+        .unwrap_or_else(|| usage());
+    let db = db.unwrap_or_else(|| usage());
+    let mut store = Store::open(&db, program_id)?;
+
+    let txs = match (json, rpc) {
+        (Some(json), None) => source::from_json_dump(&json)?,
+        (None, Some(rpc)) => {
+            let last = store.last_signature()?;
+            source::from_rpc(&rpc, &program_id, last.as_deref())?
+        }
+        _ => usage(),
+    };
+
+    let mut indexed = 0;
+    for tx in &txs {
+        if store.index(tx)? {
+            indexed += 1;
+        }
+    }
+    println!("indexed {} new transactions ({} seen)", indexed, txs.len());
+
+    Ok(())
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
@@ -0,0 +1,109 @@
+use std::{fs, path::Path, str::FromStr};
+
+use serde_json::Value;
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_config::RpcTransactionConfig,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+
+use crate::{error::IndexerError, TransactionLogs};
+
+/// Reads a JSON array of `getTransaction` RPC results, oldest first.
+pub fn from_json_dump(path: &Path) -> Result<Vec<TransactionLogs>, IndexerError> {
+    let dump: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let txs = dump
+        .as_array()
+        .ok_or_else(|| IndexerError::InvalidDump("expected an array".to_string()))?;
+
+    txs.iter().map(parse_transaction).collect()
+}
+
+fn parse_transaction(tx: &Value) -> Result<TransactionLogs, IndexerError> {
+    let signature = tx["transaction"]["signatures"][0]
+        .as_str()
+        .ok_or_else(|| IndexerError::InvalidDump("missing signature".to_string()))?
+        .to_string();
+    let slot = tx["slot"]
+        .as_u64()
+        .ok_or_else(|| IndexerError::InvalidDump(format!("missing slot for {}", signature)))?;
+    let block_time = tx["blockTime"].as_i64();
+    let logs = tx["meta"]["logMessages"]
+        .as_array()
+        .ok_or_else(|| IndexerError::InvalidDump(format!("missing logs for {}", signature)))?
+        .iter()
+        .filter_map(|log| log.as_str().map(str::to_string))
+        .collect();
+
+    Ok(TransactionLogs {
+        signature,
+        slot,
+        block_time,
+        logs,
+    })
+}
+
+/// Fetches every successful transaction mentioning `program_id` from an RPC node such as
+/// `solana-test-validator`, oldest first. Stops at `until` if given.
+pub fn from_rpc(
+    url: &str,
+    program_id: &Pubkey,
+    until: Option<&str>,
+) -> Result<Vec<TransactionLogs>, IndexerError> {
+    let client = RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed());
+    let until = until
+        .map(Signature::from_str)
+        .transpose()
+        .map_err(|e| IndexerError::InvalidDump(e.to_string()))?;
+
+    // signatures come back newest first, page backwards until exhausted
+    let mut signatures = vec![];
+    let mut before = None;
+    loop {
+        let page = client.get_signatures_for_address_with_config(
+            program_id,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: None,
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )?;
+        if page.is_empty() {
+            break;
+        }
+        before = Some(
+            Signature::from_str(&page[page.len() - 1].signature)
+                .map_err(|e| IndexerError::InvalidDump(e.to_string()))?,
+        );
+        signatures.extend(page.into_iter().filter(|s| s.err.is_none()));
+    }
+
+    let mut txs = vec![];
+    for status in signatures.iter().rev() {
+        let signature = Signature::from_str(&status.signature)
+            .map_err(|e| IndexerError::InvalidDump(e.to_string()))?;
+        let tx = client.get_transaction_with_config(
+            &signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Json),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            },
+        )?;
+        let logs = tx
+            .transaction
+            .meta
+            .and_then(|meta| Option::from(meta.log_messages))
+            .unwrap_or_default();
+        txs.push(TransactionLogs {
+            signature: status.signature.clone(),
+            slot: tx.slot,
+            block_time: tx.block_time,
+            logs,
+        });
+    }
+
+    Ok(txs)
+}
@@ -0,0 +1,377 @@
+use std::path::Path;
+
+use chudex::events::{
+    parse_logs, ChudexEvent, Deposited, PoolClosed, PoolInitialized, Swapped, Withdrawn,
+};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use solana_program::pubkey::Pubkey;
+
+use crate::{error::IndexerError, TransactionLogs};
+
+/// Candle widths in seconds used when none are given.
+pub const DEFAULT_CANDLE_INTERVALS: &[i64] = &[60, 3600, 86400];
+
+// sqlite integers are signed, token amounts are stored as their i64 bit pattern
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS signatures (
+    signature TEXT PRIMARY KEY,
+    slot INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS pools (
+    pool TEXT PRIMARY KEY,
+    mint_a TEXT NOT NULL,
+    mint_b TEXT NOT NULL,
+    pool_mint TEXT NOT NULL,
+    fee INTEGER NOT NULL,
+    fee_decimals INTEGER NOT NULL,
+    created_slot INTEGER NOT NULL,
+    reserve_a INTEGER NOT NULL DEFAULT 0,
+    reserve_b INTEGER NOT NULL DEFAULT 0,
+    pool_token_supply INTEGER NOT NULL DEFAULT 0
+);
+CREATE TABLE IF NOT EXISTS trades (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    pool TEXT NOT NULL,
+    user TEXT NOT NULL,
+    mint_in TEXT NOT NULL,
+    mint_out TEXT NOT NULL,
+    amount_in INTEGER NOT NULL,
+    amount_out INTEGER NOT NULL,
+    fee INTEGER NOT NULL,
+    reserve_a INTEGER NOT NULL,
+    reserve_b INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS trades_pool ON trades (pool, slot);
+CREATE TABLE IF NOT EXISTS lp_positions (
+    pool TEXT NOT NULL,
+    user TEXT NOT NULL,
+    pool_tokens INTEGER NOT NULL DEFAULT 0,
+    deposited_a INTEGER NOT NULL DEFAULT 0,
+    deposited_b INTEGER NOT NULL DEFAULT 0,
+    withdrawn_a INTEGER NOT NULL DEFAULT 0,
+    withdrawn_b INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (pool, user)
+);
+CREATE TABLE IF NOT EXISTS candles (
+    pool TEXT NOT NULL,
+    interval INTEGER NOT NULL,
+    start_time INTEGER NOT NULL,
+    open REAL NOT NULL,
+    high REAL NOT NULL,
+    low REAL NOT NULL,
+    close REAL NOT NULL,
+    volume_a INTEGER NOT NULL,
+    volume_b INTEGER NOT NULL,
+    PRIMARY KEY (pool, interval, start_time)
+);
+";
+
+/// SQLite backed pool history built from program events.
+pub struct Store {
+    conn: Connection,
+    program_id: Pubkey,
+    candle_intervals: Vec<i64>,
+}
+
+impl Store {
+    pub fn open(path: &Path, program_id: Pubkey) -> Result<Self, IndexerError> {
+        Self::new(Connection::open(path)?, program_id)
+    }
+
+    pub fn open_in_memory(program_id: Pubkey) -> Result<Self, IndexerError> {
+        Self::new(Connection::open_in_memory()?, program_id)
+    }
+
+    fn new(conn: Connection, program_id: Pubkey) -> Result<Self, IndexerError> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn,
+            program_id,
+            candle_intervals: DEFAULT_CANDLE_INTERVALS.to_vec(),
+        })
+    }
+
+    pub fn with_candle_intervals(mut self, candle_intervals: Vec<i64>) -> Self {
+        self.candle_intervals = candle_intervals;
+        self
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Most recently indexed signature, used to resume from an rpc node.
+    pub fn last_signature(&self) -> Result<Option<String>, IndexerError> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT signature FROM signatures ORDER BY slot DESC, rowid DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Applies every event in `tx`. Already indexed transactions are skipped.
+    /// Returns whether the transaction was new.
+    pub fn index(&mut self, tx: &TransactionLogs) -> Result<bool, IndexerError> {
+        let events = parse_logs(&self.program_id, &tx.logs);
+        let db = self.conn.transaction()?;
+
+        let inserted = db.execute(
+            "INSERT OR IGNORE INTO signatures (signature, slot) VALUES (?1, ?2)",
+            params![tx.signature, tx.slot as i64],
+        )?;
+        if inserted == 0 {
+            return Ok(false);
+        }
+
+        for event in events {
+            match event {
+                ChudexEvent::PoolInitialized(e) => index_pool_initialized(&db, tx, &e)?,
+                ChudexEvent::Deposited(e) => index_deposited(&db, &e)?,
+                ChudexEvent::Withdrawn(e) => index_withdrawn(&db, &e)?,
+                ChudexEvent::Swapped(e) => index_swapped(&db, tx, &e, &self.candle_intervals)?,
+                ChudexEvent::LongTermOrdersSettled(e) => {
+                    update_reserves(&db, &e.pool, e.reserve_a, e.reserve_b, None)?
+                }
+                ChudexEvent::Synced(e) => {
+                    update_reserves(&db, &e.pool, e.reserve_a, e.reserve_b, None)?
+                }
+                ChudexEvent::PoolClosed(e) => index_pool_closed(&db, &e)?,
+            }
+        }
+
+        db.commit()?;
+        Ok(true)
+    }
+}
+
+fn index_pool_initialized(
+    db: &Transaction,
+    tx: &TransactionLogs,
+    e: &PoolInitialized,
+) -> Result<(), IndexerError> {
+    db.execute(
+        "INSERT OR IGNORE INTO pools (pool, mint_a, mint_b, pool_mint, fee, fee_decimals, created_slot)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            e.pool.to_string(),
+            e.mint_a.to_string(),
+            e.mint_b.to_string(),
+            e.pool_mint.to_string(),
+            e.fee as i64,
+            e.fee_decimals as i64,
+            tx.slot as i64,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Drops the pool and its lp positions, trades and candles stay as history.
+fn index_pool_closed(db: &Transaction, e: &PoolClosed) -> Result<(), IndexerError> {
+    db.execute(
+        "DELETE FROM lp_positions WHERE pool = ?1",
+        params![e.pool.to_string()],
+    )?;
+    db.execute(
+        "DELETE FROM pools WHERE pool = ?1",
+        params![e.pool.to_string()],
+    )?;
+    Ok(())
+}
+
+fn update_reserves(
+    db: &Transaction,
+    pool: &Pubkey,
+    reserve_a: u64,
+    reserve_b: u64,
+    pool_token_supply: Option<u64>,
+) -> Result<(), IndexerError> {
+    db.execute(
+        "UPDATE pools SET reserve_a = ?2, reserve_b = ?3,
+         pool_token_supply = COALESCE(?4, pool_token_supply) WHERE pool = ?1",
+        params![
+            pool.to_string(),
+            reserve_a as i64,
+            reserve_b as i64,
+            pool_token_supply.map(|s| s as i64),
+        ],
+    )?;
+    Ok(())
+}
+
+fn index_deposited(db: &Transaction, e: &Deposited) -> Result<(), IndexerError> {
+    update_reserves(
+        db,
+        &e.pool,
+        e.reserve_a,
+        e.reserve_b,
+        Some(e.pool_token_supply),
+    )?;
+    db.execute(
+        "INSERT INTO lp_positions (pool, user, pool_tokens, deposited_a, deposited_b)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT (pool, user) DO UPDATE SET
+            pool_tokens = pool_tokens + excluded.pool_tokens,
+            deposited_a = deposited_a + excluded.deposited_a,
+            deposited_b = deposited_b + excluded.deposited_b",
+        params![
+            e.pool.to_string(),
+            e.user.to_string(),
+            e.pool_token_amount as i64,
+            e.token_a_amount as i64,
+            e.token_b_amount as i64,
+        ],
+    )?;
+    Ok(())
+}
+
+fn index_withdrawn(db: &Transaction, e: &Withdrawn) -> Result<(), IndexerError> {
+    update_reserves(
+        db,
+        &e.pool,
+        e.reserve_a,
+        e.reserve_b,
+        Some(e.pool_token_supply),
+    )?;
+    db.execute(
+        "INSERT INTO lp_positions (pool, user, pool_tokens, withdrawn_a, withdrawn_b)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT (pool, user) DO UPDATE SET
+            pool_tokens = pool_tokens + excluded.pool_tokens,
+            withdrawn_a = withdrawn_a + excluded.withdrawn_a,
+            withdrawn_b = withdrawn_b + excluded.withdrawn_b",
+        params![
+            e.pool.to_string(),
+            e.user.to_string(),
+            -(e.pool_token_amount as i64),
+            e.token_a_amount as i64,
+            e.token_b_amount as i64,
+        ],
+    )?;
+    Ok(())
+}
+
+fn index_swapped(
+    db: &Transaction,
+    tx: &TransactionLogs,
+    e: &Swapped,
+    candle_intervals: &[i64],
+) -> Result<(), IndexerError> {
+    update_reserves(db, &e.pool, e.reserve_a, e.reserve_b, None)?;
+    db.execute(
+        "INSERT INTO trades (signature, slot, block_time, pool, user, mint_in, mint_out,
+            amount_in, amount_out, fee, reserve_a, reserve_b)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            tx.signature,
+            tx.slot as i64,
+            tx.block_time,
+            e.pool.to_string(),
+            e.user.to_string(),
+            e.mint_in.to_string(),
+            e.mint_out.to_string(),
+            e.amount_in as i64,
+            e.amount_out as i64,
+            e.fee as i64,
+            e.reserve_a as i64,
+            e.reserve_b as i64,
+        ],
+    )?;
+
+    // candles are keyed by wall clock time, skip trades the node has no time for
+    let block_time = match tx.block_time {
+        Some(block_time) => block_time,
+        None => return Ok(()),
+    };
+    for interval in candle_intervals {
+        rebuild_candle(db, &e.pool, *interval, block_time)?;
+    }
+
+    Ok(())
+}
+
+/// Rebuilds the pool's candle of `interval` around `block_time` from its trades in chain
+/// order, so a transaction indexed after later ones still lands in the right place. Trades
+/// of the same slot keep the order they were indexed in, the sources give them oldest first.
+fn rebuild_candle(
+    db: &Transaction,
+    pool: &Pubkey,
+    interval: i64,
+    block_time: i64,
+) -> Result<(), IndexerError> {
+    let start_time = block_time - block_time.rem_euclid(interval);
+    let mint_a: Option<String> = db
+        .query_row(
+            "SELECT mint_a FROM pools WHERE pool = ?1",
+            params![pool.to_string()],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    let mut trades = db.prepare(
+        "SELECT mint_in, amount_in, amount_out, reserve_a, reserve_b FROM trades
+         WHERE pool = ?1 AND block_time >= ?2 AND block_time < ?3 AND reserve_a != 0
+         ORDER BY slot, id",
+    )?;
+    let trades = trades.query_map(
+        params![pool.to_string(), start_time, start_time + interval],
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)? as u64,
+                row.get::<_, i64>(2)? as u64,
+                row.get::<_, i64>(3)? as u64,
+                row.get::<_, i64>(4)? as u64,
+            ))
+        },
+    )?;
+
+    // (open, high, low, close, volume a, volume b)
+    let mut candle: Option<(f64, f64, f64, f64, u64, u64)> = None;
+    for trade in trades {
+        let (mint_in, amount_in, amount_out, reserve_a, reserve_b) = trade?;
+        // price of a in b, raw token units
+        let price = reserve_b as f64 / reserve_a as f64;
+        let (volume_a, volume_b) = if mint_a.as_deref() == Some(mint_in.as_str()) {
+            (amount_in, amount_out)
+        } else {
+            (amount_out, amount_in)
+        };
+        candle = Some(match candle {
+            None => (price, price, price, price, volume_a, volume_b),
+            Some((open, high, low, _, total_a, total_b)) => (
+                open,
+                high.max(price),
+                low.min(price),
+                price,
+                total_a.wrapping_add(volume_a),
+                total_b.wrapping_add(volume_b),
+            ),
+        });
+    }
+
+    if let Some((open, high, low, close, volume_a, volume_b)) = candle {
+        db.execute(
+            "INSERT OR REPLACE INTO candles
+                (pool, interval, start_time, open, high, low, close, volume_a, volume_b)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                pool.to_string(),
+                interval,
+                start_time,
+                open,
+                high,
+                low,
+                close,
+                volume_a as i64,
+                volume_b as i64,
+            ],
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,286 @@
+use borsh::BorshSerialize;
+use chudex::events::{
+    ChudexEvent, Deposited, LongTermOrdersSettled, PoolClosed, PoolInitialized, Swapped,
+    PROGRAM_DATA_PREFIX,
+};
+use chudex_indexer::{store::Store, TransactionLogs};
+use solana_program::pubkey::Pubkey;
+
+/// A transaction of `program_id` logging `events`.
+fn transaction(
+    program_id: &Pubkey,
+    signature: &str,
+    slot: u64,
+    block_time: i64,
+    events: &[ChudexEvent],
+) -> TransactionLogs {
+    let mut logs = vec![format!("Program {} invoke [1]", program_id)];
+    logs.extend(events.iter().map(|event| {
+        format!(
+            "{}{}",
+            PROGRAM_DATA_PREFIX,
+            base64::encode(event.try_to_vec().unwrap())
+        )
+    }));
+    logs.push(format!("Program {} success", program_id));
+    TransactionLogs {
+        signature: signature.to_string(),
+        slot,
+        block_time: Some(block_time),
+        logs,
+    }
+}
+
+struct Pool {
+    pool: Pubkey,
+    user: Pubkey,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+}
+
+impl Pool {
+    fn new() -> Self {
+        Self {
+            pool: Pubkey::new_unique(),
+            user: Pubkey::new_unique(),
+            mint_a: Pubkey::new_unique(),
+            mint_b: Pubkey::new_unique(),
+        }
+    }
+
+    fn initialized(&self) -> ChudexEvent {
+        ChudexEvent::PoolInitialized(PoolInitialized {
+            pool: self.pool,
+            user: self.user,
+            mint_a: self.mint_a,
+            mint_b: self.mint_b,
+            pool_mint: Pubkey::new_unique(),
+            fee: 30,
+            fee_decimals: 4,
+        })
+    }
+
+    fn deposited(&self) -> ChudexEvent {
+        ChudexEvent::Deposited(Deposited {
+            pool: self.pool,
+            user: self.user,
+            token_a_amount: 1_000,
+            token_b_amount: 2_000,
+            pool_token_amount: 1_414,
+            reserve_a: 1_000,
+            reserve_b: 2_000,
+            pool_token_supply: 1_414,
+        })
+    }
+
+    /// A swap of a for b leaving the given reserves.
+    fn swapped(&self, amount_in: u64, reserve_a: u64, reserve_b: u64) -> ChudexEvent {
+        ChudexEvent::Swapped(Swapped {
+            pool: self.pool,
+            user: self.user,
+            mint_in: self.mint_a,
+            mint_out: self.mint_b,
+            amount_in,
+            amount_out: amount_in,
+            fee: 0,
+            reserve_a,
+            reserve_b,
+        })
+    }
+}
+
+/// (open, high, low, close, volume a, volume b) of the pool's candle.
+fn candle(store: &Store, pool: &Pubkey, interval: i64) -> (f64, f64, f64, f64, i64, i64) {
+    store
+        .connection()
+        .query_row(
+            "SELECT open, high, low, close, volume_a, volume_b FROM candles
+             WHERE pool = ?1 AND interval = ?2",
+            rusqlite::params![pool.to_string(), interval],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                ))
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn indexes_pool_history() {
+    let program_id = Pubkey::new_unique();
+    let pool = Pool::new();
+    let mut store = Store::open_in_memory(program_id).unwrap();
+
+    let created = transaction(
+        &program_id,
+        "created",
+        1,
+        100,
+        &[pool.initialized(), pool.deposited()],
+    );
+    assert!(store.index(&created).unwrap());
+    assert!(store
+        .index(&transaction(
+            &program_id,
+            "swap",
+            2,
+            110,
+            &[pool.swapped(100, 1_100, 1_900)]
+        ))
+        .unwrap());
+    // the same transaction again changes nothing
+    assert!(!store.index(&created).unwrap());
+
+    let (reserve_a, reserve_b, supply): (i64, i64, i64) = store
+        .connection()
+        .query_row(
+            "SELECT reserve_a, reserve_b, pool_token_supply FROM pools WHERE pool = ?1",
+            [pool.pool.to_string()],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap();
+    assert_eq!((reserve_a, reserve_b, supply), (1_100, 1_900, 1_414));
+    let pool_tokens: i64 = store
+        .connection()
+        .query_row(
+            "SELECT pool_tokens FROM lp_positions WHERE pool = ?1 AND user = ?2",
+            [pool.pool.to_string(), pool.user.to_string()],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(pool_tokens, 1_414);
+    assert_eq!(store.last_signature().unwrap().as_deref(), Some("swap"));
+}
+
+#[test]
+fn settled_long_term_orders_move_the_reserves() {
+    let program_id = Pubkey::new_unique();
+    let pool = Pool::new();
+    let mut store = Store::open_in_memory(program_id).unwrap();
+    let settled = ChudexEvent::LongTermOrdersSettled(LongTermOrdersSettled {
+        pool: pool.pool,
+        reserve_a: 1_050,
+        reserve_b: 1_905,
+    });
+    store
+        .index(&transaction(
+            &program_id,
+            "created",
+            1,
+            100,
+            &[pool.initialized(), pool.deposited(), settled],
+        ))
+        .unwrap();
+
+    let reserves: (i64, i64) = store
+        .connection()
+        .query_row(
+            "SELECT reserve_a, reserve_b FROM pools WHERE pool = ?1",
+            [pool.pool.to_string()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(reserves, (1_050, 1_905));
+}
+
+#[test]
+fn candles_follow_chain_order_whatever_the_ingest_order() {
+    let program_id = Pubkey::new_unique();
+    let pool = Pool::new();
+    let txs = [
+        transaction(&program_id, "created", 1, 60, &[pool.initialized()]),
+        transaction(&program_id, "first", 2, 61, &[pool.swapped(1, 100, 200)]),
+        transaction(&program_id, "high", 3, 62, &[pool.swapped(2, 100, 400)]),
+        transaction(&program_id, "low", 4, 63, &[pool.swapped(3, 100, 50)]),
+        transaction(&program_id, "last", 5, 64, &[pool.swapped(4, 100, 300)]),
+    ];
+
+    let mut in_order = Store::open_in_memory(program_id)
+        .unwrap()
+        .with_candle_intervals(vec![60]);
+    for tx in &txs {
+        in_order.index(tx).unwrap();
+    }
+    // a backfill indexing older transactions after newer ones
+    let mut out_of_order = Store::open_in_memory(program_id)
+        .unwrap()
+        .with_candle_intervals(vec![60]);
+    for i in [0, 4, 2, 1, 3] {
+        out_of_order.index(&txs[i]).unwrap();
+    }
+
+    let expected = (2.0, 4.0, 0.5, 3.0, 10, 10);
+    assert_eq!(candle(&in_order, &pool.pool, 60), expected);
+    assert_eq!(candle(&out_of_order, &pool.pool, 60), expected);
+}
+
+#[test]
+fn closed_pools_keep_their_trades() {
+    let program_id = Pubkey::new_unique();
+    let pool = Pool::new();
+    let mut store = Store::open_in_memory(program_id).unwrap();
+    store
+        .index(&transaction(
+            &program_id,
+            "created",
+            1,
+            100,
+            &[
+                pool.initialized(),
+                pool.deposited(),
+                pool.swapped(5, 1_005, 1_995),
+            ],
+        ))
+        .unwrap();
+    store
+        .index(&transaction(
+            &program_id,
+            "closed",
+            2,
+            200,
+            &[ChudexEvent::PoolClosed(PoolClosed { pool: pool.pool })],
+        ))
+        .unwrap();
+
+    let count = |table: &str| -> i64 {
+        store
+            .connection()
+            .query_row(
+                &format!("SELECT COUNT(*) FROM {} WHERE pool = ?1", table),
+                [pool.pool.to_string()],
+                |row| row.get(0),
+            )
+            .unwrap()
+    };
+    assert_eq!(count("pools"), 0);
+    assert_eq!(count("lp_positions"), 0);
+    assert_eq!(count("trades"), 1);
+}
+
+#[test]
+fn other_programs_are_ignored() {
+    let program_id = Pubkey::new_unique();
+    let pool = Pool::new();
+    let mut store = Store::open_in_memory(program_id).unwrap();
+    store
+        .index(&transaction(
+            &Pubkey::new_unique(),
+            "other",
+            1,
+            100,
+            &[pool.initialized()],
+        ))
+        .unwrap();
+
+    let pools: i64 = store
+        .connection()
+        .query_row("SELECT COUNT(*) FROM pools", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(pools, 0);
+}
@@ -0,0 +1,80 @@
+//! Permissioned pools. A pool with an allowlist only lets listed users deposit, withdraw
+//! and trade, checked against an allowlist account or a ticket proven against a Merkle root.
+
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, hash, pubkey::Pubkey};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::ChudexError,
+    state::{Allowlist, AllowlistMode, AllowlistTicket, Pool},
+    utils::assert_msg,
+};
+
+/// Merkle leaf of a user.
+pub fn leaf(user: &Pubkey) -> [u8; 32] {
+    hash::hash(user.as_ref()).to_bytes()
+}
+
+/// Checks `leaf` is under `root`, hashing sorted pairs up the proof.
+pub fn verify_proof(root: &[u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            hash::hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hash::hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == *root
+}
+
+/// Checks `user` may use `pool`. Pools without an allowlist let anyone in, otherwise
+/// `proof_ai` has to be the pool's allowlist account listing the user, or the user's
+/// allowlist ticket for a Merkle root pool.
+pub fn check(
+    program_id: &Pubkey,
+    pool_ai: &AccountInfo,
+    pool: &Pool,
+    user: &Pubkey,
+    proof_ai: Option<&AccountInfo>,
+) -> ProgramResult {
+    let allowlist = match &pool.allowlist {
+        Some(allowlist) => allowlist,
+        None => return Ok(()),
+    };
+    let proof_ai = proof_ai.ok_or(ChudexError::NotAllowlisted)?;
+    assert_msg(
+        proof_ai.owner == program_id,
+        ChudexError::NotAllowlisted.into(),
+        "Allowlist account not owned by program",
+    )?;
+
+    match allowlist {
+        AllowlistMode::Account(allowlist_key) => {
+            assert_msg(
+                proof_ai.key == allowlist_key,
+                ChudexError::InvalidAccountAddress.into(),
+                "Allowlist account doesn't match pool",
+            )?;
+            let allowlist = Allowlist::try_from_slice(&proof_ai.try_borrow_data()?)?;
+            assert_msg(
+                allowlist.members().contains(user),
+                ChudexError::NotAllowlisted.into(),
+                "User not on allowlist",
+            )?;
+        }
+        AllowlistMode::MerkleRoot(_) => {
+            let (ticket_key, _) = Pubkey::find_program_address(
+                &[AllowlistTicket::SEED, pool_ai.key.as_ref(), user.as_ref()],
+                program_id,
+            );
+            assert_msg(
+                *proof_ai.key == ticket_key && proof_ai.data_len() == AllowlistTicket::LEN,
+                ChudexError::NotAllowlisted.into(),
+                "User has no allowlist ticket for pool, prove against the root first",
+            )?;
+        }
+    }
+
+    Ok(())
+}
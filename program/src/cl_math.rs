@@ -0,0 +1,443 @@
+//! Math for concentrated liquidity pools.
+//!
+//! Prices are stored as sqrt(price of a in b) in Q64.64 fixed point. Liquidity is kept
+//! within u64 so `liquidity << 64` and `liquidity * (sqrt price >> 64)` fit in a u128.
+
+use crate::error::ChudexError;
+
+pub const MIN_TICK: i32 = -443_636;
+pub const MAX_TICK: i32 = 443_636;
+const Q64: u128 = 1 << 64;
+const LOW_MASK: u128 = u64::MAX as u128;
+
+/// 1 / sqrt(1.0001^(2^i)) in Q0.128, for each bit i of a tick, as in Uniswap v3's TickMath.
+const TICK_BIT_FACTORS: [u128; 19] = [
+    0xfffcb933bd6fad37aa2d162d1a594001,
+    0xfff97272373d413259a46990580e213a,
+    0xfff2e50f5f656932ef12357cf3c7fdcc,
+    0xffe5caca7e10e4e61c3624eaa0941cd0,
+    0xffcb9843d60f6159c9db58835c926644,
+    0xff973b41fa98c081472e6896dfb254c0,
+    0xff2ea16466c96a3843ec78b326b52861,
+    0xfe5dee046a99a2a811c461f1969c3053,
+    0xfcbe86c7900a88aedcffc83b479aa3a4,
+    0xf987a7253ac413176f2b074cf7815e54,
+    0xf3392b0822b70005940c7a398e4b70f3,
+    0xe7159475a2c29b7443b29c7fa6e889d9,
+    0xd097f3bdfd2022b8845ad8f792aa5825,
+    0xa9f746462d870fdf8a65dc1f90e061e5,
+    0x70d869a156d2a1b890bb3df62baf32f7,
+    0x31be135f97d08fd981231505542fcfa6,
+    0x9aa508b5b7a84e1c677de54f3e99bc9,
+    0x5d6af8dedb81196699c329225ee604,
+    0x2216e584f5fa1ea926041bedfe98,
+];
+
+/// Ticks per unit of log2(sqrt price), 2 / log2(1.0001), in Q32.
+const TICKS_PER_LOG2_X32: i128 = 59_543_866_431_255;
+
+/// High 128 bits of the 256 bit product `a * b`.
+fn mul_shift_128(a: u128, b: u128) -> u128 {
+    let (a_high, a_low) = (a >> 64, a & LOW_MASK);
+    let (b_high, b_low) = (b >> 64, b & LOW_MASK);
+    let high_low = a_high * b_low;
+    let low_high = a_low * b_high;
+    let middle = ((a_low * b_low) >> 64) + (high_low & LOW_MASK) + (low_high & LOW_MASK);
+    a_high * b_high + (high_low >> 64) + (low_high >> 64) + (middle >> 64)
+}
+
+/// 2^192 / `value` rounded up, for `value` above 2^64 so it fits.
+fn reciprocal_q64_ceil(value: u128) -> u128 {
+    // 2^128 / value first, then the remaining 64 bits one at a time
+    let mut quotient = u128::MAX / value;
+    let mut remainder = u128::MAX % value + 1;
+    if remainder == value {
+        quotient += 1;
+        remainder = 0;
+    }
+    for _ in 0..64 {
+        let carry = remainder >> 127 == 1;
+        remainder <<= 1;
+        quotient <<= 1;
+        if carry || remainder >= value {
+            remainder = remainder.wrapping_sub(value);
+            quotient |= 1;
+        }
+    }
+    if remainder > 0 {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
+/// sqrt(1.0001^tick) in Q64.64, rounded up. Multiplies the factors of the tick's bits in
+/// Q0.128 like Uniswap v3's getSqrtRatioAtTick, inverting for positive ticks.
+pub fn sqrt_price_from_tick(tick: i32) -> Result<u128, ChudexError> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return Err(ChudexError::InvalidTick);
+    }
+    let abs_tick = tick.unsigned_abs();
+    let mut ratio = None;
+    for (bit, factor) in TICK_BIT_FACTORS.iter().enumerate() {
+        if abs_tick & (1 << bit) != 0 {
+            ratio = Some(match ratio {
+                Some(ratio) => mul_shift_128(ratio, *factor),
+                None => *factor,
+            });
+        }
+    }
+    Ok(match ratio {
+        None => Q64,
+        Some(ratio) if tick < 0 => (ratio >> 64) + u128::from(ratio & LOW_MASK != 0),
+        Some(ratio) => reciprocal_q64_ceil(ratio),
+    })
+}
+
+/// Largest tick whose sqrt price is at or below `sqrt_price`.
+pub fn tick_from_sqrt_price(sqrt_price: u128) -> Result<i32, ChudexError> {
+    if sqrt_price == 0 {
+        return Err(ChudexError::CalculationFailure);
+    }
+
+    // log2 of the Q64.64 price in Q32: the integer part from the top bit, then the fraction
+    // bit by bit by squaring the normalized value
+    let msb = 127 - sqrt_price.leading_zeros();
+    let mut log2_x32 = (msb as i128 - 64) << 32;
+    let mut normalized = if msb >= 63 {
+        sqrt_price >> (msb - 63)
+    } else {
+        sqrt_price << (63 - msb)
+    };
+    for bit in 1..=16 {
+        normalized = (normalized * normalized) >> 63;
+        if normalized >= 1 << 64 {
+            normalized >>= 1;
+            log2_x32 += 1 << (32 - bit);
+        }
+    }
+    let estimate = (log2_x32 * TICKS_PER_LOG2_X32) >> 64;
+    let mut tick = (estimate.clamp(MIN_TICK as i128, MAX_TICK as i128)) as i32;
+
+    // the truncated log leaves the estimate a tick or two off, settle it exactly
+    while tick > MIN_TICK && sqrt_price_from_tick(tick)? > sqrt_price {
+        tick -= 1;
+    }
+    while tick < MAX_TICK && sqrt_price_from_tick(tick + 1)? <= sqrt_price {
+        tick += 1;
+    }
+    Ok(tick)
+}
+
+/// `a * b >> 64` for `a` within u64, without overflowing.
+fn mul_shift_64(a: u128, b: u128) -> Result<u128, ChudexError> {
+    if a > LOW_MASK {
+        return Err(ChudexError::CalculationFailure);
+    }
+    let high = a
+        .checked_mul(b >> 64)
+        .ok_or(ChudexError::CalculationFailure)?;
+    let low = (a * (b & LOW_MASK)) >> 64;
+    high.checked_add(low).ok_or(ChudexError::CalculationFailure)
+}
+
+fn to_u64(amount: u128) -> Result<u64, ChudexError> {
+    u64::try_from(amount).map_err(|_| ChudexError::CalculationFailure)
+}
+
+/// Token a between two sqrt prices for `liquidity`, L / sqrt(lower) - L / sqrt(upper).
+pub fn amount_a_delta(
+    sqrt_price_lower: u128,
+    sqrt_price_upper: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<u64, ChudexError> {
+    if liquidity > LOW_MASK || sqrt_price_lower == 0 || sqrt_price_lower > sqrt_price_upper {
+        return Err(ChudexError::CalculationFailure);
+    }
+    let numerator = liquidity << 64;
+    let (at_lower, at_upper) = if round_up {
+        (
+            numerator.div_ceil(sqrt_price_lower),
+            numerator / sqrt_price_upper,
+        )
+    } else {
+        (
+            numerator / sqrt_price_lower,
+            numerator.div_ceil(sqrt_price_upper),
+        )
+    };
+    to_u64(at_lower.saturating_sub(at_upper))
+}
+
+/// Token b between two sqrt prices for `liquidity`, L * (sqrt(upper) - sqrt(lower)).
+pub fn amount_b_delta(
+    sqrt_price_lower: u128,
+    sqrt_price_upper: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<u64, ChudexError> {
+    if sqrt_price_lower > sqrt_price_upper {
+        return Err(ChudexError::CalculationFailure);
+    }
+    let delta = mul_shift_64(liquidity, sqrt_price_upper - sqrt_price_lower)?;
+    let remainder = liquidity.wrapping_mul((sqrt_price_upper - sqrt_price_lower) & LOW_MASK);
+    if round_up && remainder & LOW_MASK != 0 {
+        to_u64(delta + 1)
+    } else {
+        to_u64(delta)
+    }
+}
+
+/// Token amounts for `liquidity` over [lower, upper] at the current price.
+pub fn amounts_for_liquidity(
+    sqrt_price: u128,
+    sqrt_price_lower: u128,
+    sqrt_price_upper: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<(u64, u64), ChudexError> {
+    if sqrt_price <= sqrt_price_lower {
+        Ok((
+            amount_a_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up)?,
+            0,
+        ))
+    } else if sqrt_price >= sqrt_price_upper {
+        Ok((
+            0,
+            amount_b_delta(sqrt_price_lower, sqrt_price_upper, liquidity, round_up)?,
+        ))
+    } else {
+        Ok((
+            amount_a_delta(sqrt_price, sqrt_price_upper, liquidity, round_up)?,
+            amount_b_delta(sqrt_price_lower, sqrt_price, liquidity, round_up)?,
+        ))
+    }
+}
+
+/// Sqrt price after adding `amount_in` of a (price moves down) or b (price moves up).
+pub fn next_sqrt_price(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount_in: u64,
+    a_to_b: bool,
+) -> Result<u128, ChudexError> {
+    if liquidity == 0 || liquidity > LOW_MASK || sqrt_price == 0 {
+        return Err(ChudexError::CalculationFailure);
+    }
+    if a_to_b {
+        // L / sqrt(new) = L / sqrt(old) + amount, rounded up to keep the pool whole
+        let numerator = liquidity << 64;
+        let denominator = (numerator / sqrt_price)
+            .checked_add(amount_in as u128)
+            .ok_or(ChudexError::CalculationFailure)?;
+        Ok(numerator.div_ceil(denominator))
+    } else {
+        // sqrt(new) = sqrt(old) + amount / L, rounded down
+        sqrt_price
+            .checked_add(((amount_in as u128) << 64) / liquidity)
+            .ok_or(ChudexError::CalculationFailure)
+    }
+}
+
+/// Result of swapping within a single tick range.
+pub struct SwapStep {
+    pub sqrt_price_next: u128,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+}
+
+/// Swaps as much of `amount_remaining` as fits before reaching `sqrt_price_target`.
+pub fn compute_swap_step(
+    sqrt_price: u128,
+    sqrt_price_target: u128,
+    liquidity: u128,
+    amount_remaining: u64,
+    fee: u64,
+    fee_decimals: u64,
+    a_to_b: bool,
+) -> Result<SwapStep, ChudexError> {
+    let fee_on_remaining = crate::curve::fee_amount(amount_remaining, fee, fee_decimals)?;
+    let amount_remaining_after_fee = amount_remaining - fee_on_remaining;
+
+    let amount_to_target = if liquidity == 0 {
+        0
+    } else if a_to_b {
+        amount_a_delta(sqrt_price_target, sqrt_price, liquidity, true)?
+    } else {
+        amount_b_delta(sqrt_price, sqrt_price_target, liquidity, true)?
+    };
+
+    // no liquidity in range, or enough input to reach the target: jump there
+    let (sqrt_price_next, amount_in) =
+        if liquidity == 0 || amount_remaining_after_fee >= amount_to_target {
+            (sqrt_price_target, amount_to_target)
+        } else {
+            (
+                next_sqrt_price(sqrt_price, liquidity, amount_remaining_after_fee, a_to_b)?,
+                amount_remaining_after_fee,
+            )
+        };
+
+    let amount_out = if liquidity == 0 {
+        0
+    } else if a_to_b {
+        amount_b_delta(sqrt_price_next, sqrt_price, liquidity, false)?
+    } else {
+        amount_a_delta(sqrt_price, sqrt_price_next, liquidity, false)?
+    };
+
+    // when the whole remainder is consumed it pays the whole fee. reaching the target, the
+    // fee is on the gross input amount_in + fee, so amount_in * fee / (1 - fee), rounded up
+    let fee_amount = if sqrt_price_next != sqrt_price_target {
+        amount_remaining - amount_in
+    } else {
+        let denominator = 10u128
+            .checked_pow(fee_decimals as u32)
+            .ok_or(ChudexError::CalculationFailure)?
+            - fee as u128;
+        let numerator = (amount_in as u128) * (fee as u128);
+        to_u64(numerator.div_ceil(denominator))?.min(amount_remaining - amount_in)
+    };
+
+    Ok(SwapStep {
+        sqrt_price_next,
+        amount_in,
+        amount_out,
+        fee_amount,
+    })
+}
+
+/// Fee growth per unit of liquidity in Q64.64.
+pub fn fee_growth_delta(fee_amount: u64, liquidity: u128) -> u128 {
+    ((fee_amount as u128) << 64)
+        .checked_div(liquidity)
+        .unwrap_or(0)
+}
+
+/// Fees earned by `liquidity` over a change in fee growth inside its range.
+pub fn fees_owed(fee_growth_delta: u128, liquidity: u128) -> Result<u64, ChudexError> {
+    to_u64(mul_shift_64(liquidity, fee_growth_delta)?)
+}
+
+/// Liquidity after adding a signed delta.
+pub fn add_liquidity_delta(liquidity: u128, delta: i128) -> Result<u128, ChudexError> {
+    let liquidity = if delta >= 0 {
+        liquidity.checked_add(delta as u128)
+    } else {
+        liquidity.checked_sub(delta.unsigned_abs())
+    }
+    .ok_or(ChudexError::CalculationFailure)?;
+    if liquidity > LOW_MASK {
+        return Err(ChudexError::CalculationFailure);
+    }
+    Ok(liquidity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqrt_price_from_tick_matches_known_values() {
+        assert_eq!(sqrt_price_from_tick(0).unwrap(), Q64);
+        // sqrt(1.0001^±443636) in Q64.64, rounded up
+        assert_eq!(sqrt_price_from_tick(MIN_TICK).unwrap(), 4_295_048_017);
+        assert_eq!(
+            sqrt_price_from_tick(MAX_TICK).unwrap(),
+            79_226_673_515_401_279_992_447_579_062
+        );
+        assert_eq!(
+            sqrt_price_from_tick(MAX_TICK + 1),
+            Err(ChudexError::InvalidTick)
+        );
+        assert_eq!(
+            sqrt_price_from_tick(MIN_TICK - 1),
+            Err(ChudexError::InvalidTick)
+        );
+
+        // exact values rounded up, the bit factors are off by at most a unit or so
+        for (tick, expected) in [
+            (-200_000, 837_899_702_510_259u128),
+            (-1_000, 17_547_129_613_991_598_782),
+            (-1, 18_445_821_805_675_392_312),
+            (1, 18_447_666_387_855_959_851),
+            (1_000, 19_392_480_388_906_836_278),
+            (200_000, 406_113_483_393_643_373_014_940),
+        ] {
+            let actual = sqrt_price_from_tick(tick).unwrap();
+            assert!(
+                (actual as i128 - expected as i128).abs() <= 2,
+                "tick {}: {}",
+                tick,
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn sqrt_price_from_tick_increases() {
+        let mut last = 0;
+        for tick in (MIN_TICK..=MAX_TICK).step_by(997).chain([MAX_TICK]) {
+            let sqrt_price = sqrt_price_from_tick(tick).unwrap();
+            assert!(sqrt_price > last, "tick {}", tick);
+            last = sqrt_price;
+        }
+    }
+
+    #[test]
+    fn tick_round_trips() {
+        for tick in
+            (MIN_TICK..=MAX_TICK)
+                .step_by(1_009)
+                .chain([MIN_TICK, -1, 0, 1, MAX_TICK - 1, MAX_TICK])
+        {
+            let sqrt_price = sqrt_price_from_tick(tick).unwrap();
+            assert_eq!(tick_from_sqrt_price(sqrt_price).unwrap(), tick);
+            // just below a tick's price is the tick under it
+            if tick > MIN_TICK {
+                assert_eq!(tick_from_sqrt_price(sqrt_price - 1).unwrap(), tick - 1);
+            }
+            // just below the next tick's price is still the tick
+            if tick < MAX_TICK {
+                let next = sqrt_price_from_tick(tick + 1).unwrap();
+                assert_eq!(tick_from_sqrt_price(next - 1).unwrap(), tick);
+            }
+        }
+        assert_eq!(
+            tick_from_sqrt_price(0),
+            Err(ChudexError::CalculationFailure)
+        );
+    }
+
+    #[test]
+    fn swap_step_within_range_takes_fee_from_remainder() {
+        let liquidity = 1_000_000_000;
+        let sqrt_price = Q64;
+        let target = sqrt_price_from_tick(-10_000).unwrap();
+        let step = compute_swap_step(sqrt_price, target, liquidity, 1_000, 30, 4, true).unwrap();
+        assert!(step.sqrt_price_next < sqrt_price && step.sqrt_price_next > target);
+        assert_eq!(step.amount_in + step.fee_amount, 1_000);
+        assert_eq!(step.fee_amount, 3);
+        assert!(step.amount_out > 0 && step.amount_out < step.amount_in);
+    }
+
+    #[test]
+    fn swap_step_to_target_charges_fee_on_gross_input() {
+        let liquidity = 1_000_000_000;
+        let sqrt_price = Q64;
+        let target = sqrt_price_from_tick(-10).unwrap();
+        let step =
+            compute_swap_step(sqrt_price, target, liquidity, u64::MAX / 2, 30, 4, true).unwrap();
+        assert_eq!(step.sqrt_price_next, target);
+        assert_eq!(
+            step.amount_in,
+            amount_a_delta(target, sqrt_price, liquidity, true).unwrap()
+        );
+        // the fee is 0.3% of what the trader pays in, not of the net amount
+        let gross = (step.amount_in + step.fee_amount) as u128;
+        assert!(step.fee_amount as u128 * 10_000 >= gross * 30);
+        assert!((step.fee_amount as u128 - 1) * 10_000 < gross * 30);
+    }
+}
@@ -0,0 +1,80 @@
+//! Results of exchange, deposit and withdraw, left as return data for programs calling
+//! them. Read them right after the cpi, any later cpi replaces the return data.
+//! Amounts are in the order the caller passed the token accounts, not pool mint order.
+
+use solana_program::{
+    entrypoint::ProgramResult,
+    msg,
+    program::{get_return_data, set_return_data},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct ExchangeResult {
+    /// Taken from the user.
+    pub amount_in: u64,
+    /// Received by the user, after any transfer fee.
+    pub amount_out: u64,
+    /// Swap fee, in the in token.
+    pub fee: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct DepositResult {
+    /// Taken from the user.
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+    /// Minted to the user.
+    pub pool_token_amount: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct WithdrawResult {
+    /// Burned from the user.
+    pub pool_token_amount: u64,
+    /// Received by the user, after any transfer fees.
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+}
+
+/// Runs an instruction's `operation` and sets its result as the return data. Any cpi the
+/// operation makes, hook calls and token transfers included, can replace the return data,
+/// so it's only set here, once the operation is done.
+pub fn with_result<T: BorshSerialize>(
+    operation: impl FnOnce() -> Result<T, ProgramError>,
+) -> ProgramResult {
+    let result = operation()?;
+    set_return_data(&result.try_to_vec()?);
+    Ok(())
+}
+
+/// Reads the result of the last instruction of `program_id`, the dex's id, called.
+pub fn get_result<T: BorshDeserialize>(program_id: &Pubkey) -> Result<T, ProgramError> {
+    match get_return_data() {
+        Some((returning_program_id, data)) if returning_program_id == *program_id => {
+            Ok(T::try_from_slice(&data)?)
+        }
+        _ => {
+            msg!("No return data from {}", program_id);
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+
+/// Result of an exchange just called on `program_id`.
+pub fn exchange_result(program_id: &Pubkey) -> Result<ExchangeResult, ProgramError> {
+    get_result(program_id)
+}
+
+/// Result of a deposit just called on `program_id`.
+pub fn deposit_result(program_id: &Pubkey) -> Result<DepositResult, ProgramError> {
+    get_result(program_id)
+}
+
+/// Result of a withdraw just called on `program_id`.
+pub fn withdraw_result(program_id: &Pubkey) -> Result<WithdrawResult, ProgramError> {
+    get_result(program_id)
+}
@@ -0,0 +1,2196 @@
+//! Cross-program invocation of every instruction, for programs built on the dex. Each
+//! instruction gets an accounts struct, in the order and with the writability documented
+//! on `ChudexInstruction`, and a function invoking it with the caller's signer seeds.
+//! The first account of each instruction signs, except for Sync and Skim which nobody
+//! signs, and the others keep the writability the caller passed them with. Results of
+//! exchange, deposit and withdraw are read with the client module.
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+    pubkey::Pubkey,
+};
+
+use borsh::BorshSerialize;
+
+use crate::{
+    instruction::ChudexInstruction,
+    state::{AllowlistMode, DynamicFeeConfig, TriggerSource},
+};
+
+/// Invokes `instruction` on `program`, with `signer`, if any, ahead of the other accounts.
+fn invoke_chudex<'a>(
+    program: &AccountInfo<'a>,
+    instruction: &ChudexInstruction,
+    signer: Option<&AccountInfo<'a>>,
+    accounts: &[&AccountInfo<'a>],
+    remaining_accounts: &[AccountInfo<'a>],
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let mut account_metas = vec![];
+    let mut account_infos = vec![];
+    if let Some(signer) = signer {
+        account_metas.push(AccountMeta {
+            pubkey: *signer.key,
+            is_signer: true,
+            is_writable: signer.is_writable,
+        });
+        account_infos.push(signer.clone());
+    }
+    for ai in accounts {
+        account_metas.push(AccountMeta {
+            pubkey: *ai.key,
+            is_signer: false,
+            is_writable: ai.is_writable,
+        });
+        account_infos.push((*ai).clone());
+    }
+    for ai in remaining_accounts {
+        account_metas.push(AccountMeta {
+            pubkey: *ai.key,
+            is_signer: ai.is_signer,
+            is_writable: ai.is_writable,
+        });
+        account_infos.push(ai.clone());
+    }
+    account_infos.push(program.clone());
+
+    invoke_signed(
+        &Instruction {
+            program_id: *program.key,
+            accounts: account_metas,
+            data: instruction.try_to_vec()?,
+        },
+        &account_infos,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::InitializePool`.
+pub struct InitializePoolAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub user: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_vault_a: AccountInfo<'a>,
+    pub pool_vault_b: AccountInfo<'a>,
+    pub mint_a: AccountInfo<'a>,
+    pub mint_b: AccountInfo<'a>,
+    pub pool_mint: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub sysvar_rent: AccountInfo<'a>,
+    pub associated_token_program: AccountInfo<'a>,
+    pub token_program_a: AccountInfo<'a>,
+    pub token_program_b: AccountInfo<'a>,
+    pub config: AccountInfo<'a>,
+    pub factory: AccountInfo<'a>,
+    pub registry_page: AccountInfo<'a>,
+    pub mint_index_a: AccountInfo<'a>,
+    pub mint_index_page_a: AccountInfo<'a>,
+    pub mint_index_b: AccountInfo<'a>,
+    pub mint_index_page_b: AccountInfo<'a>,
+    pub lp_metadata: AccountInfo<'a>,
+}
+
+pub fn initialize_pool<'a>(
+    accounts: InitializePoolAccounts<'a>,
+    fee_tier: u16,
+    dynamic_fee: Option<DynamicFeeConfig>,
+    allowlist: Option<AllowlistMode>,
+    hook_program: Option<Pubkey>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::InitializePool {
+            fee_tier,
+            dynamic_fee,
+            allowlist,
+            hook_program,
+        },
+        Some(&accounts.user),
+        &[
+            &accounts.pool,
+            &accounts.pool_vault_a,
+            &accounts.pool_vault_b,
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.pool_mint,
+            &accounts.token_program,
+            &accounts.system_program,
+            &accounts.sysvar_rent,
+            &accounts.associated_token_program,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+            &accounts.config,
+            &accounts.factory,
+            &accounts.registry_page,
+            &accounts.mint_index_a,
+            &accounts.mint_index_page_a,
+            &accounts.mint_index_b,
+            &accounts.mint_index_page_b,
+            &accounts.lp_metadata,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::Deposit`.
+pub struct DepositAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub user: AccountInfo<'a>,
+    pub user_token_a: AccountInfo<'a>,
+    pub user_token_b: AccountInfo<'a>,
+    pub user_pool_token: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_vault_a: AccountInfo<'a>,
+    pub pool_vault_b: AccountInfo<'a>,
+    pub pool_mint: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub sysvar_rent: AccountInfo<'a>,
+    pub associated_token_program: AccountInfo<'a>,
+    pub mint_a: AccountInfo<'a>,
+    pub mint_b: AccountInfo<'a>,
+    pub token_program_a: AccountInfo<'a>,
+    pub token_program_b: AccountInfo<'a>,
+    /// Twamm and its token accs a and b, allowlist or ticket, then hook program, hook authority
+    /// and hook accounts, as the pool needs.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn deposit<'a>(
+    accounts: DepositAccounts<'a>,
+    token_a_amount: u64,
+    max_token_b_amount: u64,
+    native_sol: bool,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::Deposit {
+            token_a_amount,
+            max_token_b_amount,
+            native_sol,
+        },
+        Some(&accounts.user),
+        &[
+            &accounts.user_token_a,
+            &accounts.user_token_b,
+            &accounts.user_pool_token,
+            &accounts.pool,
+            &accounts.pool_vault_a,
+            &accounts.pool_vault_b,
+            &accounts.pool_mint,
+            &accounts.token_program,
+            &accounts.system_program,
+            &accounts.sysvar_rent,
+            &accounts.associated_token_program,
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::Withdraw`.
+pub struct WithdrawAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub user: AccountInfo<'a>,
+    pub user_token_a: AccountInfo<'a>,
+    pub user_token_b: AccountInfo<'a>,
+    pub user_pool_token: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_vault_a: AccountInfo<'a>,
+    pub pool_vault_b: AccountInfo<'a>,
+    pub pool_mint: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+    pub mint_a: AccountInfo<'a>,
+    pub mint_b: AccountInfo<'a>,
+    pub token_program_a: AccountInfo<'a>,
+    pub token_program_b: AccountInfo<'a>,
+    /// System program and sysvar with native_sol, then twamm and its token accs a and b,
+    /// allowlist or ticket, hook program, hook authority and hook accounts, as the pool needs.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn withdraw<'a>(
+    accounts: WithdrawAccounts<'a>,
+    pool_token_amount: u64,
+    min_token_a_amount: u64,
+    min_token_b_amount: u64,
+    native_sol: bool,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::Withdraw {
+            pool_token_amount,
+            min_token_a_amount,
+            min_token_b_amount,
+            native_sol,
+        },
+        Some(&accounts.user),
+        &[
+            &accounts.user_token_a,
+            &accounts.user_token_b,
+            &accounts.user_pool_token,
+            &accounts.pool,
+            &accounts.pool_vault_a,
+            &accounts.pool_vault_b,
+            &accounts.pool_mint,
+            &accounts.token_program,
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::Exchange`.
+pub struct ExchangeAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub user: AccountInfo<'a>,
+    pub user_token_src: AccountInfo<'a>,
+    pub user_token_dst: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_vault_src: AccountInfo<'a>,
+    pub pool_vault_dst: AccountInfo<'a>,
+    pub token_program_src: AccountInfo<'a>,
+    pub token_program_dst: AccountInfo<'a>,
+    pub mint_src: AccountInfo<'a>,
+    pub mint_dst: AccountInfo<'a>,
+    /// System program and sysvar with native_sol, then twamm and its token accs a and b,
+    /// allowlist or ticket, hook program, hook authority and hook accounts, as the pool needs.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn exchange<'a>(
+    accounts: ExchangeAccounts<'a>,
+    amount_in: u64,
+    min_amount_out: u64,
+    native_sol: bool,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::Exchange {
+            amount_in,
+            min_amount_out,
+            native_sol,
+        },
+        Some(&accounts.user),
+        &[
+            &accounts.user_token_src,
+            &accounts.user_token_dst,
+            &accounts.pool,
+            &accounts.pool_vault_src,
+            &accounts.pool_vault_dst,
+            &accounts.token_program_src,
+            &accounts.token_program_dst,
+            &accounts.mint_src,
+            &accounts.mint_dst,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::InitializeWeightedPool`.
+pub struct InitializeWeightedPoolAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub user: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_mint: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub sysvar_rent: AccountInfo<'a>,
+    pub associated_token_program: AccountInfo<'a>,
+    /// Mint, pool token acc and token program for each token.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn initialize_weighted_pool<'a>(
+    accounts: InitializeWeightedPoolAccounts<'a>,
+    weights: Vec<u64>,
+    fee: u64,
+    fee_decimals: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::InitializeWeightedPool {
+            weights,
+            fee,
+            fee_decimals,
+        },
+        Some(&accounts.user),
+        &[
+            &accounts.pool,
+            &accounts.pool_mint,
+            &accounts.token_program,
+            &accounts.system_program,
+            &accounts.sysvar_rent,
+            &accounts.associated_token_program,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::WeightedDeposit`.
+pub struct WeightedDepositAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub user: AccountInfo<'a>,
+    pub user_pool_token: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_mint: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub sysvar_rent: AccountInfo<'a>,
+    pub associated_token_program: AccountInfo<'a>,
+    /// User token acc, pool token acc, mint and token program for each token, in pool order.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn weighted_deposit<'a>(
+    accounts: WeightedDepositAccounts<'a>,
+    pool_token_amount: u64,
+    max_token_amounts: Vec<u64>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::WeightedDeposit {
+            pool_token_amount,
+            max_token_amounts,
+        },
+        Some(&accounts.user),
+        &[
+            &accounts.user_pool_token,
+            &accounts.pool,
+            &accounts.pool_mint,
+            &accounts.token_program,
+            &accounts.system_program,
+            &accounts.sysvar_rent,
+            &accounts.associated_token_program,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::WeightedWithdraw`.
+pub struct WeightedWithdrawAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub user: AccountInfo<'a>,
+    pub user_pool_token: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_mint: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+    /// User token acc, pool token acc, mint and token program for each token, in pool order.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn weighted_withdraw<'a>(
+    accounts: WeightedWithdrawAccounts<'a>,
+    pool_token_amount: u64,
+    min_token_amounts: Vec<u64>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::WeightedWithdraw {
+            pool_token_amount,
+            min_token_amounts,
+        },
+        Some(&accounts.user),
+        &[
+            &accounts.user_pool_token,
+            &accounts.pool,
+            &accounts.pool_mint,
+            &accounts.token_program,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::WeightedExchange`.
+pub struct WeightedExchangeAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub user: AccountInfo<'a>,
+    pub user_token_src: AccountInfo<'a>,
+    pub user_token_dst: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_vault_src: AccountInfo<'a>,
+    pub pool_vault_dst: AccountInfo<'a>,
+    pub token_program_src: AccountInfo<'a>,
+    pub token_program_dst: AccountInfo<'a>,
+    pub mint_src: AccountInfo<'a>,
+    pub mint_dst: AccountInfo<'a>,
+}
+
+pub fn weighted_exchange<'a>(
+    accounts: WeightedExchangeAccounts<'a>,
+    amount_in: u64,
+    min_amount_out: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::WeightedExchange {
+            amount_in,
+            min_amount_out,
+        },
+        Some(&accounts.user),
+        &[
+            &accounts.user_token_src,
+            &accounts.user_token_dst,
+            &accounts.pool,
+            &accounts.pool_vault_src,
+            &accounts.pool_vault_dst,
+            &accounts.token_program_src,
+            &accounts.token_program_dst,
+            &accounts.mint_src,
+            &accounts.mint_dst,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::InitializeClPool`.
+pub struct InitializeClPoolAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub user: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_vault_a: AccountInfo<'a>,
+    pub pool_vault_b: AccountInfo<'a>,
+    pub mint_a: AccountInfo<'a>,
+    pub mint_b: AccountInfo<'a>,
+    pub token_program_a: AccountInfo<'a>,
+    pub token_program_b: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub sysvar_rent: AccountInfo<'a>,
+    pub associated_token_program: AccountInfo<'a>,
+}
+
+pub fn initialize_cl_pool<'a>(
+    accounts: InitializeClPoolAccounts<'a>,
+    tick_spacing: u16,
+    initial_sqrt_price: u128,
+    fee: u64,
+    fee_decimals: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::InitializeClPool {
+            tick_spacing,
+            initial_sqrt_price,
+            fee,
+            fee_decimals,
+        },
+        Some(&accounts.user),
+        &[
+            &accounts.pool,
+            &accounts.pool_vault_a,
+            &accounts.pool_vault_b,
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+            &accounts.system_program,
+            &accounts.sysvar_rent,
+            &accounts.associated_token_program,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::InitializeTickArray`.
+pub struct InitializeTickArrayAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub user: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub tick_array: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+}
+
+pub fn initialize_tick_array<'a>(
+    accounts: InitializeTickArrayAccounts<'a>,
+    start_tick: i32,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::InitializeTickArray { start_tick },
+        Some(&accounts.user),
+        &[
+            &accounts.pool,
+            &accounts.tick_array,
+            &accounts.system_program,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::OpenPosition`.
+pub struct OpenPositionAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub position: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+}
+
+pub fn open_position<'a>(
+    accounts: OpenPositionAccounts<'a>,
+    tick_lower: i32,
+    tick_upper: i32,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::OpenPosition {
+            tick_lower,
+            tick_upper,
+        },
+        Some(&accounts.owner),
+        &[&accounts.pool, &accounts.position, &accounts.system_program],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::IncreaseLiquidity`.
+pub struct IncreaseLiquidityAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub position: AccountInfo<'a>,
+    pub tick_array_lower: AccountInfo<'a>,
+    pub tick_array_upper: AccountInfo<'a>,
+    pub user_token_a: AccountInfo<'a>,
+    pub user_token_b: AccountInfo<'a>,
+    pub pool_vault_a: AccountInfo<'a>,
+    pub pool_vault_b: AccountInfo<'a>,
+    pub mint_a: AccountInfo<'a>,
+    pub mint_b: AccountInfo<'a>,
+    pub token_program_a: AccountInfo<'a>,
+    pub token_program_b: AccountInfo<'a>,
+}
+
+pub fn increase_liquidity<'a>(
+    accounts: IncreaseLiquidityAccounts<'a>,
+    liquidity: u128,
+    max_token_a_amount: u64,
+    max_token_b_amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::IncreaseLiquidity {
+            liquidity,
+            max_token_a_amount,
+            max_token_b_amount,
+        },
+        Some(&accounts.owner),
+        &[
+            &accounts.pool,
+            &accounts.position,
+            &accounts.tick_array_lower,
+            &accounts.tick_array_upper,
+            &accounts.user_token_a,
+            &accounts.user_token_b,
+            &accounts.pool_vault_a,
+            &accounts.pool_vault_b,
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::DecreaseLiquidity`, same as IncreaseLiquidity.
+pub type DecreaseLiquidityAccounts<'a> = IncreaseLiquidityAccounts<'a>;
+
+pub fn decrease_liquidity<'a>(
+    accounts: DecreaseLiquidityAccounts<'a>,
+    liquidity: u128,
+    min_token_a_amount: u64,
+    min_token_b_amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::DecreaseLiquidity {
+            liquidity,
+            min_token_a_amount,
+            min_token_b_amount,
+        },
+        Some(&accounts.owner),
+        &[
+            &accounts.pool,
+            &accounts.position,
+            &accounts.tick_array_lower,
+            &accounts.tick_array_upper,
+            &accounts.user_token_a,
+            &accounts.user_token_b,
+            &accounts.pool_vault_a,
+            &accounts.pool_vault_b,
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::CollectFees`.
+pub struct CollectFeesAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub position: AccountInfo<'a>,
+    pub user_token_a: AccountInfo<'a>,
+    pub user_token_b: AccountInfo<'a>,
+    pub pool_vault_a: AccountInfo<'a>,
+    pub pool_vault_b: AccountInfo<'a>,
+    pub mint_a: AccountInfo<'a>,
+    pub mint_b: AccountInfo<'a>,
+    pub token_program_a: AccountInfo<'a>,
+    pub token_program_b: AccountInfo<'a>,
+}
+
+pub fn collect_fees<'a>(
+    accounts: CollectFeesAccounts<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::CollectFees,
+        Some(&accounts.owner),
+        &[
+            &accounts.pool,
+            &accounts.position,
+            &accounts.user_token_a,
+            &accounts.user_token_b,
+            &accounts.pool_vault_a,
+            &accounts.pool_vault_b,
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::ClosePosition`.
+pub struct ClosePositionAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub position: AccountInfo<'a>,
+}
+
+pub fn close_position<'a>(
+    accounts: ClosePositionAccounts<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::ClosePosition,
+        Some(&accounts.owner),
+        &[&accounts.position],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::ClExchange`.
+pub struct ClExchangeAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub user: AccountInfo<'a>,
+    pub user_token_src: AccountInfo<'a>,
+    pub user_token_dst: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_vault_src: AccountInfo<'a>,
+    pub pool_vault_dst: AccountInfo<'a>,
+    pub token_program_src: AccountInfo<'a>,
+    pub token_program_dst: AccountInfo<'a>,
+    pub mint_src: AccountInfo<'a>,
+    pub mint_dst: AccountInfo<'a>,
+    /// Tick arrays, starting at the current tick in swap direction.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn cl_exchange<'a>(
+    accounts: ClExchangeAccounts<'a>,
+    amount_in: u64,
+    min_amount_out: u64,
+    a_to_b: bool,
+    sqrt_price_limit: u128,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::ClExchange {
+            amount_in,
+            min_amount_out,
+            a_to_b,
+            sqrt_price_limit,
+        },
+        Some(&accounts.user),
+        &[
+            &accounts.user_token_src,
+            &accounts.user_token_dst,
+            &accounts.pool,
+            &accounts.pool_vault_src,
+            &accounts.pool_vault_dst,
+            &accounts.token_program_src,
+            &accounts.token_program_dst,
+            &accounts.mint_src,
+            &accounts.mint_dst,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::PlaceLimitOrder`.
+pub struct PlaceLimitOrderAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub owner_token_in: AccountInfo<'a>,
+    pub owner_token_out: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub order: AccountInfo<'a>,
+    pub order_escrow: AccountInfo<'a>,
+    pub mint_in: AccountInfo<'a>,
+    pub mint_out: AccountInfo<'a>,
+    pub token_program_in: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub sysvar_rent: AccountInfo<'a>,
+    pub associated_token_program: AccountInfo<'a>,
+    /// Allowlist or ticket, for permissioned pools.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn place_limit_order<'a>(
+    accounts: PlaceLimitOrderAccounts<'a>,
+    nonce: u64,
+    amount_in: u64,
+    min_amount_out: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::PlaceLimitOrder {
+            nonce,
+            amount_in,
+            min_amount_out,
+        },
+        Some(&accounts.owner),
+        &[
+            &accounts.owner_token_in,
+            &accounts.owner_token_out,
+            &accounts.pool,
+            &accounts.order,
+            &accounts.order_escrow,
+            &accounts.mint_in,
+            &accounts.mint_out,
+            &accounts.token_program_in,
+            &accounts.system_program,
+            &accounts.sysvar_rent,
+            &accounts.associated_token_program,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::CancelLimitOrder`.
+pub struct CancelLimitOrderAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub owner_token_in: AccountInfo<'a>,
+    pub order: AccountInfo<'a>,
+    pub order_escrow: AccountInfo<'a>,
+    pub mint_in: AccountInfo<'a>,
+    pub token_program_in: AccountInfo<'a>,
+}
+
+pub fn cancel_limit_order<'a>(
+    accounts: CancelLimitOrderAccounts<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::CancelLimitOrder,
+        Some(&accounts.owner),
+        &[
+            &accounts.owner_token_in,
+            &accounts.order,
+            &accounts.order_escrow,
+            &accounts.mint_in,
+            &accounts.token_program_in,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::FillOrders`.
+pub struct FillOrdersAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub keeper: AccountInfo<'a>,
+    pub keeper_token_out: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_vault_in: AccountInfo<'a>,
+    pub pool_vault_out: AccountInfo<'a>,
+    pub mint_in: AccountInfo<'a>,
+    pub mint_out: AccountInfo<'a>,
+    pub token_program_in: AccountInfo<'a>,
+    pub token_program_out: AccountInfo<'a>,
+    /// Twamm and its token accs a and b for pools with a twamm, then order, order escrow acc,
+    /// owner token out acc and owner for each order.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn fill_orders<'a>(
+    accounts: FillOrdersAccounts<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::FillOrders,
+        Some(&accounts.keeper),
+        &[
+            &accounts.keeper_token_out,
+            &accounts.pool,
+            &accounts.pool_vault_in,
+            &accounts.pool_vault_out,
+            &accounts.mint_in,
+            &accounts.mint_out,
+            &accounts.token_program_in,
+            &accounts.token_program_out,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::InitializeTwamm`.
+pub struct InitializeTwammAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub user: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub twamm: AccountInfo<'a>,
+    pub twamm_vault_a: AccountInfo<'a>,
+    pub twamm_vault_b: AccountInfo<'a>,
+    pub mint_a: AccountInfo<'a>,
+    pub mint_b: AccountInfo<'a>,
+    pub token_program_a: AccountInfo<'a>,
+    pub token_program_b: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub sysvar_rent: AccountInfo<'a>,
+    pub associated_token_program: AccountInfo<'a>,
+}
+
+pub fn initialize_twamm<'a>(
+    accounts: InitializeTwammAccounts<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::InitializeTwamm,
+        Some(&accounts.user),
+        &[
+            &accounts.pool,
+            &accounts.twamm,
+            &accounts.twamm_vault_a,
+            &accounts.twamm_vault_b,
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+            &accounts.system_program,
+            &accounts.sysvar_rent,
+            &accounts.associated_token_program,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::PlaceLongTermOrder`.
+pub struct PlaceLongTermOrderAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub owner_token_in: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub twamm: AccountInfo<'a>,
+    pub order: AccountInfo<'a>,
+    pub twamm_vault_a: AccountInfo<'a>,
+    pub twamm_vault_b: AccountInfo<'a>,
+    pub pool_vault_a: AccountInfo<'a>,
+    pub pool_vault_b: AccountInfo<'a>,
+    pub mint_a: AccountInfo<'a>,
+    pub mint_b: AccountInfo<'a>,
+    pub token_program_a: AccountInfo<'a>,
+    pub token_program_b: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub expired_bucket: AccountInfo<'a>,
+    /// Allowlist or ticket, for permissioned pools.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn place_long_term_order<'a>(
+    accounts: PlaceLongTermOrderAccounts<'a>,
+    nonce: u64,
+    amount_in: u64,
+    a_to_b: bool,
+    num_slots: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::PlaceLongTermOrder {
+            nonce,
+            amount_in,
+            a_to_b,
+            num_slots,
+        },
+        Some(&accounts.owner),
+        &[
+            &accounts.owner_token_in,
+            &accounts.pool,
+            &accounts.twamm,
+            &accounts.order,
+            &accounts.twamm_vault_a,
+            &accounts.twamm_vault_b,
+            &accounts.pool_vault_a,
+            &accounts.pool_vault_b,
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+            &accounts.system_program,
+            &accounts.expired_bucket,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::WithdrawLongTermOrder`.
+pub struct WithdrawLongTermOrderAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub owner_token_a: AccountInfo<'a>,
+    pub owner_token_b: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub twamm: AccountInfo<'a>,
+    pub order: AccountInfo<'a>,
+    pub twamm_vault_a: AccountInfo<'a>,
+    pub twamm_vault_b: AccountInfo<'a>,
+    pub pool_vault_a: AccountInfo<'a>,
+    pub pool_vault_b: AccountInfo<'a>,
+    pub mint_a: AccountInfo<'a>,
+    pub mint_b: AccountInfo<'a>,
+    pub token_program_a: AccountInfo<'a>,
+    pub token_program_b: AccountInfo<'a>,
+    /// The order's expired bucket, once moved out of the twamm's ring.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn withdraw_long_term_order<'a>(
+    accounts: WithdrawLongTermOrderAccounts<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::WithdrawLongTermOrder,
+        Some(&accounts.owner),
+        &[
+            &accounts.owner_token_a,
+            &accounts.owner_token_b,
+            &accounts.pool,
+            &accounts.twamm,
+            &accounts.order,
+            &accounts.twamm_vault_a,
+            &accounts.twamm_vault_b,
+            &accounts.pool_vault_a,
+            &accounts.pool_vault_b,
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::CancelLongTermOrder`, same as WithdrawLongTermOrder.
+pub type CancelLongTermOrderAccounts<'a> = WithdrawLongTermOrderAccounts<'a>;
+
+pub fn cancel_long_term_order<'a>(
+    accounts: CancelLongTermOrderAccounts<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::CancelLongTermOrder,
+        Some(&accounts.owner),
+        &[
+            &accounts.owner_token_a,
+            &accounts.owner_token_b,
+            &accounts.pool,
+            &accounts.twamm,
+            &accounts.order,
+            &accounts.twamm_vault_a,
+            &accounts.twamm_vault_b,
+            &accounts.pool_vault_a,
+            &accounts.pool_vault_b,
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::CreateFarm`.
+pub struct CreateFarmAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub authority: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub farm: AccountInfo<'a>,
+    pub farm_vault: AccountInfo<'a>,
+    pub pool_mint: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub sysvar_rent: AccountInfo<'a>,
+    pub associated_token_program: AccountInfo<'a>,
+    /// Reward mint, authority reward token acc, farm reward token acc and reward token program for
+    /// each reward.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn create_farm<'a>(
+    accounts: CreateFarmAccounts<'a>,
+    nonce: u64,
+    emission_rates: Vec<u64>,
+    start_slot: u64,
+    end_slot: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::CreateFarm {
+            nonce,
+            emission_rates,
+            start_slot,
+            end_slot,
+        },
+        Some(&accounts.authority),
+        &[
+            &accounts.pool,
+            &accounts.farm,
+            &accounts.farm_vault,
+            &accounts.pool_mint,
+            &accounts.token_program,
+            &accounts.system_program,
+            &accounts.sysvar_rent,
+            &accounts.associated_token_program,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::Stake`.
+pub struct StakeAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub owner_pool_token: AccountInfo<'a>,
+    pub farm: AccountInfo<'a>,
+    pub stake: AccountInfo<'a>,
+    pub farm_vault: AccountInfo<'a>,
+    pub pool_mint: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+}
+
+pub fn stake<'a>(
+    accounts: StakeAccounts<'a>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::Stake { amount },
+        Some(&accounts.owner),
+        &[
+            &accounts.owner_pool_token,
+            &accounts.farm,
+            &accounts.stake,
+            &accounts.farm_vault,
+            &accounts.pool_mint,
+            &accounts.token_program,
+            &accounts.system_program,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::Unstake`.
+pub struct UnstakeAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub owner_pool_token: AccountInfo<'a>,
+    pub farm: AccountInfo<'a>,
+    pub stake: AccountInfo<'a>,
+    pub farm_vault: AccountInfo<'a>,
+    pub pool_mint: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+}
+
+pub fn unstake<'a>(
+    accounts: UnstakeAccounts<'a>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::Unstake { amount },
+        Some(&accounts.owner),
+        &[
+            &accounts.owner_pool_token,
+            &accounts.farm,
+            &accounts.stake,
+            &accounts.farm_vault,
+            &accounts.pool_mint,
+            &accounts.token_program,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::Harvest`.
+pub struct HarvestAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub farm: AccountInfo<'a>,
+    pub stake: AccountInfo<'a>,
+    /// Reward mint, farm reward token acc, owner reward token acc and reward token program for each
+    /// farm reward.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn harvest<'a>(accounts: HarvestAccounts<'a>, signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::Harvest,
+        Some(&accounts.owner),
+        &[&accounts.farm, &accounts.stake],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::ReclaimFarmRewards`.
+pub struct ReclaimFarmRewardsAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub authority: AccountInfo<'a>,
+    pub farm: AccountInfo<'a>,
+    /// Reward mint, farm reward token acc, authority reward token acc and reward token program for
+    /// each farm reward.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn reclaim_farm_rewards<'a>(
+    accounts: ReclaimFarmRewardsAccounts<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::ReclaimFarmRewards,
+        Some(&accounts.authority),
+        &[&accounts.farm],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::InitializeConfig`.
+pub struct InitializeConfigAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub admin: AccountInfo<'a>,
+    pub config: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub program_data: AccountInfo<'a>,
+}
+
+pub fn initialize_config<'a>(
+    accounts: InitializeConfigAccounts<'a>,
+    fee_tiers: Vec<u16>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::InitializeConfig { fee_tiers },
+        Some(&accounts.admin),
+        &[
+            &accounts.config,
+            &accounts.system_program,
+            &accounts.program_data,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::SetFeeTiers`.
+pub struct SetFeeTiersAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub admin: AccountInfo<'a>,
+    pub config: AccountInfo<'a>,
+}
+
+pub fn set_fee_tiers<'a>(
+    accounts: SetFeeTiersAccounts<'a>,
+    fee_tiers: Vec<u16>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::SetFeeTiers { fee_tiers },
+        Some(&accounts.admin),
+        &[&accounts.config],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::CreateAllowlist`.
+pub struct CreateAllowlistAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub authority: AccountInfo<'a>,
+    pub allowlist: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+}
+
+pub fn create_allowlist<'a>(
+    accounts: CreateAllowlistAccounts<'a>,
+    nonce: u64,
+    capacity: u32,
+    members: Vec<Pubkey>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::CreateAllowlist {
+            nonce,
+            capacity,
+            members,
+        },
+        Some(&accounts.authority),
+        &[&accounts.allowlist, &accounts.system_program],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::UpdateAllowlist`.
+pub struct UpdateAllowlistAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub authority: AccountInfo<'a>,
+    pub allowlist: AccountInfo<'a>,
+}
+
+pub fn update_allowlist<'a>(
+    accounts: UpdateAllowlistAccounts<'a>,
+    add: Vec<Pubkey>,
+    remove: Vec<Pubkey>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::UpdateAllowlist { add, remove },
+        Some(&accounts.authority),
+        &[&accounts.allowlist],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::ProveAllowlist`.
+pub struct ProveAllowlistAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub user: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub ticket: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+}
+
+pub fn prove_allowlist<'a>(
+    accounts: ProveAllowlistAccounts<'a>,
+    proof: Vec<[u8; 32]>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::ProveAllowlist { proof },
+        Some(&accounts.user),
+        &[&accounts.pool, &accounts.ticket, &accounts.system_program],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::Sync`.
+pub struct SyncAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_vault_a: AccountInfo<'a>,
+    pub pool_vault_b: AccountInfo<'a>,
+    pub mint_a: AccountInfo<'a>,
+    pub mint_b: AccountInfo<'a>,
+    pub token_program_a: AccountInfo<'a>,
+    pub token_program_b: AccountInfo<'a>,
+    /// Twamm and its token accs a and b, for pools with a twamm.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn sync<'a>(accounts: SyncAccounts<'a>, signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::Sync,
+        None,
+        &[
+            &accounts.pool,
+            &accounts.pool_vault_a,
+            &accounts.pool_vault_b,
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::Skim`.
+pub struct SkimAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_vault_a: AccountInfo<'a>,
+    pub pool_vault_b: AccountInfo<'a>,
+    pub recipient_token_a: AccountInfo<'a>,
+    pub recipient_token_b: AccountInfo<'a>,
+    pub mint_a: AccountInfo<'a>,
+    pub mint_b: AccountInfo<'a>,
+    pub token_program_a: AccountInfo<'a>,
+    pub token_program_b: AccountInfo<'a>,
+    /// Twamm and its token accs a and b, for pools with a twamm.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn skim<'a>(accounts: SkimAccounts<'a>, signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::Skim,
+        None,
+        &[
+            &accounts.pool,
+            &accounts.pool_vault_a,
+            &accounts.pool_vault_b,
+            &accounts.recipient_token_a,
+            &accounts.recipient_token_b,
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::RecoverTokens`.
+pub struct RecoverTokensAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub admin: AccountInfo<'a>,
+    pub config: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_token: AccountInfo<'a>,
+    pub recipient_token: AccountInfo<'a>,
+    pub mint: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+}
+
+pub fn recover_tokens<'a>(
+    accounts: RecoverTokensAccounts<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::RecoverTokens,
+        Some(&accounts.admin),
+        &[
+            &accounts.config,
+            &accounts.pool,
+            &accounts.pool_token,
+            &accounts.recipient_token,
+            &accounts.mint,
+            &accounts.token_program,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::ClosePool`.
+pub struct ClosePoolAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub admin: AccountInfo<'a>,
+    pub config: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_vault_a: AccountInfo<'a>,
+    pub pool_vault_b: AccountInfo<'a>,
+    pub pool_mint: AccountInfo<'a>,
+    pub token_program_a: AccountInfo<'a>,
+    pub token_program_b: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+    pub twamm: AccountInfo<'a>,
+    pub rent_receiver: AccountInfo<'a>,
+    pub mint_a: AccountInfo<'a>,
+    pub mint_b: AccountInfo<'a>,
+    pub twamm_vault_a: AccountInfo<'a>,
+    pub twamm_vault_b: AccountInfo<'a>,
+    pub lp_metadata: AccountInfo<'a>,
+    pub oracle: AccountInfo<'a>,
+    pub registry_page: AccountInfo<'a>,
+    pub mint_index_page_a: AccountInfo<'a>,
+    pub mint_index_page_b: AccountInfo<'a>,
+}
+
+pub fn close_pool<'a>(
+    accounts: ClosePoolAccounts<'a>,
+    registry_page: u64,
+    mint_a_page: u64,
+    mint_b_page: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::ClosePool {
+            registry_page,
+            mint_a_page,
+            mint_b_page,
+        },
+        Some(&accounts.admin),
+        &[
+            &accounts.config,
+            &accounts.pool,
+            &accounts.pool_vault_a,
+            &accounts.pool_vault_b,
+            &accounts.pool_mint,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+            &accounts.token_program,
+            &accounts.twamm,
+            &accounts.rent_receiver,
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.twamm_vault_a,
+            &accounts.twamm_vault_b,
+            &accounts.lp_metadata,
+            &accounts.oracle,
+            &accounts.registry_page,
+            &accounts.mint_index_page_a,
+            &accounts.mint_index_page_b,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::DepositExactPoolTokens`, same as Deposit.
+pub type DepositExactPoolTokensAccounts<'a> = DepositAccounts<'a>;
+
+pub fn deposit_exact_pool_tokens<'a>(
+    accounts: DepositExactPoolTokensAccounts<'a>,
+    pool_token_amount: u64,
+    max_token_a_amount: u64,
+    max_token_b_amount: u64,
+    native_sol: bool,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::DepositExactPoolTokens {
+            pool_token_amount,
+            max_token_a_amount,
+            max_token_b_amount,
+            native_sol,
+        },
+        Some(&accounts.user),
+        &[
+            &accounts.user_token_a,
+            &accounts.user_token_b,
+            &accounts.user_pool_token,
+            &accounts.pool,
+            &accounts.pool_vault_a,
+            &accounts.pool_vault_b,
+            &accounts.pool_mint,
+            &accounts.token_program,
+            &accounts.system_program,
+            &accounts.sysvar_rent,
+            &accounts.associated_token_program,
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::UpdateLpMetadata`.
+pub struct UpdateLpMetadataAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub admin: AccountInfo<'a>,
+    pub config: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub lp_metadata: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+}
+
+pub fn update_lp_metadata<'a>(
+    accounts: UpdateLpMetadataAccounts<'a>,
+    name: String,
+    symbol: String,
+    uri: String,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::UpdateLpMetadata { name, symbol, uri },
+        Some(&accounts.admin),
+        &[
+            &accounts.config,
+            &accounts.pool,
+            &accounts.lp_metadata,
+            &accounts.system_program,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::ConvertToPosition`.
+pub struct ConvertToPositionAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub user: AccountInfo<'a>,
+    pub user_pool_token: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_mint: AccountInfo<'a>,
+    pub position_mint: AccountInfo<'a>,
+    pub user_position_token: AccountInfo<'a>,
+    pub position: AccountInfo<'a>,
+    pub position_pool_token: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub sysvar_rent: AccountInfo<'a>,
+    pub associated_token_program: AccountInfo<'a>,
+}
+
+pub fn convert_to_position<'a>(
+    accounts: ConvertToPositionAccounts<'a>,
+    nonce: u64,
+    pool_token_amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::ConvertToPosition {
+            nonce,
+            pool_token_amount,
+        },
+        Some(&accounts.user),
+        &[
+            &accounts.user_pool_token,
+            &accounts.pool,
+            &accounts.pool_mint,
+            &accounts.position_mint,
+            &accounts.user_position_token,
+            &accounts.position,
+            &accounts.position_pool_token,
+            &accounts.token_program,
+            &accounts.system_program,
+            &accounts.sysvar_rent,
+            &accounts.associated_token_program,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::ConvertFromPosition`.
+pub struct ConvertFromPositionAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub holder: AccountInfo<'a>,
+    pub holder_position_token: AccountInfo<'a>,
+    pub holder_pool_token: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_mint: AccountInfo<'a>,
+    pub position_mint: AccountInfo<'a>,
+    pub position: AccountInfo<'a>,
+    pub position_pool_token: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub sysvar_rent: AccountInfo<'a>,
+    pub associated_token_program: AccountInfo<'a>,
+}
+
+pub fn convert_from_position<'a>(
+    accounts: ConvertFromPositionAccounts<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::ConvertFromPosition,
+        Some(&accounts.holder),
+        &[
+            &accounts.holder_position_token,
+            &accounts.holder_pool_token,
+            &accounts.pool,
+            &accounts.pool_mint,
+            &accounts.position_mint,
+            &accounts.position,
+            &accounts.position_pool_token,
+            &accounts.token_program,
+            &accounts.system_program,
+            &accounts.sysvar_rent,
+            &accounts.associated_token_program,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::DepositToPosition`.
+pub struct DepositToPositionAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub user: AccountInfo<'a>,
+    pub position_mint: AccountInfo<'a>,
+    pub user_position_token: AccountInfo<'a>,
+    pub position: AccountInfo<'a>,
+    pub position_pool_token: AccountInfo<'a>,
+    pub user_token_a: AccountInfo<'a>,
+    pub user_token_b: AccountInfo<'a>,
+    pub user_pool_token: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_vault_a: AccountInfo<'a>,
+    pub pool_vault_b: AccountInfo<'a>,
+    pub pool_mint: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub sysvar_rent: AccountInfo<'a>,
+    pub associated_token_program: AccountInfo<'a>,
+    pub mint_a: AccountInfo<'a>,
+    pub mint_b: AccountInfo<'a>,
+    pub token_program_a: AccountInfo<'a>,
+    pub token_program_b: AccountInfo<'a>,
+    /// Twamm and its token accs a and b, allowlist or ticket, then hook program, hook authority
+    /// and hook accounts, as the pool needs.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn deposit_to_position<'a>(
+    accounts: DepositToPositionAccounts<'a>,
+    nonce: u64,
+    token_a_amount: u64,
+    max_token_b_amount: u64,
+    native_sol: bool,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::DepositToPosition {
+            nonce,
+            token_a_amount,
+            max_token_b_amount,
+            native_sol,
+        },
+        Some(&accounts.user),
+        &[
+            &accounts.position_mint,
+            &accounts.user_position_token,
+            &accounts.position,
+            &accounts.position_pool_token,
+            &accounts.user_token_a,
+            &accounts.user_token_b,
+            &accounts.user_pool_token,
+            &accounts.pool,
+            &accounts.pool_vault_a,
+            &accounts.pool_vault_b,
+            &accounts.pool_mint,
+            &accounts.token_program,
+            &accounts.system_program,
+            &accounts.sysvar_rent,
+            &accounts.associated_token_program,
+            &accounts.mint_a,
+            &accounts.mint_b,
+            &accounts.token_program_a,
+            &accounts.token_program_b,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::LockLiquidity`.
+pub struct LockLiquidityAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub user: AccountInfo<'a>,
+    pub user_pool_token: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_mint: AccountInfo<'a>,
+    pub pool_lockers: AccountInfo<'a>,
+    pub locker: AccountInfo<'a>,
+    pub locker_pool_token: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub sysvar_rent: AccountInfo<'a>,
+    pub associated_token_program: AccountInfo<'a>,
+}
+
+pub fn lock_liquidity<'a>(
+    accounts: LockLiquidityAccounts<'a>,
+    amount: u64,
+    unlock_timestamp: i64,
+    vesting_end_timestamp: Option<i64>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::LockLiquidity {
+            amount,
+            unlock_timestamp,
+            vesting_end_timestamp,
+        },
+        Some(&accounts.user),
+        &[
+            &accounts.user_pool_token,
+            &accounts.pool,
+            &accounts.pool_mint,
+            &accounts.pool_lockers,
+            &accounts.locker,
+            &accounts.locker_pool_token,
+            &accounts.token_program,
+            &accounts.system_program,
+            &accounts.sysvar_rent,
+            &accounts.associated_token_program,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::UnlockLiquidity`.
+pub struct UnlockLiquidityAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub owner_pool_token: AccountInfo<'a>,
+    pub locker: AccountInfo<'a>,
+    pub locker_pool_token: AccountInfo<'a>,
+    pub token_program: AccountInfo<'a>,
+}
+
+pub fn unlock_liquidity<'a>(
+    accounts: UnlockLiquidityAccounts<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::UnlockLiquidity,
+        Some(&accounts.owner),
+        &[
+            &accounts.owner_pool_token,
+            &accounts.locker,
+            &accounts.locker_pool_token,
+            &accounts.token_program,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::UpdateOracle`.
+pub struct UpdateOracleAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub payer: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub oracle: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+}
+
+pub fn update_oracle<'a>(
+    accounts: UpdateOracleAccounts<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::UpdateOracle,
+        Some(&accounts.payer),
+        &[&accounts.pool, &accounts.oracle, &accounts.system_program],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::PlaceConditional`.
+pub struct PlaceConditionalAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub owner_token_in: AccountInfo<'a>,
+    pub owner_token_out: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub order: AccountInfo<'a>,
+    pub order_escrow: AccountInfo<'a>,
+    pub mint_in: AccountInfo<'a>,
+    pub mint_out: AccountInfo<'a>,
+    pub token_program_in: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub sysvar_rent: AccountInfo<'a>,
+    pub associated_token_program: AccountInfo<'a>,
+    /// Allowlist or ticket, for permissioned pools.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn place_conditional<'a>(
+    accounts: PlaceConditionalAccounts<'a>,
+    nonce: u64,
+    amount_in: u64,
+    min_amount_out: u64,
+    trigger_price: u128,
+    take_profit: bool,
+    trigger_source: TriggerSource,
+    expiry_timestamp: i64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::PlaceConditional {
+            nonce,
+            amount_in,
+            min_amount_out,
+            trigger_price,
+            take_profit,
+            trigger_source,
+            expiry_timestamp,
+        },
+        Some(&accounts.owner),
+        &[
+            &accounts.owner_token_in,
+            &accounts.owner_token_out,
+            &accounts.pool,
+            &accounts.order,
+            &accounts.order_escrow,
+            &accounts.mint_in,
+            &accounts.mint_out,
+            &accounts.token_program_in,
+            &accounts.system_program,
+            &accounts.sysvar_rent,
+            &accounts.associated_token_program,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::CancelConditional`.
+pub struct CancelConditionalAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub owner_token_in: AccountInfo<'a>,
+    pub order: AccountInfo<'a>,
+    pub order_escrow: AccountInfo<'a>,
+    pub mint_in: AccountInfo<'a>,
+    pub token_program_in: AccountInfo<'a>,
+}
+
+pub fn cancel_conditional<'a>(
+    accounts: CancelConditionalAccounts<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::CancelConditional,
+        Some(&accounts.owner),
+        &[
+            &accounts.owner_token_in,
+            &accounts.order,
+            &accounts.order_escrow,
+            &accounts.mint_in,
+            &accounts.token_program_in,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::ExecuteConditional`.
+pub struct ExecuteConditionalAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub keeper: AccountInfo<'a>,
+    pub keeper_token_in: AccountInfo<'a>,
+    pub order: AccountInfo<'a>,
+    pub order_escrow: AccountInfo<'a>,
+    pub owner_token_out: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub oracle: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_vault_src: AccountInfo<'a>,
+    pub pool_vault_dst: AccountInfo<'a>,
+    pub token_program_src: AccountInfo<'a>,
+    pub token_program_dst: AccountInfo<'a>,
+    pub mint_src: AccountInfo<'a>,
+    pub mint_dst: AccountInfo<'a>,
+    /// Twamm and its token accs a and b, allowlist or ticket, then hook program, hook authority
+    /// and hook accounts, as the pool needs.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn execute_conditional<'a>(
+    accounts: ExecuteConditionalAccounts<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::ExecuteConditional,
+        Some(&accounts.keeper),
+        &[
+            &accounts.keeper_token_in,
+            &accounts.order,
+            &accounts.order_escrow,
+            &accounts.owner_token_out,
+            &accounts.owner,
+            &accounts.oracle,
+            &accounts.pool,
+            &accounts.pool_vault_src,
+            &accounts.pool_vault_dst,
+            &accounts.token_program_src,
+            &accounts.token_program_dst,
+            &accounts.mint_src,
+            &accounts.mint_dst,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::CreateDca`.
+pub struct CreateDcaAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub owner_token_in: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub dca: AccountInfo<'a>,
+    pub dca_escrow: AccountInfo<'a>,
+    pub mint_in: AccountInfo<'a>,
+    pub mint_out: AccountInfo<'a>,
+    pub token_program_in: AccountInfo<'a>,
+    pub system_program: AccountInfo<'a>,
+    pub sysvar_rent: AccountInfo<'a>,
+    pub associated_token_program: AccountInfo<'a>,
+    /// Allowlist or ticket, for permissioned pools.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn create_dca<'a>(
+    accounts: CreateDcaAccounts<'a>,
+    nonce: u64,
+    amount_per_cycle: u64,
+    interval_seconds: u32,
+    cycles: u32,
+    min_amount_out_per_cycle: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::CreateDca {
+            nonce,
+            amount_per_cycle,
+            interval_seconds,
+            cycles,
+            min_amount_out_per_cycle,
+        },
+        Some(&accounts.owner),
+        &[
+            &accounts.owner_token_in,
+            &accounts.pool,
+            &accounts.dca,
+            &accounts.dca_escrow,
+            &accounts.mint_in,
+            &accounts.mint_out,
+            &accounts.token_program_in,
+            &accounts.system_program,
+            &accounts.sysvar_rent,
+            &accounts.associated_token_program,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::RunDca`.
+pub struct RunDcaAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub keeper: AccountInfo<'a>,
+    pub keeper_token_in: AccountInfo<'a>,
+    pub dca: AccountInfo<'a>,
+    pub dca_escrow: AccountInfo<'a>,
+    pub owner_token_out: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub pool: AccountInfo<'a>,
+    pub pool_vault_src: AccountInfo<'a>,
+    pub pool_vault_dst: AccountInfo<'a>,
+    pub token_program_src: AccountInfo<'a>,
+    pub token_program_dst: AccountInfo<'a>,
+    pub mint_src: AccountInfo<'a>,
+    pub mint_dst: AccountInfo<'a>,
+    /// Twamm and its token accs a and b, allowlist or ticket, then hook program, hook authority
+    /// and hook accounts, as the pool needs.
+    pub remaining_accounts: Vec<AccountInfo<'a>>,
+}
+
+pub fn run_dca<'a>(accounts: RunDcaAccounts<'a>, signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::RunDca,
+        Some(&accounts.keeper),
+        &[
+            &accounts.keeper_token_in,
+            &accounts.dca,
+            &accounts.dca_escrow,
+            &accounts.owner_token_out,
+            &accounts.owner,
+            &accounts.pool,
+            &accounts.pool_vault_src,
+            &accounts.pool_vault_dst,
+            &accounts.token_program_src,
+            &accounts.token_program_dst,
+            &accounts.mint_src,
+            &accounts.mint_dst,
+        ],
+        &accounts.remaining_accounts,
+        signer_seeds,
+    )
+}
+
+/// Accounts of `ChudexInstruction::CloseDca`.
+pub struct CloseDcaAccounts<'a> {
+    /// The dex program.
+    pub program: AccountInfo<'a>,
+    pub owner: AccountInfo<'a>,
+    pub owner_token_in: AccountInfo<'a>,
+    pub dca: AccountInfo<'a>,
+    pub dca_escrow: AccountInfo<'a>,
+    pub mint_in: AccountInfo<'a>,
+    pub token_program_in: AccountInfo<'a>,
+}
+
+pub fn close_dca<'a>(accounts: CloseDcaAccounts<'a>, signer_seeds: &[&[&[u8]]]) -> ProgramResult {
+    invoke_chudex(
+        &accounts.program,
+        &ChudexInstruction::CloseDca,
+        Some(&accounts.owner),
+        &[
+            &accounts.owner_token_in,
+            &accounts.dca,
+            &accounts.dca_escrow,
+            &accounts.mint_in,
+            &accounts.token_program_in,
+        ],
+        &[],
+        signer_seeds,
+    )
+}
@@ -0,0 +1,337 @@
+use crate::error::ChudexError;
+
+/// Most fee decimals a pool can use.
+pub const MAX_FEE_DECIMALS: u64 = 18;
+
+/// Checks a fee of `fee / 10^fee_decimals` is under 100%.
+pub fn check_fee(fee: u64, fee_decimals: u64) -> Result<(), ChudexError> {
+    if fee_decimals > MAX_FEE_DECIMALS || fee >= 10u64.pow(fee_decimals as u32) {
+        return Err(ChudexError::InvalidFee);
+    }
+    Ok(())
+}
+
+/// Fee charged on `amount` given a fee of `fee / 10^fee_decimals`.
+pub fn fee_amount(amount: u64, fee: u64, fee_decimals: u64) -> Result<u64, ChudexError> {
+    let denominator = 10u128
+        .checked_pow(fee_decimals as u32)
+        .ok_or(ChudexError::CalculationFailure)?;
+    let fee_amount = (amount as u128)
+        .checked_mul(fee as u128)
+        .ok_or(ChudexError::CalculationFailure)?
+        / denominator;
+    u64::try_from(fee_amount).map_err(|_| ChudexError::CalculationFailure)
+}
+
+/// Constant product swap. Returns (amount out, fee taken from amount in).
+pub fn swap(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee: u64,
+    fee_decimals: u64,
+) -> Result<(u64, u64), ChudexError> {
+    let fee_amount = fee_amount(amount_in, fee, fee_decimals)?;
+    let amount_in_after_fee = amount_in
+        .checked_sub(fee_amount)
+        .ok_or(ChudexError::CalculationFailure)?;
+
+    // x * y = k => dy = y * dx / (x + dx)
+    let numerator = (reserve_out as u128)
+        .checked_mul(amount_in_after_fee as u128)
+        .ok_or(ChudexError::CalculationFailure)?;
+    let denominator = (reserve_in as u128)
+        .checked_add(amount_in_after_fee as u128)
+        .ok_or(ChudexError::CalculationFailure)?;
+    if denominator == 0 {
+        return Err(ChudexError::CalculationFailure);
+    }
+    let amount_out =
+        u64::try_from(numerator / denominator).map_err(|_| ChudexError::CalculationFailure)?;
+
+    Ok((amount_out, fee_amount))
+}
+
+/// Share of `reserve` owned by `pool_token_amount` out of `pool_token_supply`, rounded down.
+pub fn pool_tokens_to_reserve(
+    pool_token_amount: u64,
+    pool_token_supply: u64,
+    reserve: u64,
+) -> Result<u64, ChudexError> {
+    if pool_token_supply == 0 {
+        return Err(ChudexError::CalculationFailure);
+    }
+    let amount = (pool_token_amount as u128)
+        .checked_mul(reserve as u128)
+        .ok_or(ChudexError::CalculationFailure)?
+        / pool_token_supply as u128;
+    u64::try_from(amount).map_err(|_| ChudexError::CalculationFailure)
+}
+
+/// Pool tokens that adding `amount` to `reserve` is worth out of `pool_token_supply`,
+/// rounded down. Used for what a depositor gets minted.
+pub fn reserve_to_pool_tokens(
+    amount: u64,
+    reserve: u64,
+    pool_token_supply: u64,
+) -> Result<u64, ChudexError> {
+    if reserve == 0 {
+        return Err(ChudexError::CalculationFailure);
+    }
+    let pool_token_amount = (amount as u128)
+        .checked_mul(pool_token_supply as u128)
+        .ok_or(ChudexError::CalculationFailure)?
+        / reserve as u128;
+    u64::try_from(pool_token_amount).map_err(|_| ChudexError::CalculationFailure)
+}
+
+/// Integer square root, rounded down.
+pub fn sqrt(value: u128) -> u128 {
+    let mut x = value;
+    // ceil(value / 2) without overflowing
+    let mut y = (value >> 1) + (value & 1);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+/// Spot price of the reserve_in token, reserve_out per reserve_in in Q64.64. reserve_in
+/// can't be zero.
+pub fn price(reserve_in: u64, reserve_out: u64) -> u128 {
+    ((reserve_out as u128) << 64) / reserve_in as u128
+}
+
+/// sqrt(reserve_a * reserve_b) per pool token in Q64.64. Swap fees stay in the reserves,
+/// so it only grows between deposits and withdrawals.
+pub fn liquidity_per_pool_token(
+    reserve_a: u64,
+    reserve_b: u64,
+    pool_token_supply: u64,
+) -> Result<u128, ChudexError> {
+    if pool_token_supply == 0 {
+        return Err(ChudexError::CalculationFailure);
+    }
+    let liquidity = sqrt(reserve_a as u128 * reserve_b as u128);
+    Ok((liquidity << 64) / pool_token_supply as u128)
+}
+
+/// 1.0 in the 18 decimal fixed point the weighted math uses.
+const ONE: i128 = 1_000_000_000_000_000_000;
+/// ln(2) in 18 decimals.
+const LN_2: i128 = 693_147_180_559_945_309;
+/// Relative error bound of `pow` per unit of exponent, 10^-14 like Balancer's LogExpMath.
+const MAX_POW_RELATIVE_ERROR: i128 = 10_000;
+
+/// ln(x) for 0 < x <= 1 in 18 decimals, as k ln 2 + ln m with m in [1, 2), ln m from the
+/// series 2 atanh((m - 1) / (m + 1)).
+fn ln_fixed(x: i128) -> i128 {
+    let mut m = x;
+    let mut k = 0;
+    while m < ONE {
+        m <<= 1;
+        k -= 1;
+    }
+    let z = (m - ONE) * ONE / (m + ONE);
+    let z_squared = z * z / ONE;
+    let mut term = z;
+    let mut series = 0;
+    let mut n = 1;
+    while term != 0 {
+        series += term / n;
+        term = term * z_squared / ONE;
+        n += 2;
+    }
+    k * LN_2 + 2 * series
+}
+
+/// e^x for x <= 0 in 18 decimals, as 2^k e^r with r in [0, ln 2), e^r from its Taylor
+/// series.
+fn exp_fixed(x: i128) -> i128 {
+    // below one unit of the last decimal
+    if x < -41 * ONE {
+        return 0;
+    }
+    let k = -((-x + LN_2 - 1) / LN_2);
+    let r = x - k * LN_2;
+    let mut term = ONE;
+    let mut series = ONE;
+    let mut n = 1;
+    while term != 0 {
+        term = term * r / (ONE * n);
+        series += term;
+        n += 1;
+    }
+    series >> -k
+}
+
+/// base^exponent for 0 < base <= 1 in 18 decimals, rounded up by the error bound.
+fn pow_up(base: i128, exponent: i128) -> Result<i128, ChudexError> {
+    let power = exp_fixed(
+        exponent
+            .checked_mul(ln_fixed(base))
+            .ok_or(ChudexError::CalculationFailure)?
+            / ONE,
+    );
+    let relative_error = (MAX_POW_RELATIVE_ERROR * (exponent / ONE + 1)).min(ONE);
+    Ok(power + power * relative_error / ONE + 1)
+}
+
+/// Weighted product swap, out = balance_out * (1 - (balance_in / (balance_in + in)) ^ (w_in / w_out)).
+/// In 18 decimal fixed point, every step rounded against the trader. Returns (amount out,
+/// fee taken from amount in).
+pub fn weighted_swap(
+    amount_in: u64,
+    balance_in: u64,
+    weight_in: u64,
+    balance_out: u64,
+    weight_out: u64,
+    fee: u64,
+    fee_decimals: u64,
+) -> Result<(u64, u64), ChudexError> {
+    let fee_amount = fee_amount(amount_in, fee, fee_decimals)?;
+    let amount_in_after_fee = amount_in
+        .checked_sub(fee_amount)
+        .ok_or(ChudexError::CalculationFailure)?;
+    if balance_in == 0 || balance_out == 0 || weight_in == 0 || weight_out == 0 {
+        return Err(ChudexError::CalculationFailure);
+    }
+
+    // base rounded up and exponent down keep the power high, so the output low
+    let new_balance_in = balance_in as i128 + amount_in_after_fee as i128;
+    let base = (balance_in as i128 * ONE + new_balance_in - 1) / new_balance_in;
+    let exponent = (weight_in as i128)
+        .checked_mul(ONE)
+        .ok_or(ChudexError::CalculationFailure)?
+        / weight_out as i128;
+    let complement = (ONE - pow_up(base, exponent)?).max(0);
+    let amount_out = (balance_out as i128 * complement / ONE) as u64;
+    if amount_out >= balance_out {
+        return Err(ChudexError::CalculationFailure);
+    }
+
+    Ok((amount_out, fee_amount))
+}
+
+/// Share of `reserve` owned by `pool_token_amount` out of `pool_token_supply`, rounded up.
+/// Used for what a depositor has to pay in.
+pub fn pool_tokens_to_reserve_ceil(
+    pool_token_amount: u64,
+    pool_token_supply: u64,
+    reserve: u64,
+) -> Result<u64, ChudexError> {
+    if pool_token_supply == 0 {
+        return Err(ChudexError::CalculationFailure);
+    }
+    let numerator = (pool_token_amount as u128)
+        .checked_mul(reserve as u128)
+        .ok_or(ChudexError::CalculationFailure)?;
+    let amount = numerator.div_ceil(pool_token_supply as u128);
+    u64::try_from(amount).map_err(|_| ChudexError::CalculationFailure)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deposit_mints_share_of_supply() {
+        // a tenth of the reserve is worth a tenth of the supply
+        assert_eq!(reserve_to_pool_tokens(100, 1_000, 5_000).unwrap(), 500);
+        // rounded down, in the pool's favor
+        assert_eq!(reserve_to_pool_tokens(1, 3, 2).unwrap(), 0);
+        assert_eq!(reserve_to_pool_tokens(2, 3, 2).unwrap(), 1);
+    }
+
+    #[test]
+    fn weighted_swap_rounds_just_below_exact() {
+        // (amount in, balance in, weight in, balance out, weight out, exact out floored)
+        for (amount_in, balance_in, weight_in, balance_out, weight_out, exact) in [
+            (
+                1_000_000,
+                1_000_000_000,
+                50,
+                2_000_000_000,
+                50,
+                1_998_001u64,
+            ),
+            (1_000_000, 1_000_000_000, 80, 1_000_000_000, 20, 3_990_019),
+            (
+                500_000_000,
+                1_000_000_000,
+                20,
+                1_000_000_000,
+                80,
+                96_397_996,
+            ),
+            (
+                1_000_000_000_000_000,
+                1_000_000_000_000_000_000,
+                1,
+                1_000_000_000_000_000_000,
+                3,
+                333_111_283_806_709,
+            ),
+            (123_456_789, 987_654_321, 7, 555_555_555, 13, 34_140_185),
+        ] {
+            let (amount_out, fee) = weighted_swap(
+                amount_in,
+                balance_in,
+                weight_in,
+                balance_out,
+                weight_out,
+                0,
+                4,
+            )
+            .unwrap();
+            assert_eq!(fee, 0);
+            // never above the exact amount, and off by no more than the error bound
+            assert!(amount_out <= exact, "{} > {}", amount_out, exact);
+            assert!(
+                exact - amount_out <= balance_out / 10_000_000_000_000 + 1,
+                "{} too far below {}",
+                amount_out,
+                exact
+            );
+        }
+    }
+
+    #[test]
+    fn weighted_swap_takes_fee_and_favors_pool() {
+        let (amount_out, fee) =
+            weighted_swap(1_000_000, 1_000_000_000, 50, 1_000_000_000, 50, 30, 4).unwrap();
+        assert_eq!(fee, 3_000);
+        // equal weights never pay out more than the constant product curve
+        let (constant_product_out, _) =
+            swap(1_000_000, 1_000_000_000, 1_000_000_000, 30, 4).unwrap();
+        assert!(amount_out <= constant_product_out);
+        assert!(amount_out + 2 >= constant_product_out);
+
+        // nothing in, nothing out
+        assert_eq!(
+            weighted_swap(0, 1_000_000_000, 50, 1_000_000_000, 50, 30, 4).unwrap(),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn weighted_swap_rejects_empty_pool() {
+        assert_eq!(
+            weighted_swap(1_000, 0, 50, 1_000_000, 50, 30, 4),
+            Err(ChudexError::CalculationFailure)
+        );
+        assert_eq!(
+            weighted_swap(1_000, 1_000_000, 0, 1_000_000, 50, 30, 4),
+            Err(ChudexError::CalculationFailure)
+        );
+    }
+
+    #[test]
+    fn deposit_into_empty_reserve_fails() {
+        assert_eq!(
+            reserve_to_pool_tokens(100, 0, 5_000),
+            Err(ChudexError::CalculationFailure)
+        );
+    }
+}
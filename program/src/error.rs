@@ -26,6 +26,32 @@ pub enum ChudexError {
     InvalidAccountData,
     #[error("Deposit amount exceeds limit.")]
     DepositAmountExceedsLimit,
+    #[error("Withdraw amount below minimum.")]
+    WithdrawAmountBelowMinimum,
+    #[error("Exchange amount below minimum.")]
+    ExchangeAmountBelowMinimum,
+    #[error("Calculation failure.")]
+    CalculationFailure,
+    #[error("Invalid tick.")]
+    InvalidTick,
+    #[error("Tick array not found.")]
+    TickArrayNotFound,
+    #[error("Position not empty.")]
+    PositionNotEmpty,
+    #[error("Invalid fee.")]
+    InvalidFee,
+    #[error("User not on the pool's allowlist.")]
+    NotAllowlisted,
+    #[error("Invalid Merkle proof.")]
+    InvalidMerkleProof,
+    #[error("Pool not empty.")]
+    PoolNotEmpty,
+    #[error("Order expired.")]
+    OrderExpired,
+    #[error("Order trigger not reached.")]
+    TriggerNotReached,
+    #[error("Oracle doesn't cover the window.")]
+    OracleWindowUnavailable,
 }
 
 impl From<ChudexError> for ProgramError {
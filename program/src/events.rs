@@ -0,0 +1,268 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+/// Prefix the runtime puts in front of `sol_log_data` output.
+pub const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct PoolInitialized {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub pool_mint: Pubkey,
+    pub fee: u64,
+    pub fee_decimals: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct Deposited {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+    pub pool_token_amount: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub pool_token_supply: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct Withdrawn {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub pool_token_amount: u64,
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub pool_token_supply: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct Swapped {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub mint_in: Pubkey,
+    pub mint_out: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee: u64,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
+
+/// Long-term orders traded against the pool since its last touch, leaving these reserves.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct LongTermOrdersSettled {
+    pub pool: Pubkey,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
+
+/// Reserves set to the vault balances.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct Synced {
+    pub pool: Pubkey,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
+
+/// Pool closed, its account and vaults are gone.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct PoolClosed {
+    pub pool: Pubkey,
+}
+
+/// Events logged by the program. The borsh enum tag doubles as the event discriminator.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum ChudexEvent {
+    PoolInitialized(PoolInitialized),
+    Deposited(Deposited),
+    Withdrawn(Withdrawn),
+    Swapped(Swapped),
+    LongTermOrdersSettled(LongTermOrdersSettled),
+    Synced(Synced),
+    PoolClosed(PoolClosed),
+}
+
+impl ChudexEvent {
+    /// Logs the event as base64 borsh via `sol_log_data`.
+    pub fn emit(&self) {
+        // serializing into a vec can't fail
+        let data = self.try_to_vec().unwrap();
+        sol_log_data(&[&data]);
+    }
+
+    /// Decodes a single `Program data: ...` log line. Returns None for any other line.
+    pub fn from_log(line: &str) -> Option<Self> {
+        let encoded = line.strip_prefix(PROGRAM_DATA_PREFIX)?;
+        // emit only ever logs one field
+        let data = base64::decode(encoded.split_whitespace().next()?).ok()?;
+        Self::try_from_slice(&data).ok()
+    }
+}
+
+/// Decodes every event logged by `program_id` in a transaction's log messages.
+/// Tracks the invoke stack so data logged by other programs in the same transaction
+/// (including ones we CPI into) is skipped.
+pub fn parse_logs<S: AsRef<str>>(program_id: &Pubkey, logs: &[S]) -> Vec<ChudexEvent> {
+    let program_id = program_id.to_string();
+    let mut stack: Vec<String> = vec![];
+    let mut events = vec![];
+
+    for line in logs {
+        let line = line.as_ref();
+        if let Some(rest) = line.strip_prefix("Program ") {
+            let mut parts = rest.split_whitespace();
+            let id = parts.next().unwrap_or_default();
+            match parts.next() {
+                Some("invoke") => {
+                    stack.push(id.to_string());
+                    continue;
+                }
+                Some("success") | Some("failed:") => {
+                    stack.pop();
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        if stack.last() != Some(&program_id) {
+            continue;
+        }
+        if let Some(event) = ChudexEvent::from_log(line) {
+            events.push(event);
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The line the runtime logs for an emitted event.
+    fn data_line(event: &ChudexEvent) -> String {
+        format!(
+            "{}{}",
+            PROGRAM_DATA_PREFIX,
+            base64::encode(event.try_to_vec().unwrap())
+        )
+    }
+
+    fn events() -> Vec<ChudexEvent> {
+        let pool = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        vec![
+            ChudexEvent::PoolInitialized(PoolInitialized {
+                pool,
+                user,
+                mint_a,
+                mint_b,
+                pool_mint: Pubkey::new_unique(),
+                fee: 30,
+                fee_decimals: 4,
+            }),
+            ChudexEvent::Deposited(Deposited {
+                pool,
+                user,
+                token_a_amount: 1_000,
+                token_b_amount: 2_000,
+                pool_token_amount: 1_414,
+                reserve_a: 1_000,
+                reserve_b: 2_000,
+                pool_token_supply: 1_414,
+            }),
+            ChudexEvent::Withdrawn(Withdrawn {
+                pool,
+                user,
+                pool_token_amount: 707,
+                token_a_amount: 500,
+                token_b_amount: 1_000,
+                reserve_a: 500,
+                reserve_b: 1_000,
+                pool_token_supply: 707,
+            }),
+            ChudexEvent::Swapped(Swapped {
+                pool,
+                user,
+                mint_in: mint_a,
+                mint_out: mint_b,
+                amount_in: 100,
+                amount_out: 332,
+                fee: 1,
+                reserve_a: 600,
+                reserve_b: 668,
+            }),
+            ChudexEvent::LongTermOrdersSettled(LongTermOrdersSettled {
+                pool,
+                reserve_a: 650,
+                reserve_b: 617,
+            }),
+            ChudexEvent::Synced(Synced {
+                pool,
+                reserve_a: 700,
+                reserve_b: 668,
+            }),
+            ChudexEvent::PoolClosed(PoolClosed { pool }),
+        ]
+    }
+
+    #[test]
+    fn every_event_round_trips_through_the_logs() {
+        let program_id = Pubkey::new_unique();
+        let events = events();
+        let mut logs = vec![format!("Program {} invoke [1]", program_id)];
+        logs.extend(events.iter().map(data_line));
+        logs.push(format!("Program {} success", program_id));
+
+        for event in &events {
+            assert_eq!(
+                ChudexEvent::from_log(&data_line(event)).as_ref(),
+                Some(event)
+            );
+        }
+        assert_eq!(parse_logs(&program_id, &logs), events);
+    }
+
+    #[test]
+    fn other_lines_and_programs_are_skipped() {
+        let program_id = Pubkey::new_unique();
+        let other_id = Pubkey::new_unique();
+        let synced = ChudexEvent::Synced(Synced {
+            pool: Pubkey::new_unique(),
+            reserve_a: 1,
+            reserve_b: 2,
+        });
+        let logs = vec![
+            format!("Program {} invoke [1]", program_id),
+            "Program log: Instruction: Sync".to_string(),
+            // not base64, and base64 that isn't an event
+            format!("{}not base64!", PROGRAM_DATA_PREFIX),
+            format!("{}{}", PROGRAM_DATA_PREFIX, base64::encode([255, 1, 2])),
+            // a hook or token program's data inside our instruction
+            format!("Program {} invoke [2]", other_id),
+            data_line(&synced),
+            format!("Program {} success", other_id),
+            data_line(&synced),
+            format!(
+                "Program {} consumed 1000 of 200000 compute units",
+                program_id
+            ),
+            format!("Program return: {} AQID", program_id),
+            format!("Program {} success", program_id),
+            // data logged outside of any invocation of ours
+            format!("Program {} invoke [1]", other_id),
+            data_line(&synced),
+            format!("Program {} failed: custom program error: 0x1", other_id),
+        ];
+
+        assert_eq!(ChudexEvent::from_log("Program log: hi"), None);
+        assert_eq!(ChudexEvent::from_log(&logs[2]), None);
+        assert_eq!(ChudexEvent::from_log(&logs[3]), None);
+        assert_eq!(parse_logs(&program_id, &logs), vec![synced]);
+    }
+}
@@ -0,0 +1,128 @@
+//! Hook programs. A pool with a hook program calls it before and after every deposit,
+//! withdraw and exchange, and an error from the hook aborts the operation.
+//!
+//! The hook gets a borsh `HookPayload` as instruction data and the accounts
+//! [signer] hook authority, pda of "chudex_hook_authority" and the pool
+//! pool
+//! then whatever hook accounts the user passed, none of them signing. The hook authority
+//! only ever signs hook calls, so a hook can trust a payload signed by it.
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{error::ChudexError, state::Pool, utils::assert_msg};
+
+pub const AUTHORITY_SEED: &[u8] = b"chudex_hook_authority";
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum HookStage {
+    Before,
+    After,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum HookOperation {
+    Deposit,
+    Withdraw,
+    Exchange { a_to_b: bool },
+}
+
+/// Instruction data of a hook call. Amounts and reserves are in pool mint order.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct HookPayload {
+    pub stage: HookStage,
+    pub operation: HookOperation,
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    /// Tokens deposited or withdrawn, for an exchange the amount in and the amount out.
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+    /// Pool tokens minted or burned, 0 for an exchange.
+    pub pool_token_amount: u64,
+    /// Reserves before the operation for the Before stage, after it for the After stage.
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+}
+
+impl HookPayload {
+    /// Puts amounts and reserves given in vault order into pool mint order, the first
+    /// vault holding `vault_a_mint`.
+    pub fn in_pool_order(mut self, pool: &Pool, vault_a_mint: &Pubkey) -> Self {
+        if pool.mint_a != *vault_a_mint {
+            std::mem::swap(&mut self.token_a_amount, &mut self.token_b_amount);
+            std::mem::swap(&mut self.reserve_a, &mut self.reserve_b);
+        }
+        self
+    }
+}
+
+/// Calls the pool's hook program with `payload`, if it has one. `hook_accounts` are the
+/// hook program, the hook authority and the accounts passed on to the hook.
+pub fn call<'a>(
+    program_id: &Pubkey,
+    pool_ai: &AccountInfo<'a>,
+    pool: &Pool,
+    hook_accounts: &[AccountInfo<'a>],
+    payload: &HookPayload,
+) -> ProgramResult {
+    let hook_program = match &pool.hook_program {
+        Some(hook_program) => hook_program,
+        None => return Ok(()),
+    };
+    let (hook_program_ai, hook_authority_ai, remaining_accounts) = match hook_accounts {
+        [hook_program_ai, hook_authority_ai, remaining_accounts @ ..] => {
+            (hook_program_ai, hook_authority_ai, remaining_accounts)
+        }
+        _ => return Err(ProgramError::NotEnoughAccountKeys),
+    };
+
+    assert_msg(
+        hook_program_ai.key == hook_program,
+        ChudexError::InvalidAccountAddress.into(),
+        "Hook program doesn't match pool",
+    )?;
+
+    // hook authority pda
+    let (hook_authority_key, hook_authority_bump) =
+        Pubkey::find_program_address(&[AUTHORITY_SEED, pool_ai.key.as_ref()], program_id);
+    let hook_authority_seeds = &[AUTHORITY_SEED, pool_ai.key.as_ref(), &[hook_authority_bump]];
+    assert_msg(
+        *hook_authority_ai.key == hook_authority_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "hook authority pda aint right",
+    )?;
+
+    // user signatures don't carry over, the hook can't move the user's tokens
+    let mut account_metas = vec![
+        AccountMeta::new_readonly(hook_authority_key, true),
+        AccountMeta::new_readonly(*pool_ai.key, false),
+    ];
+    account_metas.extend(remaining_accounts.iter().map(|ai| {
+        if ai.is_writable {
+            AccountMeta::new(*ai.key, false)
+        } else {
+            AccountMeta::new_readonly(*ai.key, false)
+        }
+    }));
+    let mut account_infos = vec![hook_authority_ai.clone(), pool_ai.clone()];
+    account_infos.extend_from_slice(remaining_accounts);
+    account_infos.push(hook_program_ai.clone());
+
+    invoke_signed(
+        &Instruction {
+            program_id: *hook_program,
+            accounts: account_metas,
+            data: payload.try_to_vec()?,
+        },
+        &account_infos,
+        &[hook_authority_seeds],
+    )
+}
@@ -1,5 +1,9 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 
+use solana_program::pubkey::Pubkey;
+
+use crate::state::{AllowlistMode, DynamicFeeConfig, TriggerSource};
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub enum ChudexInstruction {
     /// Initializes a new pool. Creates mint and accounts for pool struct and token vaults.
@@ -16,21 +20,47 @@ pub enum ChudexInstruction {
     /// system program
     /// sysvar
     /// associated token program
+    /// token program a (spl token or token-2022, owner of mint a)
+    /// token program b (spl token or token-2022, owner of mint b)
+    /// config (pda of "chudex_config")
+    /// [writable] factory (pda of "chudex_factory")
+    /// [writable] registry page (pda of "chudex_registry", factory pool count / page size)
+    /// [writable] mint index a (pda of "chudex_mint_index", mint a)
+    /// [writable] mint index page a (pda of "chudex_registry", mint a, index count / page size)
+    /// [writable] mint index b
+    /// [writable] mint index page b
+    /// [writable] lp metadata (pda of "chudex_lp_metadata", pool mint)
+    ///
+    /// Records the pool in the factory registry and both mint indexes, creating any registry
+    /// account that doesn't exist yet. Page numbers are little endian u64s.
+    /// fee_tier is the swap fee in basis points and has to be one of the config's fee tiers.
+    /// With dynamic_fee, swaps pay the tier fee plus a part that grows with recent price
+    /// moves, up to the config's max fee in basis points.
+    /// With allowlist, only users on it can deposit, withdraw, trade or place orders.
+    /// With hook_program, deposits, withdrawals and exchanges call it before and after, see
+    /// the hook module, and fail if it does.
     InitializePool {
         // TODO
-        fee: u64,
-        fee_decimals: u64,
+        fee_tier: u16,
+        dynamic_fee: Option<DynamicFeeConfig>,
+        allowlist: Option<AllowlistMode>,
+        hook_program: Option<Pubkey>,
     },
 
     /// Provides liquidity at current exchange rate for both tokens.
     /// Mints pool tokens to user. Creates user pool token acc if needed.
+    /// Settles the pool's long-term orders first.
+    /// Amounts are sent amounts, pool math uses what arrives after any transfer fees.
+    /// With native_sol, the wrapped SOL side is paid in lamports from the user: pass the
+    /// user's temporary wsol account (pda of "chudex_wsol", user) as that side's user token acc.
+    /// Returns a DepositResult, see the client module.
     ///
     /// Accounts:
     /// [signer, writable] user
     /// [writable] user token acc a
     /// [writable] user token acc b
     /// [writable] user pool token acc
-    /// pool
+    /// [writable] pool
     /// [writable] pool token acc a
     /// [writable] pool token acc b
     /// [wriatble] pool token mint
@@ -38,45 +68,855 @@ pub enum ChudexInstruction {
     /// system program
     /// sysvar
     /// associated token program
+    /// mint a
+    /// mint b
+    /// token program a
+    /// token program b
+    /// [writable] twamm (pools with a twamm only)
+    /// [writable] twamm token acc a, in pool mint order
+    /// [writable] twamm token acc b
+    /// allowlist, or the user's allowlist ticket (permissioned pools only)
+    /// hook program (pools with a hook only)
+    /// hook authority (pda of "chudex_hook_authority", pool)
+    /// any accounts the hook needs, passed on without signatures
     Deposit {
         // TODO
         token_a_amount: u64,
         max_token_b_amount: u64,
+        native_sol: bool,
     },
 
     /// Withdraws tokens from pool at current exchange rate.
-    /// Burns pool tokens.
+    /// Burns pool tokens. Settles the pool's long-term orders first.
+    /// With native_sol, the wrapped SOL side is paid out as lamports through the user's
+    /// temporary wsol account, passed as that side's user token acc.
+    /// Returns a WithdrawResult, see the client module.
     ///
     /// Accounts:
-    /// user
-    /// user token acc a
-    /// user token acc b
-    /// user pool token acc
-    /// pool
-    /// pool token acc a
-    /// pool token acc b
-    /// pool token mint
+    /// [signer] user
+    /// [writable] user token acc a
+    /// [writable] user token acc b
+    /// [writable] user pool token acc
+    /// [writable] pool
+    /// [writable] pool token acc a
+    /// [writable] pool token acc b
+    /// [writable] pool token mint
     /// token program
+    /// mint a
+    /// mint b
+    /// token program a
+    /// token program b
+    /// system program (native_sol only)
+    /// sysvar (native_sol only)
+    /// [writable] twamm (pools with a twamm only)
+    /// [writable] twamm token acc a, in pool mint order
+    /// [writable] twamm token acc b
+    /// allowlist, or the user's allowlist ticket (permissioned pools only)
+    /// hook program (pools with a hook only)
+    /// hook authority (pda of "chudex_hook_authority", pool)
+    /// any accounts the hook needs, passed on without signatures
     Withdraw {
         // TODO
         pool_token_amount: u64,
         min_token_a_amount: u64,
         min_token_b_amount: u64,
+        native_sol: bool,
     },
 
-    /// Swaps one token for another.
+    /// Swaps one token for another. Settles the pool's long-term orders first.
+    /// Min amount out applies to what the user receives after any transfer fees.
+    /// With native_sol, a wrapped SOL src or dst is paid in or out as lamports through the
+    /// user's temporary wsol account, passed in place of that user token acc.
+    /// Returns an ExchangeResult, see the client module.
     ///
     /// Accounts:
-    /// user
-    /// user token src acc
-    /// user token dst acc
-    /// pool
-    /// pool token src acc
-    /// pool token dst acc
-    /// token program
+    /// [signer] user
+    /// [writable] user token src acc
+    /// [writable] user token dst acc
+    /// [writable] pool
+    /// [writable] pool token src acc
+    /// [writable] pool token dst acc
+    /// token program src
+    /// token program dst
+    /// mint src
+    /// mint dst
+    /// system program (native_sol only)
+    /// sysvar (native_sol only)
+    /// [writable] twamm (pools with a twamm only)
+    /// [writable] twamm token acc a, in pool mint order
+    /// [writable] twamm token acc b
+    /// allowlist, or the user's allowlist ticket (permissioned pools only)
+    /// hook program (pools with a hook only)
+    /// hook authority (pda of "chudex_hook_authority", pool)
+    /// any accounts the hook needs, passed on without signatures
     Exchange {
         // TODO
         amount_in: u64,
         min_amount_out: u64,
+        native_sol: bool,
+    },
+
+    /// Initializes a weighted pool of 2 to 8 tokens. Creates mint and accounts for pool
+    /// struct and token vaults. Mints are passed sorted by pubkey, weights line up with them.
+    ///
+    /// Accounts:
+    /// [signer] user
+    /// [writable] pool
+    /// [writable] pool token mint
+    /// token program
+    /// system program
+    /// sysvar
+    /// associated token program
+    /// for each token:
+    ///     mint
+    ///     [writable] pool token acc
+    ///     token program (spl token or token-2022, owner of mint)
+    InitializeWeightedPool {
+        weights: Vec<u64>,
+        fee: u64,
+        fee_decimals: u64,
+    },
+
+    /// Provides liquidity in proportion to every token in a weighted pool for an exact
+    /// amount of pool tokens. The first deposit sets the balances to the max amounts.
+    /// Creates user pool token acc if needed.
+    ///
+    /// Accounts:
+    /// [signer, writable] user
+    /// [writable] user pool token acc
+    /// pool
+    /// [writable] pool token mint
+    /// token program
+    /// system program
+    /// sysvar
+    /// associated token program
+    /// for each token, in pool order:
+    ///     [writable] user token acc
+    ///     [writable] pool token acc
+    ///     mint
+    ///     token program
+    WeightedDeposit {
+        pool_token_amount: u64,
+        max_token_amounts: Vec<u64>,
+    },
+
+    /// Burns pool tokens for a proportional share of every token in a weighted pool.
+    ///
+    /// Accounts:
+    /// [signer] user
+    /// [writable] user pool token acc
+    /// pool
+    /// [writable] pool token mint
+    /// token program
+    /// for each token, in pool order:
+    ///     [writable] user token acc
+    ///     [writable] pool token acc
+    ///     mint
+    ///     token program
+    WeightedWithdraw {
+        pool_token_amount: u64,
+        min_token_amounts: Vec<u64>,
+    },
+
+    /// Swaps between any two tokens of a weighted pool.
+    ///
+    /// Accounts:
+    /// [signer] user
+    /// [writable] user token src acc
+    /// [writable] user token dst acc
+    /// pool
+    /// [writable] pool token src acc
+    /// [writable] pool token dst acc
+    /// token program src
+    /// token program dst
+    /// mint src
+    /// mint dst
+    WeightedExchange { amount_in: u64, min_amount_out: u64 },
+
+    /// Initializes a concentrated liquidity pool at a starting sqrt price (Q64.64, price of
+    /// a in b). Creates accounts for pool struct and token vaults. Mints are sorted by pubkey.
+    ///
+    /// Accounts:
+    /// [signer, writable] user
+    /// [writable] pool
+    /// [writable] pool token acc a
+    /// [writable] pool token acc b
+    /// mint a
+    /// mint b
+    /// token program a
+    /// token program b
+    /// system program
+    /// sysvar
+    /// associated token program
+    InitializeClPool {
+        tick_spacing: u16,
+        initial_sqrt_price: u128,
+        fee: u64,
+        fee_decimals: u64,
+    },
+
+    /// Creates the tick array starting at `start_tick`. Anyone can pay for one.
+    ///
+    /// Accounts:
+    /// [signer, writable] user
+    /// pool
+    /// [writable] tick array
+    /// system program
+    InitializeTickArray { start_tick: i32 },
+
+    /// Creates an empty position over [tick_lower, tick_upper] owned by the user.
+    ///
+    /// Accounts:
+    /// [signer, writable] owner
+    /// pool
+    /// [writable] position
+    /// system program
+    OpenPosition { tick_lower: i32, tick_upper: i32 },
+
+    /// Adds liquidity to a position. Token amounts needed follow from the current price,
+    /// max amounts are sent amounts before any transfer fees.
+    ///
+    /// Accounts:
+    /// [signer] owner
+    /// [writable] pool
+    /// [writable] position
+    /// [writable] tick array holding tick lower
+    /// [writable] tick array holding tick upper (may repeat the lower one)
+    /// [writable] user token acc a
+    /// [writable] user token acc b
+    /// [writable] pool token acc a
+    /// [writable] pool token acc b
+    /// mint a
+    /// mint b
+    /// token program a
+    /// token program b
+    IncreaseLiquidity {
+        liquidity: u128,
+        max_token_a_amount: u64,
+        max_token_b_amount: u64,
+    },
+
+    /// Removes liquidity from a position and pays out the tokens. Also brings the
+    /// position's owed fees up to date, so zero liquidity just refreshes fees.
+    ///
+    /// Accounts: same as IncreaseLiquidity
+    DecreaseLiquidity {
+        liquidity: u128,
+        min_token_a_amount: u64,
+        min_token_b_amount: u64,
+    },
+
+    /// Pays out fees owed to a position as of its last liquidity change.
+    ///
+    /// Accounts:
+    /// [signer] owner
+    /// pool
+    /// [writable] position
+    /// [writable] user token acc a
+    /// [writable] user token acc b
+    /// [writable] pool token acc a
+    /// [writable] pool token acc b
+    /// mint a
+    /// mint b
+    /// token program a
+    /// token program b
+    CollectFees,
+
+    /// Closes a position with no liquidity and no fees owed, refunding rent to the owner.
+    ///
+    /// Accounts:
+    /// [signer, writable] owner
+    /// [writable] position
+    ClosePosition,
+
+    /// Swaps an exact amount in against a concentrated pool, crossing ticks as needed.
+    /// Stops early at sqrt_price_limit if given (0 for none), charging only what was swapped.
+    ///
+    /// Accounts:
+    /// [signer] user
+    /// [writable] user token src acc
+    /// [writable] user token dst acc
+    /// [writable] pool
+    /// [writable] pool token src acc
+    /// [writable] pool token dst acc
+    /// token program src
+    /// token program dst
+    /// mint src
+    /// mint dst
+    /// [writable] tick arrays, starting at the current tick in swap direction
+    ClExchange {
+        amount_in: u64,
+        min_amount_out: u64,
+        a_to_b: bool,
+        sqrt_price_limit: u128,
+    },
+
+    /// Escrows amount_in to sell into a pool for at least min_amount_out, the limit price.
+    /// The order pda is keyed by the user's nonce so a user can keep several open.
+    ///
+    /// Accounts:
+    /// [signer, writable] owner
+    /// [writable] owner token in acc
+    /// owner token out acc (receives the fill)
+    /// pool
+    /// [writable] order
+    /// [writable] order escrow acc
+    /// mint in
+    /// mint out
+    /// token program in
+    /// system program
+    /// sysvar
+    /// associated token program
+    /// allowlist, or the owner's allowlist ticket (permissioned pools only)
+    PlaceLimitOrder {
+        nonce: u64,
+        amount_in: u64,
+        min_amount_out: u64,
+    },
+
+    /// Returns escrowed tokens and closes the order.
+    ///
+    /// Accounts:
+    /// [signer, writable] owner
+    /// [writable] owner token in acc
+    /// [writable] order
+    /// [writable] order escrow acc
+    /// mint in
+    /// token program in
+    CancelLimitOrder,
+
+    /// Permissionless crank filling orders whose limit the pool spot price has crossed by
+    /// swapping them through the pool. Orders that can't fill yet are skipped. The keeper
+    /// is paid a fee out of each fill.
+    ///
+    /// Accounts:
+    /// [signer] keeper
+    /// [writable] keeper token out acc
+    /// [writable] pool
+    /// [writable] pool token in acc
+    /// [writable] pool token out acc
+    /// mint in
+    /// mint out
+    /// token program in
+    /// token program out
+    /// [writable] twamm (pools with a twamm only)
+    /// [writable] twamm token acc a
+    /// [writable] twamm token acc b
+    /// for each order, all selling mint in:
+    ///     [writable] order
+    ///     [writable] order escrow acc
+    ///     [writable] owner token out acc
+    ///     [writable] owner
+    FillOrders,
+
+    /// Sets up long-term orders for a pool: the twamm account holding the order pools and
+    /// its vaults for unsold tokens and proceeds. Anyone can pay for it.
+    ///
+    /// Accounts:
+    /// [signer, writable] user
+    /// [writable] pool
+    /// [writable] twamm (pda of "chudex_twamm", pool)
+    /// [writable] twamm token acc a
+    /// [writable] twamm token acc b
+    /// mint a
+    /// mint b
+    /// token program a
+    /// token program b
+    /// system program
+    /// sysvar
+    /// associated token program
+    InitializeTwamm,
+
+    /// Sells amount_in of a (a_to_b) or b into the pool evenly over num_slots, rounded up
+    /// to the next order interval boundary. Settles the pool's orders first.
+    ///
+    /// Accounts:
+    /// [signer, writable] owner
+    /// [writable] owner token in acc
+    /// [writable] pool
+    /// [writable] twamm
+    /// [writable] order
+    /// [writable] twamm token acc a
+    /// [writable] twamm token acc b
+    /// [writable] pool token acc a
+    /// [writable] pool token acc b
+    /// mint a
+    /// mint b
+    /// token program a
+    /// token program b
+    /// system program
+    /// [writable] expired bucket (pda of "chudex_twamm_bucket", twamm, end slot of the bucket
+    ///     now in the order's ring place), created if that bucket still has orders
+    /// allowlist, or the owner's allowlist ticket (permissioned pools only)
+    PlaceLongTermOrder {
+        nonce: u64,
+        amount_in: u64,
+        a_to_b: bool,
+        num_slots: u64,
+    },
+
+    /// Withdraws a long-term order's proceeds so far. Closes the order once it has ended.
+    ///
+    /// Accounts:
+    /// [signer, writable] owner
+    /// [writable] owner token acc a
+    /// [writable] owner token acc b
+    /// [writable] pool
+    /// [writable] twamm
+    /// [writable] order
+    /// [writable] twamm token acc a
+    /// [writable] twamm token acc b
+    /// [writable] pool token acc a
+    /// [writable] pool token acc b
+    /// mint a
+    /// mint b
+    /// token program a
+    /// token program b
+    /// [writable] expired bucket (pda of "chudex_twamm_bucket", twamm, order end slot), once
+    ///     moved out of the twamm's ring
+    WithdrawLongTermOrder,
+
+    /// Stops a long-term order, withdrawing both its unsold tokens and proceeds, and closes it.
+    ///
+    /// Accounts: same as WithdrawLongTermOrder
+    CancelLongTermOrder,
+
+    /// Creates a farm paying rewards to a pool's tokens staked in it. Each reward mint
+    /// streams its emission rate per slot from start_slot to end_slot, split by stake, and is
+    /// funded in full by the authority up front. Takes 1 to 4 reward mints, one per rate.
+    ///
+    /// Accounts:
+    /// [signer, writable] authority
+    /// pool
+    /// [writable] farm (pda of "chudex_farm", pool, authority, nonce)
+    /// [writable] farm pool token acc
+    /// pool token mint
+    /// token program
+    /// system program
+    /// sysvar
+    /// associated token program
+    /// for each reward:
+    ///     reward mint
+    ///     [writable] authority reward token acc
+    ///     [writable] farm reward token acc
+    ///     reward token program (spl token or token-2022, owner of mint)
+    CreateFarm {
+        nonce: u64,
+        emission_rates: Vec<u64>,
+        start_slot: u64,
+        end_slot: u64,
+    },
+
+    /// Stakes pool tokens in a farm. Creates the stake account on first stake.
+    ///
+    /// Accounts:
+    /// [signer, writable] owner
+    /// [writable] owner pool token acc
+    /// [writable] farm
+    /// [writable] stake (pda of "chudex_stake", farm, owner)
+    /// [writable] farm pool token acc
+    /// pool token mint
+    /// token program
+    /// system program
+    Stake { amount: u64 },
+
+    /// Unstakes pool tokens from a farm. Rewards earned stay owed until harvested.
+    ///
+    /// Accounts:
+    /// [signer] owner
+    /// [writable] owner pool token acc
+    /// [writable] farm
+    /// [writable] stake
+    /// [writable] farm pool token acc
+    /// pool token mint
+    /// token program
+    Unstake { amount: u64 },
+
+    /// Pays out every reward owed to a stake. Closes the stake once nothing is staked.
+    ///
+    /// Accounts:
+    /// [signer, writable] owner
+    /// [writable] farm
+    /// [writable] stake
+    /// for each farm reward, in farm order:
+    ///     reward mint
+    ///     [writable] farm reward token acc
+    ///     [writable] owner reward token acc
+    ///     reward token program
+    Harvest,
+
+    /// Sends a farm's authority the rewards emitted while nothing was staked, which no
+    /// stake can ever claim.
+    ///
+    /// Accounts:
+    /// [signer] authority
+    /// [writable] farm
+    /// for each farm reward, in farm order:
+    ///     reward mint
+    ///     [writable] farm reward token acc
+    ///     [writable] authority reward token acc
+    ///     reward token program
+    ReclaimFarmRewards,
+
+    /// Creates the program's config account with the fee tiers pools can be created on, in
+    /// basis points. The caller becomes admin and has to be the program's upgrade authority.
+    ///
+    /// Accounts:
+    /// [signer, writable] admin
+    /// [writable] config (pda of "chudex_config")
+    /// system program
+    /// program data (pda of program id under the upgradeable bpf loader)
+    InitializeConfig { fee_tiers: Vec<u16> },
+
+    /// Replaces the config's fee tiers. Existing pools keep their fee.
+    ///
+    /// Accounts:
+    /// [signer] admin
+    /// [writable] config
+    SetFeeTiers { fee_tiers: Vec<u16> },
+
+    /// Creates an allowlist of up to capacity pubkeys, managed by its authority, for
+    /// permissioned pools to reference.
+    ///
+    /// Accounts:
+    /// [signer, writable] authority
+    /// [writable] allowlist (pda of "chudex_allowlist", authority, nonce)
+    /// system program
+    CreateAllowlist {
+        nonce: u64,
+        capacity: u32,
+        members: Vec<Pubkey>,
+    },
+
+    /// Removes then adds allowlist members.
+    ///
+    /// Accounts:
+    /// [signer] authority
+    /// [writable] allowlist
+    UpdateAllowlist {
+        add: Vec<Pubkey>,
+        remove: Vec<Pubkey>,
+    },
+
+    /// Proves the user is under a pool's allowlist Merkle root, leaves being sha256(user)
+    /// and pairs hashed sorted. Creates the user's ticket, which stands in for the proof
+    /// from then on.
+    ///
+    /// Accounts:
+    /// [signer, writable] user
+    /// pool
+    /// [writable] ticket (pda of "chudex_allowlist_ticket", pool, user)
+    /// system program
+    ProveAllowlist { proof: Vec<[u8; 32]> },
+
+    /// Sets the pool's reserves to its vault balances, taking in any tokens sent straight
+    /// to the vaults. Anyone can sync.
+    ///
+    /// Accounts:
+    /// [writable] pool
+    /// [writable] pool token acc a, in pool mint order
+    /// [writable] pool token acc b
+    /// mint a
+    /// mint b
+    /// token program a
+    /// token program b
+    /// [writable] twamm (pools with a twamm only)
+    /// [writable] twamm token acc a
+    /// [writable] twamm token acc b
+    Sync,
+
+    /// Sends whatever the vaults hold past the pool's reserves to the recipient. Anyone can
+    /// skim.
+    ///
+    /// Accounts:
+    /// [writable] pool
+    /// [writable] pool token acc a, in pool mint order
+    /// [writable] pool token acc b
+    /// [writable] recipient token acc a
+    /// [writable] recipient token acc b
+    /// mint a
+    /// mint b
+    /// token program a
+    /// token program b
+    /// [writable] twamm (pools with a twamm only)
+    /// [writable] twamm token acc a
+    /// [writable] twamm token acc b
+    Skim,
+
+    /// Sends the whole balance of a token account owned by the pool, other than its
+    /// vaults, to the recipient. For tokens sent to the pool address by mistake.
+    ///
+    /// Accounts:
+    /// [signer] admin
+    /// config
+    /// pool
+    /// [writable] pool owned token acc
+    /// [writable] recipient token acc
+    /// mint
+    /// token program
+    RecoverTokens,
+
+    /// Closes a pool with no lp supply, empty vaults and no long-term orders, sending the
+    /// rent of the pool, its vaults, its twamm and twamm vaults, its lp metadata and its
+    /// oracle to the rent receiver. Dust left in the twamm vaults is burned. The pool is taken
+    /// off the registry page and both mint index pages given by number. The pool mint can't
+    /// be closed, it loses its mint authority instead, so the pair is retired for good and
+    /// can't get a new pool.
+    ///
+    /// Accounts:
+    /// [signer] admin
+    /// config
+    /// [writable] pool
+    /// [writable] pool token acc a, in pool mint order
+    /// [writable] pool token acc b
+    /// [writable] pool mint
+    /// token program a
+    /// token program b
+    /// token program, of the pool mint
+    /// [writable] twamm (pda of "chudex_twamm", pool), may be uninitialized
+    /// [writable] rent receiver
+    /// [writable] mint a
+    /// [writable] mint b
+    /// [writable] twamm token acc a
+    /// [writable] twamm token acc b
+    /// [writable] lp metadata (pda of "chudex_lp_metadata", pool mint)
+    /// [writable] oracle (pda of "chudex_oracle", pool), may be uninitialized
+    /// [writable] registry page (pda of "chudex_registry", registry_page)
+    /// [writable] mint a index page (pda of "chudex_registry", mint a, mint_a_page)
+    /// [writable] mint b index page (pda of "chudex_registry", mint b, mint_b_page)
+    ClosePool {
+        registry_page: u64,
+        mint_a_page: u64,
+        mint_b_page: u64,
+    },
+
+    /// Deposit minting exactly pool_token_amount, paying each token's share of the reserves
+    /// rounded up, before any transfer fees. Maximums are sent amounts. Needs a pool that
+    /// already has liquidity.
+    /// Returns a DepositResult, see the client module.
+    ///
+    /// Accounts: same as Deposit
+    DepositExactPoolTokens {
+        pool_token_amount: u64,
+        max_token_a_amount: u64,
+        max_token_b_amount: u64,
+        native_sol: bool,
+    },
+
+    /// Sets the name, symbol and uri of a pool's lp mint, up to 32, 10 and 256 bytes.
+    /// Creates the lp metadata for pools that don't have it yet.
+    ///
+    /// Accounts:
+    /// [signer, writable] admin
+    /// config
+    /// pool
+    /// [writable] lp metadata (pda of "chudex_lp_metadata", pool mint)
+    /// system program
+    UpdateLpMetadata {
+        name: String,
+        symbol: String,
+        uri: String,
+    },
+
+    /// Moves pool tokens into a new position, minting its nft, a 0 decimal mint with a
+    /// supply of 1, to the user. Whoever holds the nft owns the position.
+    ///
+    /// Accounts:
+    /// [signer, writable] user
+    /// [writable] user pool token acc
+    /// pool
+    /// pool token mint
+    /// [writable] position mint (pda of "chudex_lp_position_mint", user, nonce)
+    /// [writable] user position token acc
+    /// [writable] position (pda of "chudex_lp_position", position mint)
+    /// [writable] position pool token acc
+    /// token program
+    /// system program
+    /// sysvar
+    /// associated token program
+    ConvertToPosition { nonce: u64, pool_token_amount: u64 },
+
+    /// Closes a position, sending its pool tokens and rent to the nft holder and burning
+    /// the nft. Creates holder pool token acc if needed. There is no withdrawing straight
+    /// out of a position: follow this with a Withdraw of the position's pool_token_amount
+    /// in the same transaction.
+    ///
+    /// Accounts:
+    /// [signer, writable] holder
+    /// [writable] holder position token acc
+    /// [writable] holder pool token acc
+    /// pool
+    /// pool token mint
+    /// [writable] position mint
+    /// [writable] position
+    /// [writable] position pool token acc
+    /// token program
+    /// system program
+    /// sysvar
+    /// associated token program
+    ConvertFromPosition,
+
+    /// Deposit whose pool tokens go straight into a new position, see ConvertToPosition.
+    /// Returns a DepositResult, see the client module.
+    ///
+    /// Accounts:
+    /// [signer, writable] user
+    /// [writable] position mint (pda of "chudex_lp_position_mint", user, nonce)
+    /// [writable] user position token acc
+    /// [writable] position (pda of "chudex_lp_position", position mint)
+    /// [writable] position pool token acc
+    /// then the same as Deposit, after the user
+    DepositToPosition {
+        nonce: u64,
+        token_a_amount: u64,
+        max_token_b_amount: u64,
+        native_sol: bool,
+    },
+
+    /// Escrows pool tokens in a new locker until unlock_timestamp, unix seconds. With a
+    /// vesting end they vest linearly from the unlock to then, otherwise all at the unlock.
+    /// Lockers are pdas of the pool and their index, counted in the pool's lockers account.
+    ///
+    /// Accounts:
+    /// [signer, writable] user, the locker's owner
+    /// [writable] user pool token acc
+    /// pool
+    /// pool token mint
+    /// [writable] pool lockers (pda of "chudex_pool_lockers", pool)
+    /// [writable] locker (pda of "chudex_locker", pool, locker count)
+    /// [writable] locker pool token acc
+    /// token program
+    /// system program
+    /// sysvar
+    /// associated token program
+    LockLiquidity {
+        amount: u64,
+        unlock_timestamp: i64,
+        vesting_end_timestamp: Option<i64>,
+    },
+
+    /// Releases whatever has vested in a locker since its last unlock to its owner.
+    ///
+    /// Accounts:
+    /// [signer] owner
+    /// [writable] owner pool token acc
+    /// [writable] locker
+    /// [writable] locker pool token acc
+    /// token program
+    UnlockLiquidity,
+
+    /// Permissionless crank snapshotting a pool's price sums into its oracle, at most once
+    /// per PriceOracle::OBSERVATION_INTERVAL. The first call creates the oracle.
+    ///
+    /// Accounts:
+    /// [signer, writable] payer
+    /// pool
+    /// [writable] oracle (pda of "chudex_oracle", pool)
+    /// system program
+    UpdateOracle,
+
+    /// Escrows amount_in to sell into a pool for at least min_amount_out once the price
+    /// of mint in, mint out per mint in in Q64.64, reaches trigger_price: at or below it,
+    /// or at or above it for a take profit. A twap trigger reads the pool's oracle, which
+    /// has to be updated regularly. The order pda is keyed by the user's nonce.
+    ///
+    /// Accounts:
+    /// [signer, writable] owner
+    /// [writable] owner token in acc
+    /// owner token out acc (receives the proceeds)
+    /// pool
+    /// [writable] order (pda of "chudex_conditional", pool, owner, nonce)
+    /// [writable] order escrow acc
+    /// mint in
+    /// mint out
+    /// token program in
+    /// system program
+    /// sysvar
+    /// associated token program
+    /// allowlist, or the owner's allowlist ticket (permissioned pools only)
+    PlaceConditional {
+        nonce: u64,
+        amount_in: u64,
+        min_amount_out: u64,
+        trigger_price: u128,
+        take_profit: bool,
+        trigger_source: TriggerSource,
+        expiry_timestamp: i64,
     },
+
+    /// Returns escrowed tokens and closes a conditional order, expired or not.
+    ///
+    /// Accounts:
+    /// [signer, writable] owner
+    /// [writable] owner token in acc
+    /// [writable] order
+    /// [writable] order escrow acc
+    /// mint in
+    /// token program in
+    CancelConditional,
+
+    /// Permissionless crank executing a conditional order whose trigger is reached, before
+    /// its expiry. The keeper gets a bounty out of the escrow and the rest is swapped
+    /// through Exchange to the owner, closing the order. Returns an ExchangeResult.
+    ///
+    /// Accounts:
+    /// [signer] keeper
+    /// [writable] keeper token in acc
+    /// [writable] order
+    /// [writable] order escrow acc
+    /// [writable] owner token out acc
+    /// [writable] owner
+    /// oracle (pda of "chudex_oracle", pool, read for twap triggers)
+    /// then Exchange's accounts from the pool on, with no native sol accounts
+    ExecuteConditional,
+
+    /// Escrows amount_per_cycle times cycles to buy into a pool in cycles, one due every
+    /// interval_seconds from now, each for at least min_amount_out_per_cycle. The dca pda
+    /// is keyed by the user's nonce.
+    ///
+    /// Accounts:
+    /// [signer, writable] owner
+    /// [writable] owner token in acc
+    /// pool
+    /// [writable] dca (pda of "chudex_dca", pool, owner, nonce)
+    /// [writable] dca escrow acc
+    /// mint in
+    /// mint out
+    /// token program in
+    /// system program
+    /// sysvar
+    /// associated token program
+    /// allowlist, or the owner's allowlist ticket (permissioned pools only)
+    CreateDca {
+        nonce: u64,
+        amount_per_cycle: u64,
+        interval_seconds: u32,
+        cycles: u32,
+        min_amount_out_per_cycle: u64,
+    },
+
+    /// Permissionless crank running a dca's due cycle. The keeper gets a bounty out of the
+    /// cycle and the rest is swapped through Exchange to the owner's associated token acc.
+    /// The last cycle closes the dca. Returns an ExchangeResult.
+    ///
+    /// Accounts:
+    /// [signer] keeper
+    /// [writable] keeper token in acc
+    /// [writable] dca
+    /// [writable] dca escrow acc
+    /// [writable] owner token out acc (the owner's associated token acc)
+    /// [writable] owner
+    /// then Exchange's accounts from the pool on, with no native sol accounts
+    RunDca,
+
+    /// Refunds what's left of a dca's escrow and closes it.
+    ///
+    /// Accounts:
+    /// [signer, writable] owner
+    /// [writable] owner token in acc
+    /// [writable] dca
+    /// [writable] dca escrow acc
+    /// mint in
+    /// token program in
+    CloseDca,
 }
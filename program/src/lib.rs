@@ -1,6 +1,17 @@
+pub mod allowlist;
+pub mod cl_math;
+pub mod client;
+pub mod cpi;
+pub mod curve;
+#[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
 pub mod error;
+pub mod events;
+pub mod hook;
 pub mod instruction;
 pub mod processor;
+pub mod registry;
 pub mod state;
+pub mod token;
+pub mod twamm;
 pub mod utils;
@@ -6,10 +6,56 @@ use solana_program::{
 
 use crate::instruction::ChudexInstruction;
 
+pub mod cancel_conditional;
+pub mod cancel_limit_order;
+pub mod cl_exchange;
+pub mod close_dca;
+pub mod close_pool;
+pub mod close_position;
+pub mod collect_fees;
+pub mod convert_from_position;
+pub mod convert_to_position;
+pub mod create_allowlist;
+pub mod create_dca;
+pub mod create_farm;
+pub mod decrease_liquidity;
 pub mod deposit;
+pub mod deposit_exact_pool_tokens;
+pub mod deposit_to_position;
 pub mod exchange;
+pub mod execute_conditional;
+pub mod fill_orders;
+pub mod harvest;
+pub mod increase_liquidity;
+pub mod initialize_cl_pool;
+pub mod initialize_config;
 pub mod initialize_pool;
+pub mod initialize_tick_array;
+pub mod initialize_twamm;
+pub mod initialize_weighted_pool;
+pub mod lock_liquidity;
+pub mod open_position;
+pub mod place_conditional;
+pub mod place_limit_order;
+pub mod place_long_term_order;
+pub mod prove_allowlist;
+pub mod reclaim_farm_rewards;
+pub mod recover_tokens;
+pub mod run_dca;
+pub mod set_fee_tiers;
+pub mod skim;
+pub mod stake;
+pub mod sync;
+pub mod unlock_liquidity;
+pub mod unstake;
+pub mod update_allowlist;
+pub mod update_lp_metadata;
+pub mod update_oracle;
+pub mod weighted_deposit;
+pub mod weighted_exchange;
+pub mod weighted_withdraw;
 pub mod withdraw;
+pub mod withdraw_long_term_order;
 
 pub struct Processor {}
 
@@ -23,21 +69,41 @@ impl Processor {
             .map_err(|_| ProgramError::InvalidInstructionData)?;
 
         match instruction {
-            ChudexInstruction::InitializePool { fee, fee_decimals } => {
+            ChudexInstruction::InitializePool {
+                fee_tier,
+                dynamic_fee,
+                allowlist,
+                hook_program,
+            } => {
                 msg!("Instruction: InitializePool");
-                initialize_pool::process(program_id, accounts, fee, fee_decimals)?;
+                initialize_pool::process(
+                    program_id,
+                    accounts,
+                    fee_tier,
+                    dynamic_fee,
+                    allowlist,
+                    hook_program,
+                )?;
             }
             ChudexInstruction::Deposit {
                 token_a_amount,
                 max_token_b_amount,
+                native_sol,
             } => {
                 msg!("Instruction: Deposit");
-                deposit::process(program_id, accounts, token_a_amount, max_token_b_amount)?;
+                deposit::process(
+                    program_id,
+                    accounts,
+                    token_a_amount,
+                    max_token_b_amount,
+                    native_sol,
+                )?;
             }
             ChudexInstruction::Withdraw {
                 pool_token_amount,
                 min_token_a_amount,
                 min_token_b_amount,
+                native_sol,
             } => {
                 msg!("Instruction: Withdraw");
                 withdraw::process(
@@ -46,14 +112,386 @@ impl Processor {
                     pool_token_amount,
                     min_token_a_amount,
                     min_token_b_amount,
+                    native_sol,
                 )?;
             }
             ChudexInstruction::Exchange {
                 amount_in,
                 min_amount_out,
+                native_sol,
             } => {
-                msg!("Instruction: Withdraw");
-                exchange::process(program_id, accounts, amount_in, min_amount_out)?;
+                msg!("Instruction: Exchange");
+                exchange::process(program_id, accounts, amount_in, min_amount_out, native_sol)?;
+            }
+            ChudexInstruction::InitializeWeightedPool {
+                weights,
+                fee,
+                fee_decimals,
+            } => {
+                msg!("Instruction: InitializeWeightedPool");
+                initialize_weighted_pool::process(
+                    program_id,
+                    accounts,
+                    weights,
+                    fee,
+                    fee_decimals,
+                )?;
+            }
+            ChudexInstruction::WeightedDeposit {
+                pool_token_amount,
+                max_token_amounts,
+            } => {
+                msg!("Instruction: WeightedDeposit");
+                weighted_deposit::process(
+                    program_id,
+                    accounts,
+                    pool_token_amount,
+                    max_token_amounts,
+                )?;
+            }
+            ChudexInstruction::WeightedWithdraw {
+                pool_token_amount,
+                min_token_amounts,
+            } => {
+                msg!("Instruction: WeightedWithdraw");
+                weighted_withdraw::process(
+                    program_id,
+                    accounts,
+                    pool_token_amount,
+                    min_token_amounts,
+                )?;
+            }
+            ChudexInstruction::WeightedExchange {
+                amount_in,
+                min_amount_out,
+            } => {
+                msg!("Instruction: WeightedExchange");
+                weighted_exchange::process(program_id, accounts, amount_in, min_amount_out)?;
+            }
+            ChudexInstruction::InitializeClPool {
+                tick_spacing,
+                initial_sqrt_price,
+                fee,
+                fee_decimals,
+            } => {
+                msg!("Instruction: InitializeClPool");
+                initialize_cl_pool::process(
+                    program_id,
+                    accounts,
+                    tick_spacing,
+                    initial_sqrt_price,
+                    fee,
+                    fee_decimals,
+                )?;
+            }
+            ChudexInstruction::InitializeTickArray { start_tick } => {
+                msg!("Instruction: InitializeTickArray");
+                initialize_tick_array::process(program_id, accounts, start_tick)?;
+            }
+            ChudexInstruction::OpenPosition {
+                tick_lower,
+                tick_upper,
+            } => {
+                msg!("Instruction: OpenPosition");
+                open_position::process(program_id, accounts, tick_lower, tick_upper)?;
+            }
+            ChudexInstruction::IncreaseLiquidity {
+                liquidity,
+                max_token_a_amount,
+                max_token_b_amount,
+            } => {
+                msg!("Instruction: IncreaseLiquidity");
+                increase_liquidity::process(
+                    program_id,
+                    accounts,
+                    liquidity,
+                    max_token_a_amount,
+                    max_token_b_amount,
+                )?;
+            }
+            ChudexInstruction::DecreaseLiquidity {
+                liquidity,
+                min_token_a_amount,
+                min_token_b_amount,
+            } => {
+                msg!("Instruction: DecreaseLiquidity");
+                decrease_liquidity::process(
+                    program_id,
+                    accounts,
+                    liquidity,
+                    min_token_a_amount,
+                    min_token_b_amount,
+                )?;
+            }
+            ChudexInstruction::CollectFees => {
+                msg!("Instruction: CollectFees");
+                collect_fees::process(program_id, accounts)?;
+            }
+            ChudexInstruction::ClosePosition => {
+                msg!("Instruction: ClosePosition");
+                close_position::process(program_id, accounts)?;
+            }
+            ChudexInstruction::ClExchange {
+                amount_in,
+                min_amount_out,
+                a_to_b,
+                sqrt_price_limit,
+            } => {
+                msg!("Instruction: ClExchange");
+                cl_exchange::process(
+                    program_id,
+                    accounts,
+                    amount_in,
+                    min_amount_out,
+                    a_to_b,
+                    sqrt_price_limit,
+                )?;
+            }
+            ChudexInstruction::PlaceLimitOrder {
+                nonce,
+                amount_in,
+                min_amount_out,
+            } => {
+                msg!("Instruction: PlaceLimitOrder");
+                place_limit_order::process(program_id, accounts, nonce, amount_in, min_amount_out)?;
+            }
+            ChudexInstruction::CancelLimitOrder => {
+                msg!("Instruction: CancelLimitOrder");
+                cancel_limit_order::process(program_id, accounts)?;
+            }
+            ChudexInstruction::FillOrders => {
+                msg!("Instruction: FillOrders");
+                fill_orders::process(program_id, accounts)?;
+            }
+            ChudexInstruction::InitializeTwamm => {
+                msg!("Instruction: InitializeTwamm");
+                initialize_twamm::process(program_id, accounts)?;
+            }
+            ChudexInstruction::PlaceLongTermOrder {
+                nonce,
+                amount_in,
+                a_to_b,
+                num_slots,
+            } => {
+                msg!("Instruction: PlaceLongTermOrder");
+                place_long_term_order::process(
+                    program_id, accounts, nonce, amount_in, a_to_b, num_slots,
+                )?;
+            }
+            ChudexInstruction::WithdrawLongTermOrder => {
+                msg!("Instruction: WithdrawLongTermOrder");
+                withdraw_long_term_order::process(program_id, accounts, false)?;
+            }
+            ChudexInstruction::CancelLongTermOrder => {
+                msg!("Instruction: CancelLongTermOrder");
+                withdraw_long_term_order::process(program_id, accounts, true)?;
+            }
+            ChudexInstruction::CreateFarm {
+                nonce,
+                emission_rates,
+                start_slot,
+                end_slot,
+            } => {
+                msg!("Instruction: CreateFarm");
+                create_farm::process(
+                    program_id,
+                    accounts,
+                    nonce,
+                    emission_rates,
+                    start_slot,
+                    end_slot,
+                )?;
+            }
+            ChudexInstruction::Stake { amount } => {
+                msg!("Instruction: Stake");
+                stake::process(program_id, accounts, amount)?;
+            }
+            ChudexInstruction::Unstake { amount } => {
+                msg!("Instruction: Unstake");
+                unstake::process(program_id, accounts, amount)?;
+            }
+            ChudexInstruction::Harvest => {
+                msg!("Instruction: Harvest");
+                harvest::process(program_id, accounts)?;
+            }
+            ChudexInstruction::ReclaimFarmRewards => {
+                msg!("Instruction: ReclaimFarmRewards");
+                reclaim_farm_rewards::process(program_id, accounts)?;
+            }
+            ChudexInstruction::InitializeConfig { fee_tiers } => {
+                msg!("Instruction: InitializeConfig");
+                initialize_config::process(program_id, accounts, fee_tiers)?;
+            }
+            ChudexInstruction::SetFeeTiers { fee_tiers } => {
+                msg!("Instruction: SetFeeTiers");
+                set_fee_tiers::process(program_id, accounts, fee_tiers)?;
+            }
+            ChudexInstruction::CreateAllowlist {
+                nonce,
+                capacity,
+                members,
+            } => {
+                msg!("Instruction: CreateAllowlist");
+                create_allowlist::process(program_id, accounts, nonce, capacity, members)?;
+            }
+            ChudexInstruction::UpdateAllowlist { add, remove } => {
+                msg!("Instruction: UpdateAllowlist");
+                update_allowlist::process(program_id, accounts, add, remove)?;
+            }
+            ChudexInstruction::ProveAllowlist { proof } => {
+                msg!("Instruction: ProveAllowlist");
+                prove_allowlist::process(program_id, accounts, proof)?;
+            }
+            ChudexInstruction::Sync => {
+                msg!("Instruction: Sync");
+                sync::process(program_id, accounts)?;
+            }
+            ChudexInstruction::Skim => {
+                msg!("Instruction: Skim");
+                skim::process(program_id, accounts)?;
+            }
+            ChudexInstruction::RecoverTokens => {
+                msg!("Instruction: RecoverTokens");
+                recover_tokens::process(program_id, accounts)?;
+            }
+            ChudexInstruction::ClosePool {
+                registry_page,
+                mint_a_page,
+                mint_b_page,
+            } => {
+                msg!("Instruction: ClosePool");
+                close_pool::process(
+                    program_id,
+                    accounts,
+                    registry_page,
+                    mint_a_page,
+                    mint_b_page,
+                )?;
+            }
+            ChudexInstruction::DepositExactPoolTokens {
+                pool_token_amount,
+                max_token_a_amount,
+                max_token_b_amount,
+                native_sol,
+            } => {
+                msg!("Instruction: DepositExactPoolTokens");
+                deposit_exact_pool_tokens::process(
+                    program_id,
+                    accounts,
+                    pool_token_amount,
+                    max_token_a_amount,
+                    max_token_b_amount,
+                    native_sol,
+                )?;
+            }
+            ChudexInstruction::UpdateLpMetadata { name, symbol, uri } => {
+                msg!("Instruction: UpdateLpMetadata");
+                update_lp_metadata::process(program_id, accounts, name, symbol, uri)?;
+            }
+            ChudexInstruction::ConvertToPosition {
+                nonce,
+                pool_token_amount,
+            } => {
+                msg!("Instruction: ConvertToPosition");
+                convert_to_position::process(program_id, accounts, nonce, pool_token_amount)?;
+            }
+            ChudexInstruction::ConvertFromPosition => {
+                msg!("Instruction: ConvertFromPosition");
+                convert_from_position::process(program_id, accounts)?;
+            }
+            ChudexInstruction::DepositToPosition {
+                nonce,
+                token_a_amount,
+                max_token_b_amount,
+                native_sol,
+            } => {
+                msg!("Instruction: DepositToPosition");
+                deposit_to_position::process(
+                    program_id,
+                    accounts,
+                    nonce,
+                    token_a_amount,
+                    max_token_b_amount,
+                    native_sol,
+                )?;
+            }
+            ChudexInstruction::LockLiquidity {
+                amount,
+                unlock_timestamp,
+                vesting_end_timestamp,
+            } => {
+                msg!("Instruction: LockLiquidity");
+                lock_liquidity::process(
+                    program_id,
+                    accounts,
+                    amount,
+                    unlock_timestamp,
+                    vesting_end_timestamp,
+                )?;
+            }
+            ChudexInstruction::UnlockLiquidity => {
+                msg!("Instruction: UnlockLiquidity");
+                unlock_liquidity::process(program_id, accounts)?;
+            }
+            ChudexInstruction::UpdateOracle => {
+                msg!("Instruction: UpdateOracle");
+                update_oracle::process(program_id, accounts)?;
+            }
+            ChudexInstruction::PlaceConditional {
+                nonce,
+                amount_in,
+                min_amount_out,
+                trigger_price,
+                take_profit,
+                trigger_source,
+                expiry_timestamp,
+            } => {
+                msg!("Instruction: PlaceConditional");
+                place_conditional::process(
+                    program_id,
+                    accounts,
+                    nonce,
+                    amount_in,
+                    min_amount_out,
+                    trigger_price,
+                    take_profit,
+                    trigger_source,
+                    expiry_timestamp,
+                )?;
+            }
+            ChudexInstruction::CancelConditional => {
+                msg!("Instruction: CancelConditional");
+                cancel_conditional::process(program_id, accounts)?;
+            }
+            ChudexInstruction::ExecuteConditional => {
+                msg!("Instruction: ExecuteConditional");
+                execute_conditional::process(program_id, accounts)?;
+            }
+            ChudexInstruction::CreateDca {
+                nonce,
+                amount_per_cycle,
+                interval_seconds,
+                cycles,
+                min_amount_out_per_cycle,
+            } => {
+                msg!("Instruction: CreateDca");
+                create_dca::process(
+                    program_id,
+                    accounts,
+                    nonce,
+                    amount_per_cycle,
+                    interval_seconds,
+                    cycles,
+                    min_amount_out_per_cycle,
+                )?;
+            }
+            ChudexInstruction::RunDca => {
+                msg!("Instruction: RunDca");
+                run_dca::process(program_id, accounts)?;
+            }
+            ChudexInstruction::CloseDca => {
+                msg!("Instruction: CloseDca");
+                close_dca::process(program_id, accounts)?;
             }
         }
 
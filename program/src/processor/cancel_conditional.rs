@@ -0,0 +1,127 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::ChudexError,
+    state::ConditionalOrder,
+    token,
+    utils::{assert_msg, close_program_account},
+};
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let owner_token_in_ai = next_account_info(accounts_iter)?;
+    let order_ai = next_account_info(accounts_iter)?;
+    let escrow_ai = next_account_info(accounts_iter)?;
+    let mint_in_ai = next_account_info(accounts_iter)?;
+    let token_program_in = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        order_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Order not owned by program",
+    )?;
+    let order = ConditionalOrder::try_from_slice(&order_ai.try_borrow_data()?)?;
+    let escrow = token::unpack_token_account(escrow_ai)?;
+    let mint_in = token::unpack_mint(mint_in_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // owner is signer
+    assert_msg(
+        owner.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "Owner not signer",
+    )?;
+    assert_msg(
+        order.owner == *owner.key,
+        ChudexError::InvalidAccountData.into(),
+        "Order not owned by owner",
+    )?;
+
+    // mint matches order
+    assert_msg(
+        *mint_in_ai.key == order.mint_in,
+        ChudexError::InvalidAccountAddress.into(),
+        "Mint in doesn't match order",
+    )?;
+
+    // pda verification
+
+    // order pda
+    let nonce_bytes = order.nonce.to_le_bytes();
+    let (order_key, order_bump) = Pubkey::find_program_address(
+        &[
+            ConditionalOrder::SEED,
+            order.pool.as_ref(),
+            order.owner.as_ref(),
+            &nonce_bytes,
+        ],
+        program_id,
+    );
+    let order_seeds = &[
+        ConditionalOrder::SEED,
+        order.pool.as_ref(),
+        order.owner.as_ref(),
+        &nonce_bytes,
+        &[order_bump],
+    ];
+    assert_msg(
+        *order_ai.key == order_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "order pda aint right",
+    )?;
+
+    // escrow pda
+    let (escrow_key, _) = Pubkey::find_program_address(
+        &[
+            order_ai.key.as_ref(),
+            token_program_in.key.as_ref(),
+            mint_in_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        escrow_key == *escrow_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "escrow pda aint right",
+    )?;
+
+    // external program verification
+    // mint token program, either spl token or token-2022
+    assert_msg(
+        mint_in_ai.owner == token_program_in.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program in wrong address",
+    )?;
+
+    // LOGIC
+
+    // return escrow, then close escrow and order to the owner
+    msg!("cancelling order...");
+    token::transfer(
+        token_program_in,
+        escrow_ai,
+        mint_in_ai,
+        owner_token_in_ai,
+        order_ai,
+        escrow.amount,
+        mint_in.decimals,
+        &[order_seeds],
+    )?;
+    token::close_account(token_program_in, escrow_ai, owner, order_ai, &[order_seeds])?;
+    close_program_account(order_ai, owner)?;
+    msg!("cancelled order");
+
+    Ok(())
+}
@@ -0,0 +1,329 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    cl_math,
+    error::ChudexError,
+    state::{ClPool, TickArray},
+    token,
+    utils::assert_msg,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use spl_token::error::TokenError;
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_in: u64,
+    min_amount_out: u64,
+    a_to_b: bool,
+    sqrt_price_limit: u128,
+) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let user_token_src_ai = next_account_info(accounts_iter)?;
+    let user_token_dst_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let pool_vault_src_ai = next_account_info(accounts_iter)?;
+    let pool_vault_dst_ai = next_account_info(accounts_iter)?;
+    let token_program_src = next_account_info(accounts_iter)?;
+    let token_program_dst = next_account_info(accounts_iter)?;
+    let mint_src_ai = next_account_info(accounts_iter)?;
+    let mint_dst_ai = next_account_info(accounts_iter)?;
+    let tick_array_ais: Vec<&AccountInfo> = accounts_iter.collect();
+
+    // deserialization
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let mut pool = ClPool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    let user_token_src = token::unpack_token_account(user_token_src_ai)?;
+    let mint_src = token::unpack_mint(mint_src_ai)?;
+    let mint_dst = token::unpack_mint(mint_dst_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // user is signer
+    assert_msg(
+        user.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "User not signer",
+    )?;
+
+    // token account ownership
+    assert_msg(
+        user_token_src.owner == *user.key,
+        TokenError::OwnerMismatch.into(),
+        "user token src not owned by user",
+    )?;
+
+    // mints match pool in swap direction
+    let (mint_a_ai, mint_b_ai) = if a_to_b {
+        (mint_src_ai, mint_dst_ai)
+    } else {
+        (mint_dst_ai, mint_src_ai)
+    };
+    assert_msg(
+        *mint_a_ai.key == pool.mint_a && *mint_b_ai.key == pool.mint_b,
+        ChudexError::InvalidAccountAddress.into(),
+        "Mints don't match pool",
+    )?;
+
+    // pda verification
+
+    // pool pda
+    let tick_spacing_bytes = pool.tick_spacing.to_le_bytes();
+    let (pool_key, pool_bump) = Pubkey::find_program_address(
+        &[
+            ClPool::SEED,
+            mint_a_ai.key.as_ref(),
+            mint_b_ai.key.as_ref(),
+            &tick_spacing_bytes,
+        ],
+        program_id,
+    );
+    let pool_seeds = &[
+        ClPool::SEED,
+        mint_a_ai.key.as_ref(),
+        mint_b_ai.key.as_ref(),
+        &tick_spacing_bytes,
+        &[pool_bump],
+    ];
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // vault src pda
+    let (vault_src_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_src.key.as_ref(),
+            mint_src_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_src_key == *pool_vault_src_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault src pda aint right",
+    )?;
+
+    // vault dst pda
+    let (vault_dst_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_dst.key.as_ref(),
+            mint_dst_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_dst_key == *pool_vault_dst_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault dst pda aint right",
+    )?;
+
+    // tick arrays, contiguous from the one holding the current tick in swap direction
+    let ticks_per_array = TickArray::SIZE as i32 * pool.tick_spacing as i32;
+    let first_start = pool.tick_array_start(pool.current_tick);
+    let mut tick_arrays = Vec::with_capacity(tick_array_ais.len());
+    for (i, tick_array_ai) in tick_array_ais.iter().enumerate() {
+        assert_msg(
+            tick_array_ai.owner == program_id,
+            ChudexError::InvalidAccountAddress.into(),
+            "Tick array not owned by program",
+        )?;
+        let tick_array = TickArray::try_from_slice(&tick_array_ai.try_borrow_data()?)?;
+        let expected_start = if a_to_b {
+            first_start - i as i32 * ticks_per_array
+        } else {
+            first_start + i as i32 * ticks_per_array
+        };
+        assert_msg(
+            tick_array.pool == *pool_ai.key && tick_array.start_tick == expected_start,
+            ChudexError::TickArrayNotFound.into(),
+            "Tick arrays not in swap order",
+        )?;
+        tick_arrays.push(tick_array);
+    }
+    assert_msg(
+        !tick_arrays.is_empty(),
+        ChudexError::TickArrayNotFound.into(),
+        "Need at least one tick array",
+    )?;
+
+    // price limit
+    let sqrt_price_limit = if sqrt_price_limit == 0 {
+        if a_to_b {
+            cl_math::sqrt_price_from_tick(cl_math::MIN_TICK)?
+        } else {
+            cl_math::sqrt_price_from_tick(cl_math::MAX_TICK)?
+        }
+    } else {
+        sqrt_price_limit
+    };
+    assert_msg(
+        if a_to_b {
+            sqrt_price_limit < pool.sqrt_price
+                && sqrt_price_limit >= cl_math::sqrt_price_from_tick(cl_math::MIN_TICK)?
+        } else {
+            sqrt_price_limit > pool.sqrt_price
+                && sqrt_price_limit <= cl_math::sqrt_price_from_tick(cl_math::MAX_TICK)?
+        },
+        ChudexError::InvalidInstructionInput.into(),
+        "Sqrt price limit invalid",
+    )?;
+
+    // external program verification
+    // mint token programs, either spl token or token-2022
+    assert_msg(
+        mint_src_ai.owner == token_program_src.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program src wrong address",
+    )?;
+    assert_msg(
+        mint_dst_ai.owner == token_program_dst.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program dst wrong address",
+    )?;
+
+    // LOGIC
+
+    // pool math uses what actually lands in the vault after any transfer fee
+    let amount_in_received = token::amount_after_transfer_fee(mint_src_ai, amount_in)?;
+    let mut amount_remaining = amount_in_received;
+    let mut amount_out: u64 = 0;
+    let mut fee_total: u64 = 0;
+
+    // swap range by range until the input runs out or the price hits the limit
+    while amount_remaining > 0 && pool.sqrt_price != sqrt_price_limit {
+        // next initialized tick in the loaded arrays, otherwise the end of them
+        let next_tick = tick_arrays.iter().find_map(|tick_array| {
+            tick_array.next_initialized_tick(pool.current_tick, pool.tick_spacing, a_to_b)
+        });
+        let (tick_next, initialized) = match next_tick {
+            Some(tick) => (tick, true),
+            None => {
+                let last = tick_arrays.last().unwrap();
+                let boundary = if a_to_b {
+                    last.start_tick
+                } else {
+                    last.start_tick + ticks_per_array
+                };
+                (boundary.clamp(cl_math::MIN_TICK, cl_math::MAX_TICK), false)
+            }
+        };
+        let sqrt_price_tick = cl_math::sqrt_price_from_tick(tick_next)?;
+        let sqrt_price_target = if a_to_b {
+            sqrt_price_tick.max(sqrt_price_limit)
+        } else {
+            sqrt_price_tick.min(sqrt_price_limit)
+        };
+        if !initialized && sqrt_price_target == pool.sqrt_price {
+            msg!("ran out of tick arrays");
+            return Err(ChudexError::TickArrayNotFound.into());
+        }
+
+        let step = cl_math::compute_swap_step(
+            pool.sqrt_price,
+            sqrt_price_target,
+            pool.liquidity,
+            amount_remaining,
+            pool.fee,
+            pool.fee_decimals,
+            a_to_b,
+        )?;
+        amount_remaining -= step.amount_in + step.fee_amount;
+        amount_out = amount_out
+            .checked_add(step.amount_out)
+            .ok_or(ChudexError::CalculationFailure)?;
+        fee_total += step.fee_amount;
+
+        // fees go to liquidity in range
+        let fee_growth = cl_math::fee_growth_delta(step.fee_amount, pool.liquidity);
+        if a_to_b {
+            pool.fee_growth_global_a = pool.fee_growth_global_a.wrapping_add(fee_growth);
+        } else {
+            pool.fee_growth_global_b = pool.fee_growth_global_b.wrapping_add(fee_growth);
+        }
+
+        if step.sqrt_price_next == sqrt_price_tick {
+            // reached the tick, cross it if anything is bounded by it
+            if initialized {
+                let tick_state = tick_arrays
+                    .iter_mut()
+                    .find_map(|tick_array| tick_array.tick_mut(tick_next, pool.tick_spacing))
+                    .ok_or(ChudexError::TickArrayNotFound)?;
+                pool.cross_tick(tick_state, a_to_b)?;
+            }
+            pool.current_tick = if a_to_b { tick_next - 1 } else { tick_next };
+        } else if step.sqrt_price_next != pool.sqrt_price {
+            pool.current_tick = cl_math::tick_from_sqrt_price(step.sqrt_price_next)?;
+        }
+        pool.sqrt_price = step.sqrt_price_next;
+    }
+
+    // minimum applies to what the user receives
+    let amount_out_received = token::amount_after_transfer_fee(mint_dst_ai, amount_out)?;
+    if amount_out_received < min_amount_out {
+        return Err(ChudexError::ExchangeAmountBelowMinimum.into());
+    }
+
+    // only charge what was swapped when the limit stopped it early
+    let amount_in = if amount_remaining == 0 {
+        amount_in
+    } else {
+        token::amount_before_transfer_fee(mint_src_ai, amount_in_received - amount_remaining)?
+    };
+
+    msg!(
+        "Got amount in: {} amount out: {} fee: {}",
+        amount_in,
+        amount_out,
+        fee_total
+    );
+
+    // send tokens in
+    token::transfer(
+        token_program_src,
+        user_token_src_ai,
+        mint_src_ai,
+        pool_vault_src_ai,
+        user,
+        amount_in,
+        mint_src.decimals,
+        &[],
+    )?;
+
+    // send tokens out
+    token::transfer(
+        token_program_dst,
+        pool_vault_dst_ai,
+        mint_dst_ai,
+        user_token_dst_ai,
+        pool_ai,
+        amount_out,
+        mint_dst.decimals,
+        &[pool_seeds],
+    )?;
+
+    // save state
+    pool.serialize(&mut &mut pool_ai.try_borrow_mut_data()?[..])?;
+    for (tick_array_ai, tick_array) in tick_array_ais.iter().zip(tick_arrays) {
+        tick_array.serialize(&mut &mut tick_array_ai.try_borrow_mut_data()?[..])?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::ChudexError,
+    state::Dca,
+    token,
+    utils::{assert_msg, close_program_account},
+};
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let owner_token_in_ai = next_account_info(accounts_iter)?;
+    let dca_ai = next_account_info(accounts_iter)?;
+    let escrow_ai = next_account_info(accounts_iter)?;
+    let mint_in_ai = next_account_info(accounts_iter)?;
+    let token_program_in = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        dca_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Dca not owned by program",
+    )?;
+    let dca = Dca::try_from_slice(&dca_ai.try_borrow_data()?)?;
+    let escrow = token::unpack_token_account(escrow_ai)?;
+    let mint_in = token::unpack_mint(mint_in_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // owner is signer
+    assert_msg(
+        owner.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "Owner not signer",
+    )?;
+    assert_msg(
+        dca.owner == *owner.key,
+        ChudexError::InvalidAccountData.into(),
+        "Dca not owned by owner",
+    )?;
+
+    // mint matches dca
+    assert_msg(
+        *mint_in_ai.key == dca.mint_in,
+        ChudexError::InvalidAccountAddress.into(),
+        "Mint in doesn't match dca",
+    )?;
+
+    // pda verification
+
+    // dca pda
+    let nonce_bytes = dca.nonce.to_le_bytes();
+    let (dca_key, dca_bump) = Pubkey::find_program_address(
+        &[
+            Dca::SEED,
+            dca.pool.as_ref(),
+            dca.owner.as_ref(),
+            &nonce_bytes,
+        ],
+        program_id,
+    );
+    let dca_seeds = &[
+        Dca::SEED,
+        dca.pool.as_ref(),
+        dca.owner.as_ref(),
+        &nonce_bytes,
+        &[dca_bump],
+    ];
+    assert_msg(
+        *dca_ai.key == dca_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "dca pda aint right",
+    )?;
+
+    // escrow pda
+    let (escrow_key, _) = Pubkey::find_program_address(
+        &[
+            dca_ai.key.as_ref(),
+            token_program_in.key.as_ref(),
+            mint_in_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        escrow_key == *escrow_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "escrow pda aint right",
+    )?;
+
+    // external program verification
+    // mint token program, either spl token or token-2022
+    assert_msg(
+        mint_in_ai.owner == token_program_in.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program in wrong address",
+    )?;
+
+    // LOGIC
+
+    // refund what's left, then close escrow and dca to the owner
+    msg!("cancelling dca...");
+    token::transfer(
+        token_program_in,
+        escrow_ai,
+        mint_in_ai,
+        owner_token_in_ai,
+        dca_ai,
+        escrow.amount,
+        mint_in.decimals,
+        &[dca_seeds],
+    )?;
+    token::close_account(token_program_in, escrow_ai, owner, dca_ai, &[dca_seeds])?;
+    close_program_account(dca_ai, owner)?;
+    msg!("closed dca");
+
+    Ok(())
+}
@@ -0,0 +1,328 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ChudexError,
+    events::{ChudexEvent, PoolClosed},
+    registry,
+    state::{GlobalConfig, LpMetadata, Pool, PriceOracle, TwammPool},
+    token,
+    utils::{assert_msg, close_program_account},
+};
+
+use borsh::BorshDeserialize;
+
+use spl_token::instruction::{set_authority, AuthorityType};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    registry_page: u64,
+    mint_a_page: u64,
+    mint_b_page: u64,
+) -> ProgramResult {
+    // GET ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let config_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let pool_vault_a_ai = next_account_info(accounts_iter)?;
+    let pool_vault_b_ai = next_account_info(accounts_iter)?;
+    let pool_mint_ai = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let twamm_ai = next_account_info(accounts_iter)?;
+    let rent_receiver = next_account_info(accounts_iter)?;
+    let mint_a_ai = next_account_info(accounts_iter)?;
+    let mint_b_ai = next_account_info(accounts_iter)?;
+    let twamm_vault_a_ai = next_account_info(accounts_iter)?;
+    let twamm_vault_b_ai = next_account_info(accounts_iter)?;
+    let lp_metadata_ai = next_account_info(accounts_iter)?;
+    let oracle_ai = next_account_info(accounts_iter)?;
+    let registry_page_ai = next_account_info(accounts_iter)?;
+    let mint_index_page_a_ai = next_account_info(accounts_iter)?;
+    let mint_index_page_b_ai = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        config_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Config not owned by program",
+    )?;
+    let config = GlobalConfig::try_from_slice(&config_ai.try_borrow_data()?)?;
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    let pool_vault_a = token::unpack_token_account(pool_vault_a_ai)?;
+    let pool_vault_b = token::unpack_token_account(pool_vault_b_ai)?;
+    let pool_mint = token::unpack_mint(pool_mint_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // admin is signer
+    assert_msg(
+        admin.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "Admin not signer",
+    )?;
+    assert_msg(
+        config.admin == *admin.key,
+        ChudexError::IncorrectAdmin.into(),
+        "Admin doesn't match config",
+    )?;
+
+    // pool mint is the pool's
+    assert_msg(
+        *pool_mint_ai.key == pool.mint,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool mint doesn't match pool",
+    )?;
+
+    // mints are the pool's, in pool mint order
+    assert_msg(
+        *mint_a_ai.key == pool.mint_a && *mint_b_ai.key == pool.mint_b,
+        ChudexError::InvalidAccountAddress.into(),
+        "Mints don't match pool",
+    )?;
+
+    // nothing left in the pool, no lp tokens out
+    assert_msg(
+        pool_mint.supply == 0 && pool_vault_a.amount == 0 && pool_vault_b.amount == 0,
+        ChudexError::PoolNotEmpty.into(),
+        "Pool still has liquidity",
+    )?;
+
+    // pda verification
+
+    // config pda
+    let (config_key, _) = Pubkey::find_program_address(&[GlobalConfig::SEED], program_id);
+    assert_msg(
+        *config_ai.key == config_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "config pda aint right",
+    )?;
+
+    // pool pda
+    let (pool_key, pool_bump) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    let pool_seeds = &[
+        b"chudex_pool",
+        pool.mint_a.as_ref(),
+        pool.mint_b.as_ref(),
+        &[pool_bump],
+    ];
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // vault pdas, in pool mint order
+    for (pool_vault_ai, mint, token_program) in [
+        (pool_vault_a_ai, &pool.mint_a, token_program_a),
+        (pool_vault_b_ai, &pool.mint_b, token_program_b),
+    ] {
+        let (vault_key, _) = Pubkey::find_program_address(
+            &[
+                pool_ai.key.as_ref(),
+                token_program.key.as_ref(),
+                mint.as_ref(),
+            ],
+            &spl_associated_token_account::id(),
+        );
+        assert_msg(
+            vault_key == *pool_vault_ai.key,
+            ChudexError::InvalidProgramAddress.into(),
+            "vault pda aint right",
+        )?;
+    }
+
+    // twamm pda, no long-term orders left against the pool
+    let (twamm_key, twamm_bump) =
+        Pubkey::find_program_address(&[TwammPool::SEED, pool_ai.key.as_ref()], program_id);
+    let twamm_seeds = &[TwammPool::SEED, pool_ai.key.as_ref(), &[twamm_bump]];
+    assert_msg(
+        *twamm_ai.key == twamm_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "twamm pda aint right",
+    )?;
+    let has_twamm = twamm_ai.data_len() > 0;
+    if has_twamm {
+        assert_msg(
+            twamm_ai.owner == program_id,
+            ChudexError::InvalidAccountAddress.into(),
+            "Twamm not owned by program",
+        )?;
+        let twamm = TwammPool::try_from_slice(&twamm_ai.try_borrow_data()?)?;
+        assert_msg(
+            twamm.expired_open_orders == 0
+                && twamm.buckets.iter().all(|bucket| bucket.open_orders == 0),
+            ChudexError::PoolNotEmpty.into(),
+            "Pool still has long-term orders",
+        )?;
+
+        // twamm vault pdas
+        for (twamm_vault_ai, mint, token_program) in [
+            (twamm_vault_a_ai, &pool.mint_a, token_program_a),
+            (twamm_vault_b_ai, &pool.mint_b, token_program_b),
+        ] {
+            let (vault_key, _) = Pubkey::find_program_address(
+                &[
+                    twamm_ai.key.as_ref(),
+                    token_program.key.as_ref(),
+                    mint.as_ref(),
+                ],
+                &spl_associated_token_account::id(),
+            );
+            assert_msg(
+                vault_key == *twamm_vault_ai.key,
+                ChudexError::InvalidProgramAddress.into(),
+                "twamm vault pda aint right",
+            )?;
+        }
+    }
+
+    // lp metadata pda
+    let (lp_metadata_key, _) =
+        Pubkey::find_program_address(&[LpMetadata::SEED, pool_mint_ai.key.as_ref()], program_id);
+    assert_msg(
+        *lp_metadata_ai.key == lp_metadata_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "lp metadata pda aint right",
+    )?;
+
+    // oracle pda, may be uninitialized
+    let (oracle_key, _) =
+        Pubkey::find_program_address(&[PriceOracle::SEED, pool_ai.key.as_ref()], program_id);
+    assert_msg(
+        *oracle_ai.key == oracle_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "oracle pda aint right",
+    )?;
+
+    // external program verification
+    // vault token programs, either spl token or token-2022
+    assert_msg(
+        pool_vault_a_ai.owner == token_program_a.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program a wrong address",
+    )?;
+    assert_msg(
+        pool_vault_b_ai.owner == token_program_b.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program b wrong address",
+    )?;
+    // pool mints are spl token
+    assert_msg(
+        *token_program.key == spl_token::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program wrong address",
+    )?;
+
+    // LOGIC
+
+    // vault rent goes to the receiver
+    msg!("closing vaults...");
+    token::close_account(
+        token_program_a,
+        pool_vault_a_ai,
+        rent_receiver,
+        pool_ai,
+        &[pool_seeds],
+    )?;
+    token::close_account(
+        token_program_b,
+        pool_vault_b_ai,
+        rent_receiver,
+        pool_ai,
+        &[pool_seeds],
+    )?;
+
+    // with every order withdrawn, only rounding dust is left in the twamm vaults
+    if has_twamm {
+        msg!("closing twamm...");
+        for (twamm_vault_ai, mint_ai, token_program) in [
+            (twamm_vault_a_ai, mint_a_ai, token_program_a),
+            (twamm_vault_b_ai, mint_b_ai, token_program_b),
+        ] {
+            let dust = token::unpack_token_account(twamm_vault_ai)?.amount;
+            if dust > 0 {
+                let mint = token::unpack_mint(mint_ai)?;
+                token::burn(
+                    token_program,
+                    twamm_vault_ai,
+                    mint_ai,
+                    twamm_ai,
+                    dust,
+                    mint.decimals,
+                    &[twamm_seeds],
+                )?;
+            }
+            token::close_account(
+                token_program,
+                twamm_vault_ai,
+                rent_receiver,
+                twamm_ai,
+                &[twamm_seeds],
+            )?;
+        }
+        close_program_account(twamm_ai, rent_receiver)?;
+    }
+
+    // spl token mints can't be closed, dropping the mint authority leaves the supply at
+    // zero for good
+    msg!("disabling pool mint...");
+    invoke_signed(
+        &set_authority(
+            token_program.key,
+            pool_mint_ai.key,
+            None,
+            AuthorityType::MintTokens,
+            pool_ai.key,
+            &[],
+        )?,
+        &[pool_mint_ai.clone(), pool_ai.clone(), token_program.clone()],
+        &[pool_seeds],
+    )?;
+
+    // the mint's metadata and the pool's price history go with it
+    if lp_metadata_ai.owner == program_id {
+        close_program_account(lp_metadata_ai, rent_receiver)?;
+    }
+    if oracle_ai.owner == program_id {
+        close_program_account(oracle_ai, rent_receiver)?;
+    }
+
+    // no longer listed for discovery
+    registry::deregister_pool(
+        program_id,
+        pool_ai.key,
+        (registry_page_ai, registry_page),
+        [
+            (&pool.mint_a, mint_index_page_a_ai, mint_a_page),
+            (&pool.mint_b, mint_index_page_b_ai, mint_b_page),
+        ],
+    )?;
+
+    // refund rent and wipe the pool
+    msg!("closing pool...");
+    close_program_account(pool_ai, rent_receiver)?;
+    msg!("closed pool");
+
+    ChudexEvent::PoolClosed(PoolClosed { pool: *pool_ai.key }).emit();
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::ChudexError,
+    state::Position,
+    utils::{assert_msg, close_program_account},
+};
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let position_ai = next_account_info(accounts_iter)?;
+
+    // ACCOUNT VALIDATION
+
+    // owner is signer
+    assert_msg(
+        owner.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "Owner not signer",
+    )?;
+
+    // position
+    assert_msg(
+        position_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Position not owned by program",
+    )?;
+    let position = Position::try_from_slice(&position_ai.try_borrow_data()?)?;
+    assert_msg(
+        position.owner == *owner.key,
+        ChudexError::InvalidAccountData.into(),
+        "Position not owned by owner",
+    )?;
+
+    // nothing left to withdraw or collect
+    assert_msg(
+        position.liquidity == 0 && position.fees_owed_a == 0 && position.fees_owed_b == 0,
+        ChudexError::PositionNotEmpty.into(),
+        "Position still has liquidity or fees owed",
+    )?;
+
+    // LOGIC
+
+    // refund rent and wipe the account
+    msg!("closing position...");
+    close_program_account(position_ai, owner)?;
+    msg!("closed position");
+
+    Ok(())
+}
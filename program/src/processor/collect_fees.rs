@@ -0,0 +1,193 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::ChudexError,
+    state::{ClPool, Position},
+    token,
+    utils::assert_msg,
+};
+
+use spl_token::error::TokenError;
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let position_ai = next_account_info(accounts_iter)?;
+    let user_token_a_ai = next_account_info(accounts_iter)?;
+    let user_token_b_ai = next_account_info(accounts_iter)?;
+    let pool_vault_a_ai = next_account_info(accounts_iter)?;
+    let pool_vault_b_ai = next_account_info(accounts_iter)?;
+    let mint_a_ai = next_account_info(accounts_iter)?;
+    let mint_b_ai = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
+
+    // ACCOUNT VALIDATION
+
+    // owner is signer
+    assert_msg(
+        owner.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "Owner not signer",
+    )?;
+
+    // program accounts
+    assert_msg(
+        pool_ai.owner == program_id && position_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool or position not owned by program",
+    )?;
+
+    // deserialization
+    let pool = ClPool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    let mut position = Position::try_from_slice(&position_ai.try_borrow_data()?)?;
+    let user_token_a = token::unpack_token_account(user_token_a_ai)?;
+    let user_token_b = token::unpack_token_account(user_token_b_ai)?;
+
+    // position belongs to owner and pool
+    assert_msg(
+        position.owner == *owner.key && position.pool == *pool_ai.key,
+        ChudexError::InvalidAccountData.into(),
+        "Position doesn't match owner or pool",
+    )?;
+
+    // token account ownership
+    assert_msg(
+        user_token_a.owner == *owner.key,
+        TokenError::OwnerMismatch.into(),
+        "user token a not owned by owner",
+    )?;
+    assert_msg(
+        user_token_b.owner == *owner.key,
+        TokenError::OwnerMismatch.into(),
+        "user token b not owned by owner",
+    )?;
+
+    // mints match pool
+    assert_msg(
+        *mint_a_ai.key == pool.mint_a && *mint_b_ai.key == pool.mint_b,
+        ChudexError::InvalidAccountAddress.into(),
+        "Mints don't match pool",
+    )?;
+
+    // pda verification
+
+    // pool pda
+    let tick_spacing_bytes = pool.tick_spacing.to_le_bytes();
+    let (pool_key, pool_bump) = Pubkey::find_program_address(
+        &[
+            ClPool::SEED,
+            mint_a_ai.key.as_ref(),
+            mint_b_ai.key.as_ref(),
+            &tick_spacing_bytes,
+        ],
+        program_id,
+    );
+    let pool_seeds = &[
+        ClPool::SEED,
+        mint_a_ai.key.as_ref(),
+        mint_b_ai.key.as_ref(),
+        &tick_spacing_bytes,
+        &[pool_bump],
+    ];
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // vault a pda
+    let (vault_a_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_a.key.as_ref(),
+            pool.mint_a.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_a_key == *pool_vault_a_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault a pda aint right",
+    )?;
+
+    // vault b pda
+    let (vault_b_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_b.key.as_ref(),
+            pool.mint_b.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_b_key == *pool_vault_b_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault b pda aint right",
+    )?;
+
+    // external program verification
+    // mint token programs, either spl token or token-2022
+    assert_msg(
+        mint_a_ai.owner == token_program_a.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program a wrong address",
+    )?;
+    assert_msg(
+        mint_b_ai.owner == token_program_b.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program b wrong address",
+    )?;
+
+    // LOGIC
+
+    // fees as of the last liquidity change, refresh with a zero liquidity decrease first
+    let amount_a = position.fees_owed_a;
+    let amount_b = position.fees_owed_b;
+    position.fees_owed_a = 0;
+    position.fees_owed_b = 0;
+    msg!("Got fees a: {} fees b: {}", amount_a, amount_b);
+
+    // withdraw
+    if amount_a > 0 {
+        let mint_a = token::unpack_mint(mint_a_ai)?;
+        token::transfer(
+            token_program_a,
+            pool_vault_a_ai,
+            mint_a_ai,
+            user_token_a_ai,
+            pool_ai,
+            amount_a,
+            mint_a.decimals,
+            &[pool_seeds],
+        )?;
+    }
+    if amount_b > 0 {
+        let mint_b = token::unpack_mint(mint_b_ai)?;
+        token::transfer(
+            token_program_b,
+            pool_vault_b_ai,
+            mint_b_ai,
+            user_token_b_ai,
+            pool_ai,
+            amount_b,
+            mint_b.decimals,
+            &[pool_seeds],
+        )?;
+    }
+
+    // save state
+    position.serialize(&mut &mut position_ai.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
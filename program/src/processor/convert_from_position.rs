@@ -0,0 +1,263 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program::id as system_program_id,
+    sysvar::rent,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    error::ChudexError,
+    state::{LpPosition, Pool},
+    token,
+    utils::{assert_msg, close_program_account},
+};
+
+use spl_token::{error::TokenError, instruction, state::Account as TokenAccount};
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let holder = next_account_info(accounts_iter)?;
+    let holder_position_token_ai = next_account_info(accounts_iter)?;
+    let holder_pool_token_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let pool_mint_ai = next_account_info(accounts_iter)?;
+    let position_mint_ai = next_account_info(accounts_iter)?;
+    let position_ai = next_account_info(accounts_iter)?;
+    let position_pool_token_ai = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let sysvar_rent = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    assert_msg(
+        position_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Position not owned by program",
+    )?;
+    let position = LpPosition::try_from_slice(&position_ai.try_borrow_data()?)?;
+    let holder_position_token =
+        TokenAccount::unpack_from_slice(&holder_position_token_ai.try_borrow_data()?)?;
+    let position_pool_token =
+        TokenAccount::unpack_from_slice(&position_pool_token_ai.try_borrow_data()?)?;
+
+    // ACCOUNT VALIDATION
+
+    // holder is signer
+    assert_msg(
+        holder.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "Holder not signer",
+    )?;
+
+    // holder has the position's nft
+    assert_msg(
+        holder_position_token.owner == *holder.key,
+        TokenError::OwnerMismatch.into(),
+        "position token not owned by holder",
+    )?;
+    assert_msg(
+        holder_position_token.mint == position.mint && position.mint == *position_mint_ai.key,
+        TokenError::MintMismatch.into(),
+        "Position mint doesn't match position",
+    )?;
+    assert_msg(
+        holder_position_token.amount == 1,
+        TokenError::InsufficientFunds.into(),
+        "Holder doesn't hold the position",
+    )?;
+
+    // position is the pool's
+    assert_msg(
+        position.pool == *pool_ai.key,
+        ChudexError::InvalidAccountData.into(),
+        "Position not of this pool",
+    )?;
+    assert_msg(
+        *pool_mint_ai.key == pool.mint,
+        TokenError::MintMismatch.into(),
+        "Pool mint doesn't match pool",
+    )?;
+
+    // pda verification
+
+    // position pda
+    let (position_key, position_bump) = Pubkey::find_program_address(
+        &[LpPosition::SEED, position_mint_ai.key.as_ref()],
+        program_id,
+    );
+    let position_seeds = &[
+        LpPosition::SEED,
+        position_mint_ai.key.as_ref(),
+        &[position_bump],
+    ];
+    assert_msg(
+        *position_ai.key == position_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "position pda aint right",
+    )?;
+
+    // position pool token pda
+    let (position_pool_token_key, _) = Pubkey::find_program_address(
+        &[
+            position_ai.key.as_ref(),
+            token_program.key.as_ref(),
+            pool_mint_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        *position_pool_token_ai.key == position_pool_token_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "position pool token pda aint right",
+    )?;
+
+    // holder pool token pda
+    let (holder_pool_token_key, _) = Pubkey::find_program_address(
+        &[
+            holder.key.as_ref(),
+            token_program.key.as_ref(),
+            pool_mint_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        *holder_pool_token_ai.key == holder_pool_token_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "holder pool token pda aint right",
+    )?;
+
+    // external program verification
+    assert_msg(
+        *token_program.key == spl_token::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program wrong address",
+    )?;
+    assert_msg(
+        *system_program.key == system_program_id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+    assert_msg(
+        *sysvar_rent.key == rent::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Sysvar program wrong address",
+    )?;
+    assert_msg(
+        *associated_token_program.key == spl_associated_token_account::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Associated token program wrong address",
+    )?;
+
+    // LOGIC
+
+    // initialize pool token holder account if needed
+    if holder_pool_token_ai.data_len() == 0 {
+        invoke(
+            &token::create_associated_token_account(
+                holder.key,
+                holder.key,
+                pool_mint_ai.key,
+                &spl_token::id(),
+            ),
+            &[
+                holder.clone(),
+                holder_pool_token_ai.clone(),
+                holder.clone(),
+                pool_mint_ai.clone(),
+                system_program.clone(),
+                token_program.clone(),
+                sysvar_rent.clone(),
+                associated_token_program.clone(),
+            ],
+        )?;
+    }
+
+    // pool tokens back to the holder, escrow rent too. Anything sent to the escrow on top
+    // of the position goes along, or it couldn't be closed
+    msg!("releasing pool tokens...");
+    invoke_signed(
+        &instruction::transfer(
+            &spl_token::id(),
+            position_pool_token_ai.key,
+            holder_pool_token_ai.key,
+            position_ai.key,
+            &[],
+            position_pool_token.amount,
+        )?,
+        &[
+            position_pool_token_ai.clone(),
+            holder_pool_token_ai.clone(),
+            position_ai.clone(),
+        ],
+        &[position_seeds],
+    )?;
+    invoke_signed(
+        &instruction::close_account(
+            &spl_token::id(),
+            position_pool_token_ai.key,
+            holder.key,
+            position_ai.key,
+            &[],
+        )?,
+        &[
+            position_pool_token_ai.clone(),
+            holder.clone(),
+            position_ai.clone(),
+        ],
+        &[position_seeds],
+    )?;
+
+    // burn the nft, the mint is left with no supply and no authority
+    msg!("burning position nft...");
+    invoke(
+        &instruction::burn(
+            &spl_token::id(),
+            holder_position_token_ai.key,
+            position_mint_ai.key,
+            holder.key,
+            &[],
+            1,
+        )?,
+        &[
+            holder_position_token_ai.clone(),
+            position_mint_ai.clone(),
+            holder.clone(),
+        ],
+    )?;
+    invoke(
+        &instruction::close_account(
+            &spl_token::id(),
+            holder_position_token_ai.key,
+            holder.key,
+            holder.key,
+            &[],
+        )?,
+        &[holder_position_token_ai.clone(), holder.clone()],
+    )?;
+
+    // refund rent and wipe the position
+    close_program_account(position_ai, holder)?;
+    msg!(
+        "Closed position {} with {} pool tokens",
+        position_mint_ai.key,
+        position.pool_token_amount
+    );
+
+    Ok(())
+}
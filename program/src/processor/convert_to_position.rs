@@ -0,0 +1,374 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+    system_program::id as system_program_id,
+    sysvar::{clock::Clock, rent, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    curve,
+    error::ChudexError,
+    state::{LpPosition, Pool},
+    token,
+    utils::assert_msg,
+};
+
+use spl_token::{
+    error::TokenError,
+    instruction::{self, AuthorityType},
+    state::{Account as TokenAccount, Mint},
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    nonce: u64,
+    pool_token_amount: u64,
+) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let user_pool_token_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let pool_mint_ai = next_account_info(accounts_iter)?;
+    let position_mint_ai = next_account_info(accounts_iter)?;
+    let user_position_token_ai = next_account_info(accounts_iter)?;
+    let position_ai = next_account_info(accounts_iter)?;
+    let position_pool_token_ai = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let sysvar_rent = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+
+    open_position(
+        program_id,
+        user,
+        user_pool_token_ai,
+        pool_ai,
+        pool_mint_ai,
+        position_mint_ai,
+        user_position_token_ai,
+        position_ai,
+        position_pool_token_ai,
+        token_program,
+        system_program,
+        sysvar_rent,
+        associated_token_program,
+        nonce,
+        pool_token_amount,
+    )
+}
+
+/// Moves pool_token_amount of the user's pool tokens into a new position, minting its nft
+/// to the user. Shared with DepositToPosition.
+#[allow(clippy::too_many_arguments)]
+pub fn open_position<'a>(
+    program_id: &Pubkey,
+    user: &AccountInfo<'a>,
+    user_pool_token_ai: &AccountInfo<'a>,
+    pool_ai: &AccountInfo<'a>,
+    pool_mint_ai: &AccountInfo<'a>,
+    position_mint_ai: &AccountInfo<'a>,
+    user_position_token_ai: &AccountInfo<'a>,
+    position_ai: &AccountInfo<'a>,
+    position_pool_token_ai: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    sysvar_rent: &AccountInfo<'a>,
+    associated_token_program: &AccountInfo<'a>,
+    nonce: u64,
+    pool_token_amount: u64,
+) -> ProgramResult {
+    // deserialization
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    let pool_mint = Mint::unpack_from_slice(&pool_mint_ai.try_borrow_data()?)?;
+    let user_pool_token = TokenAccount::unpack_from_slice(&user_pool_token_ai.try_borrow_data()?)?;
+
+    // ACCOUNT VALIDATION
+
+    // user is signer
+    assert_msg(
+        user.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "User not signer",
+    )?;
+
+    // pool tokens are the user's, of this pool
+    assert_msg(
+        user_pool_token.owner == *user.key,
+        TokenError::OwnerMismatch.into(),
+        "user pool token not owned by user",
+    )?;
+    assert_msg(
+        *pool_mint_ai.key == pool.mint && user_pool_token.mint == pool.mint,
+        TokenError::MintMismatch.into(),
+        "Pool mint doesn't match pool",
+    )?;
+    assert_msg(
+        pool_token_amount > 0,
+        ChudexError::InvalidInstructionInput.into(),
+        "Position needs pool tokens",
+    )?;
+
+    // pda verification
+
+    // pool pda
+    let (pool_key, _) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // position mint pda
+    let nonce_bytes = nonce.to_le_bytes();
+    let (position_mint_key, position_mint_bump) = Pubkey::find_program_address(
+        &[LpPosition::MINT_SEED, user.key.as_ref(), &nonce_bytes],
+        program_id,
+    );
+    let position_mint_seeds = &[
+        LpPosition::MINT_SEED,
+        user.key.as_ref(),
+        &nonce_bytes,
+        &[position_mint_bump],
+    ];
+    assert_msg(
+        *position_mint_ai.key == position_mint_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "position mint pda aint right",
+    )?;
+
+    // position pda
+    let (position_key, position_bump) = Pubkey::find_program_address(
+        &[LpPosition::SEED, position_mint_ai.key.as_ref()],
+        program_id,
+    );
+    let position_seeds = &[
+        LpPosition::SEED,
+        position_mint_ai.key.as_ref(),
+        &[position_bump],
+    ];
+    assert_msg(
+        *position_ai.key == position_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "position pda aint right",
+    )?;
+
+    // user position token pda
+    let (user_position_token_key, _) = Pubkey::find_program_address(
+        &[
+            user.key.as_ref(),
+            token_program.key.as_ref(),
+            position_mint_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        *user_position_token_ai.key == user_position_token_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "user position token pda aint right",
+    )?;
+
+    // position pool token pda
+    let (position_pool_token_key, _) = Pubkey::find_program_address(
+        &[
+            position_ai.key.as_ref(),
+            token_program.key.as_ref(),
+            pool_mint_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        *position_pool_token_ai.key == position_pool_token_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "position pool token pda aint right",
+    )?;
+
+    // external program verification
+    assert_msg(
+        *token_program.key == spl_token::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program wrong address",
+    )?;
+    assert_msg(
+        *system_program.key == system_program_id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+    assert_msg(
+        *sysvar_rent.key == rent::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Sysvar program wrong address",
+    )?;
+    assert_msg(
+        *associated_token_program.key == spl_associated_token_account::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Associated token program wrong address",
+    )?;
+
+    // LOGIC
+
+    // position nft, minted once then no mint authority
+    msg!("creating position mint...");
+    invoke_signed(
+        &system_instruction::create_account(
+            user.key,
+            position_mint_ai.key,
+            rent::Rent::get()?.minimum_balance(Mint::LEN),
+            Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        &[
+            user.clone(),
+            position_mint_ai.clone(),
+            system_program.clone(),
+        ],
+        &[position_mint_seeds],
+    )?;
+    invoke(
+        &instruction::initialize_mint(
+            &spl_token::id(),
+            position_mint_ai.key,
+            position_ai.key,
+            None,
+            0,
+        )?,
+        &[
+            position_mint_ai.clone(),
+            sysvar_rent.clone(),
+            token_program.clone(),
+        ],
+    )?;
+    invoke(
+        &token::create_associated_token_account(
+            user.key,
+            user.key,
+            position_mint_ai.key,
+            &spl_token::id(),
+        ),
+        &[
+            user.clone(),
+            user_position_token_ai.clone(),
+            user.clone(),
+            position_mint_ai.clone(),
+            system_program.clone(),
+            token_program.clone(),
+            sysvar_rent.clone(),
+            associated_token_program.clone(),
+        ],
+    )?;
+    invoke_signed(
+        &instruction::mint_to(
+            &spl_token::id(),
+            position_mint_ai.key,
+            user_position_token_ai.key,
+            position_ai.key,
+            &[],
+            1,
+        )?,
+        &[
+            position_mint_ai.clone(),
+            user_position_token_ai.clone(),
+            position_ai.clone(),
+        ],
+        &[position_seeds],
+    )?;
+    invoke_signed(
+        &instruction::set_authority(
+            &spl_token::id(),
+            position_mint_ai.key,
+            None,
+            AuthorityType::MintTokens,
+            position_ai.key,
+            &[],
+        )?,
+        &[position_mint_ai.clone(), position_ai.clone()],
+        &[position_seeds],
+    )?;
+
+    // escrow the pool tokens with the position
+    msg!("escrowing pool tokens...");
+    invoke(
+        &token::create_associated_token_account(
+            user.key,
+            position_ai.key,
+            pool_mint_ai.key,
+            &spl_token::id(),
+        ),
+        &[
+            user.clone(),
+            position_pool_token_ai.clone(),
+            position_ai.clone(),
+            pool_mint_ai.clone(),
+            system_program.clone(),
+            token_program.clone(),
+            sysvar_rent.clone(),
+            associated_token_program.clone(),
+        ],
+    )?;
+    invoke(
+        &instruction::transfer(
+            &spl_token::id(),
+            user_pool_token_ai.key,
+            position_pool_token_ai.key,
+            user.key,
+            &[],
+            pool_token_amount,
+        )?,
+        &[
+            user_pool_token_ai.clone(),
+            position_pool_token_ai.clone(),
+            user.clone(),
+        ],
+    )?;
+
+    // create position account
+    msg!("creating position...");
+    invoke_signed(
+        &system_instruction::create_account(
+            user.key,
+            position_ai.key,
+            rent::Rent::get()?.minimum_balance(LpPosition::LEN),
+            LpPosition::LEN as u64,
+            program_id,
+        ),
+        &[user.clone(), position_ai.clone(), system_program.clone()],
+        &[position_seeds],
+    )?;
+    let position = LpPosition {
+        pool: *pool_ai.key,
+        mint: *position_mint_ai.key,
+        pool_token_amount,
+        deposit_timestamp: Clock::get()?.unix_timestamp,
+        entry_liquidity_per_pool_token: curve::liquidity_per_pool_token(
+            pool.reserve_a,
+            pool.reserve_b,
+            pool_mint.supply,
+        )?,
+    };
+    position.serialize(&mut &mut position_ai.try_borrow_mut_data()?[..])?;
+    msg!(
+        "Opened position {} with {} pool tokens",
+        position_mint_ai.key,
+        pool_token_amount
+    );
+
+    Ok(())
+}
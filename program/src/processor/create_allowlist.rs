@@ -0,0 +1,114 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction, system_program as system_program_ext,
+    sysvar::{rent, Sysvar},
+};
+
+use crate::{error::ChudexError, state::Allowlist, utils::assert_msg};
+
+use borsh::BorshSerialize;
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    nonce: u64,
+    capacity: u32,
+    members: Vec<Pubkey>,
+) -> ProgramResult {
+    // GET ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let authority = next_account_info(accounts_iter)?;
+    let allowlist_ai = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    // ACCOUNT VALIDATION
+
+    // authority is signer
+    assert_msg(
+        authority.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "Authority not signer",
+    )?;
+    assert_msg(
+        members.len() <= capacity as usize,
+        ChudexError::InvalidInstructionInput.into(),
+        "More members than capacity",
+    )?;
+
+    // pda verification
+
+    // allowlist pda
+    let nonce_bytes = nonce.to_le_bytes();
+    let (allowlist_key, allowlist_bump) = Pubkey::find_program_address(
+        &[Allowlist::SEED, authority.key.as_ref(), &nonce_bytes],
+        program_id,
+    );
+    let allowlist_seeds = &[
+        Allowlist::SEED,
+        authority.key.as_ref(),
+        &nonce_bytes,
+        &[allowlist_bump],
+    ];
+    assert_msg(
+        *allowlist_ai.key == allowlist_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "allowlist pda aint right",
+    )?;
+    assert_msg(
+        allowlist_ai.data_len() == 0,
+        ChudexError::AccountAlreadyInitialized.into(),
+        "Allowlist already initialized",
+    )?;
+
+    // external program verification
+
+    // system program
+    assert_msg(
+        *system_program.key == system_program_ext::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+
+    // LOGIC
+
+    // create allowlist account sized for its capacity
+    msg!("creating allowlist...");
+    let allowlist_len = Allowlist::len(capacity as usize);
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            allowlist_ai.key,
+            rent::Rent::get()?.minimum_balance(allowlist_len),
+            allowlist_len as u64,
+            program_id,
+        ),
+        &[
+            authority.clone(),
+            allowlist_ai.clone(),
+            system_program.clone(),
+        ],
+        &[allowlist_seeds],
+    )?;
+
+    let mut allowlist = Allowlist {
+        authority: *authority.key,
+        len: 0,
+        members: vec![Pubkey::default(); capacity as usize],
+    };
+    for member in members {
+        if !allowlist.members().contains(&member) {
+            allowlist.members[allowlist.len as usize] = member;
+            allowlist.len += 1;
+        }
+    }
+    allowlist.serialize(&mut &mut allowlist_ai.try_borrow_mut_data()?[..])?;
+    msg!("created allowlist with {} members", allowlist.len);
+
+    Ok(())
+}
@@ -0,0 +1,242 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    pubkey::Pubkey,
+    system_instruction, system_program as system_program_ext,
+    sysvar::{clock::Clock, rent, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    allowlist,
+    error::ChudexError,
+    state::{Dca, Pool},
+    token,
+    utils::assert_msg,
+};
+
+#[allow(clippy::too_many_arguments)]
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    nonce: u64,
+    amount_per_cycle: u64,
+    interval_seconds: u32,
+    cycles: u32,
+    min_amount_out_per_cycle: u64,
+) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let owner_token_in_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let dca_ai = next_account_info(accounts_iter)?;
+    let escrow_ai = next_account_info(accounts_iter)?;
+    let mint_in_ai = next_account_info(accounts_iter)?;
+    let mint_out_ai = next_account_info(accounts_iter)?;
+    let token_program_in = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let sysvar_rent = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+    // allowlist or allowlist ticket, permissioned pools only
+    let allowlist_ai = next_account_info(accounts_iter).ok();
+
+    // deserialization
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    let mint_in = token::unpack_mint(mint_in_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // owner is signer
+    assert_msg(
+        owner.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "Owner not signer",
+    )?;
+
+    // owner is on the pool's allowlist, if it has one
+    allowlist::check(program_id, pool_ai, &pool, owner.key, allowlist_ai)?;
+
+    assert_msg(
+        amount_per_cycle > 0 && interval_seconds > 0 && cycles > 0,
+        ChudexError::InvalidInstructionInput.into(),
+        "Dca amounts must be positive",
+    )?;
+    let amount_in = amount_per_cycle
+        .checked_mul(cycles as u64)
+        .ok_or(ChudexError::CalculationFailure)?;
+
+    // mints are the pool's two mints
+    assert_msg(
+        (*mint_in_ai.key == pool.mint_a && *mint_out_ai.key == pool.mint_b)
+            || (*mint_in_ai.key == pool.mint_b && *mint_out_ai.key == pool.mint_a),
+        ChudexError::InvalidAccountAddress.into(),
+        "Mints don't match pool",
+    )?;
+
+    // pda verification
+
+    // pool pda
+    let (pool_key, _) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // dca pda
+    let nonce_bytes = nonce.to_le_bytes();
+    let (dca_key, dca_bump) = Pubkey::find_program_address(
+        &[
+            Dca::SEED,
+            pool_ai.key.as_ref(),
+            owner.key.as_ref(),
+            &nonce_bytes,
+        ],
+        program_id,
+    );
+    let dca_seeds = &[
+        Dca::SEED,
+        pool_ai.key.as_ref(),
+        owner.key.as_ref(),
+        &nonce_bytes,
+        &[dca_bump],
+    ];
+    assert_msg(
+        *dca_ai.key == dca_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "dca pda aint right",
+    )?;
+    assert_msg(
+        dca_ai.data_len() == 0,
+        ChudexError::AccountAlreadyInitialized.into(),
+        "Dca already initialized",
+    )?;
+
+    // escrow pda
+    let (escrow_key, _) = Pubkey::find_program_address(
+        &[
+            dca_ai.key.as_ref(),
+            token_program_in.key.as_ref(),
+            mint_in_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        escrow_key == *escrow_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "escrow pda aint right",
+    )?;
+
+    // external program verification
+    // mint token program, either spl token or token-2022
+    assert_msg(
+        mint_in_ai.owner == token_program_in.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program in wrong address",
+    )?;
+
+    // system program
+    assert_msg(
+        *system_program.key == system_program_ext::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+
+    // sysvar program
+    assert_msg(
+        *sysvar_rent.key == rent::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Sysvar program wrong address",
+    )?;
+
+    // associated token program
+    assert_msg(
+        *associated_token_program.key == spl_associated_token_account::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Associated token program wrong address",
+    )?;
+
+    // LOGIC
+
+    // create dca account
+    msg!("creating dca...");
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            dca_ai.key,
+            rent::Rent::get()?.minimum_balance(Dca::LEN),
+            Dca::LEN as u64,
+            program_id,
+        ),
+        &[owner.clone(), dca_ai.clone(), system_program.clone()],
+        &[dca_seeds],
+    )?;
+
+    // create escrow owned by the dca
+    invoke(
+        &token::create_associated_token_account(
+            owner.key,
+            dca_ai.key,
+            mint_in_ai.key,
+            token_program_in.key,
+        ),
+        &[
+            owner.clone(),
+            escrow_ai.clone(),
+            dca_ai.clone(),
+            mint_in_ai.clone(),
+            system_program.clone(),
+            token_program_in.clone(),
+            sysvar_rent.clone(),
+            associated_token_program.clone(),
+        ],
+    )?;
+
+    // escrow every cycle up front, any transfer fee comes out of the last one
+    token::transfer(
+        token_program_in,
+        owner_token_in_ai,
+        mint_in_ai,
+        escrow_ai,
+        owner,
+        amount_in,
+        mint_in.decimals,
+        &[],
+    )?;
+
+    // the first cycle is due right away
+    let dca = Dca {
+        pool: *pool_ai.key,
+        owner: *owner.key,
+        nonce,
+        mint_in: *mint_in_ai.key,
+        mint_out: *mint_out_ai.key,
+        amount_per_cycle,
+        min_amount_out_per_cycle,
+        interval_seconds,
+        cycles_left: cycles,
+        next_cycle_timestamp: Clock::get()?.unix_timestamp,
+    };
+    dca.serialize(&mut &mut dca_ai.try_borrow_mut_data()?[..])?;
+    msg!(
+        "created dca of {} cycles of {} every {}s",
+        cycles,
+        amount_per_cycle,
+        interval_seconds
+    );
+
+    Ok(())
+}
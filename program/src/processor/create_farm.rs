@@ -0,0 +1,314 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction, system_program as system_program_ext,
+    sysvar::{clock::Clock, rent, Sysvar},
+};
+
+use crate::{
+    error::ChudexError,
+    state::{Farm, FarmReward, Pool},
+    token,
+    utils::assert_msg,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use spl_token::error::TokenError;
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    nonce: u64,
+    emission_rates: Vec<u64>,
+    start_slot: u64,
+    end_slot: u64,
+) -> ProgramResult {
+    // GET ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let authority = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let farm_ai = next_account_info(accounts_iter)?;
+    let farm_vault_ai = next_account_info(accounts_iter)?;
+    let pool_mint_ai = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let sysvar_rent = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+
+    let num_rewards = emission_rates.len();
+    assert_msg(
+        (1..=Farm::MAX_REWARDS).contains(&num_rewards),
+        ChudexError::InvalidInstructionInput.into(),
+        "Farm needs 1 to 4 reward mints",
+    )?;
+    assert_msg(
+        emission_rates.iter().all(|rate| *rate > 0),
+        ChudexError::InvalidInstructionInput.into(),
+        "Emission rates must be positive",
+    )?;
+
+    // (mint, authority reward acc, farm reward acc, token program) per reward
+    let mut rewards = Vec::with_capacity(num_rewards);
+    for _ in 0..num_rewards {
+        rewards.push((
+            next_account_info(accounts_iter)?,
+            next_account_info(accounts_iter)?,
+            next_account_info(accounts_iter)?,
+            next_account_info(accounts_iter)?,
+        ));
+    }
+
+    // deserialization
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+
+    // ACCOUNT VALIDATION
+
+    // authority is signer
+    assert_msg(
+        authority.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "Authority not signer",
+    )?;
+
+    let now = Clock::get()?.slot;
+    assert_msg(
+        start_slot >= now && end_slot > start_slot,
+        ChudexError::InvalidInstructionInput.into(),
+        "Farm has to run from now or later to a later end",
+    )?;
+
+    // staked token is the pool's lp mint
+    assert_msg(
+        *pool_mint_ai.key == pool.mint,
+        TokenError::MintMismatch.into(),
+        "Pool mint doesn't match pool",
+    )?;
+
+    // reward mints unique
+    for (i, (mint_ai, _, _, _)) in rewards.iter().enumerate() {
+        assert_msg(
+            rewards[..i].iter().all(|other| other.0.key != mint_ai.key),
+            ChudexError::InvalidInstructionInput.into(),
+            "Reward mints not unique",
+        )?;
+    }
+
+    // pda verification
+
+    // pool pda
+    let (pool_key, _) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // farm pda
+    let nonce_bytes = nonce.to_le_bytes();
+    let (farm_key, farm_bump) = Pubkey::find_program_address(
+        &[
+            Farm::SEED,
+            pool_ai.key.as_ref(),
+            authority.key.as_ref(),
+            &nonce_bytes,
+        ],
+        program_id,
+    );
+    let farm_seeds = &[
+        Farm::SEED,
+        pool_ai.key.as_ref(),
+        authority.key.as_ref(),
+        &nonce_bytes,
+        &[farm_bump],
+    ];
+    assert_msg(
+        *farm_ai.key == farm_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "farm pda aint right",
+    )?;
+    assert_msg(
+        farm_ai.data_len() == 0,
+        ChudexError::AccountAlreadyInitialized.into(),
+        "Farm already initialized",
+    )?;
+
+    // farm vault pda
+    let (farm_vault_key, _) = Pubkey::find_program_address(
+        &[
+            farm_ai.key.as_ref(),
+            token_program.key.as_ref(),
+            pool_mint_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        farm_vault_key == *farm_vault_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "farm vault pda aint right",
+    )?;
+
+    // farm reward vault pdas
+    for (mint_ai, _, farm_reward_vault_ai, token_program_ai) in &rewards {
+        let (farm_reward_vault_key, _) = Pubkey::find_program_address(
+            &[
+                farm_ai.key.as_ref(),
+                token_program_ai.key.as_ref(),
+                mint_ai.key.as_ref(),
+            ],
+            &spl_associated_token_account::id(),
+        );
+        assert_msg(
+            farm_reward_vault_key == *farm_reward_vault_ai.key,
+            ChudexError::InvalidProgramAddress.into(),
+            "farm reward vault pda aint right",
+        )?;
+    }
+
+    // external program verification
+
+    // token program, pool mints are spl token
+    assert_msg(
+        *token_program.key == spl_token::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program wrong address",
+    )?;
+
+    // reward mint token programs, either spl token or token-2022
+    for (mint_ai, _, _, token_program_ai) in &rewards {
+        assert_msg(
+            mint_ai.owner == token_program_ai.key && token::is_token_program(token_program_ai.key),
+            ChudexError::InvalidAccountAddress.into(),
+            "Reward token program wrong address",
+        )?;
+    }
+
+    // system program
+    assert_msg(
+        *system_program.key == system_program_ext::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+
+    // sysvar program
+    assert_msg(
+        *sysvar_rent.key == rent::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Sysvar program wrong address",
+    )?;
+
+    // associated token program
+    assert_msg(
+        *associated_token_program.key == spl_associated_token_account::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Associated token program wrong address",
+    )?;
+
+    // LOGIC
+
+    // create farm account
+    msg!("creating farm...");
+    let farm_len = Farm::len(num_rewards);
+    invoke_signed(
+        &system_instruction::create_account(
+            authority.key,
+            farm_ai.key,
+            rent::Rent::get()?.minimum_balance(farm_len),
+            farm_len as u64,
+            program_id,
+        ),
+        &[authority.clone(), farm_ai.clone(), system_program.clone()],
+        &[farm_seeds],
+    )?;
+
+    // vault for staked pool tokens, then one per reward mint
+    let vaults = [(farm_vault_ai, pool_mint_ai, token_program)]
+        .into_iter()
+        .chain(
+            rewards
+                .iter()
+                .map(|(mint_ai, _, vault_ai, token_program_ai)| {
+                    (*vault_ai, *mint_ai, *token_program_ai)
+                }),
+        );
+    for (vault_ai, mint_ai, token_program_ai) in vaults {
+        invoke(
+            &token::create_associated_token_account(
+                authority.key,
+                farm_ai.key,
+                mint_ai.key,
+                token_program_ai.key,
+            ),
+            &[
+                authority.clone(),
+                vault_ai.clone(),
+                farm_ai.clone(),
+                mint_ai.clone(),
+                system_program.clone(),
+                token_program_ai.clone(),
+                sysvar_rent.clone(),
+                associated_token_program.clone(),
+            ],
+        )?;
+    }
+
+    // fund every reward for the whole farm up front, so that harvests can't run dry
+    let duration = end_slot - start_slot;
+    for ((mint_ai, authority_reward_ai, farm_reward_vault_ai, token_program_ai), emission_rate) in
+        rewards.iter().zip(&emission_rates)
+    {
+        let total = emission_rate
+            .checked_mul(duration)
+            .ok_or(ChudexError::CalculationFailure)?;
+        let mint = token::unpack_mint(mint_ai)?;
+        token::transfer(
+            token_program_ai,
+            authority_reward_ai,
+            mint_ai,
+            farm_reward_vault_ai,
+            authority,
+            token::amount_before_transfer_fee(mint_ai, total)?,
+            mint.decimals,
+            &[],
+        )?;
+        msg!("Funded reward {} with {}", mint_ai.key, total);
+    }
+
+    let farm = Farm {
+        pool: *pool_ai.key,
+        mint: pool.mint,
+        authority: *authority.key,
+        nonce,
+        start_slot,
+        end_slot,
+        last_update_slot: start_slot,
+        total_staked: 0,
+        rewards: rewards
+            .iter()
+            .zip(&emission_rates)
+            .map(|((mint_ai, _, _, _), emission_rate)| FarmReward {
+                mint: *mint_ai.key,
+                emission_rate: *emission_rate,
+                reward_per_share: 0,
+                unallocated: 0,
+            })
+            .collect(),
+    };
+    farm.serialize(&mut &mut farm_ai.try_borrow_mut_data()?[..])?;
+    msg!("created farm");
+
+    Ok(())
+}
@@ -6,28 +6,71 @@ use solana_program::{
     program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
-    system_instruction,
     system_program::id as system_program_id,
-    sysvar::{rent, Sysvar},
+    sysvar::{clock::Clock, rent, Sysvar},
 };
 
 use borsh::{BorshDeserialize, BorshSerialize};
 
-use crate::{error::ChudexError, state::Pool, utils::assert_msg};
-
-use spl_associated_token_account::{create_associated_token_account, id};
-use spl_token::{
-    error::TokenError,
-    instruction,
-    state::{Account as TokenAccount, Mint},
+use crate::{
+    allowlist,
+    client::{self, DepositResult},
+    curve,
+    error::ChudexError,
+    events::{ChudexEvent, Deposited},
+    hook::{self, HookOperation, HookPayload, HookStage},
+    state::Pool,
+    token, twamm,
+    utils::assert_msg,
 };
 
+use spl_token::{error::TokenError, instruction, state::Mint};
+
+use std::cmp;
+
+/// What a deposit is sized by, amounts sent in the caller's account order.
+pub enum DepositAmounts {
+    /// All of token a, token b at the pool's price.
+    TokenA {
+        token_a_amount: u64,
+        max_token_b_amount: u64,
+    },
+    /// Exactly pool_token_amount minted, both tokens at their share of the reserves.
+    PoolTokens {
+        pool_token_amount: u64,
+        max_token_a_amount: u64,
+        max_token_b_amount: u64,
+    },
+}
+
 pub fn process(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     token_a_amount: u64,
     max_token_b_amount: u64,
+    native_sol: bool,
 ) -> ProgramResult {
+    client::with_result(|| {
+        deposit(
+            program_id,
+            accounts,
+            DepositAmounts::TokenA {
+                token_a_amount,
+                max_token_b_amount,
+            },
+            native_sol,
+        )
+    })
+}
+
+/// Deposit and its variants, which only differ in how the amounts are worked out. Returns
+/// what the user paid and was minted, for the caller to set as return data once done.
+pub fn deposit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amounts: DepositAmounts,
+    native_sol: bool,
+) -> Result<DepositResult, ProgramError> {
     // FETCH ACCOUNTS
     let accounts_iter = &mut accounts.iter();
 
@@ -43,30 +86,60 @@ pub fn process(
     let system_program = next_account_info(accounts_iter)?;
     let sysvar_rent = next_account_info(accounts_iter)?;
     let associated_token_program = next_account_info(accounts_iter)?;
+    let mint_a_ai = next_account_info(accounts_iter)?;
+    let mint_b_ai = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
 
     // deserialization
-    let pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
-    let user_token_a = TokenAccount::unpack_from_slice(&user_token_a_ai.try_borrow_data()?)?;
-    let user_token_b = TokenAccount::unpack_from_slice(&user_token_b_ai.try_borrow_data()?)?;
-    let pool_vault_a = TokenAccount::unpack_from_slice(&pool_vault_a_ai.try_borrow_data()?)?;
-    let pool_vault_b = TokenAccount::unpack_from_slice(&pool_vault_b_ai.try_borrow_data()?)?;
+    let mut pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    // twamm accounts for pools with a twamm, allowlist or allowlist ticket for
+    // permissioned pools, then the hook's accounts
+    let twamm_accounts = twamm::next_twamm_accounts(&pool, accounts_iter)?;
+    let allowlist_ai = match pool.allowlist {
+        Some(_) => next_account_info(accounts_iter).ok(),
+        None => None,
+    };
+    let hook_accounts = accounts_iter.as_slice();
+    let pool_vault_a = token::unpack_token_account(pool_vault_a_ai)?;
+    let pool_vault_b = token::unpack_token_account(pool_vault_b_ai)?;
     let pool_mint = Mint::unpack_from_slice(&pool_mint_ai.try_borrow_data()?)?;
+    let mint_a = token::unpack_mint(mint_a_ai)?;
+    let mint_b = token::unpack_mint(mint_b_ai)?;
 
     // ACCOUNT VALIDATION
 
-    // token account ownership
-    // user token accounts
+    // native sol is wrapped into a temporary account in place of the user token account
+    let native_a = native_sol && *mint_a_ai.key == spl_token::native_mint::id();
+    let native_b = native_sol && *mint_b_ai.key == spl_token::native_mint::id();
     assert_msg(
-        user_token_a.owner == *user.key,
-        TokenError::OwnerMismatch.into(),
-        "user token a not owned by user",
-    )?;
-    assert_msg(
-        user_token_b.owner == *user.key,
-        TokenError::OwnerMismatch.into(),
-        "user token b not owned by user",
+        !native_sol || native_a || native_b,
+        ChudexError::InvalidInstructionInput.into(),
+        "Neither mint is native sol",
     )?;
 
+    // token account ownership
+    // user token accounts
+    if !native_a {
+        let user_token_a = token::unpack_token_account(user_token_a_ai)?;
+        assert_msg(
+            user_token_a.owner == *user.key,
+            TokenError::OwnerMismatch.into(),
+            "user token a not owned by user",
+        )?;
+    }
+    if !native_b {
+        let user_token_b = token::unpack_token_account(user_token_b_ai)?;
+        assert_msg(
+            user_token_b.owner == *user.key,
+            TokenError::OwnerMismatch.into(),
+            "user token b not owned by user",
+        )?;
+    }
+
+    // user is on the pool's allowlist, if it has one
+    allowlist::check(program_id, pool_ai, &pool, user.key, allowlist_ai)?;
+
     // pool vault accounts
     assert_msg(
         pool_vault_a.owner == *pool_ai.key,
@@ -79,6 +152,26 @@ pub fn process(
         "pool vault b not owned by pool",
     )?;
 
+    // vaults hold the pool's two mints
+    assert_msg(
+        (pool_vault_a.mint == pool.mint_a && pool_vault_b.mint == pool.mint_b)
+            || (pool_vault_a.mint == pool.mint_b && pool_vault_b.mint == pool.mint_a),
+        ChudexError::InvalidAccountData.into(),
+        "pool vault mints don't match pool",
+    )?;
+
+    // mints match vaults
+    assert_msg(
+        *mint_a_ai.key == pool_vault_a.mint,
+        ChudexError::InvalidAccountAddress.into(),
+        "mint a doesn't match vault a",
+    )?;
+    assert_msg(
+        *mint_b_ai.key == pool_vault_b.mint,
+        ChudexError::InvalidAccountAddress.into(),
+        "mint b doesn't match vault b",
+    )?;
+
     // pool is mint authority
     assert_msg(
         pool_mint.mint_authority.unwrap() == *pool_ai.key,
@@ -88,30 +181,38 @@ pub fn process(
 
     // pda verification
 
-    // user token a pda
-    let (user_token_a_key, _) = Pubkey::find_program_address(
-        &[
-            user.key.as_ref(),
-            token_program.key.as_ref(),
-            pool_vault_a.mint.as_ref(),
-        ],
-        &spl_associated_token_account::id(),
-    );
+    // user token a pda, or the temporary wsol account
+    let (user_token_a_key, _) = if native_a {
+        token::wsol_address(program_id, user.key)
+    } else {
+        Pubkey::find_program_address(
+            &[
+                user.key.as_ref(),
+                token_program_a.key.as_ref(),
+                pool_vault_a.mint.as_ref(),
+            ],
+            &spl_associated_token_account::id(),
+        )
+    };
     assert_msg(
         user_token_a_key == *user_token_a_ai.key,
         ChudexError::InvalidProgramAddress.into(),
         "user token account a pda aint right",
     )?;
 
-    // user token b pda
-    let (user_token_b_key, _) = Pubkey::find_program_address(
-        &[
-            user.key.as_ref(),
-            token_program.key.as_ref(),
-            pool_vault_b.mint.as_ref(),
-        ],
-        &spl_associated_token_account::id(),
-    );
+    // user token b pda, or the temporary wsol account
+    let (user_token_b_key, _) = if native_b {
+        token::wsol_address(program_id, user.key)
+    } else {
+        Pubkey::find_program_address(
+            &[
+                user.key.as_ref(),
+                token_program_b.key.as_ref(),
+                pool_vault_b.mint.as_ref(),
+            ],
+            &spl_associated_token_account::id(),
+        )
+    };
     assert_msg(
         user_token_b_key == *user_token_b_ai.key,
         ChudexError::InvalidProgramAddress.into(),
@@ -134,10 +235,10 @@ pub fn process(
     )?;
 
     // vault a pda
-    let (vault_a_key, vault_a_bump) = Pubkey::find_program_address(
+    let (vault_a_key, _) = Pubkey::find_program_address(
         &[
             pool_ai.key.as_ref(),
-            token_program.key.as_ref(),
+            token_program_a.key.as_ref(),
             pool_vault_a.mint.as_ref(),
         ],
         &spl_associated_token_account::id(),
@@ -149,10 +250,10 @@ pub fn process(
     )?;
 
     // vault b pda
-    let (vault_b_key, vault_b_bump) = Pubkey::find_program_address(
+    let (vault_b_key, _) = Pubkey::find_program_address(
         &[
             pool_ai.key.as_ref(),
-            token_program.key.as_ref(),
+            token_program_b.key.as_ref(),
             pool_vault_b.mint.as_ref(),
         ],
         &spl_associated_token_account::id(),
@@ -163,7 +264,8 @@ pub fn process(
         "vault b pda aint right",
     )?;
 
-    // pool pda
+    // pool pda, seeds copied out since the pool's reserves get updated while they're in use
+    let (pool_mint_a, pool_mint_b) = (pool.mint_a, pool.mint_b);
     let (pool_key, pool_bump) = Pubkey::find_program_address(
         &[
             b"chudex_pool",
@@ -178,8 +280,8 @@ pub fn process(
         b"chudex_pool",
         // mint_a_ai.key.as_ref(),
         // mint_b_ai.key.as_ref(),
-        pool.mint_a.as_ref(),
-        pool.mint_b.as_ref(),
+        pool_mint_a.as_ref(),
+        pool_mint_b.as_ref(),
         &[pool_bump],
     ];
     assert_msg(
@@ -189,9 +291,8 @@ pub fn process(
     )?;
 
     // pool mint pda
-    let (pool_mint_key, pool_mint_bump) =
+    let (pool_mint_key, _) =
         Pubkey::find_program_address(&[b"chudex_pool_mint", pool_ai.key.as_ref()], program_id);
-    let pool_mint_seeds = &[b"chudex_pool_mint", pool_ai.key.as_ref(), &[pool_mint_bump]];
     assert_msg(
         *pool_mint_ai.key == pool_mint_key,
         ChudexError::InvalidAccountAddress.into(),
@@ -206,6 +307,18 @@ pub fn process(
         "Token program wrong address",
     )?;
 
+    // mint token programs, either spl token or token-2022
+    assert_msg(
+        mint_a_ai.owner == token_program_a.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program a wrong address",
+    )?;
+    assert_msg(
+        mint_b_ai.owner == token_program_b.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program b wrong address",
+    )?;
+
     // system program
     assert_msg(
         *system_program.key == system_program_id(),
@@ -229,81 +342,186 @@ pub fn process(
 
     // LOGIC
 
+    // long-term orders trade first, at the price before this deposit
+    twamm::settle(
+        program_id,
+        pool_ai,
+        &mut pool,
+        pool_seeds,
+        twamm_accounts,
+        (pool_vault_a_ai, mint_a_ai, token_program_a),
+        (pool_vault_b_ai, mint_b_ai, token_program_b),
+        Clock::get()?.slot,
+    )?;
+    // tracked reserves, in vault order
+    let reserve_a = pool.reserve(&pool_vault_a.mint);
+    let reserve_b = pool.reserve(&pool_vault_b.mint);
+
     // calculate how much of each token to deposit
-    let token_b_amount = if pool_vault_a.amount == 0 || pool_vault_b.amount == 0 {
-        max_token_b_amount
-    } else {
-        ((token_a_amount as f64) * (pool_vault_b.amount as f64) / (pool_vault_a.amount as f64))
-            as u64
+    // pool math uses what actually lands in the vaults after any transfer fees
+    let (token_a_amount, token_b_sent, pool_token_amount) = match amounts {
+        DepositAmounts::TokenA {
+            token_a_amount,
+            max_token_b_amount,
+        } => {
+            let token_a_received = token::amount_after_transfer_fee(mint_a_ai, token_a_amount)?;
+            let empty = pool_mint.supply == 0 || reserve_a == 0 || reserve_b == 0;
+            let token_b_sent = if empty {
+                max_token_b_amount
+            } else {
+                // b at the reserve ratio, rounded up
+                let token_b_needed = u64::try_from(
+                    ((token_a_received as u128) * (reserve_b as u128)).div_ceil(reserve_a as u128),
+                )
+                .map_err(|_| ChudexError::CalculationFailure)?;
+                token::amount_before_transfer_fee(mint_b_ai, token_b_needed)?
+            };
+
+            if token_b_sent > max_token_b_amount {
+                return Err(ChudexError::DepositAmountExceedsLimit.into());
+            }
+            let token_b_received = token::amount_after_transfer_fee(mint_b_ai, token_b_sent)?;
+
+            // calculate how much pool token to mint
+            // - first deposit: greater decimal token amount, tie broken by vault_a before vault_b
+            // - after that: the smaller of both tokens' shares of their reserves, rounded down
+            let pool_token_amount = if empty {
+                if pool.mint_a == pool_vault_a.mint {
+                    token_a_received
+                } else {
+                    token_b_received
+                }
+            } else {
+                cmp::min(
+                    curve::reserve_to_pool_tokens(token_a_received, reserve_a, pool_mint.supply)?,
+                    curve::reserve_to_pool_tokens(token_b_received, reserve_b, pool_mint.supply)?,
+                )
+            };
+            (token_a_amount, token_b_sent, pool_token_amount)
+        }
+        DepositAmounts::PoolTokens {
+            pool_token_amount,
+            max_token_a_amount,
+            max_token_b_amount,
+        } => {
+            // nothing to take a share of in an empty pool
+            assert_msg(
+                pool_mint.supply > 0 && reserve_a > 0 && reserve_b > 0,
+                ChudexError::InvalidInstructionInput.into(),
+                "Pool is empty, make the first deposit with Deposit",
+            )?;
+
+            // rounded up so the minted pool tokens never take value from the pool
+            let token_a_needed =
+                curve::pool_tokens_to_reserve_ceil(pool_token_amount, pool_mint.supply, reserve_a)?;
+            let token_b_needed =
+                curve::pool_tokens_to_reserve_ceil(pool_token_amount, pool_mint.supply, reserve_b)?;
+            let token_a_sent = token::amount_before_transfer_fee(mint_a_ai, token_a_needed)?;
+            let token_b_sent = token::amount_before_transfer_fee(mint_b_ai, token_b_needed)?;
+
+            if token_a_sent > max_token_a_amount || token_b_sent > max_token_b_amount {
+                return Err(ChudexError::DepositAmountExceedsLimit.into());
+            }
+            (token_a_sent, token_b_sent, pool_token_amount)
+        }
     };
-
-    if token_b_amount > max_token_b_amount {
-        return Err(ChudexError::DepositAmountExceedsLimit.into());
-    }
+    let token_a_received = token::amount_after_transfer_fee(mint_a_ai, token_a_amount)?;
+    let token_b_received = token::amount_after_transfer_fee(mint_b_ai, token_b_sent)?;
 
     msg!(
         "Got token amounts - a: {} b: {}",
-        token_a_amount,
-        token_b_amount
+        token_a_received,
+        token_b_received
     );
+    msg!("Got pool token amount");
+
+    // the pool's hook can reject the deposit
+    hook::call(
+        program_id,
+        pool_ai,
+        &pool,
+        hook_accounts,
+        &HookPayload {
+            stage: HookStage::Before,
+            operation: HookOperation::Deposit,
+            pool: *pool_ai.key,
+            user: *user.key,
+            token_a_amount: token_a_received,
+            token_b_amount: token_b_received,
+            pool_token_amount,
+            reserve_a,
+            reserve_b,
+        }
+        .in_pool_order(&pool, &pool_vault_a.mint),
+    )?;
+
+    // wrap native sol
+    if native_a {
+        token::wrap_sol(
+            program_id,
+            user,
+            user_token_a_ai,
+            mint_a_ai,
+            token_program_a,
+            system_program,
+            sysvar_rent,
+            token_a_amount,
+        )?;
+    }
+    if native_b {
+        token::wrap_sol(
+            program_id,
+            user,
+            user_token_b_ai,
+            mint_b_ai,
+            token_program_b,
+            system_program,
+            sysvar_rent,
+            token_b_sent,
+        )?;
+    }
 
     // deposit
     // deposit token 1
-    invoke(
-        &instruction::transfer(
-            &spl_token::id(),
-            &user_token_a_ai.key,
-            &pool_vault_a_ai.key,
-            &user.key,
-            &[&user.key],
-            token_a_amount,
-        )?,
-        &[
-            user_token_a_ai.clone(),
-            pool_vault_a_ai.clone(),
-            user.clone(),
-        ],
+    token::transfer(
+        token_program_a,
+        user_token_a_ai,
+        mint_a_ai,
+        pool_vault_a_ai,
+        user,
+        token_a_amount,
+        mint_a.decimals,
+        &[],
     )?;
 
     // deposit token 2
-    invoke(
-        &instruction::transfer(
-            &spl_token::id(),
-            &user_token_b_ai.key,
-            &pool_vault_b_ai.key,
-            &user.key,
-            &[&user.key],
-            token_b_amount,
-        )?,
-        &[
-            user_token_b_ai.clone(),
-            pool_vault_b_ai.clone(),
-            user.clone(),
-        ],
+    token::transfer(
+        token_program_b,
+        user_token_b_ai,
+        mint_b_ai,
+        pool_vault_b_ai,
+        user,
+        token_b_sent,
+        mint_b.decimals,
+        &[],
     )?;
 
-    // calculate how much pool token to mint
-    // - greater decimal token amount, tie broken by vault_a before vault_b
-    msg!(
-        "pool.mint_a: {} pool_vault_a.mint: {}",
-        pool.mint_a,
-        pool_vault_a.mint
-    );
-    msg!("pool.mint_b: {}", pool.mint_b);
-    let pool_token_amount = if pool.mint_a == pool_vault_a.mint {
-        token_a_amount
-    } else {
-        token_b_amount
-    };
-    msg!("Got pool token amount");
+    // close the emptied wsol account back to the user
+    if native_a {
+        token::unwrap_sol(user, user_token_a_ai, token_program_a)?;
+    }
+    if native_b {
+        token::unwrap_sol(user, user_token_b_ai, token_program_b)?;
+    }
 
     // initialize pool token user account if needed
     if user_pool_token.data_len() == 0 {
         invoke(
-            &spl_associated_token_account::create_associated_token_account(
+            &token::create_associated_token_account(
                 user.key,
                 user.key,
                 pool_mint_ai.key,
+                &spl_token::id(),
             ),
             &[
                 user.clone(),
@@ -322,10 +540,10 @@ pub fn process(
     invoke_signed(
         &instruction::mint_to(
             &spl_token::id(),
-            &pool_mint_ai.key,
-            &user_pool_token.key,
-            &pool_ai.key,
-            &[&pool_ai.key],
+            pool_mint_ai.key,
+            user_pool_token.key,
+            pool_ai.key,
+            &[pool_ai.key],
             pool_token_amount,
         )?,
         &[
@@ -336,5 +554,55 @@ pub fn process(
         &[pool_seeds],
     )?;
 
-    Ok(())
+    // callers get what they paid and were minted, in their account order
+    let result = DepositResult {
+        token_a_amount,
+        token_b_amount: token_b_sent,
+        pool_token_amount,
+    };
+
+    let reserve_a = reserve_a + token_a_received;
+    let reserve_b = reserve_b + token_b_received;
+    pool.set_reserve(&pool_vault_a.mint, reserve_a);
+    pool.set_reserve(&pool_vault_b.mint, reserve_b);
+    pool.serialize(&mut &mut pool_ai.try_borrow_mut_data()?[..])?;
+
+    // report amounts in pool mint order
+    let (token_a_amount, token_b_amount, reserve_a, reserve_b) = if pool.mint_a == pool_vault_a.mint
+    {
+        (token_a_received, token_b_received, reserve_a, reserve_b)
+    } else {
+        (token_b_received, token_a_received, reserve_b, reserve_a)
+    };
+    ChudexEvent::Deposited(Deposited {
+        pool: *pool_ai.key,
+        user: *user.key,
+        token_a_amount,
+        token_b_amount,
+        pool_token_amount,
+        reserve_a,
+        reserve_b,
+        pool_token_supply: pool_mint.supply + pool_token_amount,
+    })
+    .emit();
+
+    hook::call(
+        program_id,
+        pool_ai,
+        &pool,
+        hook_accounts,
+        &HookPayload {
+            stage: HookStage::After,
+            operation: HookOperation::Deposit,
+            pool: *pool_ai.key,
+            user: *user.key,
+            token_a_amount,
+            token_b_amount,
+            pool_token_amount,
+            reserve_a,
+            reserve_b,
+        },
+    )?;
+
+    Ok(result)
 }
@@ -0,0 +1,29 @@
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+use crate::{
+    client,
+    processor::deposit::{self, DepositAmounts},
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    pool_token_amount: u64,
+    max_token_a_amount: u64,
+    max_token_b_amount: u64,
+    native_sol: bool,
+) -> ProgramResult {
+    // same accounts and checks as Deposit, only the amounts differ
+    client::with_result(|| {
+        deposit::deposit(
+            program_id,
+            accounts,
+            DepositAmounts::PoolTokens {
+                pool_token_amount,
+                max_token_a_amount,
+                max_token_b_amount,
+            },
+            native_sol,
+        )
+    })
+}
@@ -0,0 +1,92 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    client::{self, DepositResult},
+    processor::{
+        convert_to_position,
+        deposit::{self, DepositAmounts},
+    },
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    nonce: u64,
+    token_a_amount: u64,
+    max_token_b_amount: u64,
+    native_sol: bool,
+) -> ProgramResult {
+    client::with_result(|| {
+        deposit_to_position(
+            program_id,
+            accounts,
+            nonce,
+            token_a_amount,
+            max_token_b_amount,
+            native_sol,
+        )
+    })
+}
+
+/// Deposits and puts the minted pool tokens in a new position. Returns the deposit's
+/// result.
+fn deposit_to_position(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    nonce: u64,
+    token_a_amount: u64,
+    max_token_b_amount: u64,
+    native_sol: bool,
+) -> Result<DepositResult, ProgramError> {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let position_mint_ai = next_account_info(accounts_iter)?;
+    let user_position_token_ai = next_account_info(accounts_iter)?;
+    let position_ai = next_account_info(accounts_iter)?;
+    let position_pool_token_ai = next_account_info(accounts_iter)?;
+    // Deposit's accounts, the user first
+    let deposit_accounts = [std::slice::from_ref(user), accounts_iter.as_slice()].concat();
+
+    // LOGIC
+
+    // a plain deposit checks its accounts as usual, then the minted pool tokens go into
+    // the position
+    let result = deposit::deposit(
+        program_id,
+        &deposit_accounts,
+        DepositAmounts::TokenA {
+            token_a_amount,
+            max_token_b_amount,
+        },
+        native_sol,
+    )?;
+
+    // user pool token acc, pool and pool token mint, then token, system, sysvar and
+    // associated token programs out of Deposit's accounts
+    convert_to_position::open_position(
+        program_id,
+        user,
+        &deposit_accounts[3],
+        &deposit_accounts[4],
+        &deposit_accounts[7],
+        position_mint_ai,
+        user_position_token_ai,
+        position_ai,
+        position_pool_token_ai,
+        &deposit_accounts[8],
+        &deposit_accounts[9],
+        &deposit_accounts[10],
+        &deposit_accounts[11],
+        nonce,
+        result.pool_token_amount,
+    )?;
+
+    Ok(result)
+}
@@ -1,14 +1,384 @@
-use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{clock::Clock, Sysvar},
+};
 
-use crate::{error::ChudexError, state::Pool};
+use crate::{
+    allowlist,
+    client::{self, ExchangeResult},
+    curve,
+    error::ChudexError,
+    events::{ChudexEvent, Swapped},
+    hook::{self, HookOperation, HookPayload, HookStage},
+    state::Pool,
+    token, twamm,
+    utils::assert_msg,
+};
 
 use borsh::{BorshDeserialize, BorshSerialize};
 
+use spl_token::error::TokenError;
+
 pub fn process(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     amount_in: u64,
     min_amount_out: u64,
+    native_sol: bool,
 ) -> ProgramResult {
-    Ok(())
+    client::with_result(|| {
+        exchange(
+            program_id,
+            accounts,
+            amount_in,
+            min_amount_out,
+            native_sol,
+            &[],
+        )
+    })
+}
+
+/// Swaps amount_in from the user's src acc. A program-owned user, like a conditional
+/// order, signs with `user_seeds` instead; its owner was checked against any allowlist
+/// when it was set up, so the allowlist account passed for it isn't checked again.
+/// Returns what was swapped, for the caller to set as return data once done.
+pub fn exchange(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_in: u64,
+    min_amount_out: u64,
+    native_sol: bool,
+    user_seeds: &[&[u8]],
+) -> Result<ExchangeResult, ProgramError> {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let user_token_src_ai = next_account_info(accounts_iter)?;
+    let user_token_dst_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let pool_vault_src_ai = next_account_info(accounts_iter)?;
+    let pool_vault_dst_ai = next_account_info(accounts_iter)?;
+    let token_program_src = next_account_info(accounts_iter)?;
+    let token_program_dst = next_account_info(accounts_iter)?;
+    let mint_src_ai = next_account_info(accounts_iter)?;
+    let mint_dst_ai = next_account_info(accounts_iter)?;
+    let (system_program, sysvar_rent) = if native_sol {
+        (
+            Some(next_account_info(accounts_iter)?),
+            Some(next_account_info(accounts_iter)?),
+        )
+    } else {
+        (None, None)
+    };
+
+    // deserialization
+    let mut pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    // twamm accounts for pools with a twamm, allowlist or allowlist ticket for
+    // permissioned pools, then the hook's accounts
+    let twamm_accounts = twamm::next_twamm_accounts(&pool, accounts_iter)?;
+    let allowlist_ai = match pool.allowlist {
+        Some(_) => next_account_info(accounts_iter).ok(),
+        None => None,
+    };
+    let hook_accounts = accounts_iter.as_slice();
+    let pool_vault_src = token::unpack_token_account(pool_vault_src_ai)?;
+    let pool_vault_dst = token::unpack_token_account(pool_vault_dst_ai)?;
+    let mint_src = token::unpack_mint(mint_src_ai)?;
+    let mint_dst = token::unpack_mint(mint_dst_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // native sol goes through a temporary account in place of the user token account
+    let native_src = native_sol && *mint_src_ai.key == spl_token::native_mint::id();
+    let native_dst = native_sol && *mint_dst_ai.key == spl_token::native_mint::id();
+    assert_msg(
+        !native_sol || native_src || native_dst,
+        ChudexError::InvalidInstructionInput.into(),
+        "Neither mint is native sol",
+    )?;
+
+    // user is signer, or signed for by the program
+    let program_user = !user_seeds.is_empty();
+    assert_msg(
+        user.is_signer || program_user,
+        ChudexError::MissingRequiredSignature.into(),
+        "User not signer",
+    )?;
+
+    // user is on the pool's allowlist, if it has one
+    if !program_user {
+        allowlist::check(program_id, pool_ai, &pool, user.key, allowlist_ai)?;
+    }
+
+    // pool vault accounts
+    assert_msg(
+        pool_vault_src.owner == *pool_ai.key,
+        TokenError::OwnerMismatch.into(),
+        "pool vault src not owned by pool",
+    )?;
+    assert_msg(
+        pool_vault_dst.owner == *pool_ai.key,
+        TokenError::OwnerMismatch.into(),
+        "pool vault dst not owned by pool",
+    )?;
+
+    // vaults hold the pool's two mints
+    assert_msg(
+        (pool_vault_src.mint == pool.mint_a && pool_vault_dst.mint == pool.mint_b)
+            || (pool_vault_src.mint == pool.mint_b && pool_vault_dst.mint == pool.mint_a),
+        ChudexError::InvalidAccountData.into(),
+        "pool vault mints don't match pool",
+    )?;
+
+    // mints match vaults
+    assert_msg(
+        *mint_src_ai.key == pool_vault_src.mint,
+        ChudexError::InvalidAccountAddress.into(),
+        "mint src doesn't match vault src",
+    )?;
+    assert_msg(
+        *mint_dst_ai.key == pool_vault_dst.mint,
+        ChudexError::InvalidAccountAddress.into(),
+        "mint dst doesn't match vault dst",
+    )?;
+
+    // pda verification
+
+    // vault src pda
+    let (vault_src_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_src.key.as_ref(),
+            pool_vault_src.mint.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_src_key == *pool_vault_src_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault src pda aint right",
+    )?;
+
+    // vault dst pda
+    let (vault_dst_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_dst.key.as_ref(),
+            pool_vault_dst.mint.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_dst_key == *pool_vault_dst_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault dst pda aint right",
+    )?;
+
+    // pool pda, seeds copied out since the pool's reserves get updated while they're in use
+    let (pool_mint_a, pool_mint_b) = (pool.mint_a, pool.mint_b);
+    let (pool_key, pool_bump) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    let pool_seeds = &[
+        b"chudex_pool",
+        pool_mint_a.as_ref(),
+        pool_mint_b.as_ref(),
+        &[pool_bump],
+    ];
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // external program verification
+    // mint token programs, either spl token or token-2022
+    assert_msg(
+        mint_src_ai.owner == token_program_src.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program src wrong address",
+    )?;
+    assert_msg(
+        mint_dst_ai.owner == token_program_dst.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program dst wrong address",
+    )?;
+
+    // LOGIC
+
+    // long-term orders trade first, at the price before this swap
+    let now = Clock::get()?.slot;
+    twamm::settle(
+        program_id,
+        pool_ai,
+        &mut pool,
+        pool_seeds,
+        twamm_accounts,
+        (pool_vault_src_ai, mint_src_ai, token_program_src),
+        (pool_vault_dst_ai, mint_dst_ai, token_program_dst),
+        now,
+    )?;
+    // tracked reserves
+    let reserve_src = pool.reserve(&pool_vault_src.mint);
+    let reserve_dst = pool.reserve(&pool_vault_dst.mint);
+
+    // calculate output
+    // pool math uses what actually lands in the vault after any transfer fee
+    let amount_in_received = token::amount_after_transfer_fee(mint_src_ai, amount_in)?;
+    let (amount_out, fee) = curve::swap(
+        amount_in_received,
+        reserve_src,
+        reserve_dst,
+        pool.effective_fee(now),
+        pool.fee_decimals,
+    )?;
+
+    // minimum applies to what the user receives
+    let amount_out_received = token::amount_after_transfer_fee(mint_dst_ai, amount_out)?;
+    if amount_out_received < min_amount_out {
+        return Err(ChudexError::ExchangeAmountBelowMinimum.into());
+    }
+
+    msg!("Got amount out: {} fee: {}", amount_out, fee);
+
+    // the pool's hook can reject the swap
+    let hook_operation = HookOperation::Exchange {
+        a_to_b: pool.mint_a == pool_vault_src.mint,
+    };
+    hook::call(
+        program_id,
+        pool_ai,
+        &pool,
+        hook_accounts,
+        &HookPayload {
+            stage: HookStage::Before,
+            operation: hook_operation,
+            pool: *pool_ai.key,
+            user: *user.key,
+            token_a_amount: amount_in_received,
+            token_b_amount: amount_out,
+            pool_token_amount: 0,
+            reserve_a: reserve_src,
+            reserve_b: reserve_dst,
+        }
+        .in_pool_order(&pool, &pool_vault_src.mint),
+    )?;
+
+    // wrap sol in, or create the wsol account to receive into
+    if let (Some(system_program), Some(sysvar_rent)) = (system_program, sysvar_rent) {
+        let (user_wsol_ai, native_mint_ai, native_token_program, lamports) = if native_src {
+            (user_token_src_ai, mint_src_ai, token_program_src, amount_in)
+        } else {
+            (user_token_dst_ai, mint_dst_ai, token_program_dst, 0)
+        };
+        token::wrap_sol(
+            program_id,
+            user,
+            user_wsol_ai,
+            native_mint_ai,
+            native_token_program,
+            system_program,
+            sysvar_rent,
+            lamports,
+        )?;
+    }
+
+    // send tokens in
+    let user_signer_seeds = [user_seeds];
+    token::transfer(
+        token_program_src,
+        user_token_src_ai,
+        mint_src_ai,
+        pool_vault_src_ai,
+        user,
+        amount_in,
+        mint_src.decimals,
+        if program_user {
+            &user_signer_seeds
+        } else {
+            &[]
+        },
+    )?;
+
+    // send tokens out
+    token::transfer(
+        token_program_dst,
+        pool_vault_dst_ai,
+        mint_dst_ai,
+        user_token_dst_ai,
+        pool_ai,
+        amount_out,
+        mint_dst.decimals,
+        &[pool_seeds],
+    )?;
+
+    // close the wsol account back to the user
+    if native_src {
+        token::unwrap_sol(user, user_token_src_ai, token_program_src)?;
+    }
+    if native_dst {
+        token::unwrap_sol(user, user_token_dst_ai, token_program_dst)?;
+    }
+
+    let reserves_before = (reserve_src, reserve_dst);
+    let reserve_src = reserve_src + amount_in_received;
+    let reserve_dst = reserve_dst - amount_out;
+    pool.set_reserve(&pool_vault_src.mint, reserve_src);
+    pool.set_reserve(&pool_vault_dst.mint, reserve_dst);
+
+    // this swap's price move feeds the next swaps' fee
+    if let Some(dynamic_fee) = &mut pool.dynamic_fee {
+        dynamic_fee.record_swap(now, reserves_before, (reserve_src, reserve_dst));
+    }
+    pool.serialize(&mut &mut pool_ai.try_borrow_mut_data()?[..])?;
+
+    // report reserves in pool mint order
+    let (reserve_a, reserve_b) = if pool.mint_a == pool_vault_src.mint {
+        (reserve_src, reserve_dst)
+    } else {
+        (reserve_dst, reserve_src)
+    };
+    ChudexEvent::Swapped(Swapped {
+        pool: *pool_ai.key,
+        user: *user.key,
+        mint_in: pool_vault_src.mint,
+        mint_out: pool_vault_dst.mint,
+        amount_in: amount_in_received,
+        amount_out,
+        fee,
+        reserve_a,
+        reserve_b,
+    })
+    .emit();
+
+    hook::call(
+        program_id,
+        pool_ai,
+        &pool,
+        hook_accounts,
+        &HookPayload {
+            stage: HookStage::After,
+            operation: hook_operation,
+            pool: *pool_ai.key,
+            user: *user.key,
+            token_a_amount: amount_in_received,
+            token_b_amount: amount_out,
+            pool_token_amount: 0,
+            reserve_a: reserve_src,
+            reserve_b: reserve_dst,
+        }
+        .in_pool_order(&pool, &pool_vault_src.mint),
+    )?;
+
+    Ok(ExchangeResult {
+        amount_in,
+        amount_out: amount_out_received,
+        fee,
+    })
 }
@@ -0,0 +1,231 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{clock::Clock, Sysvar},
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{
+    client::{self, ExchangeResult},
+    curve,
+    error::ChudexError,
+    processor::exchange,
+    state::{ConditionalOrder, Pool, PriceOracle, TriggerSource},
+    token,
+    utils::{assert_msg, close_program_account},
+};
+
+use spl_token::error::TokenError;
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    client::with_result(|| execute(program_id, accounts))
+}
+
+/// Executes the order if its trigger is reached. Returns the exchange's result.
+fn execute(program_id: &Pubkey, accounts: &[AccountInfo]) -> Result<ExchangeResult, ProgramError> {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let keeper = next_account_info(accounts_iter)?;
+    let keeper_token_in_ai = next_account_info(accounts_iter)?;
+    let order_ai = next_account_info(accounts_iter)?;
+    let escrow_ai = next_account_info(accounts_iter)?;
+    let owner_token_out_ai = next_account_info(accounts_iter)?;
+    let owner = next_account_info(accounts_iter)?;
+    let oracle_ai = next_account_info(accounts_iter)?;
+    // Exchange's accounts, the order selling from its escrow to the owner
+    let exchange_accounts = [
+        std::slice::from_ref(order_ai),
+        std::slice::from_ref(escrow_ai),
+        std::slice::from_ref(owner_token_out_ai),
+        accounts_iter.as_slice(),
+    ]
+    .concat();
+    let pool_ai = exchange_accounts
+        .get(3)
+        .ok_or(ChudexError::InvalidAccountData)?;
+    let token_program_in = exchange_accounts
+        .get(6)
+        .ok_or(ChudexError::InvalidAccountData)?;
+    let mint_in_ai = exchange_accounts
+        .get(8)
+        .ok_or(ChudexError::InvalidAccountData)?;
+
+    // deserialization
+    assert_msg(
+        order_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Order not owned by program",
+    )?;
+    let order = ConditionalOrder::try_from_slice(&order_ai.try_borrow_data()?)?;
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    let keeper_token_in = token::unpack_token_account(keeper_token_in_ai)?;
+    let escrow = token::unpack_token_account(escrow_ai)?;
+    let mint_in = token::unpack_mint(mint_in_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // keeper is signer
+    assert_msg(
+        keeper.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "Keeper not signer",
+    )?;
+    assert_msg(
+        keeper_token_in.mint == order.mint_in,
+        TokenError::MintMismatch.into(),
+        "keeper token in mint wrong",
+    )?;
+
+    // order is for this pool and direction, paying out to its owner
+    assert_msg(
+        order.pool == *pool_ai.key && order.mint_in == *mint_in_ai.key,
+        ChudexError::InvalidAccountData.into(),
+        "Order doesn't match pool or direction",
+    )?;
+    assert_msg(
+        order.owner_token_out == *owner_token_out_ai.key && order.owner == *owner.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Order owner accounts wrong",
+    )?;
+
+    // pda verification
+
+    // order pda
+    let nonce_bytes = order.nonce.to_le_bytes();
+    let (order_key, order_bump) = Pubkey::find_program_address(
+        &[
+            ConditionalOrder::SEED,
+            order.pool.as_ref(),
+            order.owner.as_ref(),
+            &nonce_bytes,
+        ],
+        program_id,
+    );
+    let order_seeds = &[
+        ConditionalOrder::SEED,
+        order.pool.as_ref(),
+        order.owner.as_ref(),
+        &nonce_bytes,
+        &[order_bump],
+    ];
+    assert_msg(
+        *order_ai.key == order_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "order pda aint right",
+    )?;
+
+    // escrow pda
+    let (escrow_key, _) = Pubkey::find_program_address(
+        &[
+            order_ai.key.as_ref(),
+            token_program_in.key.as_ref(),
+            mint_in_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        escrow_key == *escrow_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "escrow pda aint right",
+    )?;
+
+    // oracle pda
+    let (oracle_key, _) =
+        Pubkey::find_program_address(&[PriceOracle::SEED, pool_ai.key.as_ref()], program_id);
+    assert_msg(
+        *oracle_ai.key == oracle_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "oracle pda aint right",
+    )?;
+
+    // LOGIC
+
+    let now = Clock::get()?.unix_timestamp;
+    assert_msg(
+        now < order.expiry_timestamp,
+        ChudexError::OrderExpired.into(),
+        "Order expired",
+    )?;
+
+    // price of mint in, before long-term orders settle in the exchange
+    let a_to_b = order.mint_in == pool.mint_a;
+    let price = match order.trigger_source {
+        TriggerSource::Spot => {
+            let reserve_in = pool.reserve(&order.mint_in);
+            assert_msg(
+                reserve_in > 0,
+                ChudexError::CalculationFailure.into(),
+                "Pool empty",
+            )?;
+            curve::price(reserve_in, pool.reserve(&order.mint_out))
+        }
+        TriggerSource::Twap { window } => {
+            assert_msg(
+                oracle_ai.owner == program_id,
+                ChudexError::InvalidAccountAddress.into(),
+                "Oracle not owned by program",
+            )?;
+            let oracle = PriceOracle::try_from_slice(&oracle_ai.try_borrow_data()?)?;
+            let current = pool
+                .price_cumulative
+                .at(pool.reserve_a, pool.reserve_b, now);
+            let (price_a, price_b) = oracle
+                .twap(&current, window)
+                .ok_or(ChudexError::OracleWindowUnavailable)?;
+            if a_to_b {
+                price_a
+            } else {
+                price_b
+            }
+        }
+    };
+    assert_msg(
+        order.triggered(price),
+        ChudexError::TriggerNotReached.into(),
+        "Order trigger not reached",
+    )?;
+
+    // keeper bounty out of the escrow, the rest sold through the exchange
+    let bounty = curve::fee_amount(
+        escrow.amount,
+        ConditionalOrder::KEEPER_BOUNTY,
+        ConditionalOrder::KEEPER_BOUNTY_DECIMALS,
+    )?;
+    if bounty > 0 {
+        token::transfer(
+            token_program_in,
+            escrow_ai,
+            mint_in_ai,
+            keeper_token_in_ai,
+            order_ai,
+            bounty,
+            mint_in.decimals,
+            &[order_seeds],
+        )?;
+    }
+    msg!("Executing order {} keeper bounty: {}", order_ai.key, bounty);
+    let result = exchange::exchange(
+        program_id,
+        &exchange_accounts,
+        escrow.amount - bounty,
+        order.min_amount_out,
+        false,
+        order_seeds,
+    )?;
+
+    // order is done, rent back to the owner
+    token::close_account(token_program_in, escrow_ai, owner, order_ai, &[order_seeds])?;
+    close_program_account(order_ai, owner)?;
+
+    Ok(result)
+}
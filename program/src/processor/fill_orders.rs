@@ -0,0 +1,332 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+    sysvar::{clock::Clock, Sysvar},
+};
+
+use crate::{
+    curve,
+    error::ChudexError,
+    events::{ChudexEvent, Swapped},
+    state::{LimitOrder, Pool},
+    token, twamm,
+    utils::{assert_msg, close_program_account},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use spl_token::error::TokenError;
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let keeper = next_account_info(accounts_iter)?;
+    let keeper_token_out_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let pool_vault_in_ai = next_account_info(accounts_iter)?;
+    let pool_vault_out_ai = next_account_info(accounts_iter)?;
+    let mint_in_ai = next_account_info(accounts_iter)?;
+    let mint_out_ai = next_account_info(accounts_iter)?;
+    let token_program_in = next_account_info(accounts_iter)?;
+    let token_program_out = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let mut pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    let twamm_accounts = twamm::next_twamm_accounts(&pool, accounts_iter)?;
+    let keeper_token_out = token::unpack_token_account(keeper_token_out_ai)?;
+    let mint_in = token::unpack_mint(mint_in_ai)?;
+    let mint_out = token::unpack_mint(mint_out_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // keeper is signer
+    assert_msg(
+        keeper.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "Keeper not signer",
+    )?;
+    assert_msg(
+        keeper_token_out.mint == *mint_out_ai.key,
+        TokenError::MintMismatch.into(),
+        "keeper token out mint wrong",
+    )?;
+
+    // mints are the pool's two mints
+    assert_msg(
+        (*mint_in_ai.key == pool.mint_a && *mint_out_ai.key == pool.mint_b)
+            || (*mint_in_ai.key == pool.mint_b && *mint_out_ai.key == pool.mint_a),
+        ChudexError::InvalidAccountAddress.into(),
+        "Mints don't match pool",
+    )?;
+
+    // pda verification
+
+    // pool pda, seeds copied out since the pool's reserves get updated while they're in use
+    let (pool_mint_a, pool_mint_b) = (pool.mint_a, pool.mint_b);
+    let (pool_key, pool_bump) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    let pool_seeds = &[
+        b"chudex_pool",
+        pool_mint_a.as_ref(),
+        pool_mint_b.as_ref(),
+        &[pool_bump],
+    ];
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // vault in pda
+    let (vault_in_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_in.key.as_ref(),
+            mint_in_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_in_key == *pool_vault_in_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault in pda aint right",
+    )?;
+
+    // vault out pda
+    let (vault_out_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_out.key.as_ref(),
+            mint_out_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_out_key == *pool_vault_out_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault out pda aint right",
+    )?;
+
+    // external program verification
+    // mint token programs, either spl token or token-2022
+    assert_msg(
+        mint_in_ai.owner == token_program_in.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program in wrong address",
+    )?;
+    assert_msg(
+        mint_out_ai.owner == token_program_out.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program out wrong address",
+    )?;
+
+    // LOGIC
+
+    // long-term orders trade first, so fills see the current reserves
+    let now = Clock::get()?.slot;
+    twamm::settle(
+        program_id,
+        pool_ai,
+        &mut pool,
+        pool_seeds,
+        twamm_accounts,
+        (pool_vault_in_ai, mint_in_ai, token_program_in),
+        (pool_vault_out_ai, mint_out_ai, token_program_out),
+        now,
+    )?;
+
+    // fill orders one after another against the moving reserves
+    let mut reserve_in = pool.reserve(mint_in_ai.key);
+    let mut reserve_out = pool.reserve(mint_out_ai.key);
+    let mut filled = 0;
+
+    // (order, escrow, owner token out, owner) per order
+    while let (Ok(order_ai), Ok(escrow_ai), Ok(owner_token_out_ai), Ok(owner)) = (
+        next_account_info(accounts_iter),
+        next_account_info(accounts_iter),
+        next_account_info(accounts_iter),
+        next_account_info(accounts_iter),
+    ) {
+        assert_msg(
+            order_ai.owner == program_id,
+            ChudexError::InvalidAccountAddress.into(),
+            "Order not owned by program",
+        )?;
+        let order = LimitOrder::try_from_slice(&order_ai.try_borrow_data()?)?;
+
+        // order is for this pool and direction
+        assert_msg(
+            order.pool == *pool_ai.key && order.mint_in == *mint_in_ai.key,
+            ChudexError::InvalidAccountData.into(),
+            "Order doesn't match pool or direction",
+        )?;
+        assert_msg(
+            order.owner_token_out == *owner_token_out_ai.key && order.owner == *owner.key,
+            ChudexError::InvalidAccountAddress.into(),
+            "Order owner accounts wrong",
+        )?;
+
+        // order pda
+        let nonce_bytes = order.nonce.to_le_bytes();
+        let (order_key, order_bump) = Pubkey::find_program_address(
+            &[
+                LimitOrder::SEED,
+                order.pool.as_ref(),
+                order.owner.as_ref(),
+                &nonce_bytes,
+            ],
+            program_id,
+        );
+        let order_seeds = &[
+            LimitOrder::SEED,
+            order.pool.as_ref(),
+            order.owner.as_ref(),
+            &nonce_bytes,
+            &[order_bump],
+        ];
+        assert_msg(
+            *order_ai.key == order_key,
+            ChudexError::InvalidProgramAddress.into(),
+            "order pda aint right",
+        )?;
+
+        // escrow pda
+        let (escrow_key, _) = Pubkey::find_program_address(
+            &[
+                order_ai.key.as_ref(),
+                token_program_in.key.as_ref(),
+                mint_in_ai.key.as_ref(),
+            ],
+            &spl_associated_token_account::id(),
+        );
+        assert_msg(
+            escrow_key == *escrow_ai.key,
+            ChudexError::InvalidProgramAddress.into(),
+            "escrow pda aint right",
+        )?;
+
+        // spot price out per in has to reach the limit, min out / in <= reserve out / reserve in
+        if (order.min_amount_out as u128) * (reserve_in as u128)
+            > (order.amount_in as u128) * (reserve_out as u128)
+        {
+            msg!("order {} not crossed", order_ai.key);
+            continue;
+        }
+
+        // swap through the pool, the keeper fee comes out of the output
+        let amount_in_received = token::amount_after_transfer_fee(mint_in_ai, order.amount_in)?;
+        let (amount_out, fee) = curve::swap(
+            amount_in_received,
+            reserve_in,
+            reserve_out,
+            pool.effective_fee(now),
+            pool.fee_decimals,
+        )?;
+        let keeper_fee = curve::fee_amount(
+            amount_out,
+            LimitOrder::KEEPER_FEE,
+            LimitOrder::KEEPER_FEE_DECIMALS,
+        )?;
+        let owner_amount = amount_out - keeper_fee;
+
+        // limit applies to what the owner receives
+        if token::amount_after_transfer_fee(mint_out_ai, owner_amount)? < order.min_amount_out {
+            msg!("order {} not fillable at limit", order_ai.key);
+            continue;
+        }
+        msg!(
+            "Filling order {} amount out: {} keeper fee: {}",
+            order_ai.key,
+            owner_amount,
+            keeper_fee
+        );
+
+        // escrow into pool
+        token::transfer(
+            token_program_in,
+            escrow_ai,
+            mint_in_ai,
+            pool_vault_in_ai,
+            order_ai,
+            order.amount_in,
+            mint_in.decimals,
+            &[order_seeds],
+        )?;
+
+        // pay owner and keeper
+        token::transfer(
+            token_program_out,
+            pool_vault_out_ai,
+            mint_out_ai,
+            owner_token_out_ai,
+            pool_ai,
+            owner_amount,
+            mint_out.decimals,
+            &[pool_seeds],
+        )?;
+        if keeper_fee > 0 {
+            token::transfer(
+                token_program_out,
+                pool_vault_out_ai,
+                mint_out_ai,
+                keeper_token_out_ai,
+                pool_ai,
+                keeper_fee,
+                mint_out.decimals,
+                &[pool_seeds],
+            )?;
+        }
+
+        // order is done, rent back to the owner
+        token::close_account(token_program_in, escrow_ai, owner, order_ai, &[order_seeds])?;
+        close_program_account(order_ai, owner)?;
+
+        if let Some(dynamic_fee) = &mut pool.dynamic_fee {
+            dynamic_fee.record_swap(
+                now,
+                (reserve_in, reserve_out),
+                (reserve_in + amount_in_received, reserve_out - amount_out),
+            );
+        }
+        reserve_in += amount_in_received;
+        reserve_out -= amount_out;
+        filled += 1;
+
+        // report reserves in pool mint order
+        let (reserve_a, reserve_b) = if pool.mint_a == *mint_in_ai.key {
+            (reserve_in, reserve_out)
+        } else {
+            (reserve_out, reserve_in)
+        };
+        ChudexEvent::Swapped(Swapped {
+            pool: *pool_ai.key,
+            user: order.owner,
+            mint_in: order.mint_in,
+            mint_out: order.mint_out,
+            amount_in: amount_in_received,
+            amount_out,
+            fee,
+            reserve_a,
+            reserve_b,
+        })
+        .emit();
+    }
+
+    // written back even with no fills, the settle may have moved the reserves
+    msg!("Filled {} orders", filled);
+    pool.set_reserve(mint_in_ai.key, reserve_in);
+    pool.set_reserve(mint_out_ai.key, reserve_out);
+    pool.serialize(&mut &mut pool_ai.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
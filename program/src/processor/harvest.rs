@@ -0,0 +1,173 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+    sysvar::{clock::Clock, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::ChudexError,
+    state::{Farm, Stake},
+    token,
+    utils::{assert_msg, close_program_account},
+};
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let farm_ai = next_account_info(accounts_iter)?;
+    let stake_ai = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        farm_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Farm not owned by program",
+    )?;
+    assert_msg(
+        stake_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Stake not owned by program",
+    )?;
+    let mut farm = Farm::try_from_slice(&farm_ai.try_borrow_data()?)?;
+    let mut stake = Stake::try_from_slice(&stake_ai.try_borrow_data()?)?;
+
+    // (mint, farm reward acc, owner reward acc, token program) per farm reward, in farm order
+    let mut rewards = Vec::with_capacity(farm.rewards.len());
+    for _ in 0..farm.rewards.len() {
+        rewards.push((
+            next_account_info(accounts_iter)?,
+            next_account_info(accounts_iter)?,
+            next_account_info(accounts_iter)?,
+            next_account_info(accounts_iter)?,
+        ));
+    }
+
+    // ACCOUNT VALIDATION
+
+    // owner is signer
+    assert_msg(
+        owner.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "Owner not signer",
+    )?;
+
+    // reward mints in farm order
+    assert_msg(
+        rewards
+            .iter()
+            .zip(&farm.rewards)
+            .all(|((mint_ai, _, _, _), reward)| *mint_ai.key == reward.mint),
+        ChudexError::InvalidAccountAddress.into(),
+        "Reward mints don't match farm",
+    )?;
+
+    // pda verification
+
+    // farm pda, seeds copied out since the farm gets updated while they're in use
+    let (farm_pool, farm_authority) = (farm.pool, farm.authority);
+    let nonce_bytes = farm.nonce.to_le_bytes();
+    let (farm_key, farm_bump) = Pubkey::find_program_address(
+        &[
+            Farm::SEED,
+            farm_pool.as_ref(),
+            farm_authority.as_ref(),
+            &nonce_bytes,
+        ],
+        program_id,
+    );
+    let farm_seeds = &[
+        Farm::SEED,
+        farm_pool.as_ref(),
+        farm_authority.as_ref(),
+        &nonce_bytes,
+        &[farm_bump],
+    ];
+    assert_msg(
+        *farm_ai.key == farm_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "farm pda aint right",
+    )?;
+
+    // stake pda
+    let (stake_key, _) = Pubkey::find_program_address(
+        &[Stake::SEED, farm_ai.key.as_ref(), owner.key.as_ref()],
+        program_id,
+    );
+    assert_msg(
+        *stake_ai.key == stake_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "stake pda aint right",
+    )?;
+
+    // farm reward vault pdas
+    for (mint_ai, farm_reward_vault_ai, _, token_program_ai) in &rewards {
+        let (farm_reward_vault_key, _) = Pubkey::find_program_address(
+            &[
+                farm_ai.key.as_ref(),
+                token_program_ai.key.as_ref(),
+                mint_ai.key.as_ref(),
+            ],
+            &spl_associated_token_account::id(),
+        );
+        assert_msg(
+            farm_reward_vault_key == *farm_reward_vault_ai.key,
+            ChudexError::InvalidProgramAddress.into(),
+            "farm reward vault pda aint right",
+        )?;
+    }
+
+    // external program verification
+    // reward mint token programs, either spl token or token-2022
+    for (mint_ai, _, _, token_program_ai) in &rewards {
+        assert_msg(
+            mint_ai.owner == token_program_ai.key,
+            ChudexError::InvalidAccountAddress.into(),
+            "Reward token program wrong address",
+        )?;
+    }
+
+    // LOGIC
+
+    farm.update(Clock::get()?.slot)?;
+    stake.update_rewards(&farm)?;
+
+    // pay out everything owed
+    for (i, (mint_ai, farm_reward_vault_ai, owner_reward_ai, token_program_ai)) in
+        rewards.iter().enumerate()
+    {
+        let owed = stake.rewards_owed[i];
+        if owed == 0 {
+            continue;
+        }
+        let mint = token::unpack_mint(mint_ai)?;
+        token::transfer(
+            token_program_ai,
+            farm_reward_vault_ai,
+            mint_ai,
+            owner_reward_ai,
+            farm_ai,
+            owed,
+            mint.decimals,
+            &[farm_seeds],
+        )?;
+        stake.rewards_owed[i] = 0;
+        msg!("Harvested {} of {}", owed, mint_ai.key);
+    }
+
+    farm.serialize(&mut &mut farm_ai.try_borrow_mut_data()?[..])?;
+
+    // nothing staked and nothing owed, rent back to the owner
+    if stake.amount == 0 {
+        close_program_account(stake_ai, owner)?;
+    } else {
+        stake.serialize(&mut &mut stake_ai.try_borrow_mut_data()?[..])?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,249 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    cl_math,
+    error::ChudexError,
+    state::{ClPool, Position, TickArray},
+    token,
+    utils::assert_msg,
+};
+
+use spl_token::error::TokenError;
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    liquidity: u128,
+    max_token_a_amount: u64,
+    max_token_b_amount: u64,
+) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let position_ai = next_account_info(accounts_iter)?;
+    let tick_array_lower_ai = next_account_info(accounts_iter)?;
+    let tick_array_upper_ai = next_account_info(accounts_iter)?;
+    let user_token_a_ai = next_account_info(accounts_iter)?;
+    let user_token_b_ai = next_account_info(accounts_iter)?;
+    let pool_vault_a_ai = next_account_info(accounts_iter)?;
+    let pool_vault_b_ai = next_account_info(accounts_iter)?;
+    let mint_a_ai = next_account_info(accounts_iter)?;
+    let mint_b_ai = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
+
+    // ACCOUNT VALIDATION
+
+    // owner is signer
+    assert_msg(
+        owner.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "Owner not signer",
+    )?;
+
+    // program accounts
+    assert_msg(
+        pool_ai.owner == program_id
+            && position_ai.owner == program_id
+            && tick_array_lower_ai.owner == program_id
+            && tick_array_upper_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool, position or tick arrays not owned by program",
+    )?;
+
+    // deserialization
+    let mut pool = ClPool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    let mut position = Position::try_from_slice(&position_ai.try_borrow_data()?)?;
+    let mut tick_array_lower = TickArray::try_from_slice(&tick_array_lower_ai.try_borrow_data()?)?;
+    let mut tick_array_upper = if tick_array_upper_ai.key == tick_array_lower_ai.key {
+        None
+    } else {
+        Some(TickArray::try_from_slice(
+            &tick_array_upper_ai.try_borrow_data()?,
+        )?)
+    };
+    let user_token_a = token::unpack_token_account(user_token_a_ai)?;
+    let user_token_b = token::unpack_token_account(user_token_b_ai)?;
+
+    // position belongs to owner and pool
+    assert_msg(
+        position.owner == *owner.key && position.pool == *pool_ai.key,
+        ChudexError::InvalidAccountData.into(),
+        "Position doesn't match owner or pool",
+    )?;
+
+    // tick arrays belong to pool
+    assert_msg(
+        tick_array_lower.pool == *pool_ai.key
+            && tick_array_upper
+                .iter()
+                .all(|tick_array| tick_array.pool == *pool_ai.key),
+        ChudexError::InvalidAccountData.into(),
+        "Tick array doesn't match pool",
+    )?;
+
+    // token account ownership
+    assert_msg(
+        user_token_a.owner == *owner.key,
+        TokenError::OwnerMismatch.into(),
+        "user token a not owned by owner",
+    )?;
+    assert_msg(
+        user_token_b.owner == *owner.key,
+        TokenError::OwnerMismatch.into(),
+        "user token b not owned by owner",
+    )?;
+
+    // mints match pool
+    assert_msg(
+        *mint_a_ai.key == pool.mint_a && *mint_b_ai.key == pool.mint_b,
+        ChudexError::InvalidAccountAddress.into(),
+        "Mints don't match pool",
+    )?;
+
+    // pda verification
+
+    // vault a pda
+    let (vault_a_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_a.key.as_ref(),
+            pool.mint_a.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_a_key == *pool_vault_a_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault a pda aint right",
+    )?;
+
+    // vault b pda
+    let (vault_b_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_b.key.as_ref(),
+            pool.mint_b.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_b_key == *pool_vault_b_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault b pda aint right",
+    )?;
+
+    // external program verification
+    // mint token programs, either spl token or token-2022
+    assert_msg(
+        mint_a_ai.owner == token_program_a.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program a wrong address",
+    )?;
+    assert_msg(
+        mint_b_ai.owner == token_program_b.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program b wrong address",
+    )?;
+
+    // LOGIC
+
+    let liquidity_delta =
+        i128::try_from(liquidity).map_err(|_| ChudexError::InvalidInstructionInput)?;
+
+    // add liquidity to both bounds
+    pool.update_tick(
+        &mut tick_array_lower,
+        position.tick_lower,
+        liquidity_delta,
+        false,
+    )?;
+    pool.update_tick(
+        tick_array_upper.as_mut().unwrap_or(&mut tick_array_lower),
+        position.tick_upper,
+        liquidity_delta,
+        true,
+    )?;
+
+    // settle fees earned so far at the old liquidity
+    let tick_array_upper_ref = tick_array_upper.as_ref().unwrap_or(&tick_array_lower);
+    let (fee_growth_inside_a, fee_growth_inside_b) = pool.fee_growth_inside(
+        position.tick_lower,
+        tick_array_lower
+            .tick(position.tick_lower, pool.tick_spacing)
+            .ok_or(ChudexError::TickArrayNotFound)?,
+        position.tick_upper,
+        tick_array_upper_ref
+            .tick(position.tick_upper, pool.tick_spacing)
+            .ok_or(ChudexError::TickArrayNotFound)?,
+    );
+    position.update_fees(fee_growth_inside_a, fee_growth_inside_b)?;
+    position.liquidity = cl_math::add_liquidity_delta(position.liquidity, liquidity_delta)?;
+
+    // in range liquidity
+    if position.tick_lower <= pool.current_tick && pool.current_tick < position.tick_upper {
+        pool.liquidity = cl_math::add_liquidity_delta(pool.liquidity, liquidity_delta)?;
+    }
+
+    // calculate token amounts, rounded up in the pool's favor
+    let (amount_a, amount_b) = cl_math::amounts_for_liquidity(
+        pool.sqrt_price,
+        cl_math::sqrt_price_from_tick(position.tick_lower)?,
+        cl_math::sqrt_price_from_tick(position.tick_upper)?,
+        liquidity,
+        true,
+    )?;
+    let amount_a = token::amount_before_transfer_fee(mint_a_ai, amount_a)?;
+    let amount_b = token::amount_before_transfer_fee(mint_b_ai, amount_b)?;
+    if amount_a > max_token_a_amount || amount_b > max_token_b_amount {
+        return Err(ChudexError::DepositAmountExceedsLimit.into());
+    }
+    msg!("Got token a: {} token b: {}", amount_a, amount_b);
+
+    // deposit
+    if amount_a > 0 {
+        let mint_a = token::unpack_mint(mint_a_ai)?;
+        token::transfer(
+            token_program_a,
+            user_token_a_ai,
+            mint_a_ai,
+            pool_vault_a_ai,
+            owner,
+            amount_a,
+            mint_a.decimals,
+            &[],
+        )?;
+    }
+    if amount_b > 0 {
+        let mint_b = token::unpack_mint(mint_b_ai)?;
+        token::transfer(
+            token_program_b,
+            user_token_b_ai,
+            mint_b_ai,
+            pool_vault_b_ai,
+            owner,
+            amount_b,
+            mint_b.decimals,
+            &[],
+        )?;
+    }
+
+    // save state
+    pool.serialize(&mut &mut pool_ai.try_borrow_mut_data()?[..])?;
+    position.serialize(&mut &mut position_ai.try_borrow_mut_data()?[..])?;
+    tick_array_lower.serialize(&mut &mut tick_array_lower_ai.try_borrow_mut_data()?[..])?;
+    if let Some(tick_array_upper) = tick_array_upper {
+        tick_array_upper.serialize(&mut &mut tick_array_upper_ai.try_borrow_mut_data()?[..])?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,228 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction, system_program as system_program_ext,
+    sysvar::{rent, Sysvar},
+};
+
+use crate::{cl_math, curve, error::ChudexError, state::ClPool, token, utils::assert_msg};
+
+use borsh::BorshSerialize;
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    tick_spacing: u16,
+    initial_sqrt_price: u128,
+    fee: u64,
+    fee_decimals: u64,
+) -> ProgramResult {
+    // GET ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let pool_vault_a_ai = next_account_info(accounts_iter)?;
+    let pool_vault_b_ai = next_account_info(accounts_iter)?;
+    let mint_a_ai = next_account_info(accounts_iter)?;
+    let mint_b_ai = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let sysvar_rent = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+
+    // ACCOUNT VALIDATION
+
+    // user is signer
+    assert_msg(
+        user.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "User not signer",
+    )?;
+
+    // fee under 100%
+    curve::check_fee(fee, fee_decimals)?;
+
+    // mints sorted, which also makes the pool pda unique per pair and spacing
+    assert_msg(
+        mint_a_ai.key < mint_b_ai.key,
+        ChudexError::InvalidInstructionInput.into(),
+        "Mints not sorted or not unique",
+    )?;
+
+    assert_msg(
+        tick_spacing > 0,
+        ChudexError::InvalidInstructionInput.into(),
+        "Tick spacing must be positive",
+    )?;
+
+    // starting price within the tick range
+    assert_msg(
+        initial_sqrt_price >= cl_math::sqrt_price_from_tick(cl_math::MIN_TICK)?
+            && initial_sqrt_price < cl_math::sqrt_price_from_tick(cl_math::MAX_TICK)?,
+        ChudexError::InvalidInstructionInput.into(),
+        "Initial sqrt price out of range",
+    )?;
+
+    // pda verification
+
+    // pool pda
+    let tick_spacing_bytes = tick_spacing.to_le_bytes();
+    let (pool_key, pool_bump) = Pubkey::find_program_address(
+        &[
+            ClPool::SEED,
+            mint_a_ai.key.as_ref(),
+            mint_b_ai.key.as_ref(),
+            &tick_spacing_bytes,
+        ],
+        program_id,
+    );
+    let pool_seeds = &[
+        ClPool::SEED,
+        mint_a_ai.key.as_ref(),
+        mint_b_ai.key.as_ref(),
+        &tick_spacing_bytes,
+        &[pool_bump],
+    ];
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // vault a pda
+    let (vault_a_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_a.key.as_ref(),
+            mint_a_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_a_key == *pool_vault_a_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault a pda aint right",
+    )?;
+
+    // vault b pda
+    let (vault_b_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_b.key.as_ref(),
+            mint_b_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_b_key == *pool_vault_b_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault b pda aint right",
+    )?;
+
+    // external program verification
+    // mint token programs, either spl token or token-2022
+    assert_msg(
+        mint_a_ai.owner == token_program_a.key && token::is_token_program(token_program_a.key),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program a wrong address",
+    )?;
+    assert_msg(
+        mint_b_ai.owner == token_program_b.key && token::is_token_program(token_program_b.key),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program b wrong address",
+    )?;
+
+    // system program
+    assert_msg(
+        *system_program.key == system_program_ext::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+
+    // sysvar program
+    assert_msg(
+        *sysvar_rent.key == rent::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Sysvar program wrong address",
+    )?;
+
+    // associated token program
+    assert_msg(
+        *associated_token_program.key == spl_associated_token_account::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Associated token program wrong address",
+    )?;
+
+    // pool data
+    assert_msg(
+        pool_ai.data_len() == 0,
+        ChudexError::AccountAlreadyInitialized.into(),
+        "Pool already initialized",
+    )?;
+
+    // LOGIC
+
+    // create token vaults
+    msg!("initializing vaults...");
+    for (vault_ai, mint_ai, token_program_ai) in [
+        (pool_vault_a_ai, mint_a_ai, token_program_a),
+        (pool_vault_b_ai, mint_b_ai, token_program_b),
+    ] {
+        invoke(
+            &token::create_associated_token_account(
+                user.key,
+                pool_ai.key,
+                mint_ai.key,
+                token_program_ai.key,
+            ),
+            &[
+                user.clone(),
+                vault_ai.clone(),
+                pool_ai.clone(),
+                mint_ai.clone(),
+                system_program.clone(),
+                token_program_ai.clone(),
+                sysvar_rent.clone(),
+                associated_token_program.clone(),
+            ],
+        )?;
+    }
+    msg!("initialized vaults");
+
+    // create pool account to store data
+    msg!("initializing pool...");
+    invoke_signed(
+        &system_instruction::create_account(
+            user.key,
+            pool_ai.key,
+            rent::Rent::get()?.minimum_balance(ClPool::LEN),
+            ClPool::LEN as u64,
+            program_id,
+        ),
+        &[user.clone(), pool_ai.clone(), system_program.clone()],
+        &[pool_seeds],
+    )?;
+
+    let pool = ClPool {
+        mint_a: *mint_a_ai.key,
+        mint_b: *mint_b_ai.key,
+        tick_spacing,
+        fee,
+        fee_decimals,
+        sqrt_price: initial_sqrt_price,
+        current_tick: cl_math::tick_from_sqrt_price(initial_sqrt_price)?,
+        liquidity: 0,
+        fee_growth_global_a: 0,
+        fee_growth_global_b: 0,
+    };
+    pool.serialize(&mut &mut pool_ai.try_borrow_mut_data()?[..])?;
+    msg!("initialized pool");
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction, system_program as system_program_ext,
+    sysvar::{rent, Sysvar},
+};
+
+use crate::{
+    error::ChudexError,
+    state::GlobalConfig,
+    utils::{assert_msg, upgrade_authority},
+};
+
+use borsh::BorshSerialize;
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fee_tiers: Vec<u16>,
+) -> ProgramResult {
+    // GET ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let config_ai = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let program_data_ai = next_account_info(accounts_iter)?;
+
+    // ACCOUNT VALIDATION
+
+    // admin is signer
+    assert_msg(
+        admin.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "Admin not signer",
+    )?;
+
+    // only whoever deployed the program can claim admin, so nobody can front-run it
+    assert_msg(
+        upgrade_authority(program_id, program_data_ai)? == Some(*admin.key),
+        ChudexError::IncorrectAdmin.into(),
+        "Admin isn't the program's upgrade authority",
+    )?;
+
+    // pda verification
+
+    // config pda
+    let (config_key, config_bump) = Pubkey::find_program_address(&[GlobalConfig::SEED], program_id);
+    let config_seeds = &[GlobalConfig::SEED, &[config_bump]];
+    assert_msg(
+        *config_ai.key == config_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "config pda aint right",
+    )?;
+    assert_msg(
+        config_ai.data_len() == 0,
+        ChudexError::AccountAlreadyInitialized.into(),
+        "Config already initialized",
+    )?;
+
+    // external program verification
+
+    // system program
+    assert_msg(
+        *system_program.key == system_program_ext::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+
+    // LOGIC
+
+    let mut config = GlobalConfig {
+        admin: *admin.key,
+        num_fee_tiers: 0,
+        fee_tiers: [0; GlobalConfig::MAX_FEE_TIERS],
+    };
+    config.set_fee_tiers(&fee_tiers)?;
+
+    // create config account, the upgrade authority becomes admin
+    msg!("initializing config...");
+    invoke_signed(
+        &system_instruction::create_account(
+            admin.key,
+            config_ai.key,
+            rent::Rent::get()?.minimum_balance(GlobalConfig::LEN),
+            GlobalConfig::LEN as u64,
+            program_id,
+        ),
+        &[admin.clone(), config_ai.clone(), system_program.clone()],
+        &[config_seeds],
+    )?;
+    config.serialize(&mut &mut config_ai.try_borrow_mut_data()?[..])?;
+    msg!("initialized config with fee tiers {:?}", config.fee_tiers());
+
+    Ok(())
+}
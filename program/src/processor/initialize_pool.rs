@@ -1,40 +1,41 @@
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
-    instruction::AccountMeta,
     msg,
     program::{invoke, invoke_signed},
     program_error::ProgramError,
-    program_pack::Pack,
     pubkey::Pubkey,
     system_instruction, system_program as system_program_ext,
-    sysvar::{rent, Sysvar},
+    sysvar::{clock::Clock, rent, Sysvar},
 };
 
 use bs58;
 use std::cmp;
 
-use crate::{error::ChudexError, state::Pool, utils::assert_msg};
-
-use borsh::{BorshDeserialize, BorshSerialize};
-
-use spl_associated_token_account::{create_associated_token_account, id};
-use spl_token::{
-    instruction,
-    state::{Account as TokenAccount, Mint},
+use crate::{
+    curve,
+    error::ChudexError,
+    events::{ChudexEvent, PoolInitialized},
+    registry,
+    state::{
+        AllowlistMode, DynamicFee, DynamicFeeConfig, GlobalConfig, LpMetadata, Pool,
+        PriceCumulative,
+    },
+    token,
+    utils::assert_msg,
 };
 
-use std::mem;
+use borsh::{BorshDeserialize, BorshSerialize};
 
-const FEE: u64 = 5;
-const FEE_DECIMALS: u8 = 3;
-const POOL_MINT_DECIMALS: u8 = 9;
+use spl_token::instruction;
 
 pub fn process(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    fee: u64,
-    fee_decimals: u64,
+    fee_tier: u16,
+    dynamic_fee: Option<DynamicFeeConfig>,
+    allowlist: Option<AllowlistMode>,
+    hook_program: Option<Pubkey>,
 ) -> ProgramResult {
     // GET ACCOUNTS
     let accounts_iter = &mut accounts.iter();
@@ -50,6 +51,24 @@ pub fn process(
     let system_program = next_account_info(accounts_iter)?;
     let sysvar_rent = next_account_info(accounts_iter)?;
     let associated_token_program = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
+    let config_ai = next_account_info(accounts_iter)?;
+    let factory_ai = next_account_info(accounts_iter)?;
+    let registry_page_ai = next_account_info(accounts_iter)?;
+    let mint_index_a_ai = next_account_info(accounts_iter)?;
+    let mint_index_page_a_ai = next_account_info(accounts_iter)?;
+    let mint_index_b_ai = next_account_info(accounts_iter)?;
+    let mint_index_page_b_ai = next_account_info(accounts_iter)?;
+    let lp_metadata_ai = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        config_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Config not owned by program",
+    )?;
+    let config = GlobalConfig::try_from_slice(&config_ai.try_borrow_data()?)?;
 
     // ACCOUNT VALIDATION
 
@@ -60,13 +79,52 @@ pub fn process(
         "User not signer",
     )?;
 
+    // fee is one of the config's tiers
+    assert_msg(
+        config.fee_tiers().contains(&fee_tier),
+        ChudexError::InvalidFee.into(),
+        "Fee not one of the fee tiers",
+    )?;
+    let fee = fee_tier as u64;
+    let fee_decimals = GlobalConfig::FEE_DECIMALS;
+
+    // dynamic fee rises from the tier fee to under 100% and needs to decay
+    if let Some(dynamic_fee) = &dynamic_fee {
+        curve::check_fee(dynamic_fee.max_fee, fee_decimals)?;
+        assert_msg(
+            dynamic_fee.max_fee >= fee,
+            ChudexError::InvalidFee.into(),
+            "Dynamic max fee below the fee",
+        )?;
+        assert_msg(
+            dynamic_fee.half_life > 0,
+            ChudexError::InvalidInstructionInput.into(),
+            "Dynamic fee needs a half life",
+        )?;
+    }
+
+    // hooks are other programs, calling back into this one isn't allowed
+    assert_msg(
+        hook_program != Some(*program_id),
+        ChudexError::InvalidInstructionInput.into(),
+        "Pool can't be its own hook program",
+    )?;
+
     // PDAs
 
+    // config pda
+    let (config_key, _) = Pubkey::find_program_address(&[GlobalConfig::SEED], program_id);
+    assert_msg(
+        *config_ai.key == config_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "config pda aint right",
+    )?;
+
     // vault a pda
-    let (vault_a_key, vault_a_bump) = Pubkey::find_program_address(
+    let (vault_a_key, _) = Pubkey::find_program_address(
         &[
             pool_ai.key.as_ref(),
-            token_program.key.as_ref(),
+            token_program_a.key.as_ref(),
             mint_a_ai.key.as_ref(),
         ],
         &spl_associated_token_account::id(),
@@ -78,10 +136,10 @@ pub fn process(
     )?;
 
     // vault b pda
-    let (vault_b_key, vault_b_bump) = Pubkey::find_program_address(
+    let (vault_b_key, _) = Pubkey::find_program_address(
         &[
             pool_ai.key.as_ref(),
-            token_program.key.as_ref(),
+            token_program_b.key.as_ref(),
             mint_b_ai.key.as_ref(),
         ],
         &spl_associated_token_account::id(),
@@ -93,8 +151,8 @@ pub fn process(
     )?;
 
     // pool pda
-    let mint_a = Mint::unpack_from_slice(&mint_a_ai.try_borrow_data()?)?;
-    let mint_b = Mint::unpack_from_slice(&mint_b_ai.try_borrow_data()?)?;
+    let mint_a = token::unpack_mint(mint_a_ai)?;
+    let mint_b = token::unpack_mint(mint_b_ai)?;
     let (mint_a_seed, mint_b_seed) = if mint_a.decimals == mint_b.decimals {
         if bs58::encode(mint_a_ai.key).into_string() < bs58::encode(mint_b_ai.key).into_string() {
             (mint_a_ai.key, mint_b_ai.key)
@@ -157,6 +215,18 @@ pub fn process(
         "Token program wrong address",
     )?;
 
+    // mint token programs, either spl token or token-2022
+    assert_msg(
+        mint_a_ai.owner == token_program_a.key && token::is_token_program(token_program_a.key),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program a wrong address",
+    )?;
+    assert_msg(
+        mint_b_ai.owner == token_program_b.key && token::is_token_program(token_program_b.key),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program b wrong address",
+    )?;
+
     // system program
     assert_msg(
         *system_program.key == system_program_ext::id(),
@@ -200,6 +270,14 @@ pub fn process(
         "Pool already initialized",
     )?;
 
+    // pool mint data, left behind without a mint authority when a pool for the pair was
+    // closed, which retires the pair
+    assert_msg(
+        pool_mint_ai.data_len() == 0,
+        ChudexError::AccountAlreadyInitialized.into(),
+        "Pool mint already initialized, the pair's pool was closed",
+    )?;
+
     // LOGIC
 
     // create token vaults
@@ -208,10 +286,11 @@ pub fn process(
 
     // create account
     invoke(
-        &spl_associated_token_account::create_associated_token_account(
+        &token::create_associated_token_account(
             user.key,
             pool_ai.key,
             mint_a_ai.key,
+            token_program_a.key,
         ),
         &[
             user.clone(),
@@ -219,7 +298,7 @@ pub fn process(
             pool_ai.clone(),
             mint_a_ai.clone(),
             system_program.clone(),
-            token_program.clone(),
+            token_program_a.clone(),
             sysvar_rent.clone(),
             associated_token_program.clone(),
         ],
@@ -229,10 +308,11 @@ pub fn process(
     // create vault b
     msg!("initializing vault b...");
     invoke(
-        &spl_associated_token_account::create_associated_token_account(
+        &token::create_associated_token_account(
             user.key,
             pool_ai.key,
             mint_b_ai.key,
+            token_program_b.key,
         ),
         &[
             user.clone(),
@@ -240,7 +320,7 @@ pub fn process(
             pool_ai.clone(),
             mint_b_ai.clone(),
             system_program.clone(),
-            token_program.clone(),
+            token_program_b.clone(),
             sysvar_rent.clone(),
             associated_token_program.clone(),
         ],
@@ -283,12 +363,17 @@ pub fn process(
 
     // create pool account to store data
     msg!("initializing pool...");
+    let pool_len = Pool::len(
+        dynamic_fee.is_some(),
+        allowlist.is_some(),
+        hook_program.is_some(),
+    );
     invoke_signed(
         &system_instruction::create_account(
             user.key,
             pool_ai.key,
-            rent::Rent::get()?.minimum_balance(mem::size_of::<Pool>()),
-            mem::size_of::<Pool>() as u64,
+            rent::Rent::get()?.minimum_balance(pool_len),
+            pool_len as u64,
             program_id,
         ),
         &[user.clone(), pool_ai.clone(), system_program.clone()],
@@ -300,11 +385,81 @@ pub fn process(
         mint_a: *mint_a_seed,
         mint_b: *mint_b_seed,
         mint: *pool_mint_ai.key,
-        fee: fee,
-        fee_decimals: fee_decimals,
+        fee,
+        fee_decimals,
+        reserve_a: 0,
+        reserve_b: 0,
+        twamm: false,
+        price_cumulative: PriceCumulative {
+            last_timestamp: Clock::get()?.unix_timestamp,
+            ..PriceCumulative::default()
+        },
+        dynamic_fee: match dynamic_fee {
+            Some(config) => Some(DynamicFee {
+                config,
+                volatility: 0,
+                last_slot: Clock::get()?.slot,
+            }),
+            None => None,
+        },
+        allowlist,
+        hook_program,
     };
-    pool.serialize(&mut *pool_ai.try_borrow_mut_data()?)?;
+    pool.serialize(&mut &mut pool_ai.try_borrow_mut_data()?[..])?;
     msg!("initialized pool");
 
+    // list the pool for discovery, the registry pdas are checked as they're loaded
+    registry::register_pool(
+        program_id,
+        user,
+        system_program,
+        pool_ai.key,
+        factory_ai,
+        registry_page_ai,
+        [
+            (&pool.mint_a, mint_index_a_ai, mint_index_page_a_ai),
+            (&pool.mint_b, mint_index_b_ai, mint_index_page_b_ai),
+        ],
+    )?;
+
+    // name the lp mint after the pool's mints, the admin can rename it later
+    let (lp_metadata_key, lp_metadata_bump) =
+        Pubkey::find_program_address(&[LpMetadata::SEED, pool_mint_ai.key.as_ref()], program_id);
+    let lp_metadata_seeds = &[
+        LpMetadata::SEED,
+        pool_mint_ai.key.as_ref(),
+        &[lp_metadata_bump],
+    ];
+    assert_msg(
+        *lp_metadata_ai.key == lp_metadata_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "lp metadata pda aint right",
+    )?;
+    invoke_signed(
+        &system_instruction::create_account(
+            user.key,
+            lp_metadata_ai.key,
+            rent::Rent::get()?.minimum_balance(LpMetadata::LEN),
+            LpMetadata::LEN as u64,
+            program_id,
+        ),
+        &[user.clone(), lp_metadata_ai.clone(), system_program.clone()],
+        &[lp_metadata_seeds],
+    )?;
+    let lp_metadata = LpMetadata::new(pool.mint, &pool.mint_a, &pool.mint_b)?;
+    lp_metadata.serialize(&mut &mut lp_metadata_ai.try_borrow_mut_data()?[..])?;
+    msg!("Named lp mint {}", lp_metadata.name());
+
+    ChudexEvent::PoolInitialized(PoolInitialized {
+        pool: *pool_ai.key,
+        user: *user.key,
+        mint_a: pool.mint_a,
+        mint_b: pool.mint_b,
+        pool_mint: pool.mint,
+        fee: pool.fee,
+        fee_decimals: pool.fee_decimals,
+    })
+    .emit();
+
     Ok(())
 }
@@ -0,0 +1,113 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction, system_program as system_program_ext,
+    sysvar::{rent, Sysvar},
+};
+
+use crate::{
+    cl_math,
+    error::ChudexError,
+    state::{ClPool, Tick, TickArray},
+    utils::assert_msg,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], start_tick: i32) -> ProgramResult {
+    // GET ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let tick_array_ai = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    // ACCOUNT VALIDATION
+
+    // user is signer
+    assert_msg(
+        user.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "User not signer",
+    )?;
+
+    // pool
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let pool = ClPool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+
+    // start tick lines up with the arrays and overlaps the tick range
+    assert_msg(
+        start_tick == pool.tick_array_start(start_tick)
+            && start_tick <= cl_math::MAX_TICK
+            && start_tick + TickArray::SIZE as i32 * pool.tick_spacing as i32 > cl_math::MIN_TICK,
+        ChudexError::InvalidTick.into(),
+        "Tick array start invalid",
+    )?;
+
+    // pda verification
+
+    // tick array pda
+    let start_tick_bytes = start_tick.to_le_bytes();
+    let (tick_array_key, tick_array_bump) = Pubkey::find_program_address(
+        &[TickArray::SEED, pool_ai.key.as_ref(), &start_tick_bytes],
+        program_id,
+    );
+    let tick_array_seeds = &[
+        TickArray::SEED,
+        pool_ai.key.as_ref(),
+        &start_tick_bytes,
+        &[tick_array_bump],
+    ];
+    assert_msg(
+        *tick_array_ai.key == tick_array_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "tick array pda aint right",
+    )?;
+    assert_msg(
+        tick_array_ai.data_len() == 0,
+        ChudexError::AccountAlreadyInitialized.into(),
+        "Tick array already initialized",
+    )?;
+
+    // external program verification
+    // system program
+    assert_msg(
+        *system_program.key == system_program_ext::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+
+    // LOGIC
+
+    msg!("initializing tick array...");
+    invoke_signed(
+        &system_instruction::create_account(
+            user.key,
+            tick_array_ai.key,
+            rent::Rent::get()?.minimum_balance(TickArray::LEN),
+            TickArray::LEN as u64,
+            program_id,
+        ),
+        &[user.clone(), tick_array_ai.clone(), system_program.clone()],
+        &[tick_array_seeds],
+    )?;
+
+    let tick_array = TickArray {
+        pool: *pool_ai.key,
+        start_tick,
+        ticks: vec![Tick::default(); TickArray::SIZE],
+    };
+    tick_array.serialize(&mut &mut tick_array_ai.try_borrow_mut_data()?[..])?;
+    msg!("initialized tick array");
+
+    Ok(())
+}
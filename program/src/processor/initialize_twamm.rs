@@ -0,0 +1,211 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction, system_program as system_program_ext,
+    sysvar::{clock::Clock, rent, Sysvar},
+};
+
+use crate::{
+    error::ChudexError,
+    state::{ExpiryBucket, Pool, TwammPool},
+    token,
+    utils::assert_msg,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // GET ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let twamm_ai = next_account_info(accounts_iter)?;
+    let twamm_vault_a_ai = next_account_info(accounts_iter)?;
+    let twamm_vault_b_ai = next_account_info(accounts_iter)?;
+    let mint_a_ai = next_account_info(accounts_iter)?;
+    let mint_b_ai = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let sysvar_rent = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let mut pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+
+    // ACCOUNT VALIDATION
+
+    // user is signer
+    assert_msg(
+        user.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "User not signer",
+    )?;
+
+    // mints in pool order
+    assert_msg(
+        *mint_a_ai.key == pool.mint_a && *mint_b_ai.key == pool.mint_b,
+        ChudexError::InvalidAccountAddress.into(),
+        "Mints don't match pool",
+    )?;
+
+    // pda verification
+
+    // pool pda
+    let (pool_key, _) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // twamm pda
+    let (twamm_key, twamm_bump) =
+        Pubkey::find_program_address(&[TwammPool::SEED, pool_ai.key.as_ref()], program_id);
+    let twamm_seeds = &[TwammPool::SEED, pool_ai.key.as_ref(), &[twamm_bump]];
+    assert_msg(
+        *twamm_ai.key == twamm_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "twamm pda aint right",
+    )?;
+    assert_msg(
+        twamm_ai.data_len() == 0,
+        ChudexError::AccountAlreadyInitialized.into(),
+        "Twamm already initialized",
+    )?;
+
+    // twamm vault a pda
+    let (twamm_vault_a_key, _) = Pubkey::find_program_address(
+        &[
+            twamm_ai.key.as_ref(),
+            token_program_a.key.as_ref(),
+            mint_a_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        twamm_vault_a_key == *twamm_vault_a_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "twamm vault a pda aint right",
+    )?;
+
+    // twamm vault b pda
+    let (twamm_vault_b_key, _) = Pubkey::find_program_address(
+        &[
+            twamm_ai.key.as_ref(),
+            token_program_b.key.as_ref(),
+            mint_b_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        twamm_vault_b_key == *twamm_vault_b_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "twamm vault b pda aint right",
+    )?;
+
+    // external program verification
+    // mint token programs, either spl token or token-2022
+    assert_msg(
+        mint_a_ai.owner == token_program_a.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program a wrong address",
+    )?;
+    assert_msg(
+        mint_b_ai.owner == token_program_b.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program b wrong address",
+    )?;
+
+    // system program
+    assert_msg(
+        *system_program.key == system_program_ext::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+
+    // sysvar program
+    assert_msg(
+        *sysvar_rent.key == rent::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Sysvar program wrong address",
+    )?;
+
+    // associated token program
+    assert_msg(
+        *associated_token_program.key == spl_associated_token_account::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Associated token program wrong address",
+    )?;
+
+    // LOGIC
+
+    // create twamm account to store order pools
+    msg!("initializing twamm...");
+    invoke_signed(
+        &system_instruction::create_account(
+            user.key,
+            twamm_ai.key,
+            rent::Rent::get()?.minimum_balance(TwammPool::LEN),
+            TwammPool::LEN as u64,
+            program_id,
+        ),
+        &[user.clone(), twamm_ai.clone(), system_program.clone()],
+        &[twamm_seeds],
+    )?;
+
+    // create vaults holding unsold orders and proceeds
+    for (vault_ai, mint_ai, token_program_ai) in [
+        (twamm_vault_a_ai, mint_a_ai, token_program_a),
+        (twamm_vault_b_ai, mint_b_ai, token_program_b),
+    ] {
+        invoke(
+            &token::create_associated_token_account(
+                user.key,
+                twamm_ai.key,
+                mint_ai.key,
+                token_program_ai.key,
+            ),
+            &[
+                user.clone(),
+                vault_ai.clone(),
+                twamm_ai.clone(),
+                mint_ai.clone(),
+                system_program.clone(),
+                token_program_ai.clone(),
+                sysvar_rent.clone(),
+                associated_token_program.clone(),
+            ],
+        )?;
+    }
+
+    let twamm = TwammPool {
+        pool: *pool_ai.key,
+        last_slot: Clock::get()?.slot,
+        sell_rate_a: 0,
+        sell_rate_b: 0,
+        earnings_factor_a: 0,
+        earnings_factor_b: 0,
+        buckets: vec![ExpiryBucket::default(); TwammPool::MAX_BUCKETS],
+        expired_open_orders: 0,
+    };
+    twamm.serialize(&mut &mut twamm_ai.try_borrow_mut_data()?[..])?;
+    pool.twamm = true;
+    pool.serialize(&mut &mut pool_ai.try_borrow_mut_data()?[..])?;
+    msg!("initialized twamm");
+
+    Ok(())
+}
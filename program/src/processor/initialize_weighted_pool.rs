@@ -0,0 +1,253 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction, system_program as system_program_ext,
+    sysvar::{rent, Sysvar},
+};
+
+use crate::{curve, error::ChudexError, state::WeightedPool, token, utils::assert_msg};
+
+use borsh::BorshSerialize;
+
+use spl_token::instruction;
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    weights: Vec<u64>,
+    fee: u64,
+    fee_decimals: u64,
+) -> ProgramResult {
+    // GET ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let pool_mint_ai = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let sysvar_rent = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+
+    let num_tokens = weights.len();
+    assert_msg(
+        (WeightedPool::MIN_TOKENS..=WeightedPool::MAX_TOKENS).contains(&num_tokens),
+        ChudexError::InvalidInstructionInput.into(),
+        "Weighted pool needs 2 to 8 tokens",
+    )?;
+    assert_msg(
+        weights.iter().all(|weight| *weight > 0),
+        ChudexError::InvalidInstructionInput.into(),
+        "Weights must be positive",
+    )?;
+
+    // (mint, vault, token program) per token
+    let mut tokens = Vec::with_capacity(num_tokens);
+    for _ in 0..num_tokens {
+        tokens.push((
+            next_account_info(accounts_iter)?,
+            next_account_info(accounts_iter)?,
+            next_account_info(accounts_iter)?,
+        ));
+    }
+
+    // ACCOUNT VALIDATION
+
+    // user is signer
+    assert_msg(
+        user.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "User not signer",
+    )?;
+
+    // fee under 100%
+    curve::check_fee(fee, fee_decimals)?;
+
+    // mints sorted and unique, which also makes the pool pda unique per mint set
+    assert_msg(
+        tokens.windows(2).all(|pair| pair[0].0.key < pair[1].0.key),
+        ChudexError::InvalidInstructionInput.into(),
+        "Mints not sorted or not unique",
+    )?;
+
+    let mut pool_mint_decimals = 0;
+    for (mint_ai, vault_ai, token_program_ai) in &tokens {
+        let mint = token::unpack_mint(mint_ai)?;
+        pool_mint_decimals = pool_mint_decimals.max(mint.decimals);
+
+        // mint token program, either spl token or token-2022
+        assert_msg(
+            mint_ai.owner == token_program_ai.key,
+            ChudexError::InvalidAccountAddress.into(),
+            "Mint token program wrong address",
+        )?;
+
+        // vault pda
+        let (vault_key, _) = Pubkey::find_program_address(
+            &[
+                pool_ai.key.as_ref(),
+                token_program_ai.key.as_ref(),
+                mint_ai.key.as_ref(),
+            ],
+            &spl_associated_token_account::id(),
+        );
+        assert_msg(
+            vault_key == *vault_ai.key,
+            ChudexError::InvalidProgramAddress.into(),
+            "vault pda aint right",
+        )?;
+        assert_msg(
+            vault_ai.data_len() == 0,
+            ChudexError::AccountAlreadyInitialized.into(),
+            "Vault already initialized",
+        )?;
+    }
+
+    // pool pda
+    let mints: Vec<Pubkey> = tokens.iter().map(|(mint_ai, _, _)| *mint_ai.key).collect();
+    let mut pool_seeds = WeightedPool::seeds(&mints);
+    let (pool_key, pool_bump) = Pubkey::find_program_address(&pool_seeds, program_id);
+    let pool_bump = [pool_bump];
+    pool_seeds.push(&pool_bump);
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // pool mint pda
+    let (pool_mint_key, pool_mint_bump) =
+        Pubkey::find_program_address(&[b"chudex_pool_mint", pool_ai.key.as_ref()], program_id);
+    let pool_mint_seeds = &[b"chudex_pool_mint", pool_ai.key.as_ref(), &[pool_mint_bump]];
+    assert_msg(
+        *pool_mint_ai.key == pool_mint_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool mint address invalid",
+    )?;
+
+    // external program verification
+    // token program
+    assert_msg(
+        *token_program.key == spl_token::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program wrong address",
+    )?;
+
+    // system program
+    assert_msg(
+        *system_program.key == system_program_ext::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+
+    // sysvar program
+    assert_msg(
+        *sysvar_rent.key == rent::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Sysvar program wrong address",
+    )?;
+
+    // associated token program
+    assert_msg(
+        *associated_token_program.key == spl_associated_token_account::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Associated token program wrong address",
+    )?;
+
+    // pool data
+    assert_msg(
+        pool_ai.data_len() == 0,
+        ChudexError::AccountAlreadyInitialized.into(),
+        "Pool already initialized",
+    )?;
+
+    // LOGIC
+
+    // create token vaults
+    msg!("initializing vaults...");
+    for (mint_ai, vault_ai, token_program_ai) in &tokens {
+        invoke(
+            &token::create_associated_token_account(
+                user.key,
+                pool_ai.key,
+                mint_ai.key,
+                token_program_ai.key,
+            ),
+            &[
+                user.clone(),
+                (*vault_ai).clone(),
+                pool_ai.clone(),
+                (*mint_ai).clone(),
+                system_program.clone(),
+                (*token_program_ai).clone(),
+                sysvar_rent.clone(),
+                associated_token_program.clone(),
+            ],
+        )?;
+    }
+    msg!("initialized vaults");
+
+    // create mint
+    msg!("Initializing mint...");
+    let mint_data_len = 82;
+    invoke_signed(
+        &system_instruction::create_account(
+            user.key,
+            pool_mint_ai.key,
+            rent::Rent::get()?.minimum_balance(mint_data_len),
+            mint_data_len as u64,
+            &spl_token::id(),
+        ),
+        &[user.clone(), pool_mint_ai.clone(), system_program.clone()],
+        &[pool_mint_seeds],
+    )?;
+
+    // pool token takes the largest decimals of the token mints
+    invoke_signed(
+        &instruction::initialize_mint(
+            &spl_token::id(),
+            pool_mint_ai.key,
+            pool_ai.key,
+            Some(pool_ai.key),
+            pool_mint_decimals,
+        )?,
+        &[
+            pool_mint_ai.clone(),
+            sysvar_rent.clone(),
+            token_program.clone(),
+        ],
+        &[pool_mint_seeds],
+    )?;
+    msg!("Initialized mint");
+
+    // create pool account to store data
+    msg!("initializing pool...");
+    let pool_len = WeightedPool::len(num_tokens);
+    invoke_signed(
+        &system_instruction::create_account(
+            user.key,
+            pool_ai.key,
+            rent::Rent::get()?.minimum_balance(pool_len),
+            pool_len as u64,
+            program_id,
+        ),
+        &[user.clone(), pool_ai.clone(), system_program.clone()],
+        &[pool_seeds.as_slice()],
+    )?;
+
+    let pool = WeightedPool {
+        mints,
+        weights,
+        mint: *pool_mint_ai.key,
+        fee,
+        fee_decimals,
+    };
+    pool.serialize(&mut &mut pool_ai.try_borrow_mut_data()?[..])?;
+    msg!("initialized pool");
+
+    Ok(())
+}
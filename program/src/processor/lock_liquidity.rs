@@ -0,0 +1,273 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    system_program::id as system_program_id,
+    sysvar::{rent, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::ChudexError,
+    state::{Locker, Pool, PoolLockers},
+    token,
+    utils::assert_msg,
+};
+
+use spl_token::{error::TokenError, instruction};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    unlock_timestamp: i64,
+    vesting_end_timestamp: Option<i64>,
+) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let user_pool_token_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let pool_mint_ai = next_account_info(accounts_iter)?;
+    let pool_lockers_ai = next_account_info(accounts_iter)?;
+    let locker_ai = next_account_info(accounts_iter)?;
+    let locker_pool_token_ai = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let sysvar_rent = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    let user_pool_token = token::unpack_token_account(user_pool_token_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // user is signer
+    assert_msg(
+        user.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "User not signer",
+    )?;
+
+    // pool tokens are the user's, of this pool
+    assert_msg(
+        user_pool_token.owner == *user.key,
+        TokenError::OwnerMismatch.into(),
+        "user pool token not owned by user",
+    )?;
+    assert_msg(
+        *pool_mint_ai.key == pool.mint && user_pool_token.mint == pool.mint,
+        TokenError::MintMismatch.into(),
+        "Pool mint doesn't match pool",
+    )?;
+
+    // something to lock, vesting ends after the unlock
+    assert_msg(
+        amount > 0,
+        ChudexError::InvalidInstructionInput.into(),
+        "Nothing to lock",
+    )?;
+    if let Some(vesting_end_timestamp) = vesting_end_timestamp {
+        assert_msg(
+            vesting_end_timestamp > unlock_timestamp,
+            ChudexError::InvalidInstructionInput.into(),
+            "Vesting ends before the unlock",
+        )?;
+    }
+
+    // pda verification
+
+    // pool pda
+    let (pool_key, _) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // pool lockers pda
+    let (pool_lockers_key, pool_lockers_bump) =
+        Pubkey::find_program_address(&[PoolLockers::SEED, pool_ai.key.as_ref()], program_id);
+    let pool_lockers_seeds = &[
+        PoolLockers::SEED,
+        pool_ai.key.as_ref(),
+        &[pool_lockers_bump],
+    ];
+    assert_msg(
+        *pool_lockers_ai.key == pool_lockers_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "pool lockers pda aint right",
+    )?;
+
+    // external program verification
+    assert_msg(
+        *token_program.key == spl_token::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program wrong address",
+    )?;
+    assert_msg(
+        *system_program.key == system_program_id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+    assert_msg(
+        *sysvar_rent.key == rent::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Sysvar program wrong address",
+    )?;
+    assert_msg(
+        *associated_token_program.key == spl_associated_token_account::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Associated token program wrong address",
+    )?;
+
+    // LOGIC
+
+    // the pool's first locker creates its count
+    let mut pool_lockers = if pool_lockers_ai.data_len() == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                user.key,
+                pool_lockers_ai.key,
+                rent::Rent::get()?.minimum_balance(PoolLockers::LEN),
+                PoolLockers::LEN as u64,
+                program_id,
+            ),
+            &[
+                user.clone(),
+                pool_lockers_ai.clone(),
+                system_program.clone(),
+            ],
+            &[pool_lockers_seeds],
+        )?;
+        PoolLockers {
+            pool: *pool_ai.key,
+            locker_count: 0,
+        }
+    } else {
+        assert_msg(
+            pool_lockers_ai.owner == program_id,
+            ChudexError::InvalidAccountAddress.into(),
+            "Pool lockers not owned by program",
+        )?;
+        PoolLockers::try_from_slice(&pool_lockers_ai.try_borrow_data()?)?
+    };
+
+    // locker pda, next index of the pool's lockers
+    let index_bytes = pool_lockers.locker_count.to_le_bytes();
+    let (locker_key, locker_bump) = Pubkey::find_program_address(
+        &[Locker::SEED, pool_ai.key.as_ref(), &index_bytes],
+        program_id,
+    );
+    let locker_seeds = &[
+        Locker::SEED,
+        pool_ai.key.as_ref(),
+        &index_bytes,
+        &[locker_bump],
+    ];
+    assert_msg(
+        *locker_ai.key == locker_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "locker pda aint right",
+    )?;
+
+    // locker pool token pda
+    let (locker_pool_token_key, _) = Pubkey::find_program_address(
+        &[
+            locker_ai.key.as_ref(),
+            token_program.key.as_ref(),
+            pool_mint_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        *locker_pool_token_ai.key == locker_pool_token_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "locker pool token pda aint right",
+    )?;
+
+    // create locker account
+    let locker_len = Locker::len(vesting_end_timestamp.is_some());
+    invoke_signed(
+        &system_instruction::create_account(
+            user.key,
+            locker_ai.key,
+            rent::Rent::get()?.minimum_balance(locker_len),
+            locker_len as u64,
+            program_id,
+        ),
+        &[user.clone(), locker_ai.clone(), system_program.clone()],
+        &[locker_seeds],
+    )?;
+    let locker = Locker {
+        pool: *pool_ai.key,
+        index: pool_lockers.locker_count,
+        owner: *user.key,
+        amount,
+        released: 0,
+        unlock_timestamp,
+        vesting_end_timestamp,
+    };
+    locker.serialize(&mut &mut locker_ai.try_borrow_mut_data()?[..])?;
+
+    pool_lockers.locker_count += 1;
+    pool_lockers.serialize(&mut &mut pool_lockers_ai.try_borrow_mut_data()?[..])?;
+
+    // escrow the pool tokens with the locker
+    invoke(
+        &token::create_associated_token_account(
+            user.key,
+            locker_ai.key,
+            pool_mint_ai.key,
+            &spl_token::id(),
+        ),
+        &[
+            user.clone(),
+            locker_pool_token_ai.clone(),
+            locker_ai.clone(),
+            pool_mint_ai.clone(),
+            system_program.clone(),
+            token_program.clone(),
+            sysvar_rent.clone(),
+            associated_token_program.clone(),
+        ],
+    )?;
+    invoke(
+        &instruction::transfer(
+            &spl_token::id(),
+            user_pool_token_ai.key,
+            locker_pool_token_ai.key,
+            user.key,
+            &[],
+            amount,
+        )?,
+        &[
+            user_pool_token_ai.clone(),
+            locker_pool_token_ai.clone(),
+            user.clone(),
+        ],
+    )?;
+    msg!(
+        "Locked {} pool tokens in locker {} until {}",
+        amount,
+        locker_ai.key,
+        unlock_timestamp
+    );
+
+    Ok(())
+}
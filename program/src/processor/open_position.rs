@@ -0,0 +1,136 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction, system_program as system_program_ext,
+    sysvar::{rent, Sysvar},
+};
+
+use crate::{
+    cl_math,
+    error::ChudexError,
+    state::{ClPool, Position},
+    utils::assert_msg,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    tick_lower: i32,
+    tick_upper: i32,
+) -> ProgramResult {
+    // GET ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let position_ai = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    // ACCOUNT VALIDATION
+
+    // owner is signer
+    assert_msg(
+        owner.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "Owner not signer",
+    )?;
+
+    // pool
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let pool = ClPool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+
+    // tick range
+    let tick_spacing = pool.tick_spacing as i32;
+    assert_msg(
+        tick_lower < tick_upper
+            && tick_lower >= cl_math::MIN_TICK
+            && tick_upper <= cl_math::MAX_TICK
+            && tick_lower % tick_spacing == 0
+            && tick_upper % tick_spacing == 0,
+        ChudexError::InvalidTick.into(),
+        "Position ticks invalid",
+    )?;
+
+    // pda verification
+
+    // position pda
+    let tick_lower_bytes = tick_lower.to_le_bytes();
+    let tick_upper_bytes = tick_upper.to_le_bytes();
+    let (position_key, position_bump) = Pubkey::find_program_address(
+        &[
+            Position::SEED,
+            pool_ai.key.as_ref(),
+            owner.key.as_ref(),
+            &tick_lower_bytes,
+            &tick_upper_bytes,
+        ],
+        program_id,
+    );
+    let position_seeds = &[
+        Position::SEED,
+        pool_ai.key.as_ref(),
+        owner.key.as_ref(),
+        &tick_lower_bytes,
+        &tick_upper_bytes,
+        &[position_bump],
+    ];
+    assert_msg(
+        *position_ai.key == position_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "position pda aint right",
+    )?;
+    assert_msg(
+        position_ai.data_len() == 0,
+        ChudexError::AccountAlreadyInitialized.into(),
+        "Position already initialized",
+    )?;
+
+    // external program verification
+    // system program
+    assert_msg(
+        *system_program.key == system_program_ext::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+
+    // LOGIC
+
+    msg!("opening position...");
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            position_ai.key,
+            rent::Rent::get()?.minimum_balance(Position::LEN),
+            Position::LEN as u64,
+            program_id,
+        ),
+        &[owner.clone(), position_ai.clone(), system_program.clone()],
+        &[position_seeds],
+    )?;
+
+    let position = Position {
+        pool: *pool_ai.key,
+        owner: *owner.key,
+        tick_lower,
+        tick_upper,
+        liquidity: 0,
+        fee_growth_inside_last_a: 0,
+        fee_growth_inside_last_b: 0,
+        fees_owed_a: 0,
+        fees_owed_b: 0,
+    };
+    position.serialize(&mut &mut position_ai.try_borrow_mut_data()?[..])?;
+    msg!("opened position");
+
+    Ok(())
+}
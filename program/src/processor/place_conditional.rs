@@ -0,0 +1,277 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    pubkey::Pubkey,
+    system_instruction, system_program as system_program_ext,
+    sysvar::{clock::Clock, rent, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    allowlist,
+    error::ChudexError,
+    state::{ConditionalOrder, Pool, PriceOracle, TriggerSource},
+    token,
+    utils::assert_msg,
+};
+
+use spl_token::error::TokenError;
+
+#[allow(clippy::too_many_arguments)]
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    nonce: u64,
+    amount_in: u64,
+    min_amount_out: u64,
+    trigger_price: u128,
+    take_profit: bool,
+    trigger_source: TriggerSource,
+    expiry_timestamp: i64,
+) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let owner_token_in_ai = next_account_info(accounts_iter)?;
+    let owner_token_out_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let order_ai = next_account_info(accounts_iter)?;
+    let escrow_ai = next_account_info(accounts_iter)?;
+    let mint_in_ai = next_account_info(accounts_iter)?;
+    let mint_out_ai = next_account_info(accounts_iter)?;
+    let token_program_in = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let sysvar_rent = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+    // allowlist or allowlist ticket, permissioned pools only
+    let allowlist_ai = next_account_info(accounts_iter).ok();
+
+    // deserialization
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    let owner_token_out = token::unpack_token_account(owner_token_out_ai)?;
+    let mint_in = token::unpack_mint(mint_in_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // owner is signer
+    assert_msg(
+        owner.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "Owner not signer",
+    )?;
+
+    // owner is on the pool's allowlist, if it has one
+    allowlist::check(program_id, pool_ai, &pool, owner.key, allowlist_ai)?;
+
+    assert_msg(
+        amount_in > 0 && min_amount_out > 0,
+        ChudexError::InvalidInstructionInput.into(),
+        "Order amounts must be positive",
+    )?;
+
+    // trigger is a price, the order can still run, and a twap window fits in the oracle
+    assert_msg(
+        trigger_price > 0,
+        ChudexError::InvalidInstructionInput.into(),
+        "Trigger price must be positive",
+    )?;
+    assert_msg(
+        expiry_timestamp > Clock::get()?.unix_timestamp,
+        ChudexError::OrderExpired.into(),
+        "Order already expired",
+    )?;
+    if let TriggerSource::Twap { window } = trigger_source {
+        assert_msg(
+            window > 0
+                && window as i64
+                    <= (PriceOracle::MAX_OBSERVATIONS as i64 - 1)
+                        * PriceOracle::OBSERVATION_INTERVAL,
+            ChudexError::InvalidInstructionInput.into(),
+            "Twap window out of the oracle's range",
+        )?;
+    }
+
+    // mints are the pool's two mints
+    assert_msg(
+        (*mint_in_ai.key == pool.mint_a && *mint_out_ai.key == pool.mint_b)
+            || (*mint_in_ai.key == pool.mint_b && *mint_out_ai.key == pool.mint_a),
+        ChudexError::InvalidAccountAddress.into(),
+        "Mints don't match pool",
+    )?;
+
+    // proceeds go to the owner's token out acc
+    assert_msg(
+        owner_token_out.owner == *owner.key,
+        TokenError::OwnerMismatch.into(),
+        "owner token out not owned by owner",
+    )?;
+    assert_msg(
+        owner_token_out.mint == *mint_out_ai.key,
+        TokenError::MintMismatch.into(),
+        "owner token out mint wrong",
+    )?;
+
+    // pda verification
+
+    // pool pda
+    let (pool_key, _) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // order pda
+    let nonce_bytes = nonce.to_le_bytes();
+    let (order_key, order_bump) = Pubkey::find_program_address(
+        &[
+            ConditionalOrder::SEED,
+            pool_ai.key.as_ref(),
+            owner.key.as_ref(),
+            &nonce_bytes,
+        ],
+        program_id,
+    );
+    let order_seeds = &[
+        ConditionalOrder::SEED,
+        pool_ai.key.as_ref(),
+        owner.key.as_ref(),
+        &nonce_bytes,
+        &[order_bump],
+    ];
+    assert_msg(
+        *order_ai.key == order_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "order pda aint right",
+    )?;
+    assert_msg(
+        order_ai.data_len() == 0,
+        ChudexError::AccountAlreadyInitialized.into(),
+        "Order already initialized",
+    )?;
+
+    // escrow pda
+    let (escrow_key, _) = Pubkey::find_program_address(
+        &[
+            order_ai.key.as_ref(),
+            token_program_in.key.as_ref(),
+            mint_in_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        escrow_key == *escrow_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "escrow pda aint right",
+    )?;
+
+    // external program verification
+    // mint token program, either spl token or token-2022
+    assert_msg(
+        mint_in_ai.owner == token_program_in.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program in wrong address",
+    )?;
+
+    // system program
+    assert_msg(
+        *system_program.key == system_program_ext::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+
+    // sysvar program
+    assert_msg(
+        *sysvar_rent.key == rent::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Sysvar program wrong address",
+    )?;
+
+    // associated token program
+    assert_msg(
+        *associated_token_program.key == spl_associated_token_account::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Associated token program wrong address",
+    )?;
+
+    // LOGIC
+
+    // create order account
+    msg!("placing order...");
+    let order_len = ConditionalOrder::len(trigger_source != TriggerSource::Spot);
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            order_ai.key,
+            rent::Rent::get()?.minimum_balance(order_len),
+            order_len as u64,
+            program_id,
+        ),
+        &[owner.clone(), order_ai.clone(), system_program.clone()],
+        &[order_seeds],
+    )?;
+
+    // create escrow owned by the order
+    invoke(
+        &token::create_associated_token_account(
+            owner.key,
+            order_ai.key,
+            mint_in_ai.key,
+            token_program_in.key,
+        ),
+        &[
+            owner.clone(),
+            escrow_ai.clone(),
+            order_ai.clone(),
+            mint_in_ai.clone(),
+            system_program.clone(),
+            token_program_in.clone(),
+            sysvar_rent.clone(),
+            associated_token_program.clone(),
+        ],
+    )?;
+
+    // escrow tokens in
+    token::transfer(
+        token_program_in,
+        owner_token_in_ai,
+        mint_in_ai,
+        escrow_ai,
+        owner,
+        amount_in,
+        mint_in.decimals,
+        &[],
+    )?;
+
+    // the order sells what actually landed in escrow after any transfer fee
+    let order = ConditionalOrder {
+        pool: *pool_ai.key,
+        owner: *owner.key,
+        nonce,
+        mint_in: *mint_in_ai.key,
+        mint_out: *mint_out_ai.key,
+        owner_token_out: *owner_token_out_ai.key,
+        amount_in: token::amount_after_transfer_fee(mint_in_ai, amount_in)?,
+        min_amount_out,
+        trigger_price,
+        take_profit,
+        trigger_source,
+        expiry_timestamp,
+    };
+    order.serialize(&mut &mut order_ai.try_borrow_mut_data()?[..])?;
+    msg!("placed order");
+
+    Ok(())
+}
@@ -0,0 +1,331 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    pubkey::Pubkey,
+    system_instruction, system_program as system_program_ext,
+    sysvar::{clock::Clock, rent, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    allowlist,
+    error::ChudexError,
+    state::{ExpiryBucket, LongTermOrder, Pool, TwammPool},
+    token, twamm,
+    utils::assert_msg,
+};
+
+use spl_token::error::TokenError;
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    nonce: u64,
+    amount_in: u64,
+    a_to_b: bool,
+    num_slots: u64,
+) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let owner_token_in_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let twamm_ai = next_account_info(accounts_iter)?;
+    let order_ai = next_account_info(accounts_iter)?;
+    let twamm_vault_a_ai = next_account_info(accounts_iter)?;
+    let twamm_vault_b_ai = next_account_info(accounts_iter)?;
+    let pool_vault_a_ai = next_account_info(accounts_iter)?;
+    let pool_vault_b_ai = next_account_info(accounts_iter)?;
+    let mint_a_ai = next_account_info(accounts_iter)?;
+    let mint_b_ai = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let expired_bucket_ai = next_account_info(accounts_iter)?;
+    // allowlist or allowlist ticket, permissioned pools only
+    let allowlist_ai = next_account_info(accounts_iter).ok();
+
+    // deserialization
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let mut pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    let owner_token_in = token::unpack_token_account(owner_token_in_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // owner is signer
+    assert_msg(
+        owner.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "Owner not signer",
+    )?;
+
+    // owner is on the pool's allowlist, if it has one
+    allowlist::check(program_id, pool_ai, &pool, owner.key, allowlist_ai)?;
+    assert_msg(
+        amount_in > 0 && num_slots > 0,
+        ChudexError::InvalidInstructionInput.into(),
+        "Order amount and duration must be positive",
+    )?;
+
+    // mints in pool order
+    assert_msg(
+        *mint_a_ai.key == pool.mint_a && *mint_b_ai.key == pool.mint_b,
+        ChudexError::InvalidAccountAddress.into(),
+        "Mints don't match pool",
+    )?;
+
+    // token account ownership
+    assert_msg(
+        owner_token_in.owner == *owner.key,
+        TokenError::OwnerMismatch.into(),
+        "owner token in not owned by owner",
+    )?;
+
+    // pda verification
+
+    // pool pda, seeds copied out since the pool's reserves get updated while they're in use
+    let (pool_mint_a, pool_mint_b) = (pool.mint_a, pool.mint_b);
+    let (pool_key, pool_bump) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    let pool_seeds = &[
+        b"chudex_pool",
+        pool_mint_a.as_ref(),
+        pool_mint_b.as_ref(),
+        &[pool_bump],
+    ];
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // vault a pda
+    let (vault_a_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_a.key.as_ref(),
+            mint_a_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_a_key == *pool_vault_a_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault a pda aint right",
+    )?;
+
+    // vault b pda
+    let (vault_b_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_b.key.as_ref(),
+            mint_b_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_b_key == *pool_vault_b_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault b pda aint right",
+    )?;
+
+    // order pda
+    let nonce_bytes = nonce.to_le_bytes();
+    let (order_key, order_bump) = Pubkey::find_program_address(
+        &[
+            LongTermOrder::SEED,
+            pool_ai.key.as_ref(),
+            owner.key.as_ref(),
+            &nonce_bytes,
+        ],
+        program_id,
+    );
+    let order_seeds = &[
+        LongTermOrder::SEED,
+        pool_ai.key.as_ref(),
+        owner.key.as_ref(),
+        &nonce_bytes,
+        &[order_bump],
+    ];
+    assert_msg(
+        *order_ai.key == order_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "order pda aint right",
+    )?;
+    assert_msg(
+        order_ai.data_len() == 0,
+        ChudexError::AccountAlreadyInitialized.into(),
+        "Order already initialized",
+    )?;
+
+    // external program verification
+    // mint token programs, either spl token or token-2022
+    assert_msg(
+        mint_a_ai.owner == token_program_a.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program a wrong address",
+    )?;
+    assert_msg(
+        mint_b_ai.owner == token_program_b.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program b wrong address",
+    )?;
+
+    // system program
+    assert_msg(
+        *system_program.key == system_program_ext::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+
+    // LOGIC
+
+    // bring the order pools up to now before joining one
+    let now = Clock::get()?.slot;
+    let mut twamm = twamm::settle(
+        program_id,
+        pool_ai,
+        &mut pool,
+        pool_seeds,
+        Some((twamm_ai, twamm_vault_a_ai, twamm_vault_b_ai)),
+        (pool_vault_a_ai, mint_a_ai, token_program_a),
+        (pool_vault_b_ai, mint_b_ai, token_program_b),
+        now,
+    )?
+    .ok_or(ChudexError::InvalidAccountData)?;
+    pool.serialize(&mut &mut pool_ai.try_borrow_mut_data()?[..])?;
+
+    // orders end on the first interval boundary after the requested slots
+    let end_slot = now
+        .checked_add(num_slots)
+        .ok_or(ChudexError::CalculationFailure)?
+        .div_ceil(TwammPool::ORDER_INTERVAL)
+        * TwammPool::ORDER_INTERVAL;
+    assert_msg(
+        end_slot - now < TwammPool::MAX_BUCKETS as u64 * TwammPool::ORDER_INTERVAL,
+        ChudexError::InvalidInstructionInput.into(),
+        "Order too long",
+    )?;
+
+    // sell what actually lands in the twamm vault after any transfer fee, spread to the end
+    let (mint_in_ai, twamm_vault_in_ai, token_program_in) = if a_to_b {
+        (mint_a_ai, twamm_vault_a_ai, token_program_a)
+    } else {
+        (mint_b_ai, twamm_vault_b_ai, token_program_b)
+    };
+    let amount_in_received = token::amount_after_transfer_fee(mint_in_ai, amount_in)?;
+    let sell_rate =
+        ((amount_in_received as u128) << TwammPool::RATE_SHIFT) / (end_slot - now) as u128;
+    assert_msg(
+        sell_rate > 0,
+        ChudexError::InvalidInstructionInput.into(),
+        "Order amount too small for its duration",
+    )?;
+    msg!("Got sell rate: {} end slot: {}", sell_rate, end_slot);
+
+    // join the order pool and the bucket ending it
+    // orders are shorter than the ring, so a bucket from another end slot has expired. if it
+    // still has orders to withdraw, it moves to its own account for them, paid for by the owner
+    if let Some(expired_bucket) = twamm.claim_bucket(end_slot) {
+        let end_slot_bytes = expired_bucket.end_slot.to_le_bytes();
+        let (expired_bucket_key, expired_bucket_bump) = Pubkey::find_program_address(
+            &[ExpiryBucket::SEED, twamm_ai.key.as_ref(), &end_slot_bytes],
+            program_id,
+        );
+        let expired_bucket_seeds = &[
+            ExpiryBucket::SEED,
+            twamm_ai.key.as_ref(),
+            &end_slot_bytes,
+            &[expired_bucket_bump],
+        ];
+        assert_msg(
+            *expired_bucket_ai.key == expired_bucket_key,
+            ChudexError::InvalidProgramAddress.into(),
+            "expired bucket pda aint right",
+        )?;
+        msg!(
+            "moving expired bucket {} out of the ring...",
+            expired_bucket.end_slot
+        );
+        invoke_signed(
+            &system_instruction::create_account(
+                owner.key,
+                expired_bucket_ai.key,
+                rent::Rent::get()?.minimum_balance(ExpiryBucket::LEN),
+                ExpiryBucket::LEN as u64,
+                program_id,
+            ),
+            &[
+                owner.clone(),
+                expired_bucket_ai.clone(),
+                system_program.clone(),
+            ],
+            &[expired_bucket_seeds],
+        )?;
+        expired_bucket.serialize(&mut &mut expired_bucket_ai.try_borrow_mut_data()?[..])?;
+    }
+    let bucket = twamm.bucket_mut(end_slot);
+    bucket.open_orders += 1;
+    if a_to_b {
+        bucket.expiring_rate_a += sell_rate;
+    } else {
+        bucket.expiring_rate_b += sell_rate;
+    }
+    let earnings_factor_last = if a_to_b {
+        twamm.sell_rate_a += sell_rate;
+        twamm.earnings_factor_a
+    } else {
+        twamm.sell_rate_b += sell_rate;
+        twamm.earnings_factor_b
+    };
+
+    // create order account
+    invoke_signed(
+        &system_instruction::create_account(
+            owner.key,
+            order_ai.key,
+            rent::Rent::get()?.minimum_balance(LongTermOrder::LEN),
+            LongTermOrder::LEN as u64,
+            program_id,
+        ),
+        &[owner.clone(), order_ai.clone(), system_program.clone()],
+        &[order_seeds],
+    )?;
+
+    // tokens to sell
+    let mint_in = token::unpack_mint(mint_in_ai)?;
+    token::transfer(
+        token_program_in,
+        owner_token_in_ai,
+        mint_in_ai,
+        twamm_vault_in_ai,
+        owner,
+        amount_in,
+        mint_in.decimals,
+        &[],
+    )?;
+
+    let order = LongTermOrder {
+        pool: *pool_ai.key,
+        owner: *owner.key,
+        nonce,
+        a_to_b,
+        sell_rate,
+        end_slot,
+        earnings_factor_last,
+    };
+    order.serialize(&mut &mut order_ai.try_borrow_mut_data()?[..])?;
+    twamm.serialize(&mut &mut twamm_ai.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
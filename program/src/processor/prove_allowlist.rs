@@ -0,0 +1,136 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction, system_program as system_program_ext,
+    sysvar::{rent, Sysvar},
+};
+
+use crate::{
+    allowlist,
+    error::ChudexError,
+    state::{AllowlistMode, AllowlistTicket, Pool},
+    utils::assert_msg,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    proof: Vec<[u8; 32]>,
+) -> ProgramResult {
+    // GET ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let ticket_ai = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+
+    // ACCOUNT VALIDATION
+
+    // user is signer
+    assert_msg(
+        user.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "User not signer",
+    )?;
+
+    // pool is allowlisted by a merkle root
+    let root = match &pool.allowlist {
+        Some(AllowlistMode::MerkleRoot(root)) => *root,
+        _ => {
+            msg!("Pool has no allowlist merkle root");
+            return Err(ChudexError::InvalidAccountData.into());
+        }
+    };
+
+    // pda verification
+
+    // pool pda
+    let (pool_key, _) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // ticket pda
+    let (ticket_key, ticket_bump) = Pubkey::find_program_address(
+        &[
+            AllowlistTicket::SEED,
+            pool_ai.key.as_ref(),
+            user.key.as_ref(),
+        ],
+        program_id,
+    );
+    let ticket_seeds = &[
+        AllowlistTicket::SEED,
+        pool_ai.key.as_ref(),
+        user.key.as_ref(),
+        &[ticket_bump],
+    ];
+    assert_msg(
+        *ticket_ai.key == ticket_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "ticket pda aint right",
+    )?;
+    assert_msg(
+        ticket_ai.data_len() == 0,
+        ChudexError::AccountAlreadyInitialized.into(),
+        "Ticket already initialized",
+    )?;
+
+    // external program verification
+
+    // system program
+    assert_msg(
+        *system_program.key == system_program_ext::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+
+    // LOGIC
+
+    assert_msg(
+        allowlist::verify_proof(&root, allowlist::leaf(user.key), &proof),
+        ChudexError::InvalidMerkleProof.into(),
+        "Proof doesn't match pool allowlist root",
+    )?;
+
+    // the ticket stands in for the proof on every later deposit, withdraw and exchange
+    invoke_signed(
+        &system_instruction::create_account(
+            user.key,
+            ticket_ai.key,
+            rent::Rent::get()?.minimum_balance(AllowlistTicket::LEN),
+            AllowlistTicket::LEN as u64,
+            program_id,
+        ),
+        &[user.clone(), ticket_ai.clone(), system_program.clone()],
+        &[ticket_seeds],
+    )?;
+    let ticket = AllowlistTicket {
+        pool: *pool_ai.key,
+        user: *user.key,
+    };
+    ticket.serialize(&mut &mut ticket_ai.try_borrow_mut_data()?[..])?;
+    msg!("Proved allowlist for {}", user.key);
+
+    Ok(())
+}
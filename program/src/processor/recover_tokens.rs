@@ -0,0 +1,147 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::ChudexError,
+    state::{GlobalConfig, Pool},
+    token,
+    utils::assert_msg,
+};
+
+use borsh::BorshDeserialize;
+
+use spl_token::error::TokenError;
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // GET ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let config_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let pool_token_ai = next_account_info(accounts_iter)?;
+    let recipient_token_ai = next_account_info(accounts_iter)?;
+    let mint_ai = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        config_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Config not owned by program",
+    )?;
+    let config = GlobalConfig::try_from_slice(&config_ai.try_borrow_data()?)?;
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    let pool_token = token::unpack_token_account(pool_token_ai)?;
+    let mint = token::unpack_mint(mint_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // admin is signer
+    assert_msg(
+        admin.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "Admin not signer",
+    )?;
+    assert_msg(
+        config.admin == *admin.key,
+        ChudexError::IncorrectAdmin.into(),
+        "Admin doesn't match config",
+    )?;
+
+    // token account is the pool's
+    assert_msg(
+        pool_token.owner == *pool_ai.key,
+        ChudexError::InvalidAccountOwner.into(),
+        "Token account not owned by pool",
+    )?;
+    assert_msg(
+        pool_token.mint == *mint_ai.key,
+        TokenError::MintMismatch.into(),
+        "Mint doesn't match token account",
+    )?;
+
+    // pda verification
+
+    // config pda
+    let (config_key, _) = Pubkey::find_program_address(&[GlobalConfig::SEED], program_id);
+    assert_msg(
+        *config_ai.key == config_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "config pda aint right",
+    )?;
+
+    // pool pda
+    let (pool_key, pool_bump) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    let pool_seeds = &[
+        b"chudex_pool",
+        pool.mint_a.as_ref(),
+        pool.mint_b.as_ref(),
+        &[pool_bump],
+    ];
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // the vaults hold the reserves, never recoverable, under either token program
+    for vault_mint in [&pool.mint_a, &pool.mint_b] {
+        for vault_token_program in [spl_token::id(), spl_token_2022::id()] {
+            let (vault_key, _) = Pubkey::find_program_address(
+                &[
+                    pool_ai.key.as_ref(),
+                    vault_token_program.as_ref(),
+                    vault_mint.as_ref(),
+                ],
+                &spl_associated_token_account::id(),
+            );
+            assert_msg(
+                *pool_token_ai.key != vault_key,
+                ChudexError::InvalidAccountAddress.into(),
+                "Can't recover from a pool vault",
+            )?;
+        }
+    }
+
+    // external program verification
+    assert_msg(
+        pool_token_ai.owner == token_program.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program wrong address",
+    )?;
+
+    // LOGIC
+
+    msg!(
+        "Recovering {} of mint {} from {}",
+        pool_token.amount,
+        mint_ai.key,
+        pool_token_ai.key
+    );
+    token::transfer(
+        token_program,
+        pool_token_ai,
+        mint_ai,
+        recipient_token_ai,
+        pool_ai,
+        pool_token.amount,
+        mint.decimals,
+        &[pool_seeds],
+    )?;
+
+    Ok(())
+}
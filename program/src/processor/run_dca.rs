@@ -0,0 +1,227 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{clock::Clock, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    client::{self, ExchangeResult},
+    curve,
+    error::ChudexError,
+    processor::exchange,
+    state::Dca,
+    token,
+    utils::{assert_msg, close_program_account},
+};
+
+use spl_token::error::TokenError;
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    client::with_result(|| run_cycle(program_id, accounts))
+}
+
+/// Runs the dca's due cycle. Returns the exchange's result.
+fn run_cycle(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<ExchangeResult, ProgramError> {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let keeper = next_account_info(accounts_iter)?;
+    let keeper_token_in_ai = next_account_info(accounts_iter)?;
+    let dca_ai = next_account_info(accounts_iter)?;
+    let escrow_ai = next_account_info(accounts_iter)?;
+    let owner_token_out_ai = next_account_info(accounts_iter)?;
+    let owner = next_account_info(accounts_iter)?;
+    // Exchange's accounts, the dca selling from its escrow to the owner
+    let exchange_accounts = [
+        std::slice::from_ref(dca_ai),
+        std::slice::from_ref(escrow_ai),
+        std::slice::from_ref(owner_token_out_ai),
+        accounts_iter.as_slice(),
+    ]
+    .concat();
+    let pool_ai = exchange_accounts
+        .get(3)
+        .ok_or(ChudexError::InvalidAccountData)?;
+    let token_program_in = exchange_accounts
+        .get(6)
+        .ok_or(ChudexError::InvalidAccountData)?;
+    let token_program_out = exchange_accounts
+        .get(7)
+        .ok_or(ChudexError::InvalidAccountData)?;
+    let mint_in_ai = exchange_accounts
+        .get(8)
+        .ok_or(ChudexError::InvalidAccountData)?;
+    let mint_out_ai = exchange_accounts
+        .get(9)
+        .ok_or(ChudexError::InvalidAccountData)?;
+
+    // deserialization
+    assert_msg(
+        dca_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Dca not owned by program",
+    )?;
+    let mut dca = Dca::try_from_slice(&dca_ai.try_borrow_data()?)?;
+    let keeper_token_in = token::unpack_token_account(keeper_token_in_ai)?;
+    let escrow = token::unpack_token_account(escrow_ai)?;
+    let mint_in = token::unpack_mint(mint_in_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // keeper is signer
+    assert_msg(
+        keeper.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "Keeper not signer",
+    )?;
+    assert_msg(
+        keeper_token_in.mint == dca.mint_in,
+        TokenError::MintMismatch.into(),
+        "keeper token in mint wrong",
+    )?;
+
+    // dca is for this pool and direction, paying out to its owner
+    assert_msg(
+        dca.pool == *pool_ai.key
+            && dca.mint_in == *mint_in_ai.key
+            && dca.mint_out == *mint_out_ai.key,
+        ChudexError::InvalidAccountData.into(),
+        "Dca doesn't match pool or direction",
+    )?;
+    assert_msg(
+        dca.owner == *owner.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Dca owner wrong",
+    )?;
+
+    // pda verification
+
+    // dca pda
+    let nonce_bytes = dca.nonce.to_le_bytes();
+    let (dca_key, dca_bump) = Pubkey::find_program_address(
+        &[
+            Dca::SEED,
+            dca.pool.as_ref(),
+            dca.owner.as_ref(),
+            &nonce_bytes,
+        ],
+        program_id,
+    );
+    let dca_seeds = &[
+        Dca::SEED,
+        dca.pool.as_ref(),
+        dca.owner.as_ref(),
+        &nonce_bytes,
+        &[dca_bump],
+    ];
+    assert_msg(
+        *dca_ai.key == dca_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "dca pda aint right",
+    )?;
+
+    // escrow pda
+    let (escrow_key, _) = Pubkey::find_program_address(
+        &[
+            dca_ai.key.as_ref(),
+            token_program_in.key.as_ref(),
+            mint_in_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        escrow_key == *escrow_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "escrow pda aint right",
+    )?;
+
+    // owner token out pda, the owner's associated token acc
+    let (owner_token_out_key, _) = Pubkey::find_program_address(
+        &[
+            owner.key.as_ref(),
+            token_program_out.key.as_ref(),
+            mint_out_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        owner_token_out_key == *owner_token_out_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "owner token out pda aint right",
+    )?;
+
+    // LOGIC
+
+    let now = Clock::get()?.unix_timestamp;
+    assert_msg(
+        dca.cycles_left > 0 && now >= dca.next_cycle_timestamp,
+        ChudexError::InvalidInstructionInput.into(),
+        "No dca cycle due",
+    )?;
+
+    // a cycle's worth, or everything left on the last one
+    let cycle_amount = if dca.cycles_left == 1 {
+        escrow.amount
+    } else {
+        dca.amount_per_cycle.min(escrow.amount)
+    };
+
+    // keeper bounty out of the cycle, the rest sold through the exchange
+    let bounty = curve::fee_amount(
+        cycle_amount,
+        Dca::KEEPER_BOUNTY,
+        Dca::KEEPER_BOUNTY_DECIMALS,
+    )?;
+    assert_msg(
+        cycle_amount > bounty,
+        ChudexError::InvalidAccountData.into(),
+        "Dca escrow empty",
+    )?;
+    if bounty > 0 {
+        token::transfer(
+            token_program_in,
+            escrow_ai,
+            mint_in_ai,
+            keeper_token_in_ai,
+            dca_ai,
+            bounty,
+            mint_in.decimals,
+            &[dca_seeds],
+        )?;
+    }
+    msg!(
+        "Running dca {} cycle, {} left, keeper bounty: {}",
+        dca_ai.key,
+        dca.cycles_left,
+        bounty
+    );
+    let result = exchange::exchange(
+        program_id,
+        &exchange_accounts,
+        cycle_amount - bounty,
+        dca.min_amount_out_per_cycle,
+        false,
+        dca_seeds,
+    )?;
+
+    // next cycle a full interval from this one, so missed cycles don't pile up into a burst
+    dca.cycles_left -= 1;
+    dca.next_cycle_timestamp = now.saturating_add(dca.interval_seconds as i64);
+    if dca.cycles_left == 0 {
+        // dca is done, rent back to the owner
+        token::close_account(token_program_in, escrow_ai, owner, dca_ai, &[dca_seeds])?;
+        close_program_account(dca_ai, owner)?;
+    } else {
+        dca.serialize(&mut &mut dca_ai.try_borrow_mut_data()?[..])?;
+    }
+
+    Ok(result)
+}
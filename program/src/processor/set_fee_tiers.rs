@@ -0,0 +1,64 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{error::ChudexError, state::GlobalConfig, utils::assert_msg};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fee_tiers: Vec<u16>,
+) -> ProgramResult {
+    // GET ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let config_ai = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        config_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Config not owned by program",
+    )?;
+    let mut config = GlobalConfig::try_from_slice(&config_ai.try_borrow_data()?)?;
+
+    // ACCOUNT VALIDATION
+
+    // admin is signer
+    assert_msg(
+        admin.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "Admin not signer",
+    )?;
+    assert_msg(
+        config.admin == *admin.key,
+        ChudexError::IncorrectAdmin.into(),
+        "Admin doesn't match config",
+    )?;
+
+    // pda verification
+
+    // config pda
+    let (config_key, _) = Pubkey::find_program_address(&[GlobalConfig::SEED], program_id);
+    assert_msg(
+        *config_ai.key == config_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "config pda aint right",
+    )?;
+
+    // LOGIC
+
+    // existing pools keep their fee, only new pools see the new tiers
+    config.set_fee_tiers(&fee_tiers)?;
+    config.serialize(&mut &mut config_ai.try_borrow_mut_data()?[..])?;
+    msg!("Set fee tiers {:?}", config.fee_tiers());
+
+    Ok(())
+}
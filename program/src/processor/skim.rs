@@ -0,0 +1,150 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+    sysvar::{clock::Clock, Sysvar},
+};
+
+use crate::{error::ChudexError, state::Pool, token, twamm, utils::assert_msg};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use spl_token::error::TokenError;
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let pool_ai = next_account_info(accounts_iter)?;
+    let pool_vault_a_ai = next_account_info(accounts_iter)?;
+    let pool_vault_b_ai = next_account_info(accounts_iter)?;
+    let recipient_token_a_ai = next_account_info(accounts_iter)?;
+    let recipient_token_b_ai = next_account_info(accounts_iter)?;
+    let mint_a_ai = next_account_info(accounts_iter)?;
+    let mint_b_ai = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let mut pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    let twamm_accounts = twamm::next_twamm_accounts(&pool, accounts_iter)?;
+    let mint_a = token::unpack_mint(mint_a_ai)?;
+    let mint_b = token::unpack_mint(mint_b_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // mints are the pool's, in pool mint order
+    assert_msg(
+        *mint_a_ai.key == pool.mint_a && *mint_b_ai.key == pool.mint_b,
+        TokenError::MintMismatch.into(),
+        "Mints don't match pool",
+    )?;
+
+    // pda verification
+
+    // pool pda, seeds copied out since the pool's reserves get updated while they're in use
+    let (pool_mint_a, pool_mint_b) = (pool.mint_a, pool.mint_b);
+    let (pool_key, pool_bump) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    let pool_seeds = &[
+        b"chudex_pool",
+        pool_mint_a.as_ref(),
+        pool_mint_b.as_ref(),
+        &[pool_bump],
+    ];
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // vault pdas
+    for (pool_vault_ai, mint_ai, token_program) in [
+        (pool_vault_a_ai, mint_a_ai, token_program_a),
+        (pool_vault_b_ai, mint_b_ai, token_program_b),
+    ] {
+        let (vault_key, _) = Pubkey::find_program_address(
+            &[
+                pool_ai.key.as_ref(),
+                token_program.key.as_ref(),
+                mint_ai.key.as_ref(),
+            ],
+            &spl_associated_token_account::id(),
+        );
+        assert_msg(
+            vault_key == *pool_vault_ai.key,
+            ChudexError::InvalidProgramAddress.into(),
+            "vault pda aint right",
+        )?;
+    }
+
+    // external program verification
+    // mint token programs, either spl token or token-2022
+    assert_msg(
+        mint_a_ai.owner == token_program_a.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program a wrong address",
+    )?;
+    assert_msg(
+        mint_b_ai.owner == token_program_b.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program b wrong address",
+    )?;
+
+    // LOGIC
+
+    // long-term orders trade first, so the reserves are current
+    twamm::settle(
+        program_id,
+        pool_ai,
+        &mut pool,
+        pool_seeds,
+        twamm_accounts,
+        (pool_vault_a_ai, mint_a_ai, token_program_a),
+        (pool_vault_b_ai, mint_b_ai, token_program_b),
+        Clock::get()?.slot,
+    )?;
+    pool.serialize(&mut &mut pool_ai.try_borrow_mut_data()?[..])?;
+
+    // anything in the vaults past the tracked reserves goes to the recipient
+    let pool_vault_a = token::unpack_token_account(pool_vault_a_ai)?;
+    let pool_vault_b = token::unpack_token_account(pool_vault_b_ai)?;
+    let excess_a = pool_vault_a.amount.saturating_sub(pool.reserve_a);
+    let excess_b = pool_vault_b.amount.saturating_sub(pool.reserve_b);
+    msg!("Skimming a: {} b: {}", excess_a, excess_b);
+
+    if excess_a > 0 {
+        token::transfer(
+            token_program_a,
+            pool_vault_a_ai,
+            mint_a_ai,
+            recipient_token_a_ai,
+            pool_ai,
+            excess_a,
+            mint_a.decimals,
+            &[pool_seeds],
+        )?;
+    }
+    if excess_b > 0 {
+        token::transfer(
+            token_program_b,
+            pool_vault_b_ai,
+            mint_b_ai,
+            recipient_token_b_ai,
+            pool_ai,
+            excess_b,
+            mint_b.decimals,
+            &[pool_seeds],
+        )?;
+    }
+
+    Ok(())
+}
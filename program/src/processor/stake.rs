@@ -0,0 +1,188 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    pubkey::Pubkey,
+    system_instruction, system_program as system_program_ext,
+    sysvar::{clock::Clock, rent, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::ChudexError,
+    state::{Farm, Stake},
+    token,
+    utils::assert_msg,
+};
+
+use spl_token::error::TokenError;
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let owner_pool_token_ai = next_account_info(accounts_iter)?;
+    let farm_ai = next_account_info(accounts_iter)?;
+    let stake_ai = next_account_info(accounts_iter)?;
+    let farm_vault_ai = next_account_info(accounts_iter)?;
+    let pool_mint_ai = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        farm_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Farm not owned by program",
+    )?;
+    let mut farm = Farm::try_from_slice(&farm_ai.try_borrow_data()?)?;
+
+    // ACCOUNT VALIDATION
+
+    // owner is signer
+    assert_msg(
+        owner.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "Owner not signer",
+    )?;
+    assert_msg(
+        amount > 0,
+        ChudexError::InvalidInstructionInput.into(),
+        "Stake amount must be positive",
+    )?;
+
+    // staked mint is the farm's
+    assert_msg(
+        *pool_mint_ai.key == farm.mint,
+        TokenError::MintMismatch.into(),
+        "Pool mint doesn't match farm",
+    )?;
+
+    // pda verification
+
+    // farm pda
+    let (farm_key, _) = Pubkey::find_program_address(
+        &[
+            Farm::SEED,
+            farm.pool.as_ref(),
+            farm.authority.as_ref(),
+            &farm.nonce.to_le_bytes(),
+        ],
+        program_id,
+    );
+    assert_msg(
+        *farm_ai.key == farm_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "farm pda aint right",
+    )?;
+
+    // farm vault pda
+    let (farm_vault_key, _) = Pubkey::find_program_address(
+        &[
+            farm_ai.key.as_ref(),
+            token_program.key.as_ref(),
+            pool_mint_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        farm_vault_key == *farm_vault_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "farm vault pda aint right",
+    )?;
+
+    // stake pda
+    let (stake_key, stake_bump) = Pubkey::find_program_address(
+        &[Stake::SEED, farm_ai.key.as_ref(), owner.key.as_ref()],
+        program_id,
+    );
+    let stake_seeds = &[
+        Stake::SEED,
+        farm_ai.key.as_ref(),
+        owner.key.as_ref(),
+        &[stake_bump],
+    ];
+    assert_msg(
+        *stake_ai.key == stake_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "stake pda aint right",
+    )?;
+
+    // external program verification
+
+    // token program, pool mints are spl token
+    assert_msg(
+        *token_program.key == spl_token::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program wrong address",
+    )?;
+
+    // system program
+    assert_msg(
+        *system_program.key == system_program_ext::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+
+    // LOGIC
+
+    // create stake account on first stake
+    let num_rewards = farm.rewards.len();
+    let mut stake = if stake_ai.data_len() == 0 {
+        let stake_len = Stake::len(num_rewards);
+        invoke_signed(
+            &system_instruction::create_account(
+                owner.key,
+                stake_ai.key,
+                rent::Rent::get()?.minimum_balance(stake_len),
+                stake_len as u64,
+                program_id,
+            ),
+            &[owner.clone(), stake_ai.clone(), system_program.clone()],
+            &[stake_seeds],
+        )?;
+        Stake {
+            farm: *farm_ai.key,
+            owner: *owner.key,
+            amount: 0,
+            reward_per_share_last: vec![0; num_rewards],
+            rewards_owed: vec![0; num_rewards],
+        }
+    } else {
+        Stake::try_from_slice(&stake_ai.try_borrow_data()?)?
+    };
+
+    // settle rewards at the old stake before changing it
+    farm.update(Clock::get()?.slot)?;
+    stake.update_rewards(&farm)?;
+
+    let mint = token::unpack_mint(pool_mint_ai)?;
+    token::transfer(
+        token_program,
+        owner_pool_token_ai,
+        pool_mint_ai,
+        farm_vault_ai,
+        owner,
+        amount,
+        mint.decimals,
+        &[],
+    )?;
+
+    stake.amount = stake
+        .amount
+        .checked_add(amount)
+        .ok_or(ChudexError::CalculationFailure)?;
+    farm.total_staked = farm
+        .total_staked
+        .checked_add(amount)
+        .ok_or(ChudexError::CalculationFailure)?;
+    msg!("Staked {} total staked: {}", amount, farm.total_staked);
+
+    stake.serialize(&mut &mut stake_ai.try_borrow_mut_data()?[..])?;
+    farm.serialize(&mut &mut farm_ai.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{clock::Clock, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{error::ChudexError, state::Locker, token, utils::assert_msg};
+
+use spl_token::{error::TokenError, instruction};
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let owner_pool_token_ai = next_account_info(accounts_iter)?;
+    let locker_ai = next_account_info(accounts_iter)?;
+    let locker_pool_token_ai = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        locker_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Locker not owned by program",
+    )?;
+    let mut locker = Locker::try_from_slice(&locker_ai.try_borrow_data()?)?;
+    let owner_pool_token = token::unpack_token_account(owner_pool_token_ai)?;
+    let locker_pool_token = token::unpack_token_account(locker_pool_token_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // owner is signer
+    assert_msg(
+        owner.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "Owner not signer",
+    )?;
+    assert_msg(
+        locker.owner == *owner.key,
+        ChudexError::InvalidAccountOwner.into(),
+        "Owner doesn't match locker",
+    )?;
+
+    // released to a pool token acc of the same mint
+    assert_msg(
+        owner_pool_token.mint == locker_pool_token.mint,
+        TokenError::MintMismatch.into(),
+        "Owner pool token mint doesn't match locker",
+    )?;
+
+    // pda verification
+
+    // locker pda
+    let index_bytes = locker.index.to_le_bytes();
+    let (locker_key, locker_bump) = Pubkey::find_program_address(
+        &[Locker::SEED, locker.pool.as_ref(), &index_bytes],
+        program_id,
+    );
+    let locker_seeds = &[
+        Locker::SEED,
+        locker.pool.as_ref(),
+        &index_bytes,
+        &[locker_bump],
+    ];
+    assert_msg(
+        *locker_ai.key == locker_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "locker pda aint right",
+    )?;
+
+    // locker pool token pda
+    let (locker_pool_token_key, _) = Pubkey::find_program_address(
+        &[
+            locker_ai.key.as_ref(),
+            token_program.key.as_ref(),
+            locker_pool_token.mint.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        *locker_pool_token_ai.key == locker_pool_token_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "locker pool token pda aint right",
+    )?;
+
+    // external program verification
+    assert_msg(
+        *token_program.key == spl_token::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program wrong address",
+    )?;
+
+    // LOGIC
+
+    // only what vested since the last unlock
+    let releasable = locker
+        .vested(Clock::get()?.unix_timestamp)
+        .saturating_sub(locker.released);
+    assert_msg(
+        releasable > 0,
+        ChudexError::InvalidInstructionInput.into(),
+        "Nothing vested to unlock",
+    )?;
+
+    invoke_signed(
+        &instruction::transfer(
+            &spl_token::id(),
+            locker_pool_token_ai.key,
+            owner_pool_token_ai.key,
+            locker_ai.key,
+            &[],
+            releasable,
+        )?,
+        &[
+            locker_pool_token_ai.clone(),
+            owner_pool_token_ai.clone(),
+            locker_ai.clone(),
+        ],
+        &[locker_seeds],
+    )?;
+
+    locker.released += releasable;
+    locker.serialize(&mut &mut locker_ai.try_borrow_mut_data()?[..])?;
+    msg!(
+        "Unlocked {} pool tokens, {} of {} released",
+        releasable,
+        locker.released,
+        locker.amount
+    );
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+    sysvar::{clock::Clock, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::ChudexError,
+    state::{Farm, Stake},
+    token,
+    utils::assert_msg,
+};
+
+use spl_token::error::TokenError;
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let owner_pool_token_ai = next_account_info(accounts_iter)?;
+    let farm_ai = next_account_info(accounts_iter)?;
+    let stake_ai = next_account_info(accounts_iter)?;
+    let farm_vault_ai = next_account_info(accounts_iter)?;
+    let pool_mint_ai = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        farm_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Farm not owned by program",
+    )?;
+    assert_msg(
+        stake_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Stake not owned by program",
+    )?;
+    let mut farm = Farm::try_from_slice(&farm_ai.try_borrow_data()?)?;
+    let mut stake = Stake::try_from_slice(&stake_ai.try_borrow_data()?)?;
+
+    // ACCOUNT VALIDATION
+
+    // owner is signer
+    assert_msg(
+        owner.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "Owner not signer",
+    )?;
+    assert_msg(
+        amount > 0 && amount <= stake.amount,
+        ChudexError::InvalidInstructionInput.into(),
+        "Unstake amount must be positive and at most the stake",
+    )?;
+
+    // staked mint is the farm's
+    assert_msg(
+        *pool_mint_ai.key == farm.mint,
+        TokenError::MintMismatch.into(),
+        "Pool mint doesn't match farm",
+    )?;
+
+    // pda verification
+
+    // farm pda, seeds copied out since the farm gets updated while they're in use
+    let (farm_pool, farm_authority) = (farm.pool, farm.authority);
+    let nonce_bytes = farm.nonce.to_le_bytes();
+    let (farm_key, farm_bump) = Pubkey::find_program_address(
+        &[
+            Farm::SEED,
+            farm_pool.as_ref(),
+            farm_authority.as_ref(),
+            &nonce_bytes,
+        ],
+        program_id,
+    );
+    let farm_seeds = &[
+        Farm::SEED,
+        farm_pool.as_ref(),
+        farm_authority.as_ref(),
+        &nonce_bytes,
+        &[farm_bump],
+    ];
+    assert_msg(
+        *farm_ai.key == farm_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "farm pda aint right",
+    )?;
+
+    // farm vault pda
+    let (farm_vault_key, _) = Pubkey::find_program_address(
+        &[
+            farm_ai.key.as_ref(),
+            token_program.key.as_ref(),
+            pool_mint_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        farm_vault_key == *farm_vault_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "farm vault pda aint right",
+    )?;
+
+    // stake pda
+    let (stake_key, _) = Pubkey::find_program_address(
+        &[Stake::SEED, farm_ai.key.as_ref(), owner.key.as_ref()],
+        program_id,
+    );
+    assert_msg(
+        *stake_ai.key == stake_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "stake pda aint right",
+    )?;
+
+    // external program verification
+
+    // token program, pool mints are spl token
+    assert_msg(
+        *token_program.key == spl_token::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program wrong address",
+    )?;
+
+    // LOGIC
+
+    // settle rewards at the old stake before changing it, they stay owed until harvested
+    farm.update(Clock::get()?.slot)?;
+    stake.update_rewards(&farm)?;
+
+    let mint = token::unpack_mint(pool_mint_ai)?;
+    token::transfer(
+        token_program,
+        farm_vault_ai,
+        pool_mint_ai,
+        owner_pool_token_ai,
+        farm_ai,
+        amount,
+        mint.decimals,
+        &[farm_seeds],
+    )?;
+
+    stake.amount -= amount;
+    farm.total_staked -= amount;
+    msg!("Unstaked {} total staked: {}", amount, farm.total_staked);
+
+    stake.serialize(&mut &mut stake_ai.try_borrow_mut_data()?[..])?;
+    farm.serialize(&mut &mut farm_ai.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{error::ChudexError, state::Allowlist, utils::assert_msg};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    add: Vec<Pubkey>,
+    remove: Vec<Pubkey>,
+) -> ProgramResult {
+    // GET ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let authority = next_account_info(accounts_iter)?;
+    let allowlist_ai = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        allowlist_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Allowlist not owned by program",
+    )?;
+    let mut allowlist = Allowlist::try_from_slice(&allowlist_ai.try_borrow_data()?)?;
+
+    // ACCOUNT VALIDATION
+
+    // authority is signer
+    assert_msg(
+        authority.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "Authority not signer",
+    )?;
+    assert_msg(
+        allowlist.authority == *authority.key,
+        ChudexError::IncorrectAdmin.into(),
+        "Authority doesn't match allowlist",
+    )?;
+
+    // LOGIC
+
+    // removals swap in the last member
+    for member in remove {
+        if let Some(i) = allowlist.members().iter().position(|m| *m == member) {
+            let last = allowlist.len as usize - 1;
+            allowlist.members[i] = allowlist.members[last];
+            allowlist.members[last] = Pubkey::default();
+            allowlist.len -= 1;
+        }
+    }
+    for member in add {
+        if allowlist.members().contains(&member) {
+            continue;
+        }
+        assert_msg(
+            (allowlist.len as usize) < allowlist.members.len(),
+            ChudexError::InvalidInstructionInput.into(),
+            "Allowlist full",
+        )?;
+        allowlist.members[allowlist.len as usize] = member;
+        allowlist.len += 1;
+    }
+
+    allowlist.serialize(&mut &mut allowlist_ai.try_borrow_mut_data()?[..])?;
+    msg!("Allowlist has {} members", allowlist.len);
+
+    Ok(())
+}
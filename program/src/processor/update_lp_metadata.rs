@@ -0,0 +1,141 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction, system_program,
+    sysvar::{rent, Sysvar},
+};
+
+use crate::{
+    error::ChudexError,
+    state::{GlobalConfig, LpMetadata, Pool},
+    utils::assert_msg,
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    name: String,
+    symbol: String,
+    uri: String,
+) -> ProgramResult {
+    // GET ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let admin = next_account_info(accounts_iter)?;
+    let config_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let lp_metadata_ai = next_account_info(accounts_iter)?;
+    let system_program_ai = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        config_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Config not owned by program",
+    )?;
+    let config = GlobalConfig::try_from_slice(&config_ai.try_borrow_data()?)?;
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+
+    // ACCOUNT VALIDATION
+
+    // admin is signer
+    assert_msg(
+        admin.is_signer,
+        ProgramError::MissingRequiredSignature,
+        "Admin not signer",
+    )?;
+    assert_msg(
+        config.admin == *admin.key,
+        ChudexError::IncorrectAdmin.into(),
+        "Admin doesn't match config",
+    )?;
+
+    // pda verification
+
+    // config pda
+    let (config_key, _) = Pubkey::find_program_address(&[GlobalConfig::SEED], program_id);
+    assert_msg(
+        *config_ai.key == config_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "config pda aint right",
+    )?;
+
+    // pool pda
+    let (pool_key, _) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // lp metadata pda
+    let (lp_metadata_key, lp_metadata_bump) =
+        Pubkey::find_program_address(&[LpMetadata::SEED, pool.mint.as_ref()], program_id);
+    let lp_metadata_seeds = &[LpMetadata::SEED, pool.mint.as_ref(), &[lp_metadata_bump]];
+    assert_msg(
+        *lp_metadata_ai.key == lp_metadata_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "lp metadata pda aint right",
+    )?;
+
+    // external program verification
+    assert_msg(
+        *system_program_ai.key == system_program::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+
+    // LOGIC
+
+    // pools created before lp metadata existed get theirs here, paid by the admin
+    let mut lp_metadata = if lp_metadata_ai.data_len() == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                admin.key,
+                lp_metadata_ai.key,
+                rent::Rent::get()?.minimum_balance(LpMetadata::LEN),
+                LpMetadata::LEN as u64,
+                program_id,
+            ),
+            &[
+                admin.clone(),
+                lp_metadata_ai.clone(),
+                system_program_ai.clone(),
+            ],
+            &[lp_metadata_seeds],
+        )?;
+        LpMetadata::new(pool.mint, &pool.mint_a, &pool.mint_b)?
+    } else {
+        assert_msg(
+            lp_metadata_ai.owner == program_id,
+            ChudexError::InvalidAccountAddress.into(),
+            "Lp metadata not owned by program",
+        )?;
+        LpMetadata::try_from_slice(&lp_metadata_ai.try_borrow_data()?)?
+    };
+
+    lp_metadata.set(&name, &symbol, &uri)?;
+    lp_metadata.serialize(&mut &mut lp_metadata_ai.try_borrow_mut_data()?[..])?;
+    msg!(
+        "Set lp metadata {} {} {}",
+        lp_metadata.name(),
+        lp_metadata.symbol(),
+        lp_metadata.uri()
+    );
+
+    Ok(())
+}
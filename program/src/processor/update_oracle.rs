@@ -0,0 +1,121 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    pubkey::Pubkey,
+    system_instruction,
+    system_program::id as system_program_id,
+    sysvar::{clock::Clock, rent, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::ChudexError,
+    state::{Pool, PriceCumulative, PriceOracle},
+    utils::assert_msg,
+};
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let payer = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let oracle_ai = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+
+    // deserialization
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+
+    // ACCOUNT VALIDATION
+
+    // payer is signer
+    assert_msg(
+        payer.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "Payer not signer",
+    )?;
+
+    // pda verification
+
+    // pool pda
+    let (pool_key, _) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // oracle pda
+    let (oracle_key, oracle_bump) =
+        Pubkey::find_program_address(&[PriceOracle::SEED, pool_ai.key.as_ref()], program_id);
+    let oracle_seeds = &[PriceOracle::SEED, pool_ai.key.as_ref(), &[oracle_bump]];
+    assert_msg(
+        *oracle_ai.key == oracle_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "oracle pda aint right",
+    )?;
+
+    // external program verification
+    assert_msg(
+        *system_program.key == system_program_id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+
+    // LOGIC
+
+    // the pool's sums as of now, at the reserves since its last change
+    let observation =
+        pool.price_cumulative
+            .at(pool.reserve_a, pool.reserve_b, Clock::get()?.unix_timestamp);
+
+    // the first update creates the oracle with this as its only snapshot
+    let oracle = if oracle_ai.data_len() == 0 {
+        msg!("creating oracle...");
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                oracle_ai.key,
+                rent::Rent::get()?.minimum_balance(PriceOracle::LEN),
+                PriceOracle::LEN as u64,
+                program_id,
+            ),
+            &[payer.clone(), oracle_ai.clone(), system_program.clone()],
+            &[oracle_seeds],
+        )?;
+        let mut observations = vec![PriceCumulative::default(); PriceOracle::MAX_OBSERVATIONS];
+        observations[0] = observation;
+        PriceOracle {
+            pool: *pool_ai.key,
+            latest: 0,
+            observations,
+        }
+    } else {
+        assert_msg(
+            oracle_ai.owner == program_id,
+            ChudexError::InvalidAccountAddress.into(),
+            "Oracle not owned by program",
+        )?;
+        let mut oracle = PriceOracle::try_from_slice(&oracle_ai.try_borrow_data()?)?;
+        if !oracle.record(observation) {
+            msg!("Latest observation still recent");
+            return Ok(());
+        }
+        oracle
+    };
+    oracle.serialize(&mut &mut oracle_ai.try_borrow_mut_data()?[..])?;
+    msg!("Observed prices at {}", observation.last_timestamp);
+
+    Ok(())
+}
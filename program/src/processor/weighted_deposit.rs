@@ -0,0 +1,241 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program::id as system_program_id,
+    sysvar::rent,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{curve, error::ChudexError, state::WeightedPool, token, utils::assert_msg};
+
+use spl_token::{error::TokenError, instruction, state::Mint};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    pool_token_amount: u64,
+    max_token_amounts: Vec<u64>,
+) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let user_pool_token = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let pool_mint_ai = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let system_program = next_account_info(accounts_iter)?;
+    let sysvar_rent = next_account_info(accounts_iter)?;
+    let associated_token_program = next_account_info(accounts_iter)?;
+
+    // deserialization
+    let pool = WeightedPool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    let pool_mint = Mint::unpack_from_slice(&pool_mint_ai.try_borrow_data()?)?;
+
+    assert_msg(
+        max_token_amounts.len() == pool.mints.len(),
+        ChudexError::InvalidInstructionInput.into(),
+        "Need a max amount per pool token",
+    )?;
+
+    // (user token acc, vault, mint, token program) per token
+    let mut tokens = Vec::with_capacity(pool.mints.len());
+    for _ in 0..pool.mints.len() {
+        tokens.push((
+            next_account_info(accounts_iter)?,
+            next_account_info(accounts_iter)?,
+            next_account_info(accounts_iter)?,
+            next_account_info(accounts_iter)?,
+        ));
+    }
+
+    // ACCOUNT VALIDATION
+
+    // user is signer
+    assert_msg(
+        user.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "User not signer",
+    )?;
+
+    // pool pda
+    let mut pool_seeds = WeightedPool::seeds(&pool.mints);
+    let (pool_key, pool_bump) = Pubkey::find_program_address(&pool_seeds, program_id);
+    let pool_bump = [pool_bump];
+    pool_seeds.push(&pool_bump);
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // pool mint
+    assert_msg(
+        *pool_mint_ai.key == pool.mint,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool mint address invalid",
+    )?;
+
+    // user pool token pda
+    let (user_pool_token_key, _) = Pubkey::find_program_address(
+        &[
+            user.key.as_ref(),
+            token_program.key.as_ref(),
+            pool_mint_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        user_pool_token_key == *user_pool_token.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "user pool token account pda aint right",
+    )?;
+
+    // per token accounts
+    let mut balances = Vec::with_capacity(tokens.len());
+    for ((user_token_ai, vault_ai, mint_ai, token_program_ai), pool_token_mint) in
+        tokens.iter().zip(pool.mints.iter())
+    {
+        assert_msg(
+            mint_ai.key == pool_token_mint,
+            ChudexError::InvalidAccountAddress.into(),
+            "Mint doesn't match pool",
+        )?;
+        assert_msg(
+            mint_ai.owner == token_program_ai.key,
+            ChudexError::InvalidAccountAddress.into(),
+            "Mint token program wrong address",
+        )?;
+
+        let user_token = token::unpack_token_account(user_token_ai)?;
+        assert_msg(
+            user_token.owner == *user.key,
+            TokenError::OwnerMismatch.into(),
+            "user token not owned by user",
+        )?;
+
+        let (vault_key, _) = Pubkey::find_program_address(
+            &[
+                pool_ai.key.as_ref(),
+                token_program_ai.key.as_ref(),
+                mint_ai.key.as_ref(),
+            ],
+            &spl_associated_token_account::id(),
+        );
+        assert_msg(
+            vault_key == *vault_ai.key,
+            ChudexError::InvalidProgramAddress.into(),
+            "vault pda aint right",
+        )?;
+        balances.push(token::unpack_token_account(vault_ai)?.amount);
+    }
+
+    // external program verification
+    // token program
+    assert_msg(
+        *token_program.key == spl_token::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program wrong address",
+    )?;
+
+    // system program
+    assert_msg(
+        *system_program.key == system_program_id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "System program wrong address",
+    )?;
+
+    // sysvar program
+    assert_msg(
+        *sysvar_rent.key == rent::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Sysvar program wrong address",
+    )?;
+
+    // associated token program
+    assert_msg(
+        *associated_token_program.key == spl_associated_token_account::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Associated token program wrong address",
+    )?;
+
+    // LOGIC
+
+    // deposit each token in proportion to the pool, rounded up in the pool's favor
+    // the first deposit seeds the pool with the max amounts
+    for (i, (user_token_ai, vault_ai, mint_ai, token_program_ai)) in tokens.iter().enumerate() {
+        let amount = if pool_mint.supply == 0 {
+            max_token_amounts[i]
+        } else {
+            let amount_needed = curve::pool_tokens_to_reserve_ceil(
+                pool_token_amount,
+                pool_mint.supply,
+                balances[i],
+            )?;
+            token::amount_before_transfer_fee(mint_ai, amount_needed)?
+        };
+        if amount > max_token_amounts[i] {
+            return Err(ChudexError::DepositAmountExceedsLimit.into());
+        }
+        msg!("Got token amount {}: {}", i, amount);
+
+        let mint = token::unpack_mint(mint_ai)?;
+        token::transfer(
+            token_program_ai,
+            user_token_ai,
+            mint_ai,
+            vault_ai,
+            user,
+            amount,
+            mint.decimals,
+            &[],
+        )?;
+    }
+
+    // initialize pool token user account if needed
+    if user_pool_token.data_len() == 0 {
+        invoke(
+            &token::create_associated_token_account(
+                user.key,
+                user.key,
+                pool_mint_ai.key,
+                &spl_token::id(),
+            ),
+            &[
+                user.clone(),
+                user_pool_token.clone(),
+                user.clone(),
+                pool_mint_ai.clone(),
+                system_program.clone(),
+                token_program.clone(),
+                sysvar_rent.clone(),
+                associated_token_program.clone(),
+            ],
+        )?;
+    }
+
+    // mint to user
+    invoke_signed(
+        &instruction::mint_to(
+            &spl_token::id(),
+            pool_mint_ai.key,
+            user_pool_token.key,
+            pool_ai.key,
+            &[pool_ai.key],
+            pool_token_amount,
+        )?,
+        &[
+            pool_mint_ai.clone(),
+            user_pool_token.clone(),
+            pool_ai.clone(),
+        ],
+        &[pool_seeds.as_slice()],
+    )?;
+
+    Ok(())
+}
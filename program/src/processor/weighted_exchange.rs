@@ -0,0 +1,190 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{curve, error::ChudexError, state::WeightedPool, token, utils::assert_msg};
+
+use borsh::BorshDeserialize;
+
+use spl_token::error::TokenError;
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount_in: u64,
+    min_amount_out: u64,
+) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let user_token_src_ai = next_account_info(accounts_iter)?;
+    let user_token_dst_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let pool_vault_src_ai = next_account_info(accounts_iter)?;
+    let pool_vault_dst_ai = next_account_info(accounts_iter)?;
+    let token_program_src = next_account_info(accounts_iter)?;
+    let token_program_dst = next_account_info(accounts_iter)?;
+    let mint_src_ai = next_account_info(accounts_iter)?;
+    let mint_dst_ai = next_account_info(accounts_iter)?;
+
+    // deserialization
+    let pool = WeightedPool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    let pool_vault_src = token::unpack_token_account(pool_vault_src_ai)?;
+    let pool_vault_dst = token::unpack_token_account(pool_vault_dst_ai)?;
+    let mint_src = token::unpack_mint(mint_src_ai)?;
+    let mint_dst = token::unpack_mint(mint_dst_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // user is signer
+    assert_msg(
+        user.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "User not signer",
+    )?;
+
+    // pool vault accounts
+    assert_msg(
+        pool_vault_src.owner == *pool_ai.key,
+        TokenError::OwnerMismatch.into(),
+        "pool vault src not owned by pool",
+    )?;
+    assert_msg(
+        pool_vault_dst.owner == *pool_ai.key,
+        TokenError::OwnerMismatch.into(),
+        "pool vault dst not owned by pool",
+    )?;
+
+    // vaults hold two different pool tokens
+    let (index_src, index_dst) = match (
+        pool.token_index(&pool_vault_src.mint),
+        pool.token_index(&pool_vault_dst.mint),
+    ) {
+        (Some(index_src), Some(index_dst)) if index_src != index_dst => (index_src, index_dst),
+        _ => {
+            msg!("pool vault mints don't match pool");
+            return Err(ChudexError::InvalidAccountData.into());
+        }
+    };
+
+    // mints match vaults
+    assert_msg(
+        *mint_src_ai.key == pool_vault_src.mint,
+        ChudexError::InvalidAccountAddress.into(),
+        "mint src doesn't match vault src",
+    )?;
+    assert_msg(
+        *mint_dst_ai.key == pool_vault_dst.mint,
+        ChudexError::InvalidAccountAddress.into(),
+        "mint dst doesn't match vault dst",
+    )?;
+
+    // pda verification
+
+    // vault src pda
+    let (vault_src_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_src.key.as_ref(),
+            pool_vault_src.mint.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_src_key == *pool_vault_src_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault src pda aint right",
+    )?;
+
+    // vault dst pda
+    let (vault_dst_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_dst.key.as_ref(),
+            pool_vault_dst.mint.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_dst_key == *pool_vault_dst_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault dst pda aint right",
+    )?;
+
+    // pool pda
+    let mut pool_seeds = WeightedPool::seeds(&pool.mints);
+    let (pool_key, pool_bump) = Pubkey::find_program_address(&pool_seeds, program_id);
+    let pool_bump = [pool_bump];
+    pool_seeds.push(&pool_bump);
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // external program verification
+    // mint token programs, either spl token or token-2022
+    assert_msg(
+        mint_src_ai.owner == token_program_src.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program src wrong address",
+    )?;
+    assert_msg(
+        mint_dst_ai.owner == token_program_dst.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program dst wrong address",
+    )?;
+
+    // LOGIC
+
+    // calculate output
+    // pool math uses what actually lands in the vault after any transfer fee
+    let amount_in_received = token::amount_after_transfer_fee(mint_src_ai, amount_in)?;
+    let (amount_out, fee) = curve::weighted_swap(
+        amount_in_received,
+        pool_vault_src.amount,
+        pool.weights[index_src],
+        pool_vault_dst.amount,
+        pool.weights[index_dst],
+        pool.fee,
+        pool.fee_decimals,
+    )?;
+
+    // minimum applies to what the user receives
+    let amount_out_received = token::amount_after_transfer_fee(mint_dst_ai, amount_out)?;
+    if amount_out_received < min_amount_out {
+        return Err(ChudexError::ExchangeAmountBelowMinimum.into());
+    }
+
+    msg!("Got amount out: {} fee: {}", amount_out, fee);
+
+    // send tokens in
+    token::transfer(
+        token_program_src,
+        user_token_src_ai,
+        mint_src_ai,
+        pool_vault_src_ai,
+        user,
+        amount_in,
+        mint_src.decimals,
+        &[],
+    )?;
+
+    // send tokens out
+    token::transfer(
+        token_program_dst,
+        pool_vault_dst_ai,
+        mint_dst_ai,
+        user_token_dst_ai,
+        pool_ai,
+        amount_out,
+        mint_dst.decimals,
+        &[pool_seeds.as_slice()],
+    )?;
+
+    Ok(())
+}
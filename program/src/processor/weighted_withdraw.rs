@@ -0,0 +1,181 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use borsh::BorshDeserialize;
+
+use crate::{curve, error::ChudexError, state::WeightedPool, token, utils::assert_msg};
+
+use spl_token::{
+    error::TokenError,
+    instruction,
+    state::{Account as TokenAccount, Mint},
+};
+
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    pool_token_amount: u64,
+    min_token_amounts: Vec<u64>,
+) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let user_pool_token_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let pool_mint_ai = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+
+    // deserialization
+    let pool = WeightedPool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    let user_pool_token = TokenAccount::unpack_from_slice(&user_pool_token_ai.try_borrow_data()?)?;
+    let pool_mint = Mint::unpack_from_slice(&pool_mint_ai.try_borrow_data()?)?;
+
+    assert_msg(
+        min_token_amounts.len() == pool.mints.len(),
+        ChudexError::InvalidInstructionInput.into(),
+        "Need a min amount per pool token",
+    )?;
+
+    // (user token acc, vault, mint, token program) per token
+    let mut tokens = Vec::with_capacity(pool.mints.len());
+    for _ in 0..pool.mints.len() {
+        tokens.push((
+            next_account_info(accounts_iter)?,
+            next_account_info(accounts_iter)?,
+            next_account_info(accounts_iter)?,
+            next_account_info(accounts_iter)?,
+        ));
+    }
+
+    // ACCOUNT VALIDATION
+
+    // user is signer
+    assert_msg(
+        user.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "User not signer",
+    )?;
+
+    // token account ownership
+    assert_msg(
+        user_pool_token.owner == *user.key,
+        TokenError::OwnerMismatch.into(),
+        "user pool token not owned by user",
+    )?;
+
+    // pool pda
+    let mut pool_seeds = WeightedPool::seeds(&pool.mints);
+    let (pool_key, pool_bump) = Pubkey::find_program_address(&pool_seeds, program_id);
+    let pool_bump = [pool_bump];
+    pool_seeds.push(&pool_bump);
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // pool mint
+    assert_msg(
+        *pool_mint_ai.key == pool.mint,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool mint address invalid",
+    )?;
+
+    // per token accounts
+    let mut balances = Vec::with_capacity(tokens.len());
+    for ((_, vault_ai, mint_ai, token_program_ai), pool_token_mint) in
+        tokens.iter().zip(pool.mints.iter())
+    {
+        assert_msg(
+            mint_ai.key == pool_token_mint,
+            ChudexError::InvalidAccountAddress.into(),
+            "Mint doesn't match pool",
+        )?;
+        assert_msg(
+            mint_ai.owner == token_program_ai.key,
+            ChudexError::InvalidAccountAddress.into(),
+            "Mint token program wrong address",
+        )?;
+
+        let (vault_key, _) = Pubkey::find_program_address(
+            &[
+                pool_ai.key.as_ref(),
+                token_program_ai.key.as_ref(),
+                mint_ai.key.as_ref(),
+            ],
+            &spl_associated_token_account::id(),
+        );
+        assert_msg(
+            vault_key == *vault_ai.key,
+            ChudexError::InvalidProgramAddress.into(),
+            "vault pda aint right",
+        )?;
+        balances.push(token::unpack_token_account(vault_ai)?.amount);
+    }
+
+    // external program verification
+    // token program
+    assert_msg(
+        *token_program.key == spl_token::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program wrong address",
+    )?;
+
+    // LOGIC
+
+    // calculate how much of each token to withdraw, rounded down in the pool's favor
+    let mut amounts = Vec::with_capacity(tokens.len());
+    for (i, (_, _, mint_ai, _)) in tokens.iter().enumerate() {
+        let amount =
+            curve::pool_tokens_to_reserve(pool_token_amount, pool_mint.supply, balances[i])?;
+
+        // minimums apply to what the user receives after any transfer fees
+        if token::amount_after_transfer_fee(mint_ai, amount)? < min_token_amounts[i] {
+            return Err(ChudexError::WithdrawAmountBelowMinimum.into());
+        }
+        msg!("Got token amount {}: {}", i, amount);
+        amounts.push(amount);
+    }
+
+    // burn pool tokens
+    invoke(
+        &instruction::burn(
+            &spl_token::id(),
+            user_pool_token_ai.key,
+            pool_mint_ai.key,
+            user.key,
+            &[user.key],
+            pool_token_amount,
+        )?,
+        &[
+            user_pool_token_ai.clone(),
+            pool_mint_ai.clone(),
+            user.clone(),
+        ],
+    )?;
+
+    // withdraw
+    for ((user_token_ai, vault_ai, mint_ai, token_program_ai), amount) in tokens.iter().zip(amounts)
+    {
+        let mint = token::unpack_mint(mint_ai)?;
+        token::transfer(
+            token_program_ai,
+            vault_ai,
+            mint_ai,
+            user_token_ai,
+            pool_ai,
+            amount,
+            mint.decimals,
+            &[pool_seeds.as_slice()],
+        )?;
+    }
+
+    Ok(())
+}
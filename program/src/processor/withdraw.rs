@@ -1,15 +1,412 @@
-use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    sysvar::{clock::Clock, Sysvar},
+};
 
-use crate::{error::ChudexError, state::Pool};
+use crate::{
+    allowlist,
+    client::{self, WithdrawResult},
+    curve,
+    error::ChudexError,
+    events::{ChudexEvent, Withdrawn},
+    hook::{self, HookOperation, HookPayload, HookStage},
+    state::Pool,
+    token, twamm,
+    utils::assert_msg,
+};
 
 use borsh::{BorshDeserialize, BorshSerialize};
 
+use spl_token::{
+    error::TokenError,
+    instruction,
+    state::{Account as TokenAccount, Mint},
+};
+
 pub fn process(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     pool_token_amount: u64,
     min_token_a_amount: u64,
     min_token_b_amount: u64,
+    native_sol: bool,
 ) -> ProgramResult {
-    Ok(())
+    client::with_result(|| {
+        withdraw(
+            program_id,
+            accounts,
+            pool_token_amount,
+            min_token_a_amount,
+            min_token_b_amount,
+            native_sol,
+        )
+    })
+}
+
+/// Burns pool_token_amount for the user's share of both reserves. Returns what was burned
+/// and received.
+fn withdraw(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    pool_token_amount: u64,
+    min_token_a_amount: u64,
+    min_token_b_amount: u64,
+    native_sol: bool,
+) -> Result<WithdrawResult, ProgramError> {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let user = next_account_info(accounts_iter)?;
+    let user_token_a_ai = next_account_info(accounts_iter)?;
+    let user_token_b_ai = next_account_info(accounts_iter)?;
+    let user_pool_token_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let pool_vault_a_ai = next_account_info(accounts_iter)?;
+    let pool_vault_b_ai = next_account_info(accounts_iter)?;
+    let pool_mint_ai = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let mint_a_ai = next_account_info(accounts_iter)?;
+    let mint_b_ai = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
+    let (system_program, sysvar_rent) = if native_sol {
+        (
+            Some(next_account_info(accounts_iter)?),
+            Some(next_account_info(accounts_iter)?),
+        )
+    } else {
+        (None, None)
+    };
+
+    // deserialization
+    let mut pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    // twamm accounts for pools with a twamm, allowlist or allowlist ticket for
+    // permissioned pools, then the hook's accounts
+    let twamm_accounts = twamm::next_twamm_accounts(&pool, accounts_iter)?;
+    let allowlist_ai = match pool.allowlist {
+        Some(_) => next_account_info(accounts_iter).ok(),
+        None => None,
+    };
+    let hook_accounts = accounts_iter.as_slice();
+    let user_pool_token = TokenAccount::unpack_from_slice(&user_pool_token_ai.try_borrow_data()?)?;
+    let pool_vault_a = token::unpack_token_account(pool_vault_a_ai)?;
+    let pool_vault_b = token::unpack_token_account(pool_vault_b_ai)?;
+    let pool_mint = Mint::unpack_from_slice(&pool_mint_ai.try_borrow_data()?)?;
+    let mint_a = token::unpack_mint(mint_a_ai)?;
+    let mint_b = token::unpack_mint(mint_b_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // native sol is unwrapped through a temporary account in place of the user token account
+    let native_a = native_sol && *mint_a_ai.key == spl_token::native_mint::id();
+    let native_b = native_sol && *mint_b_ai.key == spl_token::native_mint::id();
+    assert_msg(
+        !native_sol || native_a || native_b,
+        ChudexError::InvalidInstructionInput.into(),
+        "Neither mint is native sol",
+    )?;
+
+    // user is signer
+    assert_msg(
+        user.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "User not signer",
+    )?;
+
+    // user is on the pool's allowlist, if it has one
+    allowlist::check(program_id, pool_ai, &pool, user.key, allowlist_ai)?;
+
+    // token account ownership
+    assert_msg(
+        user_pool_token.owner == *user.key,
+        TokenError::OwnerMismatch.into(),
+        "user pool token not owned by user",
+    )?;
+    assert_msg(
+        pool_vault_a.owner == *pool_ai.key,
+        TokenError::OwnerMismatch.into(),
+        "pool vault a not owned by pool",
+    )?;
+    assert_msg(
+        pool_vault_b.owner == *pool_ai.key,
+        TokenError::OwnerMismatch.into(),
+        "pool vault b not owned by pool",
+    )?;
+
+    // vaults hold the pool's two mints
+    assert_msg(
+        (pool_vault_a.mint == pool.mint_a && pool_vault_b.mint == pool.mint_b)
+            || (pool_vault_a.mint == pool.mint_b && pool_vault_b.mint == pool.mint_a),
+        ChudexError::InvalidAccountData.into(),
+        "pool vault mints don't match pool",
+    )?;
+
+    // mints match vaults
+    assert_msg(
+        *mint_a_ai.key == pool_vault_a.mint,
+        ChudexError::InvalidAccountAddress.into(),
+        "mint a doesn't match vault a",
+    )?;
+    assert_msg(
+        *mint_b_ai.key == pool_vault_b.mint,
+        ChudexError::InvalidAccountAddress.into(),
+        "mint b doesn't match vault b",
+    )?;
+
+    // pda verification
+
+    // vault a pda
+    let (vault_a_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_a.key.as_ref(),
+            pool_vault_a.mint.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_a_key == *pool_vault_a_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault a pda aint right",
+    )?;
+
+    // vault b pda
+    let (vault_b_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_b.key.as_ref(),
+            pool_vault_b.mint.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_b_key == *pool_vault_b_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault b pda aint right",
+    )?;
+
+    // pool pda, seeds copied out since the pool's reserves get updated while they're in use
+    let (pool_mint_a, pool_mint_b) = (pool.mint_a, pool.mint_b);
+    let (pool_key, pool_bump) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    let pool_seeds = &[
+        b"chudex_pool",
+        pool_mint_a.as_ref(),
+        pool_mint_b.as_ref(),
+        &[pool_bump],
+    ];
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // pool mint
+    assert_msg(
+        *pool_mint_ai.key == pool.mint,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool mint address invalid",
+    )?;
+
+    // external program verification
+    // token program
+    assert_msg(
+        *token_program.key == spl_token::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program wrong address",
+    )?;
+
+    // mint token programs, either spl token or token-2022
+    assert_msg(
+        mint_a_ai.owner == token_program_a.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program a wrong address",
+    )?;
+    assert_msg(
+        mint_b_ai.owner == token_program_b.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program b wrong address",
+    )?;
+
+    // LOGIC
+
+    // long-term orders trade first, at the price before this withdrawal
+    twamm::settle(
+        program_id,
+        pool_ai,
+        &mut pool,
+        pool_seeds,
+        twamm_accounts,
+        (pool_vault_a_ai, mint_a_ai, token_program_a),
+        (pool_vault_b_ai, mint_b_ai, token_program_b),
+        Clock::get()?.slot,
+    )?;
+    // tracked reserves, in vault order
+    let reserve_a = pool.reserve(&pool_vault_a.mint);
+    let reserve_b = pool.reserve(&pool_vault_b.mint);
+
+    // calculate how much of each token to withdraw
+    let token_a_amount =
+        curve::pool_tokens_to_reserve(pool_token_amount, pool_mint.supply, reserve_a)?;
+    let token_b_amount =
+        curve::pool_tokens_to_reserve(pool_token_amount, pool_mint.supply, reserve_b)?;
+
+    // minimums apply to what the user receives after any transfer fees
+    let token_a_received = token::amount_after_transfer_fee(mint_a_ai, token_a_amount)?;
+    let token_b_received = token::amount_after_transfer_fee(mint_b_ai, token_b_amount)?;
+    if token_a_received < min_token_a_amount || token_b_received < min_token_b_amount {
+        return Err(ChudexError::WithdrawAmountBelowMinimum.into());
+    }
+
+    msg!(
+        "Got token amounts - a: {} b: {}",
+        token_a_amount,
+        token_b_amount
+    );
+
+    // the pool's hook can reject the withdrawal
+    hook::call(
+        program_id,
+        pool_ai,
+        &pool,
+        hook_accounts,
+        &HookPayload {
+            stage: HookStage::Before,
+            operation: HookOperation::Withdraw,
+            pool: *pool_ai.key,
+            user: *user.key,
+            token_a_amount,
+            token_b_amount,
+            pool_token_amount,
+            reserve_a,
+            reserve_b,
+        }
+        .in_pool_order(&pool, &pool_vault_a.mint),
+    )?;
+
+    // burn pool tokens
+    invoke(
+        &instruction::burn(
+            &spl_token::id(),
+            user_pool_token_ai.key,
+            pool_mint_ai.key,
+            user.key,
+            &[user.key],
+            pool_token_amount,
+        )?,
+        &[
+            user_pool_token_ai.clone(),
+            pool_mint_ai.clone(),
+            user.clone(),
+        ],
+    )?;
+
+    // create the wsol account to receive into
+    if let (Some(system_program), Some(sysvar_rent)) = (system_program, sysvar_rent) {
+        let (user_wsol_ai, native_mint_ai, native_token_program) = if native_a {
+            (user_token_a_ai, mint_a_ai, token_program_a)
+        } else {
+            (user_token_b_ai, mint_b_ai, token_program_b)
+        };
+        token::wrap_sol(
+            program_id,
+            user,
+            user_wsol_ai,
+            native_mint_ai,
+            native_token_program,
+            system_program,
+            sysvar_rent,
+            0,
+        )?;
+    }
+
+    // withdraw
+    // withdraw token a
+    token::transfer(
+        token_program_a,
+        pool_vault_a_ai,
+        mint_a_ai,
+        user_token_a_ai,
+        pool_ai,
+        token_a_amount,
+        mint_a.decimals,
+        &[pool_seeds],
+    )?;
+
+    // withdraw token b
+    token::transfer(
+        token_program_b,
+        pool_vault_b_ai,
+        mint_b_ai,
+        user_token_b_ai,
+        pool_ai,
+        token_b_amount,
+        mint_b.decimals,
+        &[pool_seeds],
+    )?;
+
+    // unwrap received sol to the user
+    if native_a {
+        token::unwrap_sol(user, user_token_a_ai, token_program_a)?;
+    }
+    if native_b {
+        token::unwrap_sol(user, user_token_b_ai, token_program_b)?;
+    }
+
+    let reserve_a = reserve_a - token_a_amount;
+    let reserve_b = reserve_b - token_b_amount;
+    pool.set_reserve(&pool_vault_a.mint, reserve_a);
+    pool.set_reserve(&pool_vault_b.mint, reserve_b);
+    pool.serialize(&mut &mut pool_ai.try_borrow_mut_data()?[..])?;
+
+    // report amounts in pool mint order
+    let (token_a_amount, token_b_amount, reserve_a, reserve_b) = if pool.mint_a == pool_vault_a.mint
+    {
+        (token_a_amount, token_b_amount, reserve_a, reserve_b)
+    } else {
+        (token_b_amount, token_a_amount, reserve_b, reserve_a)
+    };
+    ChudexEvent::Withdrawn(Withdrawn {
+        pool: *pool_ai.key,
+        user: *user.key,
+        pool_token_amount,
+        token_a_amount,
+        token_b_amount,
+        reserve_a,
+        reserve_b,
+        pool_token_supply: pool_mint.supply - pool_token_amount,
+    })
+    .emit();
+
+    hook::call(
+        program_id,
+        pool_ai,
+        &pool,
+        hook_accounts,
+        &HookPayload {
+            stage: HookStage::After,
+            operation: HookOperation::Withdraw,
+            pool: *pool_ai.key,
+            user: *user.key,
+            token_a_amount,
+            token_b_amount,
+            pool_token_amount,
+            reserve_a,
+            reserve_b,
+        },
+    )?;
+
+    Ok(WithdrawResult {
+        pool_token_amount,
+        token_a_amount: token_a_received,
+        token_b_amount: token_b_received,
+    })
 }
@@ -0,0 +1,323 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+    sysvar::{clock::Clock, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::ChudexError,
+    state::{ExpiryBucket, LongTermOrder, Pool, TwammPool},
+    token, twamm,
+    utils::{assert_msg, close_program_account},
+};
+
+use spl_token::error::TokenError;
+
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], cancel: bool) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let owner = next_account_info(accounts_iter)?;
+    let owner_token_a_ai = next_account_info(accounts_iter)?;
+    let owner_token_b_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+    let twamm_ai = next_account_info(accounts_iter)?;
+    let order_ai = next_account_info(accounts_iter)?;
+    let twamm_vault_a_ai = next_account_info(accounts_iter)?;
+    let twamm_vault_b_ai = next_account_info(accounts_iter)?;
+    let pool_vault_a_ai = next_account_info(accounts_iter)?;
+    let pool_vault_b_ai = next_account_info(accounts_iter)?;
+    let mint_a_ai = next_account_info(accounts_iter)?;
+    let mint_b_ai = next_account_info(accounts_iter)?;
+    let token_program_a = next_account_info(accounts_iter)?;
+    let token_program_b = next_account_info(accounts_iter)?;
+    // the order's expired bucket, once it has moved out of the twamm's ring
+    let expired_bucket_ai = next_account_info(accounts_iter).ok();
+
+    // deserialization
+    assert_msg(
+        pool_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool not owned by program",
+    )?;
+    let mut pool = Pool::try_from_slice(&pool_ai.try_borrow_data()?)?;
+    assert_msg(
+        order_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Order not owned by program",
+    )?;
+    let mut order = LongTermOrder::try_from_slice(&order_ai.try_borrow_data()?)?;
+    let owner_token_a = token::unpack_token_account(owner_token_a_ai)?;
+    let owner_token_b = token::unpack_token_account(owner_token_b_ai)?;
+
+    // ACCOUNT VALIDATION
+
+    // owner is signer
+    assert_msg(
+        owner.is_signer,
+        ChudexError::MissingRequiredSignature.into(),
+        "Owner not signer",
+    )?;
+    assert_msg(
+        order.owner == *owner.key && order.pool == *pool_ai.key,
+        ChudexError::InvalidAccountData.into(),
+        "Order doesn't match owner or pool",
+    )?;
+
+    // mints in pool order
+    assert_msg(
+        *mint_a_ai.key == pool.mint_a && *mint_b_ai.key == pool.mint_b,
+        ChudexError::InvalidAccountAddress.into(),
+        "Mints don't match pool",
+    )?;
+
+    // token account ownership
+    assert_msg(
+        owner_token_a.owner == *owner.key,
+        TokenError::OwnerMismatch.into(),
+        "owner token a not owned by owner",
+    )?;
+    assert_msg(
+        owner_token_b.owner == *owner.key,
+        TokenError::OwnerMismatch.into(),
+        "owner token b not owned by owner",
+    )?;
+
+    // pda verification
+
+    // pool pda, seeds copied out since the pool's reserves get updated while they're in use
+    let (pool_mint_a, pool_mint_b) = (pool.mint_a, pool.mint_b);
+    let (pool_key, pool_bump) = Pubkey::find_program_address(
+        &[b"chudex_pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        program_id,
+    );
+    let pool_seeds = &[
+        b"chudex_pool",
+        pool_mint_a.as_ref(),
+        pool_mint_b.as_ref(),
+        &[pool_bump],
+    ];
+    assert_msg(
+        *pool_ai.key == pool_key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Pool address invalid",
+    )?;
+
+    // vault a pda
+    let (vault_a_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_a.key.as_ref(),
+            mint_a_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_a_key == *pool_vault_a_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault a pda aint right",
+    )?;
+
+    // vault b pda
+    let (vault_b_key, _) = Pubkey::find_program_address(
+        &[
+            pool_ai.key.as_ref(),
+            token_program_b.key.as_ref(),
+            mint_b_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        vault_b_key == *pool_vault_b_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "vault b pda aint right",
+    )?;
+
+    // external program verification
+    // mint token programs, either spl token or token-2022
+    assert_msg(
+        mint_a_ai.owner == token_program_a.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program a wrong address",
+    )?;
+    assert_msg(
+        mint_b_ai.owner == token_program_b.key,
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program b wrong address",
+    )?;
+
+    // LOGIC
+
+    // bring the order pools up to now before paying out
+    let now = Clock::get()?.slot;
+    let mut twamm = twamm::settle(
+        program_id,
+        pool_ai,
+        &mut pool,
+        pool_seeds,
+        Some((twamm_ai, twamm_vault_a_ai, twamm_vault_b_ai)),
+        (pool_vault_a_ai, mint_a_ai, token_program_a),
+        (pool_vault_b_ai, mint_b_ai, token_program_b),
+        now,
+    )?
+    .ok_or(ChudexError::InvalidAccountData)?;
+    pool.serialize(&mut &mut pool_ai.try_borrow_mut_data()?[..])?;
+
+    // twamm signs for its vaults
+    let (_, twamm_bump) =
+        Pubkey::find_program_address(&[TwammPool::SEED, pool_ai.key.as_ref()], program_id);
+    let twamm_seeds = &[TwammPool::SEED, pool_ai.key.as_ref(), &[twamm_bump]];
+
+    // expired orders earned up to their end, kept in their bucket, in the ring or moved out
+    let expired = now >= order.end_slot;
+    let mut expired_bucket = None;
+    let earnings_factor = if expired {
+        let bucket = if twamm.bucket_mut(order.end_slot).end_slot == order.end_slot {
+            twamm.bucket_mut(order.end_slot).clone()
+        } else {
+            let expired_bucket_ai = expired_bucket_ai.ok_or(ChudexError::InvalidAccountData)?;
+            let (expired_bucket_key, _) = Pubkey::find_program_address(
+                &[
+                    ExpiryBucket::SEED,
+                    twamm_ai.key.as_ref(),
+                    &order.end_slot.to_le_bytes(),
+                ],
+                program_id,
+            );
+            assert_msg(
+                *expired_bucket_ai.key == expired_bucket_key,
+                ChudexError::InvalidProgramAddress.into(),
+                "expired bucket pda aint right",
+            )?;
+            assert_msg(
+                expired_bucket_ai.owner == program_id,
+                ChudexError::InvalidAccountData.into(),
+                "Order expiry bucket missing",
+            )?;
+            let bucket = ExpiryBucket::try_from_slice(&expired_bucket_ai.try_borrow_data()?)?;
+            expired_bucket = Some((expired_bucket_ai, bucket.clone()));
+            bucket
+        };
+        if order.a_to_b {
+            bucket.earnings_factor_a
+        } else {
+            bucket.earnings_factor_b
+        }
+    } else if order.a_to_b {
+        twamm.earnings_factor_a
+    } else {
+        twamm.earnings_factor_b
+    };
+    let proceeds = twamm::order_proceeds(&order, earnings_factor)?;
+    let unsold = if cancel {
+        twamm::order_unsold(&order, now)?
+    } else {
+        0
+    };
+    msg!("Got proceeds: {} unsold: {}", proceeds, unsold);
+
+    // a cancelled order stops selling now instead of at its end
+    if cancel && !expired {
+        if order.a_to_b {
+            twamm.sell_rate_a -= order.sell_rate;
+            twamm.bucket_mut(order.end_slot).expiring_rate_a -= order.sell_rate;
+        } else {
+            twamm.sell_rate_b -= order.sell_rate;
+            twamm.bucket_mut(order.end_slot).expiring_rate_b -= order.sell_rate;
+        }
+    }
+    let close = cancel || expired;
+    if let Some((expired_bucket_ai, mut bucket)) = expired_bucket {
+        // the last order out closes the moved bucket and takes its rent
+        twamm.expired_open_orders -= 1;
+        bucket.open_orders -= 1;
+        if bucket.open_orders == 0 {
+            close_program_account(expired_bucket_ai, owner)?;
+        } else {
+            bucket.serialize(&mut &mut expired_bucket_ai.try_borrow_mut_data()?[..])?;
+        }
+    } else if close {
+        twamm.bucket_mut(order.end_slot).open_orders -= 1;
+    }
+
+    // pay out unsold tokens in and proceeds in the other token
+    let (
+        (mint_in_ai, twamm_vault_in_ai, token_program_in, owner_token_in_ai),
+        (mint_out_ai, twamm_vault_out_ai, token_program_out, owner_token_out_ai),
+    ) = if order.a_to_b {
+        (
+            (
+                mint_a_ai,
+                twamm_vault_a_ai,
+                token_program_a,
+                owner_token_a_ai,
+            ),
+            (
+                mint_b_ai,
+                twamm_vault_b_ai,
+                token_program_b,
+                owner_token_b_ai,
+            ),
+        )
+    } else {
+        (
+            (
+                mint_b_ai,
+                twamm_vault_b_ai,
+                token_program_b,
+                owner_token_b_ai,
+            ),
+            (
+                mint_a_ai,
+                twamm_vault_a_ai,
+                token_program_a,
+                owner_token_a_ai,
+            ),
+        )
+    };
+    for (amount, mint_ai, twamm_vault_ai, token_program_ai, owner_token_ai) in [
+        (
+            unsold,
+            mint_in_ai,
+            twamm_vault_in_ai,
+            token_program_in,
+            owner_token_in_ai,
+        ),
+        (
+            proceeds,
+            mint_out_ai,
+            twamm_vault_out_ai,
+            token_program_out,
+            owner_token_out_ai,
+        ),
+    ] {
+        if amount > 0 {
+            let mint = token::unpack_mint(mint_ai)?;
+            token::transfer(
+                token_program_ai,
+                twamm_vault_ai,
+                mint_ai,
+                owner_token_ai,
+                twamm_ai,
+                amount,
+                mint.decimals,
+                &[twamm_seeds],
+            )?;
+        }
+    }
+
+    // save state, the order is done once cancelled or expired
+    twamm.serialize(&mut &mut twamm_ai.try_borrow_mut_data()?[..])?;
+    if close {
+        close_program_account(order_ai, owner)?;
+    } else {
+        order.earnings_factor_last = earnings_factor;
+        order.serialize(&mut &mut order_ai.try_borrow_mut_data()?[..])?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,222 @@
+//! Lists of pools for clients to find them without scanning program accounts: every pool
+//! across the factory's registry pages, and the pools of each mint across its index pages.
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{rent, Sysvar},
+};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::ChudexError,
+    state::{Factory, MintIndex, RegistryPage},
+    utils::assert_msg,
+};
+
+/// Deserializes a program account at the pda of `seeds`, creating it with `init` if it
+/// doesn't exist yet.
+fn load_or_create<'a, T: BorshDeserialize>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    account_ai: &AccountInfo<'a>,
+    seeds: &[&[u8]],
+    len: usize,
+    init: impl FnOnce() -> T,
+) -> Result<T, ProgramError> {
+    let (key, bump) = Pubkey::find_program_address(seeds, program_id);
+    assert_msg(
+        *account_ai.key == key,
+        ChudexError::InvalidProgramAddress.into(),
+        "registry pda aint right",
+    )?;
+
+    if account_ai.data_len() == 0 {
+        let bump = &[bump];
+        let mut signer_seeds = seeds.to_vec();
+        signer_seeds.push(bump);
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                account_ai.key,
+                rent::Rent::get()?.minimum_balance(len),
+                len as u64,
+                program_id,
+            ),
+            &[payer.clone(), account_ai.clone(), system_program.clone()],
+            &[&signer_seeds],
+        )?;
+        return Ok(init());
+    }
+
+    assert_msg(
+        account_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Registry account not owned by program",
+    )?;
+    Ok(T::try_from_slice(&account_ai.try_borrow_data()?)?)
+}
+
+/// Appends `pool` as entry `index` of a list, whose pages are pdas of `seeds` and the
+/// page number.
+fn append<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    page_ai: &AccountInfo<'a>,
+    seeds: &[&[u8]],
+    index: u64,
+    pool: &Pubkey,
+) -> ProgramResult {
+    let page_bytes = (index / RegistryPage::PAGE_SIZE as u64).to_le_bytes();
+    let mut page_seeds = seeds.to_vec();
+    page_seeds.push(&page_bytes);
+
+    let mut page = load_or_create(
+        program_id,
+        payer,
+        system_program,
+        page_ai,
+        &page_seeds,
+        RegistryPage::LEN,
+        || RegistryPage {
+            len: 0,
+            pools: vec![Pubkey::default(); RegistryPage::PAGE_SIZE],
+        },
+    )?;
+    page.pools[page.len as usize] = *pool;
+    page.len += 1;
+    page.serialize(&mut &mut page_ai.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+/// Records a new pool in the registry and in the indexes of both its mints, given as
+/// (mint, mint index, mint index page). Accounts get created on first use, paid by `payer`.
+#[allow(clippy::too_many_arguments)]
+pub fn register_pool<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    pool: &Pubkey,
+    factory_ai: &AccountInfo<'a>,
+    registry_page_ai: &AccountInfo<'a>,
+    mint_indexes: [(&Pubkey, &AccountInfo<'a>, &AccountInfo<'a>); 2],
+) -> ProgramResult {
+    let mut factory = load_or_create(
+        program_id,
+        payer,
+        system_program,
+        factory_ai,
+        &[Factory::SEED],
+        Factory::LEN,
+        Factory::default,
+    )?;
+    append(
+        program_id,
+        payer,
+        system_program,
+        registry_page_ai,
+        &[RegistryPage::SEED],
+        factory.pool_count,
+        pool,
+    )?;
+    factory.pool_count += 1;
+    factory.serialize(&mut &mut factory_ai.try_borrow_mut_data()?[..])?;
+
+    for (mint, mint_index_ai, mint_index_page_ai) in mint_indexes {
+        let mut mint_index = load_or_create(
+            program_id,
+            payer,
+            system_program,
+            mint_index_ai,
+            &[MintIndex::SEED, mint.as_ref()],
+            MintIndex::LEN,
+            || MintIndex {
+                mint: *mint,
+                pool_count: 0,
+            },
+        )?;
+        append(
+            program_id,
+            payer,
+            system_program,
+            mint_index_page_ai,
+            &[RegistryPage::SEED, mint.as_ref()],
+            mint_index.pool_count,
+            pool,
+        )?;
+        mint_index.pool_count += 1;
+        mint_index.serialize(&mut &mut mint_index_ai.try_borrow_mut_data()?[..])?;
+    }
+
+    Ok(())
+}
+
+/// Removes `pool` from page `page_number` of a list whose pages are pdas of `seeds` and the
+/// page number, leaving the default address in its place so later entries keep their index.
+fn remove(
+    program_id: &Pubkey,
+    page_ai: &AccountInfo,
+    seeds: &[&[u8]],
+    page_number: u64,
+    pool: &Pubkey,
+) -> ProgramResult {
+    let page_bytes = page_number.to_le_bytes();
+    let mut page_seeds = seeds.to_vec();
+    page_seeds.push(&page_bytes);
+    let (key, _) = Pubkey::find_program_address(&page_seeds, program_id);
+    assert_msg(
+        *page_ai.key == key,
+        ChudexError::InvalidProgramAddress.into(),
+        "registry pda aint right",
+    )?;
+    assert_msg(
+        page_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Registry account not owned by program",
+    )?;
+
+    let mut page = RegistryPage::try_from_slice(&page_ai.try_borrow_data()?)?;
+    let index = page
+        .pools()
+        .iter()
+        .position(|listed| listed == pool)
+        .ok_or(ChudexError::InvalidAccountData)?;
+    page.pools[index] = Pubkey::default();
+    page.serialize(&mut &mut page_ai.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+/// Takes a closed pool out of the registry and out of the indexes of both its mints, given
+/// as (registry page, page number) and (mint, mint index page, page number).
+pub fn deregister_pool(
+    program_id: &Pubkey,
+    pool: &Pubkey,
+    registry_page: (&AccountInfo, u64),
+    mint_index_pages: [(&Pubkey, &AccountInfo, u64); 2],
+) -> ProgramResult {
+    let (registry_page_ai, page_number) = registry_page;
+    remove(
+        program_id,
+        registry_page_ai,
+        &[RegistryPage::SEED],
+        page_number,
+        pool,
+    )?;
+    for (mint, mint_index_page_ai, page_number) in mint_index_pages {
+        remove(
+            program_id,
+            mint_index_page_ai,
+            &[RegistryPage::SEED, mint.as_ref()],
+            page_number,
+            pool,
+        )?;
+    }
+    Ok(())
+}
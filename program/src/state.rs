@@ -2,6 +2,8 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::pubkey::Pubkey;
 use std::mem::size_of;
 
+use crate::{cl_math, curve, error::ChudexError};
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct Pool {
     // TODO
@@ -10,4 +12,1237 @@ pub struct Pool {
     pub mint: Pubkey,
     pub fee: u64,
     pub fee_decimals: u64,
+    /// Balances the pool trades against, in pool mint order. Tokens sent straight to the
+    /// vaults don't count until a Sync.
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    /// Whether the pool has a twamm, whose accounts instructions settling long-term orders
+    /// then take.
+    pub twamm: bool,
+    pub price_cumulative: PriceCumulative,
+    pub dynamic_fee: Option<DynamicFee>,
+    pub allowlist: Option<AllowlistMode>,
+    pub hook_program: Option<Pubkey>,
+}
+
+impl Pool {
+    /// Serialized size, with or without a dynamic fee, an allowlist and a hook program.
+    pub fn len(dynamic_fee: bool, allowlist: bool, hook_program: bool) -> usize {
+        3 * size_of::<Pubkey>()
+            + 4 * size_of::<u64>()
+            + 1
+            + PriceCumulative::LEN
+            + 1
+            + dynamic_fee as usize * DynamicFee::LEN
+            + 1
+            + allowlist as usize * AllowlistMode::LEN
+            + 1
+            + hook_program as usize * size_of::<Pubkey>()
+    }
+
+    /// Tracked reserve of `mint`, one of the pool's mints.
+    pub fn reserve(&self, mint: &Pubkey) -> u64 {
+        if *mint == self.mint_a {
+            self.reserve_a
+        } else {
+            self.reserve_b
+        }
+    }
+
+    /// Sets the tracked reserve of `mint`, one of the pool's mints.
+    pub fn set_reserve(&mut self, mint: &Pubkey, amount: u64) {
+        if *mint == self.mint_a {
+            self.reserve_a = amount;
+        } else {
+            self.reserve_b = amount;
+        }
+    }
+
+    /// Accrues the current prices up to `now`, unix seconds. Called before anything
+    /// changes the reserves, so each price counts for as long as it held.
+    pub fn accrue_prices(&mut self, now: i64) {
+        self.price_cumulative = self
+            .price_cumulative
+            .at(self.reserve_a, self.reserve_b, now);
+    }
+
+    /// Fee charged by a swap at `slot`, in fee_decimals. The fixed fee unless the pool has
+    /// a dynamic fee.
+    pub fn effective_fee(&self, slot: u64) -> u64 {
+        match &self.dynamic_fee {
+            Some(dynamic_fee) => dynamic_fee.fee(self.fee, slot),
+            None => self.fee,
+        }
+    }
+}
+
+/// Running sums of the pool's spot prices, Q64.64 raw amounts of the other mint per
+/// token, times the seconds each held. Sums wrap, only differences between two of them
+/// mean anything: over an interval they give its time weighted average price.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default)]
+pub struct PriceCumulative {
+    /// Sum of the price of a in b.
+    pub price_a_cumulative: u128,
+    /// Sum of the price of b in a.
+    pub price_b_cumulative: u128,
+    pub last_timestamp: i64,
+}
+
+impl PriceCumulative {
+    pub const LEN: usize = 2 * size_of::<u128>() + size_of::<i64>();
+
+    /// Sums carried on to `now` at the prices of `reserve_a` and `reserve_b`. Empty
+    /// sides add nothing.
+    pub fn at(&self, reserve_a: u64, reserve_b: u64, now: i64) -> Self {
+        let elapsed = now.saturating_sub(self.last_timestamp).max(0) as u128;
+        let mut cumulative = *self;
+        if reserve_a > 0 && reserve_b > 0 {
+            cumulative.price_a_cumulative = self
+                .price_a_cumulative
+                .wrapping_add(curve::price(reserve_a, reserve_b).wrapping_mul(elapsed));
+            cumulative.price_b_cumulative = self
+                .price_b_cumulative
+                .wrapping_add(curve::price(reserve_b, reserve_a).wrapping_mul(elapsed));
+        }
+        cumulative.last_timestamp = now.max(self.last_timestamp);
+        cumulative
+    }
+
+    /// Average prices of a and b from `earlier` to these sums, None over no time.
+    pub fn average_since(&self, earlier: &PriceCumulative) -> Option<(u128, u128)> {
+        let elapsed = self.last_timestamp.checked_sub(earlier.last_timestamp)?;
+        if elapsed <= 0 {
+            return None;
+        }
+        Some((
+            self.price_a_cumulative
+                .wrapping_sub(earlier.price_a_cumulative)
+                / elapsed as u128,
+            self.price_b_cumulative
+                .wrapping_sub(earlier.price_b_cumulative)
+                / elapsed as u128,
+        ))
+    }
+}
+
+/// Snapshots of a pool's price sums, at least `OBSERVATION_INTERVAL` seconds apart, in a
+/// ring of `MAX_OBSERVATIONS`. Averages over a window are taken from the newest snapshot
+/// at least that old.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct PriceOracle {
+    pub pool: Pubkey,
+    /// Slot in the ring of the newest snapshot.
+    pub latest: u32,
+    pub observations: Vec<PriceCumulative>,
+}
+
+impl PriceOracle {
+    pub const SEED: &'static [u8] = b"chudex_oracle";
+    pub const OBSERVATION_INTERVAL: i64 = 300;
+    pub const MAX_OBSERVATIONS: usize = 64;
+    pub const LEN: usize =
+        size_of::<Pubkey>() + size_of::<u32>() + 4 + Self::MAX_OBSERVATIONS * PriceCumulative::LEN;
+
+    pub fn latest(&self) -> &PriceCumulative {
+        &self.observations[self.latest as usize]
+    }
+
+    /// Records `observation` unless the newest snapshot is still recent. Returns whether
+    /// it was recorded.
+    pub fn record(&mut self, observation: PriceCumulative) -> bool {
+        if observation.last_timestamp - self.latest().last_timestamp < Self::OBSERVATION_INTERVAL {
+            return false;
+        }
+        self.latest = ((self.latest as usize + 1) % Self::MAX_OBSERVATIONS) as u32;
+        self.observations[self.latest as usize] = observation;
+        true
+    }
+
+    /// Newest snapshot taken at or before `timestamp`, if the ring goes back that far.
+    pub fn observation_before(&self, timestamp: i64) -> Option<&PriceCumulative> {
+        (0..Self::MAX_OBSERVATIONS)
+            .map(|age| {
+                let index =
+                    (self.latest as usize + Self::MAX_OBSERVATIONS - age) % Self::MAX_OBSERVATIONS;
+                &self.observations[index]
+            })
+            // unused slots are zeroed
+            .take_while(|observation| observation.last_timestamp > 0)
+            .find(|observation| observation.last_timestamp <= timestamp)
+    }
+
+    /// Average prices of a and b over at least the last `window` seconds, up to `current`,
+    /// the pool's sums carried on to now. None if the ring doesn't go back that far.
+    pub fn twap(&self, current: &PriceCumulative, window: u32) -> Option<(u128, u128)> {
+        let observation = self.observation_before(current.last_timestamp - window as i64)?;
+        current.average_since(observation)
+    }
+}
+
+/// Parameters of a dynamic fee, fees in the pool's fee_decimals.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct DynamicFeeConfig {
+    /// Cap on the fee however volatile the pool gets.
+    pub max_fee: u64,
+    /// Fee added per VOLATILITY_SCALE of accumulated volatility, a 100% price move.
+    pub volatility_fee: u64,
+    /// Slots for the volatility accumulator to halve.
+    pub half_life: u64,
+}
+
+/// Fee that rises from the pool's fixed fee with recent volatility, up to `max_fee`.
+/// Every swap adds its price move in basis points to `volatility`, which halves every
+/// `half_life` slots.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct DynamicFee {
+    pub config: DynamicFeeConfig,
+    pub volatility: u64,
+    pub last_slot: u64,
+}
+
+impl DynamicFee {
+    pub const LEN: usize = 5 * size_of::<u64>();
+    pub const VOLATILITY_SCALE: u64 = 10_000;
+
+    /// Volatility accumulator decayed up to `slot`.
+    pub fn volatility_at(&self, slot: u64) -> u64 {
+        let halvings = slot.saturating_sub(self.last_slot) / self.config.half_life;
+        self.volatility.checked_shr(halvings as u32).unwrap_or(0)
+    }
+
+    /// Fee charged at `slot` on top of `base_fee`, capped at the max fee.
+    pub fn fee(&self, base_fee: u64, slot: u64) -> u64 {
+        let variable_fee = self.volatility_at(slot) as u128 * self.config.volatility_fee as u128
+            / Self::VOLATILITY_SCALE as u128;
+        (base_fee as u128 + variable_fee).min(self.config.max_fee as u128) as u64
+    }
+
+    /// Decays the accumulator to `slot` and adds the price move of a swap between the
+    /// reserves before and after it.
+    pub fn record_swap(
+        &mut self,
+        slot: u64,
+        (reserve_in, reserve_out): (u64, u64),
+        (reserve_in_after, reserve_out_after): (u64, u64),
+    ) {
+        // decay in whole half lives, carrying the rest over
+        let halvings = slot.saturating_sub(self.last_slot) / self.config.half_life;
+        self.volatility = self.volatility.checked_shr(halvings as u32).unwrap_or(0);
+        self.last_slot = self
+            .last_slot
+            .saturating_add(halvings.saturating_mul(self.config.half_life));
+
+        // |p1 - p0| / p0 in bps with p = reserve out / reserve in
+        let before = reserve_out as u128 * reserve_in_after as u128;
+        let after = reserve_out_after as u128 * reserve_in as u128;
+        if before == 0 {
+            return;
+        }
+        let difference = after.abs_diff(before);
+        let price_move = difference
+            .checked_mul(Self::VOLATILITY_SCALE as u128)
+            .map_or(u64::MAX as u128, |numerator| numerator / before);
+        self.volatility = self
+            .volatility
+            .saturating_add(price_move.min(u64::MAX as u128) as u64);
+    }
+}
+
+/// Who may deposit, withdraw and trade in a permissioned pool.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum AllowlistMode {
+    /// Users listed in this allowlist account.
+    Account(Pubkey),
+    /// Users proving sha256(user) is a leaf under this Merkle root, once per pool through
+    /// an allowlist ticket.
+    MerkleRoot([u8; 32]),
+}
+
+impl AllowlistMode {
+    pub const LEN: usize = 1 + 32;
+}
+
+/// Pubkeys allowed into the pools referencing this account, managed by `authority`.
+/// The first `len` members are set.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Allowlist {
+    pub authority: Pubkey,
+    pub len: u32,
+    pub members: Vec<Pubkey>,
+}
+
+impl Allowlist {
+    pub const SEED: &'static [u8] = b"chudex_allowlist";
+
+    /// Serialized size for an allowlist of up to `capacity` members.
+    pub fn len(capacity: usize) -> usize {
+        size_of::<Pubkey>() + size_of::<u32>() + 4 + capacity * size_of::<Pubkey>()
+    }
+
+    pub fn members(&self) -> &[Pubkey] {
+        &self.members[..self.len as usize]
+    }
+}
+
+/// Proof that `user` is under a pool's allowlist Merkle root.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct AllowlistTicket {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+}
+
+impl AllowlistTicket {
+    pub const SEED: &'static [u8] = b"chudex_allowlist_ticket";
+    pub const LEN: usize = 2 * size_of::<Pubkey>();
+}
+
+/// Program wide settings in a single account, managed by `admin`. Constant product pools
+/// are created on one of the fee tiers, in basis points.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct GlobalConfig {
+    pub admin: Pubkey,
+    pub num_fee_tiers: u8,
+    pub fee_tiers: [u16; GlobalConfig::MAX_FEE_TIERS],
+}
+
+impl GlobalConfig {
+    pub const SEED: &'static [u8] = b"chudex_config";
+    pub const MAX_FEE_TIERS: usize = 8;
+    /// Fee tiers are in basis points.
+    pub const FEE_DECIMALS: u64 = 4;
+    pub const LEN: usize = size_of::<Pubkey>() + 1 + Self::MAX_FEE_TIERS * size_of::<u16>();
+
+    pub fn fee_tiers(&self) -> &[u16] {
+        &self.fee_tiers[..self.num_fee_tiers as usize]
+    }
+
+    /// Replaces the fee tiers, which have to be unique and under 100%.
+    pub fn set_fee_tiers(&mut self, fee_tiers: &[u16]) -> Result<(), ChudexError> {
+        if fee_tiers.is_empty() || fee_tiers.len() > Self::MAX_FEE_TIERS {
+            return Err(ChudexError::InvalidInstructionInput);
+        }
+        for (i, fee_tier) in fee_tiers.iter().enumerate() {
+            curve::check_fee(*fee_tier as u64, Self::FEE_DECIMALS)?;
+            if fee_tiers[..i].contains(fee_tier) {
+                return Err(ChudexError::InvalidFee);
+            }
+        }
+        self.fee_tiers = [0; Self::MAX_FEE_TIERS];
+        self.fee_tiers[..fee_tiers.len()].copy_from_slice(fee_tiers);
+        self.num_fee_tiers = fee_tiers.len() as u8;
+        Ok(())
+    }
+}
+
+/// Counts every constant product pool. The pools are listed in creation order across
+/// registry pages (pda of "chudex_registry", page).
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct Factory {
+    pub pool_count: u64,
+}
+
+impl Factory {
+    pub const SEED: &'static [u8] = b"chudex_factory";
+    pub const LEN: usize = size_of::<u64>();
+}
+
+/// Counts the constant product pools containing `mint`. They are listed in creation order
+/// across index pages (pda of "chudex_registry", mint, page).
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct MintIndex {
+    pub mint: Pubkey,
+    pub pool_count: u64,
+}
+
+impl MintIndex {
+    pub const SEED: &'static [u8] = b"chudex_mint_index";
+    pub const LEN: usize = size_of::<Pubkey>() + size_of::<u64>();
+}
+
+/// A page of pool addresses, of the registry or of a mint index. The first `len` are set,
+/// closed pools leave the default address in their place.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RegistryPage {
+    pub len: u32,
+    pub pools: Vec<Pubkey>,
+}
+
+impl RegistryPage {
+    pub const SEED: &'static [u8] = b"chudex_registry";
+    pub const PAGE_SIZE: usize = 100;
+    pub const LEN: usize = size_of::<u32>() + 4 + Self::PAGE_SIZE * size_of::<Pubkey>();
+
+    pub fn pools(&self) -> &[Pubkey] {
+        &self.pools[..self.len as usize]
+    }
+}
+
+/// Name, symbol and uri of a pool's lp mint, for wallets and explorers. Pda of
+/// "chudex_lp_metadata", pool mint. The first `x_len` bytes of each field are set.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LpMetadata {
+    pub mint: Pubkey,
+    pub name_len: u8,
+    pub name: [u8; LpMetadata::MAX_NAME_LEN],
+    pub symbol_len: u8,
+    pub symbol: [u8; LpMetadata::MAX_SYMBOL_LEN],
+    pub uri_len: u16,
+    pub uri: [u8; LpMetadata::MAX_URI_LEN],
+}
+
+impl LpMetadata {
+    pub const SEED: &'static [u8] = b"chudex_lp_metadata";
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const MAX_SYMBOL_LEN: usize = 10;
+    pub const MAX_URI_LEN: usize = 256;
+    pub const LEN: usize = size_of::<Pubkey>()
+        + 1
+        + Self::MAX_NAME_LEN
+        + 1
+        + Self::MAX_SYMBOL_LEN
+        + size_of::<u16>()
+        + Self::MAX_URI_LEN;
+
+    /// Placeholder metadata for a new pool: symbol "CHU-LP" and a name of "CHU-LP" then the
+    /// first 4 characters of each mint's base58 address, e.g. "CHU-LP So11/EPjF". The mints'
+    /// own symbols aren't read, the admin sets a proper name with UpdateLpMetadata.
+    pub fn new(mint: Pubkey, mint_a: &Pubkey, mint_b: &Pubkey) -> Result<Self, ChudexError> {
+        let mut metadata = LpMetadata {
+            mint,
+            name_len: 0,
+            name: [0; Self::MAX_NAME_LEN],
+            symbol_len: 0,
+            symbol: [0; Self::MAX_SYMBOL_LEN],
+            uri_len: 0,
+            uri: [0; Self::MAX_URI_LEN],
+        };
+        let name = format!(
+            "CHU-LP {}/{}",
+            &mint_a.to_string()[..4],
+            &mint_b.to_string()[..4]
+        );
+        metadata.set(&name, "CHU-LP", "")?;
+        Ok(metadata)
+    }
+
+    pub fn name(&self) -> &str {
+        std::str::from_utf8(&self.name[..self.name_len as usize]).unwrap_or_default()
+    }
+
+    pub fn symbol(&self) -> &str {
+        std::str::from_utf8(&self.symbol[..self.symbol_len as usize]).unwrap_or_default()
+    }
+
+    pub fn uri(&self) -> &str {
+        std::str::from_utf8(&self.uri[..self.uri_len as usize]).unwrap_or_default()
+    }
+
+    /// Replaces every field, each has to fit its max length.
+    pub fn set(&mut self, name: &str, symbol: &str, uri: &str) -> Result<(), ChudexError> {
+        if name.len() > Self::MAX_NAME_LEN
+            || symbol.len() > Self::MAX_SYMBOL_LEN
+            || uri.len() > Self::MAX_URI_LEN
+        {
+            return Err(ChudexError::InvalidInstructionInput);
+        }
+        self.name = [0; Self::MAX_NAME_LEN];
+        self.name[..name.len()].copy_from_slice(name.as_bytes());
+        self.name_len = name.len() as u8;
+        self.symbol = [0; Self::MAX_SYMBOL_LEN];
+        self.symbol[..symbol.len()].copy_from_slice(symbol.as_bytes());
+        self.symbol_len = symbol.len() as u8;
+        self.uri = [0; Self::MAX_URI_LEN];
+        self.uri[..uri.len()].copy_from_slice(uri.as_bytes());
+        self.uri_len = uri.len() as u16;
+        Ok(())
+    }
+}
+
+/// Non-fungible share of a constant product pool: pool tokens escrowed in the position's
+/// pool token acc, owned by whoever holds the position's mint, a supply of 1 nft.
+/// Pda of "chudex_lp_position", position mint.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LpPosition {
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub pool_token_amount: u64,
+    pub deposit_timestamp: i64,
+    /// `curve::liquidity_per_pool_token` when the position was opened.
+    pub entry_liquidity_per_pool_token: u128,
+}
+
+impl LpPosition {
+    pub const SEED: &'static [u8] = b"chudex_lp_position";
+    /// Position mints are pdas of this, the owner and a nonce of their choosing.
+    pub const MINT_SEED: &'static [u8] = b"chudex_lp_position_mint";
+    pub const LEN: usize =
+        2 * size_of::<Pubkey>() + size_of::<u64>() + size_of::<i64>() + size_of::<u128>();
+
+    /// Swap fees earned since the position was opened, in sqrt(a * b) liquidity units,
+    /// given the pool's current `curve::liquidity_per_pool_token`.
+    pub fn fees_accrued(&self, liquidity_per_pool_token: u128) -> Result<u64, ChudexError> {
+        let growth = liquidity_per_pool_token.saturating_sub(self.entry_liquidity_per_pool_token);
+        let amount = self.pool_token_amount as u128;
+        // amount * growth >> 64, split so it can't overflow
+        let fees = (amount * (growth >> 64))
+            .checked_add((amount * (growth & u64::MAX as u128)) >> 64)
+            .ok_or(ChudexError::CalculationFailure)?;
+        u64::try_from(fees).map_err(|_| ChudexError::CalculationFailure)
+    }
+}
+
+/// Counts the lockers of a pool, pdas of "chudex_locker", pool and their index.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct PoolLockers {
+    pub pool: Pubkey,
+    pub locker_count: u64,
+}
+
+impl PoolLockers {
+    pub const SEED: &'static [u8] = b"chudex_pool_lockers";
+    pub const LEN: usize = size_of::<Pubkey>() + size_of::<u64>();
+}
+
+/// Pool tokens escrowed in the locker's pool token acc until `unlock_timestamp`, then
+/// released to `owner` all at once, or linearly until `vesting_end_timestamp` if set.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Locker {
+    pub pool: Pubkey,
+    /// Index among the pool's lockers, a seed of the locker.
+    pub index: u64,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub released: u64,
+    pub unlock_timestamp: i64,
+    pub vesting_end_timestamp: Option<i64>,
+}
+
+impl Locker {
+    pub const SEED: &'static [u8] = b"chudex_locker";
+
+    /// Serialized size, with or without vesting.
+    pub fn len(vesting: bool) -> usize {
+        2 * size_of::<Pubkey>()
+            + 3 * size_of::<u64>()
+            + size_of::<i64>()
+            + 1
+            + vesting as usize * size_of::<i64>()
+    }
+
+    /// Amount vested at `now`, released or not.
+    pub fn vested(&self, now: i64) -> u64 {
+        match self.vesting_end_timestamp {
+            _ if now < self.unlock_timestamp => 0,
+            Some(end) if now < end => {
+                let elapsed = (now - self.unlock_timestamp) as u128;
+                let duration = (end - self.unlock_timestamp) as u128;
+                (self.amount as u128 * elapsed / duration) as u64
+            }
+            _ => self.amount,
+        }
+    }
+}
+
+/// Multi-asset pool priced by a weighted product invariant, prod(balance_i ^ weight_i) = k.
+/// Mints are stored sorted, weights line up with mints and are relative to each other.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct WeightedPool {
+    pub mints: Vec<Pubkey>,
+    pub weights: Vec<u64>,
+    pub mint: Pubkey,
+    pub fee: u64,
+    pub fee_decimals: u64,
+}
+
+impl WeightedPool {
+    pub const SEED: &'static [u8] = b"chudex_weighted_pool";
+    pub const MIN_TOKENS: usize = 2;
+    pub const MAX_TOKENS: usize = 8;
+
+    /// Pda seeds, without the bump, for a pool of the given sorted mints.
+    pub fn seeds(mints: &[Pubkey]) -> Vec<&[u8]> {
+        let mut seeds = vec![Self::SEED];
+        seeds.extend(mints.iter().map(|mint| mint.as_ref()));
+        seeds
+    }
+
+    /// Serialized size for a pool of `num_tokens` mints.
+    pub fn len(num_tokens: usize) -> usize {
+        (4 + num_tokens * size_of::<Pubkey>())
+            + (4 + num_tokens * size_of::<u64>())
+            + size_of::<Pubkey>()
+            + 2 * size_of::<u64>()
+    }
+
+    pub fn token_index(&self, mint: &Pubkey) -> Option<usize> {
+        self.mints.iter().position(|m| m == mint)
+    }
+}
+
+/// Concentrated liquidity pool. Liquidity is provided over tick ranges through positions,
+/// the price is sqrt(price of a in b) in Q64.64 and fee growth is per unit of liquidity.
+/// Mints are sorted by pubkey.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ClPool {
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub tick_spacing: u16,
+    pub fee: u64,
+    pub fee_decimals: u64,
+    pub sqrt_price: u128,
+    pub current_tick: i32,
+    pub liquidity: u128,
+    pub fee_growth_global_a: u128,
+    pub fee_growth_global_b: u128,
+}
+
+impl ClPool {
+    pub const SEED: &'static [u8] = b"chudex_cl_pool";
+    pub const LEN: usize = 2 * size_of::<Pubkey>()
+        + size_of::<u16>()
+        + 2 * size_of::<u64>()
+        + size_of::<u128>()
+        + size_of::<i32>()
+        + 3 * size_of::<u128>();
+
+    /// Start of the tick array containing `tick`.
+    pub fn tick_array_start(&self, tick: i32) -> i32 {
+        let ticks_per_array = TickArray::SIZE as i32 * self.tick_spacing as i32;
+        tick.div_euclid(ticks_per_array) * ticks_per_array
+    }
+
+    /// Adds `liquidity_delta` to a position bound. A tick seen for the first time assumes
+    /// all fee growth so far happened below it.
+    pub fn update_tick(
+        &self,
+        tick_array: &mut TickArray,
+        tick: i32,
+        liquidity_delta: i128,
+        upper: bool,
+    ) -> Result<(), ChudexError> {
+        let tick_state = tick_array
+            .tick_mut(tick, self.tick_spacing)
+            .ok_or(ChudexError::TickArrayNotFound)?;
+
+        if !tick_state.is_initialized() && tick <= self.current_tick {
+            tick_state.fee_growth_outside_a = self.fee_growth_global_a;
+            tick_state.fee_growth_outside_b = self.fee_growth_global_b;
+        }
+        tick_state.liquidity_gross =
+            cl_math::add_liquidity_delta(tick_state.liquidity_gross, liquidity_delta)?;
+        tick_state.liquidity_net = if upper {
+            tick_state.liquidity_net.checked_sub(liquidity_delta)
+        } else {
+            tick_state.liquidity_net.checked_add(liquidity_delta)
+        }
+        .ok_or(ChudexError::CalculationFailure)?;
+
+        if !tick_state.is_initialized() {
+            *tick_state = Tick::default();
+        }
+        Ok(())
+    }
+
+    /// Moves the price across an initialized tick, flipping its fee growth outside and
+    /// picking up or dropping the liquidity of positions bounded by it.
+    pub fn cross_tick(&mut self, tick_state: &mut Tick, a_to_b: bool) -> Result<(), ChudexError> {
+        tick_state.fee_growth_outside_a = self
+            .fee_growth_global_a
+            .wrapping_sub(tick_state.fee_growth_outside_a);
+        tick_state.fee_growth_outside_b = self
+            .fee_growth_global_b
+            .wrapping_sub(tick_state.fee_growth_outside_b);
+        let liquidity_net = if a_to_b {
+            -tick_state.liquidity_net
+        } else {
+            tick_state.liquidity_net
+        };
+        self.liquidity = cl_math::add_liquidity_delta(self.liquidity, liquidity_net)?;
+        Ok(())
+    }
+
+    /// Fee growth inside [lower, upper] from the ticks' fee growth outside, wrapping like
+    /// the global counters do.
+    pub fn fee_growth_inside(
+        &self,
+        tick_lower: i32,
+        lower: &Tick,
+        tick_upper: i32,
+        upper: &Tick,
+    ) -> (u128, u128) {
+        let (below_a, below_b) = if self.current_tick >= tick_lower {
+            (lower.fee_growth_outside_a, lower.fee_growth_outside_b)
+        } else {
+            (
+                self.fee_growth_global_a
+                    .wrapping_sub(lower.fee_growth_outside_a),
+                self.fee_growth_global_b
+                    .wrapping_sub(lower.fee_growth_outside_b),
+            )
+        };
+        let (above_a, above_b) = if self.current_tick < tick_upper {
+            (upper.fee_growth_outside_a, upper.fee_growth_outside_b)
+        } else {
+            (
+                self.fee_growth_global_a
+                    .wrapping_sub(upper.fee_growth_outside_a),
+                self.fee_growth_global_b
+                    .wrapping_sub(upper.fee_growth_outside_b),
+            )
+        };
+        (
+            self.fee_growth_global_a
+                .wrapping_sub(below_a)
+                .wrapping_sub(above_a),
+            self.fee_growth_global_b
+                .wrapping_sub(below_b)
+                .wrapping_sub(above_b),
+        )
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct Tick {
+    pub liquidity_net: i128,
+    pub liquidity_gross: u128,
+    pub fee_growth_outside_a: u128,
+    pub fee_growth_outside_b: u128,
+}
+
+impl Tick {
+    pub const LEN: usize = size_of::<i128>() + 3 * size_of::<u128>();
+
+    pub fn is_initialized(&self) -> bool {
+        self.liquidity_gross > 0
+    }
+}
+
+/// A run of `SIZE` ticks, `tick_spacing` apart, starting at `start_tick`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct TickArray {
+    pub pool: Pubkey,
+    pub start_tick: i32,
+    pub ticks: Vec<Tick>,
+}
+
+impl TickArray {
+    pub const SEED: &'static [u8] = b"chudex_tick_array";
+    pub const SIZE: usize = 64;
+    pub const LEN: usize = size_of::<Pubkey>() + size_of::<i32>() + 4 + Self::SIZE * Tick::LEN;
+
+    fn offset(&self, tick: i32, tick_spacing: u16) -> Option<usize> {
+        let offset = tick.checked_sub(self.start_tick)?;
+        if offset < 0 || offset % tick_spacing as i32 != 0 {
+            return None;
+        }
+        let index = (offset / tick_spacing as i32) as usize;
+        if index < Self::SIZE {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    pub fn tick(&self, tick: i32, tick_spacing: u16) -> Option<&Tick> {
+        self.offset(tick, tick_spacing).map(|i| &self.ticks[i])
+    }
+
+    pub fn tick_mut(&mut self, tick: i32, tick_spacing: u16) -> Option<&mut Tick> {
+        self.offset(tick, tick_spacing)
+            .map(move |i| &mut self.ticks[i])
+    }
+
+    /// Last tick covered by this array.
+    pub fn end_tick(&self, tick_spacing: u16) -> i32 {
+        self.start_tick + (Self::SIZE as i32 - 1) * tick_spacing as i32
+    }
+
+    /// Next initialized tick at or below `tick` (a to b) or strictly above (b to a).
+    pub fn next_initialized_tick(&self, tick: i32, tick_spacing: u16, a_to_b: bool) -> Option<i32> {
+        let spacing = tick_spacing as i32;
+        let ticks = (0..Self::SIZE).map(|i| self.start_tick + i as i32 * spacing);
+        if a_to_b {
+            ticks
+                .rev()
+                .find(|t| *t <= tick && self.tick(*t, tick_spacing).unwrap().is_initialized())
+        } else {
+            ticks
+                .into_iter()
+                .find(|t| *t > tick && self.tick(*t, tick_spacing).unwrap().is_initialized())
+        }
+    }
+}
+
+/// Liquidity provided by `owner` over [tick_lower, tick_upper] of a concentrated pool.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Position {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: u128,
+    pub fee_growth_inside_last_a: u128,
+    pub fee_growth_inside_last_b: u128,
+    pub fees_owed_a: u64,
+    pub fees_owed_b: u64,
+}
+
+impl Position {
+    pub const SEED: &'static [u8] = b"chudex_position";
+    pub const LEN: usize = 2 * size_of::<Pubkey>()
+        + 2 * size_of::<i32>()
+        + 3 * size_of::<u128>()
+        + 2 * size_of::<u64>();
+
+    /// Accrues fees earned since the last update at the given fee growth inside.
+    pub fn update_fees(
+        &mut self,
+        fee_growth_inside_a: u128,
+        fee_growth_inside_b: u128,
+    ) -> Result<(), ChudexError> {
+        let owed_a = cl_math::fees_owed(
+            fee_growth_inside_a.wrapping_sub(self.fee_growth_inside_last_a),
+            self.liquidity,
+        )?;
+        let owed_b = cl_math::fees_owed(
+            fee_growth_inside_b.wrapping_sub(self.fee_growth_inside_last_b),
+            self.liquidity,
+        )?;
+        self.fees_owed_a = self.fees_owed_a.saturating_add(owed_a);
+        self.fees_owed_b = self.fees_owed_b.saturating_add(owed_b);
+        self.fee_growth_inside_last_a = fee_growth_inside_a;
+        self.fee_growth_inside_last_b = fee_growth_inside_b;
+        Ok(())
+    }
+}
+
+/// Tokens escrowed to sell into a pool once its price reaches `min_amount_out` for
+/// `amount_in`. Anyone can fill it through the pool for a keeper fee taken from the output.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LimitOrder {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub nonce: u64,
+    pub mint_in: Pubkey,
+    pub mint_out: Pubkey,
+    pub owner_token_out: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+}
+
+impl LimitOrder {
+    pub const SEED: &'static [u8] = b"chudex_limit_order";
+    pub const LEN: usize = 5 * size_of::<Pubkey>() + 3 * size_of::<u64>();
+    /// Keeper fee of 0.1% of the output.
+    pub const KEEPER_FEE: u64 = 1;
+    pub const KEEPER_FEE_DECIMALS: u64 = 3;
+}
+
+/// Price a conditional order's trigger is checked against.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum TriggerSource {
+    /// The pool's price right now. A swap earlier in the same transaction can push it across
+    /// the trigger, only the order's minimum out bounds what that execution gets.
+    Spot,
+    /// The pool's average price over at least the last `window` seconds, from its oracle.
+    Twap { window: u32 },
+}
+
+/// Tokens escrowed to sell into a pool once its price of mint in crosses `trigger_price`,
+/// at or below it for a stop loss, at or above for a take profit. Anyone can execute it
+/// through the exchange until `expiry_timestamp`, for a keeper bounty out of the escrow.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ConditionalOrder {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub nonce: u64,
+    pub mint_in: Pubkey,
+    pub mint_out: Pubkey,
+    pub owner_token_out: Pubkey,
+    pub amount_in: u64,
+    pub min_amount_out: u64,
+    /// Mint out per mint in, raw amounts in Q64.64.
+    pub trigger_price: u128,
+    pub take_profit: bool,
+    pub trigger_source: TriggerSource,
+    pub expiry_timestamp: i64,
+}
+
+impl ConditionalOrder {
+    pub const SEED: &'static [u8] = b"chudex_conditional";
+    /// Keeper bounty of 0.1% of the escrow.
+    pub const KEEPER_BOUNTY: u64 = 1;
+    pub const KEEPER_BOUNTY_DECIMALS: u64 = 3;
+
+    /// Serialized size, with a spot or a twap trigger.
+    pub fn len(twap: bool) -> usize {
+        5 * size_of::<Pubkey>()
+            + 3 * size_of::<u64>()
+            + size_of::<u128>()
+            + 1
+            + 1
+            + twap as usize * size_of::<u32>()
+            + size_of::<i64>()
+    }
+
+    /// Whether `price`, mint out per mint in in Q64.64, crosses the trigger.
+    pub fn triggered(&self, price: u128) -> bool {
+        if self.take_profit {
+            price >= self.trigger_price
+        } else {
+            price <= self.trigger_price
+        }
+    }
+}
+
+/// Recurring buy: every `interval_seconds`, anyone can sell `amount_per_cycle` of the
+/// escrow into a pool for at least `min_amount_out_per_cycle`, paying out to the owner's
+/// associated token acc, for a keeper bounty out of the cycle. The last cycle sells what's
+/// left.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Dca {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub nonce: u64,
+    pub mint_in: Pubkey,
+    pub mint_out: Pubkey,
+    pub amount_per_cycle: u64,
+    pub min_amount_out_per_cycle: u64,
+    pub interval_seconds: u32,
+    pub cycles_left: u32,
+    pub next_cycle_timestamp: i64,
+}
+
+impl Dca {
+    pub const SEED: &'static [u8] = b"chudex_dca";
+    pub const LEN: usize =
+        4 * size_of::<Pubkey>() + 3 * size_of::<u64>() + 2 * size_of::<u32>() + size_of::<i64>();
+    /// Keeper bounty of 0.1% of each cycle.
+    pub const KEEPER_BOUNTY: u64 = 1;
+    pub const KEEPER_BOUNTY_DECIMALS: u64 = 3;
+}
+
+/// Long-term order pools of a constant product pool. Each direction sells at a combined rate
+/// per slot, scaled by 2^RATE_SHIFT, and earns proceeds tracked per unit of rate in Q64.64.
+/// Orders end on `ORDER_INTERVAL` boundaries, their rates drop out through `buckets`.
+/// Buckets whose place in the ring is taken while orders are still to withdraw move to
+/// their own accounts, counted in `expired_open_orders`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct TwammPool {
+    pub pool: Pubkey,
+    pub last_slot: u64,
+    pub sell_rate_a: u128,
+    pub sell_rate_b: u128,
+    pub earnings_factor_a: u128,
+    pub earnings_factor_b: u128,
+    pub buckets: Vec<ExpiryBucket>,
+    pub expired_open_orders: u32,
+}
+
+impl TwammPool {
+    pub const SEED: &'static [u8] = b"chudex_twamm";
+    pub const ORDER_INTERVAL: u64 = 9000;
+    pub const MAX_BUCKETS: usize = 128;
+    pub const RATE_SHIFT: u32 = 32;
+    pub const LEN: usize = size_of::<Pubkey>()
+        + size_of::<u64>()
+        + 4 * size_of::<u128>()
+        + 4
+        + Self::MAX_BUCKETS * ExpiryBucket::LEN
+        + size_of::<u32>();
+
+    pub fn bucket_mut(&mut self, end_slot: u64) -> &mut ExpiryBucket {
+        let index = (end_slot / Self::ORDER_INTERVAL) as usize % Self::MAX_BUCKETS;
+        &mut self.buckets[index]
+    }
+
+    /// Makes `end_slot`'s ring place its bucket. An expired bucket from an earlier lap that
+    /// still has orders to withdraw is returned, to move to its own account.
+    pub fn claim_bucket(&mut self, end_slot: u64) -> Option<ExpiryBucket> {
+        let bucket = self.bucket_mut(end_slot);
+        if bucket.end_slot == end_slot {
+            return None;
+        }
+        let expired = std::mem::replace(
+            bucket,
+            ExpiryBucket {
+                end_slot,
+                ..ExpiryBucket::default()
+            },
+        );
+        if expired.open_orders == 0 {
+            return None;
+        }
+        self.expired_open_orders += expired.open_orders;
+        Some(expired)
+    }
+}
+
+/// Orders ending at `end_slot`: the rates that stop then, the earnings factors at that
+/// point for settling them later, and how many are still to be withdrawn. Lives in the
+/// twamm's ring until the ring wraps around to it, then at its own address if it still has
+/// orders.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct ExpiryBucket {
+    pub end_slot: u64,
+    pub expiring_rate_a: u128,
+    pub expiring_rate_b: u128,
+    pub earnings_factor_a: u128,
+    pub earnings_factor_b: u128,
+    pub open_orders: u32,
+}
+
+impl ExpiryBucket {
+    pub const SEED: &'static [u8] = b"chudex_twamm_bucket";
+    pub const LEN: usize = size_of::<u64>() + 4 * size_of::<u128>() + size_of::<u32>();
+}
+
+/// Sells tokens a (a_to_b) or b into the pool at `sell_rate` until `end_slot`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LongTermOrder {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub nonce: u64,
+    pub a_to_b: bool,
+    pub sell_rate: u128,
+    pub end_slot: u64,
+    pub earnings_factor_last: u128,
+}
+
+impl LongTermOrder {
+    pub const SEED: &'static [u8] = b"chudex_twamm_order";
+    pub const LEN: usize = 2 * size_of::<Pubkey>()
+        + size_of::<u64>()
+        + size_of::<bool>()
+        + size_of::<u128>()
+        + size_of::<u64>()
+        + size_of::<u128>();
+}
+
+/// Liquidity mining farm paying rewards to a pool's tokens staked in it. Every reward mint
+/// streams its emission rate per slot from `start_slot` to `end_slot`, split by stake.
+/// Rewards emitted while nothing is staked are set aside for the authority to reclaim.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Farm {
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub nonce: u64,
+    pub start_slot: u64,
+    pub end_slot: u64,
+    pub last_update_slot: u64,
+    pub total_staked: u64,
+    pub rewards: Vec<FarmReward>,
+}
+
+impl Farm {
+    pub const SEED: &'static [u8] = b"chudex_farm";
+    pub const MAX_REWARDS: usize = 4;
+
+    /// Serialized size for a farm paying `num_rewards` reward mints.
+    pub fn len(num_rewards: usize) -> usize {
+        3 * size_of::<Pubkey>() + 5 * size_of::<u64>() + 4 + num_rewards * FarmReward::LEN
+    }
+
+    /// Accrues every reward's per share amount up to `slot`.
+    pub fn update(&mut self, slot: u64) -> Result<(), ChudexError> {
+        let start = self.last_update_slot.max(self.start_slot);
+        let end = slot.min(self.end_slot);
+        if end > start {
+            let elapsed = (end - start) as u128;
+            for reward in self.rewards.iter_mut() {
+                let emitted = (reward.emission_rate as u128)
+                    .checked_mul(elapsed)
+                    .ok_or(ChudexError::CalculationFailure)?;
+                if self.total_staked == 0 {
+                    // nobody to pay, the farm was funded for it so it fits in a u64
+                    reward.unallocated = u64::try_from(emitted)
+                        .ok()
+                        .and_then(|emitted| reward.unallocated.checked_add(emitted))
+                        .ok_or(ChudexError::CalculationFailure)?;
+                    continue;
+                }
+                let emitted_per_share = emitted
+                    .checked_mul(1 << 64)
+                    .ok_or(ChudexError::CalculationFailure)?
+                    / self.total_staked as u128;
+                reward.reward_per_share = reward.reward_per_share.wrapping_add(emitted_per_share);
+            }
+        }
+        self.last_update_slot = self.last_update_slot.max(slot);
+        Ok(())
+    }
+}
+
+/// A reward mint of a farm and what it has paid per staked pool token so far, in Q64.64.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct FarmReward {
+    pub mint: Pubkey,
+    pub emission_rate: u64,
+    pub reward_per_share: u128,
+    /// Emitted while nothing was staked, not yet reclaimed by the authority.
+    pub unallocated: u64,
+}
+
+impl FarmReward {
+    pub const LEN: usize = size_of::<Pubkey>() + 2 * size_of::<u64>() + size_of::<u128>();
+}
+
+/// Pool tokens staked by `owner` in a farm, with rewards owed per farm reward mint.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Stake {
+    pub farm: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub reward_per_share_last: Vec<u128>,
+    pub rewards_owed: Vec<u64>,
+}
+
+impl Stake {
+    pub const SEED: &'static [u8] = b"chudex_stake";
+
+    /// Serialized size for a stake in a farm paying `num_rewards` reward mints.
+    pub fn len(num_rewards: usize) -> usize {
+        2 * size_of::<Pubkey>()
+            + size_of::<u64>()
+            + (4 + num_rewards * size_of::<u128>())
+            + (4 + num_rewards * size_of::<u64>())
+    }
+
+    /// Accrues rewards earned since the last update, the farm must be updated first.
+    pub fn update_rewards(&mut self, farm: &Farm) -> Result<(), ChudexError> {
+        for (i, reward) in farm.rewards.iter().enumerate() {
+            let owed = cl_math::fees_owed(
+                reward
+                    .reward_per_share
+                    .wrapping_sub(self.reward_per_share_last[i]),
+                self.amount as u128,
+            )?;
+            self.rewards_owed[i] = self.rewards_owed[i].saturating_add(owed);
+            self.reward_per_share_last[i] = reward.reward_per_share;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn twamm() -> TwammPool {
+        TwammPool {
+            pool: Pubkey::new_unique(),
+            last_slot: 0,
+            sell_rate_a: 0,
+            sell_rate_b: 0,
+            earnings_factor_a: 0,
+            earnings_factor_b: 0,
+            buckets: vec![ExpiryBucket::default(); TwammPool::MAX_BUCKETS],
+            expired_open_orders: 0,
+        }
+    }
+
+    #[test]
+    fn claim_bucket_reuses_empty_expired_bucket() {
+        let mut twamm = twamm();
+        let end_slot = TwammPool::ORDER_INTERVAL;
+        assert!(twamm.claim_bucket(end_slot).is_none());
+        twamm.bucket_mut(end_slot).open_orders = 1;
+        // same end slot keeps the bucket
+        assert!(twamm.claim_bucket(end_slot).is_none());
+        assert_eq!(twamm.bucket_mut(end_slot).open_orders, 1);
+
+        // a lap later, with its order withdrawn, the place is just reset
+        twamm.bucket_mut(end_slot).open_orders = 0;
+        let next_lap = end_slot + TwammPool::MAX_BUCKETS as u64 * TwammPool::ORDER_INTERVAL;
+        assert!(twamm.claim_bucket(next_lap).is_none());
+        assert_eq!(twamm.bucket_mut(next_lap).end_slot, next_lap);
+        assert_eq!(twamm.expired_open_orders, 0);
+    }
+
+    #[test]
+    fn claim_bucket_moves_out_bucket_with_open_orders() {
+        let mut twamm = twamm();
+        let end_slot = TwammPool::ORDER_INTERVAL;
+        twamm.claim_bucket(end_slot);
+        let bucket = twamm.bucket_mut(end_slot);
+        bucket.open_orders = 2;
+        bucket.earnings_factor_a = 7;
+
+        // a dust order left unwithdrawn doesn't block the place a lap later
+        let next_lap = end_slot + TwammPool::MAX_BUCKETS as u64 * TwammPool::ORDER_INTERVAL;
+        let expired = twamm.claim_bucket(next_lap).unwrap();
+        assert_eq!(expired.end_slot, end_slot);
+        assert_eq!(expired.open_orders, 2);
+        assert_eq!(expired.earnings_factor_a, 7);
+        assert_eq!(twamm.expired_open_orders, 2);
+        let bucket = twamm.bucket_mut(next_lap);
+        assert_eq!(bucket.end_slot, next_lap);
+        assert_eq!(bucket.open_orders, 0);
+    }
+
+    fn farm(emission_rate: u64, total_staked: u64) -> Farm {
+        Farm {
+            pool: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            authority: Pubkey::new_unique(),
+            nonce: 0,
+            start_slot: 100,
+            end_slot: 200,
+            last_update_slot: 100,
+            total_staked,
+            rewards: vec![FarmReward {
+                mint: Pubkey::new_unique(),
+                emission_rate,
+                reward_per_share: 0,
+                unallocated: 0,
+            }],
+        }
+    }
+
+    #[test]
+    fn farm_update_splits_emission_by_stake() {
+        let mut farm = farm(10, 4);
+        farm.update(150).unwrap();
+        // 500 emitted over 4 staked, Q64.64
+        assert_eq!(farm.rewards[0].reward_per_share, 125 << 64);
+        assert_eq!(farm.last_update_slot, 150);
+
+        // nothing more after the end
+        farm.update(300).unwrap();
+        assert_eq!(farm.rewards[0].reward_per_share, 250 << 64);
+        assert_eq!(farm.rewards[0].unallocated, 0);
+    }
+
+    #[test]
+    fn farm_update_sets_aside_unstaked_emission() {
+        let mut farm = farm(10, 0);
+        farm.update(130).unwrap();
+        assert_eq!(farm.rewards[0].reward_per_share, 0);
+        assert_eq!(farm.rewards[0].unallocated, 300);
+
+        // stakers only earn from when they joined
+        farm.total_staked = 1;
+        farm.update(200).unwrap();
+        assert_eq!(farm.rewards[0].reward_per_share, 700 << 64);
+        assert_eq!(farm.rewards[0].unallocated, 300);
+    }
+
+    #[test]
+    fn farm_update_fails_instead_of_wrapping() {
+        // more than a u64 emitted per staked token doesn't fit in Q64.64
+        let mut overflowing = farm(u64::MAX, 1);
+        assert_eq!(
+            overflowing.update(200),
+            Err(ChudexError::CalculationFailure)
+        );
+
+        // the most a funded farm emits, over one staked token
+        let mut funded = farm(u64::MAX / 100, 1);
+        funded.update(200).unwrap();
+        assert_eq!(
+            funded.rewards[0].reward_per_share,
+            ((u64::MAX / 100 * 100) as u128) << 64
+        );
+    }
 }
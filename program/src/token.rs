@@ -0,0 +1,401 @@
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction, system_program,
+    sysvar::{rent, Sysvar},
+};
+
+use spl_token_2022::{
+    extension::{
+        transfer_fee::{TransferFee, TransferFeeConfig},
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    state::{Account, Mint},
+};
+
+use crate::{error::ChudexError, utils::assert_msg};
+
+/// Whether `key` is the spl token program or token-2022.
+pub fn is_token_program(key: &Pubkey) -> bool {
+    *key == spl_token::id() || *key == spl_token_2022::id()
+}
+
+/// Unpacks a mint owned by either token program, skipping over any extensions.
+pub fn unpack_mint(mint_ai: &AccountInfo) -> Result<Mint, ProgramError> {
+    assert_msg(
+        is_token_program(mint_ai.owner),
+        ChudexError::InvalidAccountOwner.into(),
+        "mint not owned by a token program",
+    )?;
+    let data = mint_ai.try_borrow_data()?;
+    Ok(StateWithExtensions::<Mint>::unpack(&data)?.base)
+}
+
+/// Unpacks a token account owned by either token program, skipping over any extensions.
+pub fn unpack_token_account(token_account_ai: &AccountInfo) -> Result<Account, ProgramError> {
+    assert_msg(
+        is_token_program(token_account_ai.owner),
+        ChudexError::InvalidAccountOwner.into(),
+        "token account not owned by a token program",
+    )?;
+    let data = token_account_ai.try_borrow_data()?;
+    Ok(StateWithExtensions::<Account>::unpack(&data)?.base)
+}
+
+/// The mint's transfer fee this epoch, a zero fee when it has no transfer fee extension.
+pub fn epoch_transfer_fee(mint_ai: &AccountInfo) -> Result<TransferFee, ProgramError> {
+    if *mint_ai.owner != spl_token_2022::id() {
+        return Ok(TransferFee::default());
+    }
+    let data = mint_ai.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+    match mint.get_extension::<TransferFeeConfig>() {
+        Ok(config) => Ok(*config.get_epoch_fee(Clock::get()?.epoch)),
+        Err(_) => Ok(TransferFee::default()),
+    }
+}
+
+/// Amount withheld by the mint's transfer fee extension when sending `amount`.
+pub fn transfer_fee(mint_ai: &AccountInfo, amount: u64) -> Result<u64, ProgramError> {
+    epoch_transfer_fee(mint_ai)?
+        .calculate_fee(amount)
+        .ok_or_else(|| ChudexError::CalculationFailure.into())
+}
+
+/// Amount that arrives when sending `amount`.
+pub fn amount_after_transfer_fee(mint_ai: &AccountInfo, amount: u64) -> Result<u64, ProgramError> {
+    amount
+        .checked_sub(transfer_fee(mint_ai, amount)?)
+        .ok_or_else(|| ChudexError::CalculationFailure.into())
+}
+
+/// Smallest amount to send so that at least `net_amount` arrives.
+pub fn amount_before_transfer_fee(
+    mint_ai: &AccountInfo,
+    net_amount: u64,
+) -> Result<u64, ProgramError> {
+    if *mint_ai.owner != spl_token_2022::id() || net_amount == 0 {
+        return Ok(net_amount);
+    }
+    let data = mint_ai.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&data)?;
+    let config = match mint.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config,
+        Err(_) => return Ok(net_amount),
+    };
+    let epoch = Clock::get()?.epoch;
+    let fee = config.get_epoch_fee(epoch);
+    let basis_points = u16::from(fee.transfer_fee_basis_points) as u128;
+    let maximum_fee = u64::from(fee.maximum_fee);
+    if basis_points == 0 {
+        return Ok(net_amount);
+    }
+
+    // gross = net / (1 - bps), capped at net + max fee
+    let capped = net_amount
+        .checked_add(maximum_fee)
+        .ok_or(ChudexError::CalculationFailure)?;
+    let gross = if basis_points >= 10_000 {
+        capped
+    } else {
+        let denominator = 10_000 - basis_points;
+        let gross = (net_amount as u128 * 10_000).div_ceil(denominator);
+        u64::try_from(gross)
+            .map_err(|_| ChudexError::CalculationFailure)?
+            .min(capped)
+    };
+
+    // the token program rounds the fee up, which can leave us one short
+    let fee = config
+        .calculate_epoch_fee(epoch, gross)
+        .ok_or(ChudexError::CalculationFailure)?;
+    if gross - fee < net_amount {
+        Ok(gross + 1)
+    } else {
+        Ok(gross)
+    }
+}
+
+/// `transfer_checked` through whichever token program owns the mint.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer<'a>(
+    token_program: &AccountInfo<'a>,
+    source: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    amount: u64,
+    decimals: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    assert_msg(
+        mint.owner == token_program.key,
+        ChudexError::InvalidAccountOwner.into(),
+        "mint not owned by token program",
+    )?;
+    let ix = if *token_program.key == spl_token::id() {
+        spl_token::instruction::transfer_checked(
+            token_program.key,
+            source.key,
+            mint.key,
+            destination.key,
+            authority.key,
+            &[],
+            amount,
+            decimals,
+        )?
+    } else {
+        spl_token_2022::instruction::transfer_checked(
+            token_program.key,
+            source.key,
+            mint.key,
+            destination.key,
+            authority.key,
+            &[],
+            amount,
+            decimals,
+        )?
+    };
+    invoke_signed(
+        &ix,
+        &[
+            source.clone(),
+            mint.clone(),
+            destination.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )
+}
+
+/// `burn_checked` through whichever token program owns the mint.
+pub fn burn<'a>(
+    token_program: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    amount: u64,
+    decimals: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    assert_msg(
+        mint.owner == token_program.key,
+        ChudexError::InvalidAccountOwner.into(),
+        "mint not owned by token program",
+    )?;
+    let ix = if *token_program.key == spl_token::id() {
+        spl_token::instruction::burn_checked(
+            token_program.key,
+            account.key,
+            mint.key,
+            authority.key,
+            &[],
+            amount,
+            decimals,
+        )?
+    } else {
+        spl_token_2022::instruction::burn_checked(
+            token_program.key,
+            account.key,
+            mint.key,
+            authority.key,
+            &[],
+            amount,
+            decimals,
+        )?
+    };
+    invoke_signed(
+        &ix,
+        &[
+            account.clone(),
+            mint.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )
+}
+
+/// `close_account` through whichever token program owns the account.
+pub fn close_account<'a>(
+    token_program: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    signer_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = if *token_program.key == spl_token::id() {
+        spl_token::instruction::close_account(
+            token_program.key,
+            account.key,
+            destination.key,
+            authority.key,
+            &[],
+        )?
+    } else {
+        spl_token_2022::instruction::close_account(
+            token_program.key,
+            account.key,
+            destination.key,
+            authority.key,
+            &[],
+        )?
+    };
+    invoke_signed(
+        &ix,
+        &[
+            account.clone(),
+            destination.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        signer_seeds,
+    )
+}
+
+/// Associated token account create instruction for a mint owned by `token_program_id`.
+pub fn create_associated_token_account(
+    funder: &Pubkey,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program_id: &Pubkey,
+) -> Instruction {
+    let (associated_token_address, _) = Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program_id.as_ref(), mint.as_ref()],
+        &spl_associated_token_account::id(),
+    );
+    Instruction {
+        program_id: spl_associated_token_account::id(),
+        accounts: vec![
+            AccountMeta::new(*funder, true),
+            AccountMeta::new(associated_token_address, false),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(*token_program_id, false),
+            AccountMeta::new_readonly(rent::id(), false),
+        ],
+        data: vec![],
+    }
+}
+
+/// Seed of the temporary wSOL account used to wrap and unwrap native SOL for a user.
+pub const WSOL_SEED: &[u8] = b"chudex_wsol";
+
+/// Address of `user`'s temporary wSOL account.
+pub fn wsol_address(program_id: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WSOL_SEED, user.as_ref()], program_id)
+}
+
+/// Creates `user`'s temporary wSOL account and wraps `lamports` from the user into it.
+/// The account is owned by the user so it can sign transfers out of it as usual.
+#[allow(clippy::too_many_arguments)]
+pub fn wrap_sol<'a>(
+    program_id: &Pubkey,
+    user: &AccountInfo<'a>,
+    wsol_ai: &AccountInfo<'a>,
+    native_mint_ai: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    sysvar_rent: &AccountInfo<'a>,
+    lamports: u64,
+) -> ProgramResult {
+    let (wsol_key, wsol_bump) = wsol_address(program_id, user.key);
+    assert_msg(
+        *wsol_ai.key == wsol_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "wsol account pda aint right",
+    )?;
+    assert_msg(
+        *native_mint_ai.key == spl_token::native_mint::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Native mint wrong address",
+    )?;
+    assert_msg(
+        *token_program.key == spl_token::id(),
+        ChudexError::InvalidAccountAddress.into(),
+        "Token program wrong address",
+    )?;
+    let wsol_seeds = &[WSOL_SEED, user.key.as_ref(), &[wsol_bump]];
+
+    // create and initialize. Anyone can send lamports to the address first, which would make
+    // create_account fail, so top up the rent and allocate and assign it instead
+    let account_len = spl_token::state::Account::LEN;
+    let rent_due = rent::Rent::get()?
+        .minimum_balance(account_len)
+        .saturating_sub(wsol_ai.lamports());
+    if rent_due > 0 {
+        invoke(
+            &system_instruction::transfer(user.key, wsol_ai.key, rent_due),
+            &[user.clone(), wsol_ai.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(wsol_ai.key, account_len as u64),
+        &[wsol_ai.clone(), system_program.clone()],
+        &[wsol_seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(wsol_ai.key, &spl_token::id()),
+        &[wsol_ai.clone(), system_program.clone()],
+        &[wsol_seeds],
+    )?;
+    invoke(
+        &spl_token::instruction::initialize_account(
+            &spl_token::id(),
+            wsol_ai.key,
+            native_mint_ai.key,
+            user.key,
+        )?,
+        &[
+            wsol_ai.clone(),
+            native_mint_ai.clone(),
+            user.clone(),
+            sysvar_rent.clone(),
+        ],
+    )?;
+
+    if lamports == 0 {
+        return Ok(());
+    }
+
+    // fund and sync
+    invoke(
+        &system_instruction::transfer(user.key, wsol_ai.key, lamports),
+        &[user.clone(), wsol_ai.clone(), system_program.clone()],
+    )?;
+    invoke(
+        &spl_token::instruction::sync_native(&spl_token::id(), wsol_ai.key)?,
+        std::slice::from_ref(wsol_ai),
+    )
+}
+
+/// Closes `user`'s temporary wSOL account, returning its rent and any wrapped SOL to the user.
+pub fn unwrap_sol<'a>(
+    user: &AccountInfo<'a>,
+    wsol_ai: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    invoke(
+        &spl_token::instruction::close_account(
+            &spl_token::id(),
+            wsol_ai.key,
+            user.key,
+            user.key,
+            &[],
+        )?,
+        &[
+            wsol_ai.clone(),
+            user.clone(),
+            user.clone(),
+            token_program.clone(),
+        ],
+    )
+}
@@ -0,0 +1,339 @@
+//! Long-term orders streamed into a constant product pool.
+//!
+//! Between two touches of the pool, both order pools sell at their rates. The overlapping
+//! part is matched between them at the spot price, and only the rest is swapped through the
+//! pool, so opposing flows don't pay the curve or move the price.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{clock::Clock, Sysvar},
+};
+
+use spl_token_2022::extension::transfer_fee::TransferFee;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    curve,
+    error::ChudexError,
+    events::{ChudexEvent, LongTermOrdersSettled},
+    state::{LongTermOrder, Pool, TwammPool},
+    token,
+    utils::assert_msg,
+};
+
+fn to_u64(amount: u128) -> Result<u64, ChudexError> {
+    u64::try_from(amount).map_err(|_| ChudexError::CalculationFailure)
+}
+
+fn after_fee(transfer_fee: &TransferFee, amount: u64) -> Result<u64, ChudexError> {
+    transfer_fee
+        .calculate_post_fee_amount(amount)
+        .ok_or(ChudexError::CalculationFailure)
+}
+
+/// Runs the order pools up to `now`, ending orders at their interval boundaries.
+/// Returns the net amounts of a and b moving from the twamm vaults into the pool vaults,
+/// negative when moving out of the pool.
+/// Swaps are priced on what arrives after the mints' transfer fees, and sellers are
+/// credited what arrives back. settle moves the net amounts in one transfer per mint, and
+/// its fee is never more than the fees of the parts, so neither side comes up short.
+#[allow(clippy::too_many_arguments)]
+pub fn advance(
+    twamm: &mut TwammPool,
+    now: u64,
+    mut reserve_a: u64,
+    mut reserve_b: u64,
+    fee: u64,
+    fee_decimals: u64,
+    transfer_fee_a: &TransferFee,
+    transfer_fee_b: &TransferFee,
+) -> Result<(i128, i128), ChudexError> {
+    let mut to_pool_a: i128 = 0;
+    let mut to_pool_b: i128 = 0;
+
+    while twamm.last_slot < now {
+        // nothing streaming, so no orders left to end either
+        if twamm.sell_rate_a == 0 && twamm.sell_rate_b == 0 {
+            twamm.last_slot = now;
+            break;
+        }
+
+        let boundary =
+            (twamm.last_slot / TwammPool::ORDER_INTERVAL + 1) * TwammPool::ORDER_INTERVAL;
+        let slot = boundary.min(now);
+        let elapsed = (slot - twamm.last_slot) as u128;
+        let sold_a = to_u64((twamm.sell_rate_a * elapsed) >> TwammPool::RATE_SHIFT)?;
+        let sold_b = to_u64((twamm.sell_rate_b * elapsed) >> TwammPool::RATE_SHIFT)?;
+
+        if reserve_a == 0 || reserve_b == 0 {
+            msg!("pool empty, long-term orders not traded");
+        } else if (sold_a as u128) * (reserve_b as u128) >= (sold_b as u128) * (reserve_a as u128) {
+            // a sellers outweigh b sellers: match all of b at spot, swap the rest of a
+            let matched_a = to_u64(sold_b as u128 * reserve_a as u128 / reserve_b as u128)?;
+            let swap_in = sold_a - matched_a;
+            let swap_in_received = after_fee(transfer_fee_a, swap_in)?;
+            let (swap_out, _) = if swap_in_received > 0 {
+                curve::swap(swap_in_received, reserve_a, reserve_b, fee, fee_decimals)?
+            } else {
+                (0, 0)
+            };
+            reserve_a += swap_in_received;
+            reserve_b -= swap_out;
+            to_pool_a += swap_in as i128;
+            to_pool_b -= swap_out as i128;
+
+            let proceeds_a = sold_b
+                .checked_add(after_fee(transfer_fee_b, swap_out)?)
+                .ok_or(ChudexError::CalculationFailure)?;
+            credit(twamm, proceeds_a, matched_a);
+        } else {
+            // b sellers outweigh a sellers: match all of a at spot, swap the rest of b
+            let matched_b = to_u64(sold_a as u128 * reserve_b as u128 / reserve_a as u128)?;
+            let swap_in = sold_b - matched_b;
+            let swap_in_received = after_fee(transfer_fee_b, swap_in)?;
+            let (swap_out, _) = if swap_in_received > 0 {
+                curve::swap(swap_in_received, reserve_b, reserve_a, fee, fee_decimals)?
+            } else {
+                (0, 0)
+            };
+            reserve_b += swap_in_received;
+            reserve_a -= swap_out;
+            to_pool_b += swap_in as i128;
+            to_pool_a -= swap_out as i128;
+
+            let proceeds_b = sold_a
+                .checked_add(after_fee(transfer_fee_a, swap_out)?)
+                .ok_or(ChudexError::CalculationFailure)?;
+            credit(twamm, matched_b, proceeds_b);
+        }
+        twamm.last_slot = slot;
+
+        // end the orders expiring at this boundary, keeping their earnings for withdrawal
+        if slot == boundary {
+            let earnings_factor_a = twamm.earnings_factor_a;
+            let earnings_factor_b = twamm.earnings_factor_b;
+            let bucket = twamm.bucket_mut(slot);
+            if bucket.end_slot == slot {
+                bucket.earnings_factor_a = earnings_factor_a;
+                bucket.earnings_factor_b = earnings_factor_b;
+                let (expiring_rate_a, expiring_rate_b) =
+                    (bucket.expiring_rate_a, bucket.expiring_rate_b);
+                bucket.expiring_rate_a = 0;
+                bucket.expiring_rate_b = 0;
+                twamm.sell_rate_a -= expiring_rate_a;
+                twamm.sell_rate_b -= expiring_rate_b;
+            }
+        }
+    }
+
+    Ok((to_pool_a, to_pool_b))
+}
+
+/// Adds proceeds in b to a sellers and in a to b sellers, per unit of sell rate.
+fn credit(twamm: &mut TwammPool, proceeds_a: u64, proceeds_b: u64) {
+    if let Some(per_rate) = ((proceeds_a as u128) << 64).checked_div(twamm.sell_rate_a) {
+        twamm.earnings_factor_a = twamm.earnings_factor_a.wrapping_add(per_rate);
+    }
+    if let Some(per_rate) = ((proceeds_b as u128) << 64).checked_div(twamm.sell_rate_b) {
+        twamm.earnings_factor_b = twamm.earnings_factor_b.wrapping_add(per_rate);
+    }
+}
+
+/// Proceeds earned by an order since it last withdrew, up to `earnings_factor`.
+pub fn order_proceeds(order: &LongTermOrder, earnings_factor: u128) -> Result<u64, ChudexError> {
+    let delta = earnings_factor.wrapping_sub(order.earnings_factor_last);
+    to_u64(
+        order
+            .sell_rate
+            .checked_mul(delta)
+            .ok_or(ChudexError::CalculationFailure)?
+            >> 64,
+    )
+}
+
+/// Tokens an order has yet to sell at `now`.
+pub fn order_unsold(order: &LongTermOrder, now: u64) -> Result<u64, ChudexError> {
+    let remaining = order.end_slot.saturating_sub(now) as u128;
+    to_u64(
+        order
+            .sell_rate
+            .checked_mul(remaining)
+            .ok_or(ChudexError::CalculationFailure)?
+            >> TwammPool::RATE_SHIFT,
+    )
+}
+
+/// The twamm and its token accs a and b, in pool mint order.
+pub type TwammAccounts<'b, 'a> = (
+    &'b AccountInfo<'a>,
+    &'b AccountInfo<'a>,
+    &'b AccountInfo<'a>,
+);
+
+/// Takes the twamm accounts next in line if the pool has a twamm. Pools without one are
+/// passed none.
+pub fn next_twamm_accounts<'b, 'a, I: Iterator<Item = &'b AccountInfo<'a>>>(
+    pool: &Pool,
+    accounts_iter: &mut I,
+) -> Result<Option<TwammAccounts<'b, 'a>>, ProgramError> {
+    if !pool.twamm {
+        return Ok(None);
+    }
+    Ok(Some((
+        next_account_info(accounts_iter)?,
+        next_account_info(accounts_iter)?,
+        next_account_info(accounts_iter)?,
+    )))
+}
+
+/// Settles a pool's long-term orders up to `now`, moving tokens between the twamm vaults
+/// and the pool vaults and updating the pool's reserves, which the caller saves, and logs
+/// the new reserves if anything traded. Accrues the pool's prices first, as everything
+/// touching the reserves settles before. Does nothing else and returns None without twamm
+/// accounts, or if the twamm isn't set up yet.
+/// Sides are (pool vault, mint, token program), in either order. The caller has verified
+/// the pool, its vaults and their token programs.
+#[allow(clippy::too_many_arguments)]
+pub fn settle<'a>(
+    program_id: &Pubkey,
+    pool_ai: &AccountInfo<'a>,
+    pool: &mut Pool,
+    pool_seeds: &[&[u8]],
+    twamm_accounts: Option<TwammAccounts<'_, 'a>>,
+    side_1: (&AccountInfo<'a>, &AccountInfo<'a>, &AccountInfo<'a>),
+    side_2: (&AccountInfo<'a>, &AccountInfo<'a>, &AccountInfo<'a>),
+    now: u64,
+) -> Result<Option<TwammPool>, ProgramError> {
+    let (
+        (pool_vault_a_ai, mint_a_ai, token_program_a),
+        (pool_vault_b_ai, mint_b_ai, token_program_b),
+    ) = if *side_1.1.key == pool.mint_a {
+        (side_1, side_2)
+    } else {
+        (side_2, side_1)
+    };
+    pool.accrue_prices(Clock::get()?.unix_timestamp);
+    let (twamm_ai, twamm_vault_a_ai, twamm_vault_b_ai) = match twamm_accounts {
+        Some(twamm_accounts) => twamm_accounts,
+        None => return Ok(None),
+    };
+
+    // twamm pda
+    let (twamm_key, twamm_bump) =
+        Pubkey::find_program_address(&[TwammPool::SEED, pool_ai.key.as_ref()], program_id);
+    let twamm_seeds = &[TwammPool::SEED, pool_ai.key.as_ref(), &[twamm_bump]];
+    assert_msg(
+        *twamm_ai.key == twamm_key,
+        ChudexError::InvalidProgramAddress.into(),
+        "twamm pda aint right",
+    )?;
+    if twamm_ai.data_len() == 0 {
+        return Ok(None);
+    }
+    assert_msg(
+        twamm_ai.owner == program_id,
+        ChudexError::InvalidAccountAddress.into(),
+        "Twamm not owned by program",
+    )?;
+
+    // twamm vault pdas
+    let (twamm_vault_a_key, _) = Pubkey::find_program_address(
+        &[
+            twamm_ai.key.as_ref(),
+            token_program_a.key.as_ref(),
+            mint_a_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        twamm_vault_a_key == *twamm_vault_a_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "twamm vault a pda aint right",
+    )?;
+    let (twamm_vault_b_key, _) = Pubkey::find_program_address(
+        &[
+            twamm_ai.key.as_ref(),
+            token_program_b.key.as_ref(),
+            mint_b_ai.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_msg(
+        twamm_vault_b_key == *twamm_vault_b_ai.key,
+        ChudexError::InvalidProgramAddress.into(),
+        "twamm vault b pda aint right",
+    )?;
+
+    let mut twamm = TwammPool::try_from_slice(&twamm_ai.try_borrow_data()?)?;
+    let (to_pool_a, to_pool_b) = advance(
+        &mut twamm,
+        now,
+        pool.reserve_a,
+        pool.reserve_b,
+        pool.effective_fee(now),
+        pool.fee_decimals,
+        &token::epoch_transfer_fee(mint_a_ai)?,
+        &token::epoch_transfer_fee(mint_b_ai)?,
+    )?;
+
+    for (to_pool, reserve, pool_vault_ai, twamm_vault_ai, mint_ai, token_program_ai) in [
+        (
+            to_pool_a,
+            &mut pool.reserve_a,
+            pool_vault_a_ai,
+            twamm_vault_a_ai,
+            mint_a_ai,
+            token_program_a,
+        ),
+        (
+            to_pool_b,
+            &mut pool.reserve_b,
+            pool_vault_b_ai,
+            twamm_vault_b_ai,
+            mint_b_ai,
+            token_program_b,
+        ),
+    ] {
+        let mint = token::unpack_mint(mint_ai)?;
+        if to_pool > 0 {
+            token::transfer(
+                token_program_ai,
+                twamm_vault_ai,
+                mint_ai,
+                pool_vault_ai,
+                twamm_ai,
+                to_pool as u64,
+                mint.decimals,
+                &[twamm_seeds],
+            )?;
+            *reserve += token::amount_after_transfer_fee(mint_ai, to_pool as u64)?;
+        } else if to_pool < 0 {
+            token::transfer(
+                token_program_ai,
+                pool_vault_ai,
+                mint_ai,
+                twamm_vault_ai,
+                pool_ai,
+                to_pool.unsigned_abs() as u64,
+                mint.decimals,
+                &[pool_seeds],
+            )?;
+            *reserve -= to_pool.unsigned_abs() as u64;
+        }
+    }
+
+    twamm.serialize(&mut &mut twamm_ai.try_borrow_mut_data()?[..])?;
+    if to_pool_a != 0 || to_pool_b != 0 {
+        ChudexEvent::LongTermOrdersSettled(LongTermOrdersSettled {
+            pool: *pool_ai.key,
+            reserve_a: pool.reserve_a,
+            reserve_b: pool.reserve_b,
+        })
+        .emit();
+    }
+    Ok(Some(twamm))
+}
@@ -1,4 +1,9 @@
-use solana_program::{entrypoint::ProgramResult, msg, program_error::ProgramError, pubkey::Pubkey};
+use solana_program::{
+    account_info::AccountInfo, bpf_loader_upgradeable, entrypoint::ProgramResult, msg,
+    program_error::ProgramError, pubkey::Pubkey,
+};
+
+use crate::error::ChudexError;
 
 pub fn assert_msg(statement: bool, err: ProgramError, msg: &str) -> ProgramResult {
     if !statement {
@@ -8,3 +13,43 @@ pub fn assert_msg(statement: bool, err: ProgramError, msg: &str) -> ProgramResul
         Ok(())
     }
 }
+
+/// Closes an account owned by this program, sending its rent to `destination`.
+pub fn close_program_account(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    let lamports = account.lamports();
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ChudexError::CalculationFailure)?;
+    **account.try_borrow_mut_lamports()? = 0;
+    account.try_borrow_mut_data()?.fill(0);
+    Ok(())
+}
+
+/// Upgrade authority recorded in the program data account of `program_id`, None once the
+/// program is immutable. Read straight from the loader's layout: a u32 ProgramData tag of 3,
+/// the u64 deploy slot, then the authority as an option tag and address.
+pub fn upgrade_authority(
+    program_id: &Pubkey,
+    program_data_ai: &AccountInfo,
+) -> Result<Option<Pubkey>, ProgramError> {
+    let (program_data_key, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    assert_msg(
+        *program_data_ai.key == program_data_key
+            && *program_data_ai.owner == bpf_loader_upgradeable::id(),
+        ChudexError::InvalidProgramAddress.into(),
+        "program data pda aint right",
+    )?;
+
+    let data = program_data_ai.try_borrow_data()?;
+    assert_msg(
+        data.len() >= 45 && data[..4] == 3u32.to_le_bytes(),
+        ChudexError::InvalidAccountData.into(),
+        "Not a program data account",
+    )?;
+    Ok(match data[12] {
+        1 => Pubkey::try_from(&data[13..45]).ok(),
+        _ => None,
+    })
+}
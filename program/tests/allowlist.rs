@@ -0,0 +1,194 @@
+mod common;
+
+use borsh::BorshSerialize;
+use chudex::{
+    allowlist,
+    error::ChudexError,
+    instruction::ChudexInstruction,
+    state::{Allowlist, AllowlistMode, AllowlistTicket},
+};
+use common::{assert_dex_error, Dex, PoolKeys};
+use solana_program_test::tokio;
+use solana_sdk::{
+    hash,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signer,
+    system_program,
+};
+
+/// Permissioned pool of a 9 and a 6 decimals mint, created after `setup`, the payer
+/// holding a billion of each.
+async fn permissioned_pool(
+    dex: &mut Dex,
+    allowlist: AllowlistMode,
+    setup: Vec<Instruction>,
+) -> PoolKeys {
+    dex.initialize_config(vec![30]).await;
+    let mint_a = dex.create_mint(9).await;
+    let mint_b = dex.create_mint(6).await;
+    let payer = dex.payer();
+    dex.create_token_account(&payer, &mint_a, 1_000_000_000)
+        .await;
+    dex.create_token_account(&payer, &mint_b, 1_000_000_000)
+        .await;
+    let pool = dex.pool_keys(&mint_a, &mint_b).await;
+    let mut instructions = setup;
+    instructions.push(
+        dex.initialize_pool_ix(&payer, &pool, 30, None, Some(allowlist), None)
+            .await,
+    );
+    dex.process(&instructions, &[]).await.unwrap();
+    pool
+}
+
+/// Deposit of liquidity by the payer, passing `proof` as their allowlist account.
+fn deposit_ix(dex: &Dex, pool: &PoolKeys, proof: Pubkey) -> Instruction {
+    let mut deposit = dex.deposit_ix(&dex.payer(), pool, 100_000_000, 100_000_000);
+    deposit
+        .accounts
+        .push(AccountMeta::new_readonly(proof, false));
+    deposit
+}
+
+/// Exchange of a million a by `user`, passing `proof` as their allowlist account.
+fn exchange_ix(dex: &Dex, pool: &PoolKeys, user: &Pubkey, proof: Pubkey) -> Instruction {
+    let mut exchange = dex.exchange_ix(user, pool, true, 1_000_000, 1);
+    exchange
+        .accounts
+        .push(AccountMeta::new_readonly(proof, false));
+    exchange
+}
+
+fn ticket_address(dex: &Dex, pool: &PoolKeys, user: &Pubkey) -> Pubkey {
+    dex.find(&[AllowlistTicket::SEED, pool.pool.as_ref(), user.as_ref()])
+}
+
+fn prove_ix(dex: &Dex, pool: &PoolKeys, user: &Pubkey, proof: Vec<[u8; 32]>) -> Instruction {
+    Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new_readonly(pool.pool, false),
+            AccountMeta::new(ticket_address(dex, pool, user), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: ChudexInstruction::ProveAllowlist { proof }
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn allowlist_account_lets_its_members_trade() {
+    let mut dex = Dex::start().await;
+    let payer = dex.payer();
+    let member = dex.user(1_000_000_000).await;
+    let outsider = dex.user(1_000_000_000).await;
+    let allowlist = dex.find(&[Allowlist::SEED, payer.as_ref(), &0u64.to_le_bytes()]);
+    let create = Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(allowlist, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: ChudexInstruction::CreateAllowlist {
+            nonce: 0,
+            capacity: 4,
+            members: vec![payer, member.pubkey()],
+        }
+        .try_to_vec()
+        .unwrap(),
+    };
+    let pool = permissioned_pool(&mut dex, AllowlistMode::Account(allowlist), vec![create]).await;
+    dex.process(&[deposit_ix(&dex, &pool, allowlist)], &[])
+        .await
+        .unwrap();
+    dex.fund(&member.pubkey(), &pool, 2_000_000, 0).await;
+    dex.fund(&outsider.pubkey(), &pool, 1_000_000, 0).await;
+
+    let exchange = exchange_ix(&dex, &pool, &member.pubkey(), allowlist);
+    dex.process(&[exchange], &[&member]).await.unwrap();
+    assert!(
+        dex.token_balance(&pool.user_token_b(&member.pubkey()))
+            .await
+            > 0
+    );
+
+    let exchange = exchange_ix(&dex, &pool, &outsider.pubkey(), allowlist);
+    let result = dex.process(&[exchange], &[&outsider]).await;
+    assert_dex_error(result, ChudexError::NotAllowlisted);
+
+    // removed members are out too
+    let update = Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(payer, true),
+            AccountMeta::new(allowlist, false),
+        ],
+        data: ChudexInstruction::UpdateAllowlist {
+            add: vec![],
+            remove: vec![member.pubkey()],
+        }
+        .try_to_vec()
+        .unwrap(),
+    };
+    dex.process(&[update], &[]).await.unwrap();
+    let state: Allowlist = dex.state(&allowlist).await;
+    assert_eq!(state.members(), [payer]);
+    dex.refresh_blockhash().await;
+    let exchange = exchange_ix(&dex, &pool, &member.pubkey(), allowlist);
+    let result = dex.process(&[exchange], &[&member]).await;
+    assert_dex_error(result, ChudexError::NotAllowlisted);
+}
+
+#[tokio::test]
+async fn merkle_root_admits_users_who_prove_their_leaf() {
+    let mut dex = Dex::start().await;
+    let payer = dex.payer();
+    let member = dex.user(1_000_000_000).await;
+    let outsider = dex.user(1_000_000_000).await;
+
+    // a two leaf tree, each leaf the other's proof
+    let payer_leaf = allowlist::leaf(&payer);
+    let member_leaf = allowlist::leaf(&member.pubkey());
+    let root = if payer_leaf <= member_leaf {
+        hash::hashv(&[&payer_leaf, &member_leaf])
+    } else {
+        hash::hashv(&[&member_leaf, &payer_leaf])
+    }
+    .to_bytes();
+    let pool = permissioned_pool(&mut dex, AllowlistMode::MerkleRoot(root), vec![]).await;
+    dex.process(
+        &[
+            prove_ix(&dex, &pool, &payer, vec![member_leaf]),
+            deposit_ix(&dex, &pool, ticket_address(&dex, &pool, &payer)),
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+    dex.fund(&member.pubkey(), &pool, 1_000_000, 0).await;
+    dex.fund(&outsider.pubkey(), &pool, 1_000_000, 0).await;
+
+    // with a ticket the proof isn't needed again
+    let prove = prove_ix(&dex, &pool, &member.pubkey(), vec![payer_leaf]);
+    dex.process(&[prove], &[&member]).await.unwrap();
+    let ticket = ticket_address(&dex, &pool, &member.pubkey());
+    let exchange = exchange_ix(&dex, &pool, &member.pubkey(), ticket);
+    dex.process(&[exchange], &[&member]).await.unwrap();
+    assert!(
+        dex.token_balance(&pool.user_token_b(&member.pubkey()))
+            .await
+            > 0
+    );
+
+    let prove = prove_ix(&dex, &pool, &outsider.pubkey(), vec![payer_leaf]);
+    let result = dex.process(&[prove], &[&outsider]).await;
+    assert_dex_error(result, ChudexError::InvalidMerkleProof);
+    let ticket = ticket_address(&dex, &pool, &outsider.pubkey());
+    let exchange = exchange_ix(&dex, &pool, &outsider.pubkey(), ticket);
+    let result = dex.process(&[exchange], &[&outsider]).await;
+    assert_dex_error(result, ChudexError::NotAllowlisted);
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use borsh::BorshSerialize;
+use chudex::{
+    error::ChudexError,
+    instruction::ChudexInstruction,
+    state::{GlobalConfig, PriceOracle, RegistryPage},
+};
+use common::{assert_dex_error, Dex, PoolKeys};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+/// ClosePool by the admin, with the pool on the first page everywhere and its rent going
+/// to the payer.
+fn close_pool_ix(dex: &Dex, pool: &PoolKeys) -> Instruction {
+    let page = 0u64.to_le_bytes();
+    Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(dex.admin.pubkey(), true),
+            AccountMeta::new_readonly(dex.find(&[GlobalConfig::SEED]), false),
+            AccountMeta::new(pool.pool, false),
+            AccountMeta::new(pool.vault_a, false),
+            AccountMeta::new(pool.vault_b, false),
+            AccountMeta::new(pool.pool_mint, false),
+            AccountMeta::new_readonly(pool.token_program_a, false),
+            AccountMeta::new_readonly(pool.token_program_b, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new(pool.twamm, false),
+            AccountMeta::new(dex.payer(), false),
+            AccountMeta::new(pool.mint_a, false),
+            AccountMeta::new(pool.mint_b, false),
+            AccountMeta::new(pool.twamm_vault_a, false),
+            AccountMeta::new(pool.twamm_vault_b, false),
+            AccountMeta::new(pool.lp_metadata, false),
+            AccountMeta::new(dex.find(&[PriceOracle::SEED, pool.pool.as_ref()]), false),
+            AccountMeta::new(dex.find(&[RegistryPage::SEED, &page]), false),
+            AccountMeta::new(
+                dex.find(&[RegistryPage::SEED, pool.mint_a.as_ref(), &page]),
+                false,
+            ),
+            AccountMeta::new(
+                dex.find(&[RegistryPage::SEED, pool.mint_b.as_ref(), &page]),
+                false,
+            ),
+        ],
+        data: ChudexInstruction::ClosePool {
+            registry_page: 0,
+            mint_a_page: 0,
+            mint_b_page: 0,
+        }
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn empty_pool_is_closed_and_its_pair_retired() {
+    let mut dex = Dex::start().await;
+    dex.initialize_config(vec![30]).await;
+    let mint_a = dex.create_mint(9).await;
+    let mint_b = dex.create_mint(6).await;
+    let pool = dex.create_pool(&mint_a, &mint_b, 30).await;
+    let payer = dex.payer();
+    let lamports = dex.account(&payer).await.unwrap().lamports;
+
+    let admin = Keypair::from_bytes(&dex.admin.to_bytes()).unwrap();
+    dex.process(&[close_pool_ix(&dex, &pool)], &[&admin])
+        .await
+        .unwrap();
+    for account in [pool.pool, pool.vault_a, pool.vault_b, pool.lp_metadata] {
+        assert!(dex.account(&account).await.is_none());
+    }
+    // the payer paid the fee, the rent back more than covers it
+    assert!(dex.account(&payer).await.unwrap().lamports > lamports);
+    // unlisted, its entry left default so the entries after it keep their index
+    let page: RegistryPage = dex
+        .state(&dex.find(&[RegistryPage::SEED, &0u64.to_le_bytes()]))
+        .await;
+    assert_eq!(page.pools(), [Pubkey::default()]);
+
+    // the pool mint stays behind, so the pair can't get a new pool
+    let mint = dex.mint(&pool.pool_mint).await;
+    assert_eq!(mint.supply, 0);
+    assert!(mint.mint_authority.is_none());
+    dex.refresh_blockhash().await;
+    let initialize = dex
+        .initialize_pool_ix(&payer, &pool, 30, None, None, None)
+        .await;
+    let result = dex.process(&[initialize], &[]).await;
+    assert_dex_error(result, ChudexError::AccountAlreadyInitialized);
+}
+
+#[tokio::test]
+async fn pool_with_liquidity_stays_open() {
+    let mut dex = Dex::start().await;
+    let pool = dex.pool_with_liquidity().await;
+
+    let admin = Keypair::from_bytes(&dex.admin.to_bytes()).unwrap();
+    let result = dex.process(&[close_pool_ix(&dex, &pool)], &[&admin]).await;
+    assert_dex_error(result, ChudexError::PoolNotEmpty);
+    assert!(dex.account(&pool.pool).await.is_some());
+}
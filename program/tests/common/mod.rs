@@ -0,0 +1,727 @@
+//! Setup shared by the program tests: the dex with its config admin, token helpers and
+//! constant product pool accounts.
+#![allow(dead_code)]
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use chudex::{
+    error::ChudexError,
+    instruction::ChudexInstruction,
+    processor::Processor,
+    state::{
+        AllowlistMode, DynamicFeeConfig, Factory, GlobalConfig, LpMetadata, MintIndex, PriceOracle,
+        RegistryPage, TwammPool,
+    },
+    token,
+};
+use solana_program_test::{
+    processor, BanksClientError, ProgramTest, ProgramTestBanksClientExt, ProgramTestContext,
+};
+use solana_sdk::{
+    account::Account,
+    bpf_loader_upgradeable,
+    clock::Clock,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program, sysvar,
+    transaction::{Transaction, TransactionError},
+    transaction_context::TransactionReturnData,
+};
+use spl_token_2022::{
+    extension::{transfer_fee, ExtensionType, StateWithExtensions},
+    state::{Account as TokenAccount, Mint},
+};
+
+/// Program test with the dex and its upgradeable loader program data, naming `admin` as
+/// upgrade authority so it can initialize the config. More programs can be added to it.
+pub fn program_test(program_id: Pubkey, admin: &Pubkey) -> ProgramTest {
+    let mut program_test = ProgramTest::new(
+        "chudex",
+        program_id,
+        processor!(Processor::process_instruction),
+    );
+    program_test.add_account(
+        *admin,
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    );
+    let mut program_data = vec![3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+    program_data.extend_from_slice(admin.as_ref());
+    program_test.add_account(
+        program_data_address(&program_id),
+        Account {
+            lamports: 1_000_000_000,
+            data: program_data,
+            owner: bpf_loader_upgradeable::id(),
+            ..Account::default()
+        },
+    );
+    program_test
+}
+
+pub fn program_data_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0
+}
+
+/// Asserts `result` failed with `error` from the dex.
+pub fn assert_dex_error<T: std::fmt::Debug>(
+    result: Result<T, TransactionError>,
+    error: ChudexError,
+) {
+    assert_instruction_error(result, InstructionError::Custom(error as u32));
+}
+
+/// Asserts `result` failed with `error`, from whichever instruction of the transaction.
+pub fn assert_instruction_error<T: std::fmt::Debug>(
+    result: Result<T, TransactionError>,
+    error: InstructionError,
+) {
+    match result {
+        Err(TransactionError::InstructionError(_, e)) => assert_eq!(e, error),
+        other => panic!("expected {:?}, got {:?}", error, other),
+    }
+}
+
+/// The dex under test.
+pub struct Dex {
+    pub context: ProgramTestContext,
+    pub program_id: Pubkey,
+    pub admin: Keypair,
+}
+
+impl Dex {
+    pub async fn start() -> Self {
+        let program_id = Pubkey::new_unique();
+        let admin = Keypair::new();
+        let program_test = program_test(program_id, &admin.pubkey());
+        Self::start_with(program_test, program_id, admin).await
+    }
+
+    /// Starts `program_test`, made by `program_test` with the same program id and admin.
+    pub async fn start_with(program_test: ProgramTest, program_id: Pubkey, admin: Keypair) -> Self {
+        Self {
+            context: program_test.start_with_context().await,
+            program_id,
+            admin,
+        }
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    pub fn find(&self, seeds: &[&[u8]]) -> Pubkey {
+        Pubkey::find_program_address(seeds, &self.program_id).0
+    }
+
+    /// Processes `instructions` paid for by the payer, returning the return data.
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Option<TransactionReturnData>, TransactionError> {
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            self.context.last_blockhash,
+        );
+        let result = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .map_err(|e| match e {
+                BanksClientError::TransactionError(e) => e,
+                e => panic!("{:?}", e),
+            })?;
+        result.result?;
+        Ok(result.metadata.and_then(|metadata| metadata.return_data))
+    }
+
+    /// Moves on to a new blockhash, so an identical transaction can be sent again.
+    pub async fn refresh_blockhash(&mut self) {
+        self.context.last_blockhash = self
+            .context
+            .banks_client
+            .get_new_latest_blockhash(&self.context.last_blockhash)
+            .await
+            .unwrap();
+    }
+
+    /// A new keypair holding `lamports`.
+    pub async fn user(&mut self, lamports: u64) -> Keypair {
+        let user = Keypair::new();
+        let payer = self.payer();
+        self.process(
+            &[system_instruction::transfer(
+                &payer,
+                &user.pubkey(),
+                lamports,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        user
+    }
+
+    pub async fn account(&mut self, key: &Pubkey) -> Option<Account> {
+        self.context.banks_client.get_account(*key).await.unwrap()
+    }
+
+    /// Deserializes the program account at `key`, ignoring any unused space after it.
+    pub async fn state<T: BorshDeserialize>(&mut self, key: &Pubkey) -> T {
+        let account = self.account(key).await.expect("account doesn't exist");
+        T::deserialize(&mut &account.data[..]).unwrap()
+    }
+
+    pub async fn clock(&mut self) -> Clock {
+        self.context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+    }
+
+    /// Moves the clock `seconds` forward.
+    pub async fn advance_time(&mut self, seconds: i64) {
+        let mut clock = self.clock().await;
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    /// Moves the clock `slots` forward, and the time along with them at 400ms a slot.
+    pub async fn advance_slots(&mut self, slots: u64) {
+        let mut clock = self.clock().await;
+        clock.slot += slots;
+        clock.unix_timestamp += (slots * 2 / 5) as i64;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let mint = Keypair::new();
+        let payer = self.payer();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        self.process(
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &mint.pubkey(),
+                    rent.minimum_balance(spl_token::state::Mint::LEN),
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_mint(
+                    &spl_token::id(),
+                    &mint.pubkey(),
+                    &payer,
+                    None,
+                    decimals,
+                )
+                .unwrap(),
+            ],
+            &[&mint],
+        )
+        .await
+        .unwrap();
+        mint.pubkey()
+    }
+
+    /// Token-2022 mint charging `basis_points` of every transfer, up to `maximum_fee`.
+    pub async fn create_transfer_fee_mint(
+        &mut self,
+        decimals: u8,
+        basis_points: u16,
+        maximum_fee: u64,
+    ) -> Pubkey {
+        let mint = Keypair::new();
+        let payer = self.payer();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let space =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig])
+                .unwrap();
+        self.process(
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &mint.pubkey(),
+                    rent.minimum_balance(space),
+                    space as u64,
+                    &spl_token_2022::id(),
+                ),
+                transfer_fee::instruction::initialize_transfer_fee_config(
+                    &spl_token_2022::id(),
+                    &mint.pubkey(),
+                    Some(&payer),
+                    Some(&payer),
+                    basis_points,
+                    maximum_fee,
+                )
+                .unwrap(),
+                spl_token_2022::instruction::initialize_mint(
+                    &spl_token_2022::id(),
+                    &mint.pubkey(),
+                    &payer,
+                    None,
+                    decimals,
+                )
+                .unwrap(),
+            ],
+            &[&mint],
+        )
+        .await
+        .unwrap();
+        mint.pubkey()
+    }
+
+    /// Token program owning `mint`.
+    pub async fn token_program(&mut self, mint: &Pubkey) -> Pubkey {
+        self.account(mint).await.unwrap().owner
+    }
+
+    /// Creates `owner`'s associated token account for `mint`, minting it `amount`.
+    pub async fn create_token_account(
+        &mut self,
+        owner: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+    ) -> Pubkey {
+        let payer = self.payer();
+        let token_program = self.token_program(mint).await;
+        let account = associated_address(owner, mint, &token_program);
+        self.process(
+            &[token::create_associated_token_account(
+                &payer,
+                owner,
+                mint,
+                &token_program,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        if amount > 0 {
+            self.mint_to(mint, &account, amount).await;
+        }
+        account
+    }
+
+    pub async fn mint_to(&mut self, mint: &Pubkey, account: &Pubkey, amount: u64) {
+        let payer = self.payer();
+        let token_program = self.token_program(mint).await;
+        self.process(
+            &[spl_token_2022::instruction::mint_to(
+                &token_program,
+                mint,
+                account,
+                &payer,
+                &[],
+                amount,
+            )
+            .unwrap()],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Sends `amount` of `mint` from `source`, owned by `owner`, to `destination`.
+    pub async fn transfer(
+        &mut self,
+        mint: &Pubkey,
+        source: &Pubkey,
+        destination: &Pubkey,
+        owner: &Keypair,
+        amount: u64,
+    ) {
+        let token_program = self.token_program(mint).await;
+        let decimals = self.mint(mint).await.decimals;
+        self.process(
+            &[spl_token_2022::instruction::transfer_checked(
+                &token_program,
+                source,
+                mint,
+                destination,
+                &owner.pubkey(),
+                &[],
+                amount,
+                decimals,
+            )
+            .unwrap()],
+            &[owner],
+        )
+        .await
+        .unwrap();
+    }
+
+    pub async fn mint(&mut self, mint: &Pubkey) -> Mint {
+        let account = self.account(mint).await.unwrap();
+        StateWithExtensions::<Mint>::unpack(&account.data)
+            .unwrap()
+            .base
+    }
+
+    pub async fn token_balance(&mut self, account: &Pubkey) -> u64 {
+        let account = self.account(account).await.unwrap();
+        StateWithExtensions::<TokenAccount>::unpack(&account.data)
+            .unwrap()
+            .base
+            .amount
+    }
+
+    pub fn initialize_config_ix(&self, fee_tiers: Vec<u16>) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(self.admin.pubkey(), true),
+                AccountMeta::new(self.find(&[GlobalConfig::SEED]), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(program_data_address(&self.program_id), false),
+            ],
+            data: ChudexInstruction::InitializeConfig { fee_tiers }
+                .try_to_vec()
+                .unwrap(),
+        }
+    }
+
+    pub async fn initialize_config(&mut self, fee_tiers: Vec<u16>) {
+        let instruction = self.initialize_config_ix(fee_tiers);
+        let admin = Keypair::from_bytes(&self.admin.to_bytes()).unwrap();
+        self.process(&[instruction], &[&admin]).await.unwrap();
+    }
+
+    /// Accounts of a pool for `mint_a` and `mint_b`, in pool order: more decimals first.
+    pub async fn pool_keys(&mut self, mint_a: &Pubkey, mint_b: &Pubkey) -> PoolKeys {
+        let token_program_a = self.token_program(mint_a).await;
+        let token_program_b = self.token_program(mint_b).await;
+        PoolKeys::new(
+            &self.program_id,
+            *mint_a,
+            token_program_a,
+            *mint_b,
+            token_program_b,
+        )
+    }
+
+    /// InitializePool, on the next registry and mint index pages.
+    pub async fn initialize_pool_ix(
+        &mut self,
+        user: &Pubkey,
+        pool: &PoolKeys,
+        fee_tier: u16,
+        dynamic_fee: Option<DynamicFeeConfig>,
+        allowlist: Option<AllowlistMode>,
+        hook_program: Option<Pubkey>,
+    ) -> Instruction {
+        let factory = self.find(&[Factory::SEED]);
+        let pool_count = match self.account(&factory).await {
+            Some(account) => Factory::try_from_slice(&account.data).unwrap().pool_count,
+            None => 0,
+        };
+        let page = (pool_count / RegistryPage::PAGE_SIZE as u64).to_le_bytes();
+        let mut mint_indexes = vec![];
+        for mint in [&pool.mint_a, &pool.mint_b] {
+            let mint_index = self.find(&[MintIndex::SEED, mint.as_ref()]);
+            let pool_count = match self.account(&mint_index).await {
+                Some(account) => MintIndex::try_from_slice(&account.data).unwrap().pool_count,
+                None => 0,
+            };
+            let page = (pool_count / RegistryPage::PAGE_SIZE as u64).to_le_bytes();
+            mint_indexes.push(AccountMeta::new(mint_index, false));
+            mint_indexes.push(AccountMeta::new(
+                self.find(&[RegistryPage::SEED, mint.as_ref(), &page]),
+                false,
+            ));
+        }
+        let mut accounts = vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(pool.pool, false),
+            AccountMeta::new(pool.vault_a, false),
+            AccountMeta::new(pool.vault_b, false),
+            AccountMeta::new_readonly(pool.mint_a, false),
+            AccountMeta::new_readonly(pool.mint_b, false),
+            AccountMeta::new(pool.pool_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(pool.token_program_a, false),
+            AccountMeta::new_readonly(pool.token_program_b, false),
+            AccountMeta::new_readonly(self.find(&[GlobalConfig::SEED]), false),
+            AccountMeta::new(factory, false),
+            AccountMeta::new(self.find(&[RegistryPage::SEED, &page]), false),
+        ];
+        accounts.extend(mint_indexes);
+        accounts.push(AccountMeta::new(pool.lp_metadata, false));
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data: ChudexInstruction::InitializePool {
+                fee_tier,
+                dynamic_fee,
+                allowlist,
+                hook_program,
+            }
+            .try_to_vec()
+            .unwrap(),
+        }
+    }
+
+    /// Creates a plain pool for `mint_a` and `mint_b` on `fee_tier`, paid for by the payer.
+    pub async fn create_pool(
+        &mut self,
+        mint_a: &Pubkey,
+        mint_b: &Pubkey,
+        fee_tier: u16,
+    ) -> PoolKeys {
+        let pool = self.pool_keys(mint_a, mint_b).await;
+        let payer = self.payer();
+        let instruction = self
+            .initialize_pool_ix(&payer, &pool, fee_tier, None, None, None)
+            .await;
+        self.process(&[instruction], &[]).await.unwrap();
+        pool
+    }
+
+    /// Deposit of `token_a_amount` and up to `max_token_b_amount` by `user`, from their
+    /// associated token accounts.
+    pub fn deposit_ix(
+        &self,
+        user: &Pubkey,
+        pool: &PoolKeys,
+        token_a_amount: u64,
+        max_token_b_amount: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: pool.deposit_accounts(user),
+            data: ChudexInstruction::Deposit {
+                token_a_amount,
+                max_token_b_amount,
+                native_sol: false,
+            }
+            .try_to_vec()
+            .unwrap(),
+        }
+    }
+
+    /// Exchange of `amount_in` by `user`, from and to their associated token accounts.
+    pub fn exchange_ix(
+        &self,
+        user: &Pubkey,
+        pool: &PoolKeys,
+        a_to_b: bool,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Instruction {
+        let (user_src, user_dst) = if a_to_b {
+            (pool.user_token_a(user), pool.user_token_b(user))
+        } else {
+            (pool.user_token_b(user), pool.user_token_a(user))
+        };
+        let mut accounts = vec![
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(user_src, false),
+            AccountMeta::new(user_dst, false),
+        ];
+        accounts.extend(pool.exchange_accounts(a_to_b));
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data: ChudexInstruction::Exchange {
+                amount_in,
+                min_amount_out,
+                native_sol: false,
+            }
+            .try_to_vec()
+            .unwrap(),
+        }
+    }
+
+    /// Config on a 30 bps tier, a 9 and a 6 decimals mint with a billion of each in the
+    /// payer's token accounts, and their pool with a tenth of that deposited by the payer.
+    pub async fn pool_with_liquidity(&mut self) -> PoolKeys {
+        self.initialize_config(vec![30]).await;
+        let mint_a = self.create_mint(9).await;
+        let mint_b = self.create_mint(6).await;
+        let payer = self.payer();
+        self.create_token_account(&payer, &mint_a, 1_000_000_000)
+            .await;
+        self.create_token_account(&payer, &mint_b, 1_000_000_000)
+            .await;
+        let pool = self.create_pool(&mint_a, &mint_b, 30).await;
+        let deposit = self.deposit_ix(&payer, &pool, 100_000_000, 100_000_000);
+        self.process(&[deposit], &[]).await.unwrap();
+        pool
+    }
+
+    /// Gives `user` associated token accounts for both of the pool's mints, minting them
+    /// `amount_a` and `amount_b`.
+    pub async fn fund(&mut self, user: &Pubkey, pool: &PoolKeys, amount_a: u64, amount_b: u64) {
+        self.create_token_account(user, &pool.mint_a, amount_a)
+            .await;
+        self.create_token_account(user, &pool.mint_b, amount_b)
+            .await;
+    }
+
+    /// UpdateOracle on `pool`, paid for by the payer.
+    pub fn update_oracle_ix(&self, pool: &PoolKeys) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: vec![
+                AccountMeta::new(self.payer(), true),
+                AccountMeta::new_readonly(pool.pool, false),
+                AccountMeta::new(self.find(&[PriceOracle::SEED, pool.pool.as_ref()]), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: ChudexInstruction::UpdateOracle.try_to_vec().unwrap(),
+        }
+    }
+}
+
+pub fn associated_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &spl_associated_token_account::id(),
+    )
+    .0
+}
+
+/// Addresses of a constant product pool and its accounts.
+#[derive(Debug, Clone)]
+pub struct PoolKeys {
+    pub pool: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub token_program_a: Pubkey,
+    pub token_program_b: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub pool_mint: Pubkey,
+    pub twamm: Pubkey,
+    pub twamm_vault_a: Pubkey,
+    pub twamm_vault_b: Pubkey,
+    /// Set once the pool's twamm is initialized.
+    pub has_twamm: bool,
+    pub lp_metadata: Pubkey,
+}
+
+impl PoolKeys {
+    pub fn new(
+        program_id: &Pubkey,
+        mint_a: Pubkey,
+        token_program_a: Pubkey,
+        mint_b: Pubkey,
+        token_program_b: Pubkey,
+    ) -> Self {
+        let find = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, program_id).0;
+        let pool = find(&[b"chudex_pool", mint_a.as_ref(), mint_b.as_ref()]);
+        let pool_mint = find(&[b"chudex_pool_mint", pool.as_ref()]);
+        let twamm = find(&[TwammPool::SEED, pool.as_ref()]);
+        Self {
+            pool,
+            mint_a,
+            mint_b,
+            token_program_a,
+            token_program_b,
+            vault_a: associated_address(&pool, &mint_a, &token_program_a),
+            vault_b: associated_address(&pool, &mint_b, &token_program_b),
+            pool_mint,
+            twamm,
+            twamm_vault_a: associated_address(&twamm, &mint_a, &token_program_a),
+            twamm_vault_b: associated_address(&twamm, &mint_b, &token_program_b),
+            has_twamm: false,
+            lp_metadata: find(&[LpMetadata::SEED, pool_mint.as_ref()]),
+        }
+    }
+
+    pub fn user_token_a(&self, user: &Pubkey) -> Pubkey {
+        associated_address(user, &self.mint_a, &self.token_program_a)
+    }
+
+    pub fn user_token_b(&self, user: &Pubkey) -> Pubkey {
+        associated_address(user, &self.mint_b, &self.token_program_b)
+    }
+
+    pub fn user_pool_token(&self, user: &Pubkey) -> Pubkey {
+        associated_address(user, &self.pool_mint, &spl_token::id())
+    }
+
+    /// Twamm and its vaults, as passed to every instruction settling the pool's orders.
+    /// None for pools without a twamm.
+    pub fn twamm_accounts(&self) -> Vec<AccountMeta> {
+        if !self.has_twamm {
+            return vec![];
+        }
+        vec![
+            AccountMeta::new(self.twamm, false),
+            AccountMeta::new(self.twamm_vault_a, false),
+            AccountMeta::new(self.twamm_vault_b, false),
+        ]
+    }
+
+    /// Deposit's accounts, also those of DepositExactPoolTokens.
+    pub fn deposit_accounts(&self, user: &Pubkey) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(self.user_token_a(user), false),
+            AccountMeta::new(self.user_token_b(user), false),
+            AccountMeta::new(self.user_pool_token(user), false),
+            AccountMeta::new(self.pool, false),
+            AccountMeta::new(self.vault_a, false),
+            AccountMeta::new(self.vault_b, false),
+            AccountMeta::new(self.pool_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(self.mint_a, false),
+            AccountMeta::new_readonly(self.mint_b, false),
+            AccountMeta::new_readonly(self.token_program_a, false),
+            AccountMeta::new_readonly(self.token_program_b, false),
+        ];
+        accounts.extend(self.twamm_accounts());
+        accounts
+    }
+
+    /// Exchange's accounts from the pool on, selling a when `a_to_b`.
+    pub fn exchange_accounts(&self, a_to_b: bool) -> Vec<AccountMeta> {
+        let (vault_src, vault_dst, token_program_src, token_program_dst, mint_src, mint_dst) =
+            if a_to_b {
+                (
+                    self.vault_a,
+                    self.vault_b,
+                    self.token_program_a,
+                    self.token_program_b,
+                    self.mint_a,
+                    self.mint_b,
+                )
+            } else {
+                (
+                    self.vault_b,
+                    self.vault_a,
+                    self.token_program_b,
+                    self.token_program_a,
+                    self.mint_b,
+                    self.mint_a,
+                )
+            };
+        let mut accounts = vec![
+            AccountMeta::new(self.pool, false),
+            AccountMeta::new(vault_src, false),
+            AccountMeta::new(vault_dst, false),
+            AccountMeta::new_readonly(token_program_src, false),
+            AccountMeta::new_readonly(token_program_dst, false),
+            AccountMeta::new_readonly(mint_src, false),
+            AccountMeta::new_readonly(mint_dst, false),
+        ];
+        accounts.extend(self.twamm_accounts());
+        accounts
+    }
+}
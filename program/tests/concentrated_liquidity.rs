@@ -0,0 +1,238 @@
+mod common;
+
+use borsh::BorshSerialize;
+use chudex::{
+    cl_math,
+    error::ChudexError,
+    instruction::ChudexInstruction,
+    state::{ClPool, Position, TickArray},
+};
+use common::{assert_dex_error, associated_address, Dex};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+const TICK_SPACING: u16 = 10;
+
+/// Addresses of a concentrated pool with a 10 tick spacing.
+struct ClKeys {
+    pool: Pubkey,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    vault_a: Pubkey,
+    vault_b: Pubkey,
+}
+
+impl ClKeys {
+    fn tick_array(&self, dex: &Dex, start_tick: i32) -> Pubkey {
+        dex.find(&[
+            TickArray::SEED,
+            self.pool.as_ref(),
+            &start_tick.to_le_bytes(),
+        ])
+    }
+
+    fn position(&self, dex: &Dex, owner: &Pubkey, tick_lower: i32, tick_upper: i32) -> Pubkey {
+        dex.find(&[
+            Position::SEED,
+            self.pool.as_ref(),
+            owner.as_ref(),
+            &tick_lower.to_le_bytes(),
+            &tick_upper.to_le_bytes(),
+        ])
+    }
+}
+
+/// Pool at a price of 1 for two sorted mints, with a billion of each in the payer's token
+/// accounts, and the tick arrays on either side of tick 0.
+async fn cl_pool(dex: &mut Dex) -> ClKeys {
+    let payer = dex.payer();
+    let mut mints = vec![dex.create_mint(6).await, dex.create_mint(6).await];
+    mints.sort();
+    for mint in &mints {
+        dex.create_token_account(&payer, mint, 1_000_000_000).await;
+    }
+    let pool = dex.find(&[
+        ClPool::SEED,
+        mints[0].as_ref(),
+        mints[1].as_ref(),
+        &TICK_SPACING.to_le_bytes(),
+    ]);
+    let keys = ClKeys {
+        pool,
+        mint_a: mints[0],
+        mint_b: mints[1],
+        vault_a: associated_address(&pool, &mints[0], &spl_token::id()),
+        vault_b: associated_address(&pool, &mints[1], &spl_token::id()),
+    };
+
+    let mut instructions = vec![Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(keys.pool, false),
+            AccountMeta::new(keys.vault_a, false),
+            AccountMeta::new(keys.vault_b, false),
+            AccountMeta::new_readonly(keys.mint_a, false),
+            AccountMeta::new_readonly(keys.mint_b, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: ChudexInstruction::InitializeClPool {
+            tick_spacing: TICK_SPACING,
+            initial_sqrt_price: 1 << 64,
+            fee: 30,
+            fee_decimals: 4,
+        }
+        .try_to_vec()
+        .unwrap(),
+    }];
+    let ticks_per_array = TickArray::SIZE as i32 * TICK_SPACING as i32;
+    for start_tick in [-ticks_per_array, 0] {
+        instructions.push(Instruction {
+            program_id: dex.program_id,
+            accounts: vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(keys.pool, false),
+                AccountMeta::new(keys.tick_array(dex, start_tick), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: ChudexInstruction::InitializeTickArray { start_tick }
+                .try_to_vec()
+                .unwrap(),
+        });
+    }
+    dex.process(&instructions, &[]).await.unwrap();
+    keys
+}
+
+fn open_position_ix(dex: &Dex, pool: &ClKeys, tick_lower: i32, tick_upper: i32) -> Instruction {
+    let payer = dex.payer();
+    Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(pool.pool, false),
+            AccountMeta::new(pool.position(dex, &payer, tick_lower, tick_upper), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: ChudexInstruction::OpenPosition {
+            tick_lower,
+            tick_upper,
+        }
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn provides_liquidity_over_a_range_and_swaps_through_it() {
+    let mut dex = Dex::start().await;
+    let pool = cl_pool(&mut dex).await;
+    let payer = dex.payer();
+    let user_token_a = associated_address(&payer, &pool.mint_a, &spl_token::id());
+    let user_token_b = associated_address(&payer, &pool.mint_b, &spl_token::id());
+    let ticks_per_array = TickArray::SIZE as i32 * TICK_SPACING as i32;
+
+    // position over [-100, 100], its bounds in the arrays either side of tick 0
+    let liquidity = 100_000_000_000u128;
+    let (amount_a, amount_b) = cl_math::amounts_for_liquidity(
+        1 << 64,
+        cl_math::sqrt_price_from_tick(-100).unwrap(),
+        cl_math::sqrt_price_from_tick(100).unwrap(),
+        liquidity,
+        true,
+    )
+    .unwrap();
+    let increase = Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(payer, true),
+            AccountMeta::new(pool.pool, false),
+            AccountMeta::new(pool.position(&dex, &payer, -100, 100), false),
+            AccountMeta::new(pool.tick_array(&dex, -ticks_per_array), false),
+            AccountMeta::new(pool.tick_array(&dex, 0), false),
+            AccountMeta::new(user_token_a, false),
+            AccountMeta::new(user_token_b, false),
+            AccountMeta::new(pool.vault_a, false),
+            AccountMeta::new(pool.vault_b, false),
+            AccountMeta::new_readonly(pool.mint_a, false),
+            AccountMeta::new_readonly(pool.mint_b, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: ChudexInstruction::IncreaseLiquidity {
+            liquidity,
+            max_token_a_amount: amount_a,
+            max_token_b_amount: amount_b,
+        }
+        .try_to_vec()
+        .unwrap(),
+    };
+    dex.process(&[open_position_ix(&dex, &pool, -100, 100), increase], &[])
+        .await
+        .unwrap();
+    assert_eq!(dex.token_balance(&pool.vault_a).await, amount_a);
+    assert_eq!(dex.token_balance(&pool.vault_b).await, amount_b);
+    let state: ClPool = dex.state(&pool.pool).await;
+    assert_eq!(state.liquidity, liquidity);
+
+    // a in, the price moves down into the lower array
+    let b_before = dex.token_balance(&user_token_b).await;
+    let exchange = Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(payer, true),
+            AccountMeta::new(user_token_a, false),
+            AccountMeta::new(user_token_b, false),
+            AccountMeta::new(pool.pool, false),
+            AccountMeta::new(pool.vault_a, false),
+            AccountMeta::new(pool.vault_b, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(pool.mint_a, false),
+            AccountMeta::new_readonly(pool.mint_b, false),
+            AccountMeta::new(pool.tick_array(&dex, 0), false),
+            AccountMeta::new(pool.tick_array(&dex, -ticks_per_array), false),
+        ],
+        data: ChudexInstruction::ClExchange {
+            amount_in: 1_000_000,
+            min_amount_out: 1,
+            a_to_b: true,
+            sqrt_price_limit: 0,
+        }
+        .try_to_vec()
+        .unwrap(),
+    };
+    dex.process(&[exchange], &[]).await.unwrap();
+
+    let amount_out = dex.token_balance(&user_token_b).await - b_before;
+    // less than 1:1 after the fee and the price move
+    assert!(amount_out > 990_000 && amount_out < 997_000);
+    assert_eq!(dex.token_balance(&pool.vault_a).await, amount_a + 1_000_000);
+    assert_eq!(
+        dex.token_balance(&pool.vault_b).await,
+        amount_b - amount_out
+    );
+    let state: ClPool = dex.state(&pool.pool).await;
+    assert!(state.sqrt_price < 1 << 64);
+    assert!(state.current_tick < 0 && state.current_tick >= -100);
+    assert_eq!(state.liquidity, liquidity);
+}
+
+#[tokio::test]
+async fn position_ticks_have_to_be_on_the_spacing() {
+    let mut dex = Dex::start().await;
+    let pool = cl_pool(&mut dex).await;
+
+    let result = dex
+        .process(&[open_position_ix(&dex, &pool, -105, 100)], &[])
+        .await;
+    assert_dex_error(result, ChudexError::InvalidTick);
+}
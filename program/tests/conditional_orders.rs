@@ -0,0 +1,151 @@
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use chudex::{
+    client::ExchangeResult,
+    error::ChudexError,
+    instruction::ChudexInstruction,
+    state::{ConditionalOrder, PriceOracle, TriggerSource},
+};
+use common::{assert_dex_error, associated_address, Dex, PoolKeys};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+/// Stop loss at 0.9 b per a.
+const TRIGGER_PRICE: u128 = (1 << 64) * 9 / 10;
+
+/// Average over one observation interval.
+const TWAP: TriggerSource = TriggerSource::Twap {
+    window: PriceOracle::OBSERVATION_INTERVAL as u32,
+};
+
+/// Pool with liquidity and its oracle, and the payer's order selling a million a once the
+/// price of a from `trigger_source` falls to TRIGGER_PRICE.
+async fn stop_loss(dex: &mut Dex, trigger_source: TriggerSource) -> (PoolKeys, Pubkey) {
+    let pool = dex.pool_with_liquidity().await;
+    let payer = dex.payer();
+    let order = dex.find(&[
+        ConditionalOrder::SEED,
+        pool.pool.as_ref(),
+        payer.as_ref(),
+        &0u64.to_le_bytes(),
+    ]);
+    let expiry_timestamp = dex.clock().await.unix_timestamp + 3_600;
+    let place = Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(pool.user_token_a(&payer), false),
+            AccountMeta::new_readonly(pool.user_token_b(&payer), false),
+            AccountMeta::new_readonly(pool.pool, false),
+            AccountMeta::new(order, false),
+            AccountMeta::new(escrow(&pool, &order), false),
+            AccountMeta::new_readonly(pool.mint_a, false),
+            AccountMeta::new_readonly(pool.mint_b, false),
+            AccountMeta::new_readonly(pool.token_program_a, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: ChudexInstruction::PlaceConditional {
+            nonce: 0,
+            amount_in: 1_000_000,
+            min_amount_out: 1,
+            trigger_price: TRIGGER_PRICE,
+            take_profit: false,
+            trigger_source,
+            expiry_timestamp,
+        }
+        .try_to_vec()
+        .unwrap(),
+    };
+    let update = dex.update_oracle_ix(&pool);
+    dex.process(&[place, update], &[]).await.unwrap();
+    (pool, order)
+}
+
+fn escrow(pool: &PoolKeys, order: &Pubkey) -> Pubkey {
+    associated_address(order, &pool.mint_a, &pool.token_program_a)
+}
+
+/// ExecuteConditional cranked by the payer.
+fn execute_ix(dex: &Dex, pool: &PoolKeys, order: &Pubkey) -> Instruction {
+    let payer = dex.payer();
+    let mut accounts = vec![
+        AccountMeta::new_readonly(payer, true),
+        AccountMeta::new(pool.user_token_a(&payer), false),
+        AccountMeta::new(*order, false),
+        AccountMeta::new(escrow(pool, order), false),
+        AccountMeta::new(pool.user_token_b(&payer), false),
+        AccountMeta::new(payer, false),
+        AccountMeta::new_readonly(dex.find(&[PriceOracle::SEED, pool.pool.as_ref()]), false),
+    ];
+    accounts.extend(pool.exchange_accounts(true));
+    Instruction {
+        program_id: dex.program_id,
+        accounts,
+        data: ChudexInstruction::ExecuteConditional.try_to_vec().unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn order_executes_once_the_average_crosses_its_trigger() {
+    let mut dex = Dex::start().await;
+    let (pool, order) = stop_loss(&mut dex, TWAP).await;
+    let payer = dex.payer();
+    // a dump of a brings the price down to under half, for a whole window
+    let exchange = dex.exchange_ix(&payer, &pool, true, 50_000_000, 1);
+    dex.process(&[exchange], &[]).await.unwrap();
+    dex.advance_time(PriceOracle::OBSERVATION_INTERVAL).await;
+
+    let token_b = dex.token_balance(&pool.user_token_b(&payer)).await;
+    let return_data = dex
+        .process(&[execute_ix(&dex, &pool, &order)], &[])
+        .await
+        .unwrap()
+        .unwrap();
+    let exchanged = ExchangeResult::try_from_slice(&return_data.data).unwrap();
+    // less the keeper bounty
+    assert_eq!(exchanged.amount_in, 999_000);
+    assert!(exchanged.amount_out > 0);
+    assert_eq!(
+        dex.token_balance(&pool.user_token_b(&payer)).await,
+        token_b + exchanged.amount_out
+    );
+    assert!(dex.account(&order).await.is_none());
+    assert!(dex.account(&escrow(&pool, &order)).await.is_none());
+}
+
+#[tokio::test]
+async fn order_waits_for_its_trigger() {
+    let mut dex = Dex::start().await;
+    let (pool, order) = stop_loss(&mut dex, TWAP).await;
+    // the price holds at 1
+    dex.advance_time(PriceOracle::OBSERVATION_INTERVAL).await;
+
+    let result = dex.process(&[execute_ix(&dex, &pool, &order)], &[]).await;
+    assert_dex_error(result, ChudexError::TriggerNotReached);
+    assert_eq!(dex.token_balance(&escrow(&pool, &order)).await, 1_000_000);
+}
+
+#[tokio::test]
+async fn spot_order_executes_on_the_price_right_now() {
+    let mut dex = Dex::start().await;
+    let (pool, order) = stop_loss(&mut dex, TriggerSource::Spot).await;
+    let payer = dex.payer();
+    let result = dex.process(&[execute_ix(&dex, &pool, &order)], &[]).await;
+    assert_dex_error(result, ChudexError::TriggerNotReached);
+
+    // no window to wait out, the dump alone crosses the trigger
+    let exchange = dex.exchange_ix(&payer, &pool, true, 50_000_000, 1);
+    dex.process(&[exchange], &[]).await.unwrap();
+    dex.refresh_blockhash().await;
+    dex.process(&[execute_ix(&dex, &pool, &order)], &[])
+        .await
+        .unwrap();
+    assert!(dex.account(&order).await.is_none());
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use chudex::{
+    client::ExchangeResult, error::ChudexError, instruction::ChudexInstruction, state::Dca,
+};
+use common::{assert_dex_error, associated_address, Dex, PoolKeys};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+const INTERVAL: u32 = 600;
+
+/// Pool with liquidity and the payer's dca selling a into it, a million every INTERVAL
+/// over two cycles.
+async fn dca(dex: &mut Dex) -> (PoolKeys, Pubkey) {
+    let pool = dex.pool_with_liquidity().await;
+    let payer = dex.payer();
+    let dca = dex.find(&[
+        Dca::SEED,
+        pool.pool.as_ref(),
+        payer.as_ref(),
+        &0u64.to_le_bytes(),
+    ]);
+    let create = Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(pool.user_token_a(&payer), false),
+            AccountMeta::new_readonly(pool.pool, false),
+            AccountMeta::new(dca, false),
+            AccountMeta::new(escrow(&pool, &dca), false),
+            AccountMeta::new_readonly(pool.mint_a, false),
+            AccountMeta::new_readonly(pool.mint_b, false),
+            AccountMeta::new_readonly(pool.token_program_a, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: ChudexInstruction::CreateDca {
+            nonce: 0,
+            amount_per_cycle: 1_000_000,
+            interval_seconds: INTERVAL,
+            cycles: 2,
+            min_amount_out_per_cycle: 1,
+        }
+        .try_to_vec()
+        .unwrap(),
+    };
+    dex.process(&[create], &[]).await.unwrap();
+    (pool, dca)
+}
+
+fn escrow(pool: &PoolKeys, dca: &Pubkey) -> Pubkey {
+    associated_address(dca, &pool.mint_a, &pool.token_program_a)
+}
+
+/// RunDca cranked by the payer.
+fn run_ix(dex: &Dex, pool: &PoolKeys, dca: &Pubkey) -> Instruction {
+    let payer = dex.payer();
+    let mut accounts = vec![
+        AccountMeta::new_readonly(payer, true),
+        AccountMeta::new(pool.user_token_a(&payer), false),
+        AccountMeta::new(*dca, false),
+        AccountMeta::new(escrow(pool, dca), false),
+        AccountMeta::new(pool.user_token_b(&payer), false),
+        AccountMeta::new(payer, false),
+    ];
+    accounts.extend(pool.exchange_accounts(true));
+    Instruction {
+        program_id: dex.program_id,
+        accounts,
+        data: ChudexInstruction::RunDca.try_to_vec().unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn dca_buys_every_interval_until_done() {
+    let mut dex = Dex::start().await;
+    let (pool, dca) = dca(&mut dex).await;
+    let payer = dex.payer();
+    let token_b = dex.token_balance(&pool.user_token_b(&payer)).await;
+
+    // the first cycle is due right away
+    let return_data = dex
+        .process(&[run_ix(&dex, &pool, &dca)], &[])
+        .await
+        .unwrap()
+        .unwrap();
+    let first = ExchangeResult::try_from_slice(&return_data.data).unwrap();
+    // less the keeper bounty
+    assert_eq!(first.amount_in, 999_000);
+    let state: Dca = dex.state(&dca).await;
+    assert_eq!(state.cycles_left, 1);
+    assert_eq!(dex.token_balance(&escrow(&pool, &dca)).await, 1_000_000);
+
+    // the last one sells the rest and closes the dca
+    dex.advance_time(INTERVAL as i64).await;
+    dex.refresh_blockhash().await;
+    let return_data = dex
+        .process(&[run_ix(&dex, &pool, &dca)], &[])
+        .await
+        .unwrap()
+        .unwrap();
+    let last = ExchangeResult::try_from_slice(&return_data.data).unwrap();
+    assert_eq!(
+        dex.token_balance(&pool.user_token_b(&payer)).await,
+        token_b + first.amount_out + last.amount_out
+    );
+    assert!(dex.account(&dca).await.is_none());
+    assert!(dex.account(&escrow(&pool, &dca)).await.is_none());
+}
+
+#[tokio::test]
+async fn cycles_wait_their_interval_and_the_rest_is_refunded() {
+    let mut dex = Dex::start().await;
+    let (pool, dca) = dca(&mut dex).await;
+    let payer = dex.payer();
+    dex.process(&[run_ix(&dex, &pool, &dca)], &[])
+        .await
+        .unwrap();
+
+    dex.advance_time(INTERVAL as i64 - 1).await;
+    dex.refresh_blockhash().await;
+    let result = dex.process(&[run_ix(&dex, &pool, &dca)], &[]).await;
+    assert_dex_error(result, ChudexError::InvalidInstructionInput);
+
+    let token_a = dex.token_balance(&pool.user_token_a(&payer)).await;
+    let close = Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(pool.user_token_a(&payer), false),
+            AccountMeta::new(dca, false),
+            AccountMeta::new(escrow(&pool, &dca), false),
+            AccountMeta::new_readonly(pool.mint_a, false),
+            AccountMeta::new_readonly(pool.token_program_a, false),
+        ],
+        data: ChudexInstruction::CloseDca.try_to_vec().unwrap(),
+    };
+    dex.process(&[close], &[]).await.unwrap();
+    assert_eq!(
+        dex.token_balance(&pool.user_token_a(&payer)).await,
+        token_a + 1_000_000
+    );
+    assert!(dex.account(&dca).await.is_none());
+}
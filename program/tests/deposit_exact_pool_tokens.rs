@@ -0,0 +1,86 @@
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use chudex::{
+    client::DepositResult, curve, error::ChudexError, instruction::ChudexInstruction, state::Pool,
+};
+use common::{assert_dex_error, Dex, PoolKeys};
+use solana_program_test::tokio;
+use solana_sdk::instruction::Instruction;
+
+fn deposit_exact_ix(
+    dex: &Dex,
+    pool: &PoolKeys,
+    pool_token_amount: u64,
+    max_token_a_amount: u64,
+    max_token_b_amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: dex.program_id,
+        accounts: pool.deposit_accounts(&dex.payer()),
+        data: ChudexInstruction::DepositExactPoolTokens {
+            pool_token_amount,
+            max_token_a_amount,
+            max_token_b_amount,
+            native_sol: false,
+        }
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+/// What `pool_token_amount` costs in a and b at the pool's current reserves.
+async fn cost(dex: &mut Dex, pool: &PoolKeys, pool_token_amount: u64) -> (u64, u64) {
+    let state: Pool = dex.state(&pool.pool).await;
+    let supply = dex.mint(&pool.pool_mint).await.supply;
+    (
+        curve::pool_tokens_to_reserve_ceil(pool_token_amount, supply, state.reserve_a).unwrap(),
+        curve::pool_tokens_to_reserve_ceil(pool_token_amount, supply, state.reserve_b).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn mints_exactly_the_pool_tokens_asked_for() {
+    let mut dex = Dex::start().await;
+    let pool = dex.pool_with_liquidity().await;
+    let payer = dex.payer();
+    // skew the reserves so the shares don't divide evenly
+    let exchange = dex.exchange_ix(&payer, &pool, true, 3_333_333, 1);
+    dex.process(&[exchange], &[]).await.unwrap();
+
+    let pool_tokens = dex.token_balance(&pool.user_pool_token(&payer)).await;
+    let pool_token_amount = 1_234_567;
+    let (token_a_amount, token_b_amount) = cost(&mut dex, &pool, pool_token_amount).await;
+    let deposit = deposit_exact_ix(
+        &dex,
+        &pool,
+        pool_token_amount,
+        token_a_amount,
+        token_b_amount,
+    );
+    let return_data = dex.process(&[deposit], &[]).await.unwrap().unwrap();
+
+    assert_eq!(
+        DepositResult::try_from_slice(&return_data.data).unwrap(),
+        DepositResult {
+            token_a_amount,
+            token_b_amount,
+            pool_token_amount,
+        }
+    );
+    assert_eq!(
+        dex.token_balance(&pool.user_pool_token(&payer)).await,
+        pool_tokens + pool_token_amount
+    );
+}
+
+#[tokio::test]
+async fn cost_over_the_maximum_fails() {
+    let mut dex = Dex::start().await;
+    let pool = dex.pool_with_liquidity().await;
+    let (token_a_amount, token_b_amount) = cost(&mut dex, &pool, 1_000_000).await;
+
+    let deposit = deposit_exact_ix(&dex, &pool, 1_000_000, token_a_amount, token_b_amount - 1);
+    let result = dex.process(&[deposit], &[]).await;
+    assert_dex_error(result, ChudexError::DepositAmountExceedsLimit);
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use chudex::{
+    curve,
+    error::ChudexError,
+    state::{DynamicFeeConfig, Pool},
+};
+use common::{assert_dex_error, Dex, PoolKeys};
+use solana_program_test::tokio;
+use solana_sdk::instruction::Instruction;
+
+const HALF_LIFE: u64 = 1_000;
+
+/// Keys and InitializePool of a 9 and a 6 decimals mint on the 30 bps tier with
+/// `dynamic_fee`, the payer holding a billion of each.
+async fn dynamic_pool(dex: &mut Dex, dynamic_fee: DynamicFeeConfig) -> (PoolKeys, Instruction) {
+    dex.initialize_config(vec![30]).await;
+    let mint_a = dex.create_mint(9).await;
+    let mint_b = dex.create_mint(6).await;
+    let payer = dex.payer();
+    dex.create_token_account(&payer, &mint_a, 1_000_000_000)
+        .await;
+    dex.create_token_account(&payer, &mint_b, 1_000_000_000)
+        .await;
+    let pool = dex.pool_keys(&mint_a, &mint_b).await;
+    let initialize = dex
+        .initialize_pool_ix(&payer, &pool, 30, Some(dynamic_fee), None, None)
+        .await;
+    (pool, initialize)
+}
+
+#[tokio::test]
+async fn fee_rises_with_price_moves_and_decays() {
+    let mut dex = Dex::start().await;
+    let (pool, initialize) = dynamic_pool(
+        &mut dex,
+        DynamicFeeConfig {
+            max_fee: 300,
+            volatility_fee: 10_000,
+            half_life: HALF_LIFE,
+        },
+    )
+    .await;
+    let payer = dex.payer();
+    let deposit = dex.deposit_ix(&payer, &pool, 100_000_000, 100_000_000);
+    dex.process(&[initialize, deposit], &[]).await.unwrap();
+    let now = dex.clock().await.slot;
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!(state.effective_fee(now), 30);
+
+    // a tenth of the reserve in moves the price by about a fifth, more than the cap
+    let exchange = dex.exchange_ix(&payer, &pool, true, 10_000_000, 1);
+    dex.process(&[exchange], &[]).await.unwrap();
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!(state.effective_fee(now), 300);
+
+    // the next swap pays the raised fee
+    let (amount_out, _) = curve::swap(
+        1_000_000,
+        state.reserve_b,
+        state.reserve_a,
+        300,
+        state.fee_decimals,
+    )
+    .unwrap();
+    let before = dex.token_balance(&pool.user_token_a(&payer)).await;
+    let exchange = dex.exchange_ix(&payer, &pool, false, 1_000_000, 1);
+    dex.process(&[exchange], &[]).await.unwrap();
+    assert_eq!(
+        dex.token_balance(&pool.user_token_a(&payer)).await - before,
+        amount_out
+    );
+
+    // and with enough half lives gone it's back to the tier fee
+    dex.advance_slots(20 * HALF_LIFE).await;
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!(state.effective_fee(dex.clock().await.slot), 30);
+}
+
+#[tokio::test]
+async fn max_fee_cant_be_under_the_tier_fee() {
+    let mut dex = Dex::start().await;
+    let (_, initialize) = dynamic_pool(
+        &mut dex,
+        DynamicFeeConfig {
+            max_fee: 20,
+            volatility_fee: 10_000,
+            half_life: HALF_LIFE,
+        },
+    )
+    .await;
+
+    let result = dex.process(&[initialize], &[]).await;
+    assert_dex_error(result, ChudexError::InvalidFee);
+}
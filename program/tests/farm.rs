@@ -0,0 +1,204 @@
+mod common;
+
+use borsh::BorshSerialize;
+use chudex::{
+    error::ChudexError,
+    instruction::ChudexInstruction,
+    state::{Farm, Stake},
+};
+use common::{assert_dex_error, associated_address, Dex, PoolKeys};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+};
+
+const EMISSION_RATE: u64 = 1_000;
+const DURATION: u64 = 1_000;
+
+/// Addresses of a farm created by the payer, paying a single reward mint.
+struct FarmKeys {
+    farm: Pubkey,
+    vault: Pubkey,
+    reward_mint: Pubkey,
+    reward_vault: Pubkey,
+}
+
+/// Farm on a pool with liquidity from the payer, paying `EMISSION_RATE` of a fresh reward
+/// mint per slot for `DURATION` slots from now.
+async fn farm(dex: &mut Dex) -> (PoolKeys, FarmKeys) {
+    let pool = dex.pool_with_liquidity().await;
+    let payer = dex.payer();
+    let reward_mint = dex.create_mint(6).await;
+    dex.create_token_account(&payer, &reward_mint, 1_000_000_000)
+        .await;
+    let farm = dex.find(&[
+        Farm::SEED,
+        pool.pool.as_ref(),
+        payer.as_ref(),
+        &0u64.to_le_bytes(),
+    ]);
+    let keys = FarmKeys {
+        farm,
+        vault: associated_address(&farm, &pool.pool_mint, &spl_token::id()),
+        reward_mint,
+        reward_vault: associated_address(&farm, &reward_mint, &spl_token::id()),
+    };
+
+    let start_slot = dex.clock().await.slot;
+    let create = Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(pool.pool, false),
+            AccountMeta::new(keys.farm, false),
+            AccountMeta::new(keys.vault, false),
+            AccountMeta::new_readonly(pool.pool_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(reward_mint, false),
+            AccountMeta::new(
+                associated_address(&payer, &reward_mint, &spl_token::id()),
+                false,
+            ),
+            AccountMeta::new(keys.reward_vault, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: ChudexInstruction::CreateFarm {
+            nonce: 0,
+            emission_rates: vec![EMISSION_RATE],
+            start_slot,
+            end_slot: start_slot + DURATION,
+        }
+        .try_to_vec()
+        .unwrap(),
+    };
+    dex.process(&[create], &[]).await.unwrap();
+    (pool, keys)
+}
+
+/// Harvest or ReclaimFarmRewards, both of which pay `to` from the farm's reward vault.
+fn payout_ix(
+    dex: &Dex,
+    farm: &FarmKeys,
+    signer: &Pubkey,
+    to: &Pubkey,
+    instruction: ChudexInstruction,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new(farm.farm, false),
+    ];
+    if let ChudexInstruction::Harvest = instruction {
+        accounts.push(AccountMeta::new(
+            dex.find(&[Stake::SEED, farm.farm.as_ref(), signer.as_ref()]),
+            false,
+        ));
+    }
+    accounts.extend([
+        AccountMeta::new_readonly(farm.reward_mint, false),
+        AccountMeta::new(farm.reward_vault, false),
+        AccountMeta::new(
+            associated_address(to, &farm.reward_mint, &spl_token::id()),
+            false,
+        ),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ]);
+    Instruction {
+        program_id: dex.program_id,
+        accounts,
+        data: instruction.try_to_vec().unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn stake_earns_the_emissions_and_harvests_them() {
+    let mut dex = Dex::start().await;
+    let (pool, farm) = farm(&mut dex).await;
+    let payer = dex.payer();
+    assert_eq!(
+        dex.token_balance(&farm.reward_vault).await,
+        EMISSION_RATE * DURATION
+    );
+
+    let stake = Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(pool.user_pool_token(&payer), false),
+            AccountMeta::new(farm.farm, false),
+            AccountMeta::new(
+                dex.find(&[Stake::SEED, farm.farm.as_ref(), payer.as_ref()]),
+                false,
+            ),
+            AccountMeta::new(farm.vault, false),
+            AccountMeta::new_readonly(pool.pool_mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: ChudexInstruction::Stake { amount: 1_000_000 }
+            .try_to_vec()
+            .unwrap(),
+    };
+    dex.process(&[stake], &[]).await.unwrap();
+    let staked_at = dex.clock().await.slot;
+    assert_eq!(dex.token_balance(&farm.vault).await, 1_000_000);
+
+    // the only stake gets all of the emissions since it staked, bar rounding
+    dex.advance_slots(100).await;
+    let user_reward = associated_address(&payer, &farm.reward_mint, &spl_token::id());
+    let before = dex.token_balance(&user_reward).await;
+    let harvest = payout_ix(&dex, &farm, &payer, &payer, ChudexInstruction::Harvest);
+    dex.process(&[harvest], &[]).await.unwrap();
+    let harvested = dex.token_balance(&user_reward).await - before;
+    let emitted = EMISSION_RATE * (dex.clock().await.slot - staked_at);
+    assert!(harvested <= emitted && harvested + 1 >= emitted);
+
+    // still staked, so the stake stays open
+    let state: Stake = dex
+        .state(&dex.find(&[Stake::SEED, farm.farm.as_ref(), payer.as_ref()]))
+        .await;
+    assert_eq!(state.amount, 1_000_000);
+    assert_eq!(state.rewards_owed, vec![0]);
+}
+
+#[tokio::test]
+async fn only_the_authority_reclaims_unstaked_emissions() {
+    let mut dex = Dex::start().await;
+    let (_, farm) = farm(&mut dex).await;
+    let payer = dex.payer();
+    dex.advance_slots(100).await;
+
+    let other = Keypair::new();
+    let reclaim = payout_ix(
+        &dex,
+        &farm,
+        &other.pubkey(),
+        &payer,
+        ChudexInstruction::ReclaimFarmRewards,
+    );
+    let result = dex.process(&[reclaim], &[&other]).await;
+    assert_dex_error(result, ChudexError::IncorrectAdmin);
+
+    // nothing was staked, so everything emitted so far goes back
+    let user_reward = associated_address(&payer, &farm.reward_mint, &spl_token::id());
+    let before = dex.token_balance(&user_reward).await;
+    let reclaim = payout_ix(
+        &dex,
+        &farm,
+        &payer,
+        &payer,
+        ChudexInstruction::ReclaimFarmRewards,
+    );
+    dex.process(&[reclaim], &[]).await.unwrap();
+    let state: Farm = dex.state(&farm.farm).await;
+    assert_eq!(
+        dex.token_balance(&user_reward).await - before,
+        EMISSION_RATE * (state.last_update_slot - state.start_slot)
+    );
+    assert_eq!(state.rewards[0].unallocated, 0);
+}
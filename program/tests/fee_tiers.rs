@@ -0,0 +1,74 @@
+mod common;
+
+use borsh::BorshSerialize;
+use chudex::{
+    error::ChudexError,
+    instruction::ChudexInstruction,
+    state::{GlobalConfig, Pool},
+};
+use common::{assert_dex_error, Dex};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+fn set_fee_tiers_ix(dex: &Dex, admin: &Pubkey, fee_tiers: Vec<u16>) -> Instruction {
+    Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(dex.find(&[GlobalConfig::SEED]), false),
+        ],
+        data: ChudexInstruction::SetFeeTiers { fee_tiers }
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn pools_keep_the_tier_they_were_created_on() {
+    let mut dex = Dex::start().await;
+    dex.initialize_config(vec![5, 30, 100]).await;
+    let mint_a = dex.create_mint(9).await;
+    let mint_b = dex.create_mint(6).await;
+    let mint_c = dex.create_mint(6).await;
+    let pool = dex.create_pool(&mint_a, &mint_b, 100).await;
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!(state.fee, 100);
+    assert_eq!(state.fee_decimals, GlobalConfig::FEE_DECIMALS);
+
+    let admin = Keypair::from_bytes(&dex.admin.to_bytes()).unwrap();
+    let set_fee_tiers = set_fee_tiers_ix(&dex, &admin.pubkey(), vec![5]);
+    dex.process(&[set_fee_tiers], &[&admin]).await.unwrap();
+    let config: GlobalConfig = dex.state(&dex.find(&[GlobalConfig::SEED])).await;
+    assert_eq!(config.fee_tiers(), [5]);
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!(state.fee, 100);
+
+    // the removed tier is closed to new pools
+    let pool = dex.pool_keys(&mint_a, &mint_c).await;
+    let payer = dex.payer();
+    let initialize = dex
+        .initialize_pool_ix(&payer, &pool, 30, None, None, None)
+        .await;
+    let result = dex.process(&[initialize], &[]).await;
+    assert_dex_error(result, ChudexError::InvalidFee);
+}
+
+#[tokio::test]
+async fn only_the_upgrade_authority_sets_up_fees() {
+    let mut dex = Dex::start().await;
+    let other = dex.user(1_000_000_000).await;
+
+    let mut initialize = dex.initialize_config_ix(vec![30]);
+    initialize.accounts[0].pubkey = other.pubkey();
+    let result = dex.process(&[initialize], &[&other]).await;
+    assert_dex_error(result, ChudexError::IncorrectAdmin);
+
+    dex.initialize_config(vec![30]).await;
+    let set_fee_tiers = set_fee_tiers_ix(&dex, &other.pubkey(), vec![5]);
+    let result = dex.process(&[set_fee_tiers], &[&other]).await;
+    assert_dex_error(result, ChudexError::IncorrectAdmin);
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use chudex::{
+    hook::{self, HookOperation, HookPayload, HookStage},
+    state::Pool,
+};
+use common::{assert_instruction_error, program_test, Dex, PoolKeys};
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+};
+use solana_program_test::{processor, tokio};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+/// Largest exchange the test hook lets through.
+const MAX_AMOUNT_IN: u64 = 5_000_000;
+
+/// Hook that only takes calls signed by the dex and turns down big exchanges.
+fn process_hook(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let payload = HookPayload::try_from_slice(data)?;
+    if !accounts[0].is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if let (HookStage::Before, HookOperation::Exchange { a_to_b }) =
+        (payload.stage, payload.operation)
+    {
+        let amount_in = if a_to_b {
+            payload.token_a_amount
+        } else {
+            payload.token_b_amount
+        };
+        if amount_in > MAX_AMOUNT_IN {
+            msg!("Exchange too big");
+            return Err(ProgramError::Custom(42));
+        }
+    }
+    Ok(())
+}
+
+/// Dex with the test hook loaded, and a pool using it with liquidity from the payer.
+async fn hooked_pool() -> (Dex, PoolKeys, Pubkey) {
+    let program_id = Pubkey::new_unique();
+    let hook_program = Pubkey::new_unique();
+    let admin = Keypair::new();
+    let mut program_test = program_test(program_id, &admin.pubkey());
+    program_test.add_program("hook", hook_program, processor!(process_hook));
+    let mut dex = Dex::start_with(program_test, program_id, admin).await;
+
+    dex.initialize_config(vec![30]).await;
+    let mint_a = dex.create_mint(9).await;
+    let mint_b = dex.create_mint(6).await;
+    let payer = dex.payer();
+    dex.create_token_account(&payer, &mint_a, 1_000_000_000)
+        .await;
+    dex.create_token_account(&payer, &mint_b, 1_000_000_000)
+        .await;
+    let pool = dex.pool_keys(&mint_a, &mint_b).await;
+    let initialize = dex
+        .initialize_pool_ix(&payer, &pool, 30, None, None, Some(hook_program))
+        .await;
+    let mut deposit = dex.deposit_ix(&payer, &pool, 100_000_000, 100_000_000);
+    deposit
+        .accounts
+        .extend(hook_accounts(&dex, &pool, hook_program));
+    dex.process(&[initialize, deposit], &[]).await.unwrap();
+    (dex, pool, hook_program)
+}
+
+/// The hook program and the pool's hook authority, as passed after an instruction's own
+/// accounts.
+fn hook_accounts(dex: &Dex, pool: &PoolKeys, hook_program: Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(hook_program, false),
+        AccountMeta::new_readonly(dex.find(&[hook::AUTHORITY_SEED, pool.pool.as_ref()]), false),
+    ]
+}
+
+fn exchange_ix(dex: &Dex, pool: &PoolKeys, hook_program: Pubkey, amount_in: u64) -> Instruction {
+    let mut exchange = dex.exchange_ix(&dex.payer(), pool, true, amount_in, 1);
+    exchange
+        .accounts
+        .extend(hook_accounts(dex, pool, hook_program));
+    exchange
+}
+
+#[tokio::test]
+async fn hook_lets_exchanges_it_approves_through() {
+    let (mut dex, pool, hook_program) = hooked_pool().await;
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!(state.hook_program, Some(hook_program));
+    assert_eq!(dex.token_balance(&pool.vault_a).await, 100_000_000);
+
+    let exchange = exchange_ix(&dex, &pool, hook_program, MAX_AMOUNT_IN);
+    dex.process(&[exchange], &[]).await.unwrap();
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!(state.reserve_a, 100_000_000 + MAX_AMOUNT_IN);
+}
+
+#[tokio::test]
+async fn hook_rejection_aborts_the_exchange() {
+    let (mut dex, pool, hook_program) = hooked_pool().await;
+
+    let exchange = exchange_ix(&dex, &pool, hook_program, MAX_AMOUNT_IN + 1);
+    let result = dex.process(&[exchange], &[]).await;
+    assert_instruction_error(result, InstructionError::Custom(42));
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!(state.reserve_a, 100_000_000);
+
+    // and the hook can't be left out
+    let exchange = dex.exchange_ix(&dex.payer(), &pool, true, 1_000_000, 1);
+    let result = dex.process(&[exchange], &[]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+}
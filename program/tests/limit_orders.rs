@@ -0,0 +1,184 @@
+mod common;
+
+use borsh::BorshSerialize;
+use chudex::{
+    curve,
+    error::ChudexError,
+    instruction::ChudexInstruction,
+    state::{LimitOrder, Pool},
+};
+use common::{assert_dex_error, associated_address, Dex, PoolKeys};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+};
+
+fn order_address(dex: &Dex, pool: &PoolKeys, owner: &Pubkey, nonce: u64) -> Pubkey {
+    dex.find(&[
+        LimitOrder::SEED,
+        pool.pool.as_ref(),
+        owner.as_ref(),
+        &nonce.to_le_bytes(),
+    ])
+}
+
+/// PlaceLimitOrder by `owner` selling a for b.
+fn place_ix(
+    dex: &Dex,
+    pool: &PoolKeys,
+    owner: &Pubkey,
+    nonce: u64,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let order = order_address(dex, pool, owner, nonce);
+    Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(pool.user_token_a(owner), false),
+            AccountMeta::new_readonly(pool.user_token_b(owner), false),
+            AccountMeta::new_readonly(pool.pool, false),
+            AccountMeta::new(order, false),
+            AccountMeta::new(
+                associated_address(&order, &pool.mint_a, &spl_token::id()),
+                false,
+            ),
+            AccountMeta::new_readonly(pool.mint_a, false),
+            AccountMeta::new_readonly(pool.mint_b, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: ChudexInstruction::PlaceLimitOrder {
+            nonce,
+            amount_in,
+            min_amount_out,
+        }
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn keeper_fills_crossed_orders_and_skips_the_rest() {
+    let mut dex = Dex::start().await;
+    let pool = dex.pool_with_liquidity().await;
+    let owner = dex.user(1_000_000_000).await;
+    dex.fund(&owner.pubkey(), &pool, 2_000_000, 0).await;
+    let keeper = Keypair::new();
+    dex.fund(&keeper.pubkey(), &pool, 0, 0).await;
+
+    // one at 0.9 b per a, under the 1:1 spot price, one at 2 b per a, over it
+    dex.process(
+        &[
+            place_ix(&dex, &pool, &owner.pubkey(), 0, 1_000_000, 900_000),
+            place_ix(&dex, &pool, &owner.pubkey(), 1, 1_000_000, 2_000_000),
+        ],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        dex.token_balance(&pool.user_token_a(&owner.pubkey())).await,
+        0
+    );
+
+    let state: Pool = dex.state(&pool.pool).await;
+    let (amount_out, _) = curve::swap(
+        1_000_000,
+        state.reserve_a,
+        state.reserve_b,
+        state.fee,
+        state.fee_decimals,
+    )
+    .unwrap();
+    let keeper_fee = curve::fee_amount(
+        amount_out,
+        LimitOrder::KEEPER_FEE,
+        LimitOrder::KEEPER_FEE_DECIMALS,
+    )
+    .unwrap();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(keeper.pubkey(), true),
+        AccountMeta::new(pool.user_token_b(&keeper.pubkey()), false),
+    ];
+    accounts.extend([
+        AccountMeta::new(pool.pool, false),
+        AccountMeta::new(pool.vault_a, false),
+        AccountMeta::new(pool.vault_b, false),
+        AccountMeta::new_readonly(pool.mint_a, false),
+        AccountMeta::new_readonly(pool.mint_b, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ]);
+    accounts.extend(pool.twamm_accounts());
+    for nonce in [0, 1] {
+        let order = order_address(&dex, &pool, &owner.pubkey(), nonce);
+        accounts.extend([
+            AccountMeta::new(order, false),
+            AccountMeta::new(
+                associated_address(&order, &pool.mint_a, &spl_token::id()),
+                false,
+            ),
+            AccountMeta::new(pool.user_token_b(&owner.pubkey()), false),
+            AccountMeta::new(owner.pubkey(), false),
+        ]);
+    }
+    let fill = Instruction {
+        program_id: dex.program_id,
+        accounts,
+        data: ChudexInstruction::FillOrders.try_to_vec().unwrap(),
+    };
+    dex.process(&[fill], &[&keeper]).await.unwrap();
+
+    assert_eq!(
+        dex.token_balance(&pool.user_token_b(&owner.pubkey())).await,
+        amount_out - keeper_fee
+    );
+    assert_eq!(
+        dex.token_balance(&pool.user_token_b(&keeper.pubkey()))
+            .await,
+        keeper_fee
+    );
+    // the filled order is closed, the other still rests
+    assert!(dex
+        .account(&order_address(&dex, &pool, &owner.pubkey(), 0))
+        .await
+        .is_none());
+    let resting = order_address(&dex, &pool, &owner.pubkey(), 1);
+    assert!(dex.account(&resting).await.is_some());
+    assert_eq!(
+        dex.token_balance(&associated_address(
+            &resting,
+            &pool.mint_a,
+            &spl_token::id()
+        ))
+        .await,
+        1_000_000
+    );
+    let after: Pool = dex.state(&pool.pool).await;
+    assert_eq!(after.reserve_a, state.reserve_a + 1_000_000);
+    assert_eq!(after.reserve_b, state.reserve_b - amount_out);
+}
+
+#[tokio::test]
+async fn orders_need_a_limit() {
+    let mut dex = Dex::start().await;
+    let pool = dex.pool_with_liquidity().await;
+    let owner = dex.user(1_000_000_000).await;
+    dex.fund(&owner.pubkey(), &pool, 1_000_000, 0).await;
+
+    let result = dex
+        .process(
+            &[place_ix(&dex, &pool, &owner.pubkey(), 0, 1_000_000, 0)],
+            &[&owner],
+        )
+        .await;
+    assert_dex_error(result, ChudexError::InvalidInstructionInput);
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use borsh::BorshSerialize;
+use chudex::{
+    error::ChudexError,
+    instruction::ChudexInstruction,
+    state::{Locker, PoolLockers},
+};
+use common::{assert_dex_error, associated_address, Dex, PoolKeys};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+/// Pool with liquidity and the payer's first locker of a million pool tokens, unlocking in
+/// a thousand seconds.
+async fn locked_pool(dex: &mut Dex, vesting: Option<i64>) -> (PoolKeys, Pubkey) {
+    let pool = dex.pool_with_liquidity().await;
+    let payer = dex.payer();
+    let now = dex.clock().await.unix_timestamp;
+    let locker = dex.find(&[Locker::SEED, pool.pool.as_ref(), &0u64.to_le_bytes()]);
+    let lock = Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(pool.user_pool_token(&payer), false),
+            AccountMeta::new_readonly(pool.pool, false),
+            AccountMeta::new_readonly(pool.pool_mint, false),
+            AccountMeta::new(dex.find(&[PoolLockers::SEED, pool.pool.as_ref()]), false),
+            AccountMeta::new(locker, false),
+            AccountMeta::new(locker_vault(&pool, &locker), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: ChudexInstruction::LockLiquidity {
+            amount: 1_000_000,
+            unlock_timestamp: now + 1_000,
+            vesting_end_timestamp: vesting.map(|duration| now + 1_000 + duration),
+        }
+        .try_to_vec()
+        .unwrap(),
+    };
+    dex.process(&[lock], &[]).await.unwrap();
+    (pool, locker)
+}
+
+fn locker_vault(pool: &PoolKeys, locker: &Pubkey) -> Pubkey {
+    associated_address(locker, &pool.pool_mint, &spl_token::id())
+}
+
+fn unlock_ix(dex: &Dex, pool: &PoolKeys, locker: &Pubkey) -> Instruction {
+    let payer = dex.payer();
+    Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(payer, true),
+            AccountMeta::new(pool.user_pool_token(&payer), false),
+            AccountMeta::new(*locker, false),
+            AccountMeta::new(locker_vault(pool, locker), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: ChudexInstruction::UnlockLiquidity.try_to_vec().unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn vested_pool_tokens_are_released_over_time() {
+    let mut dex = Dex::start().await;
+    let (pool, locker) = locked_pool(&mut dex, Some(1_000)).await;
+    let payer = dex.payer();
+    let pool_tokens = dex.token_balance(&pool.user_pool_token(&payer)).await;
+    assert_eq!(
+        dex.token_balance(&locker_vault(&pool, &locker)).await,
+        1_000_000
+    );
+
+    // halfway through vesting
+    dex.advance_time(1_500).await;
+    let state: Locker = dex.state(&locker).await;
+    let vested = state.vested(dex.clock().await.unix_timestamp);
+    assert!(vested > 0 && vested < 1_000_000);
+    dex.process(&[unlock_ix(&dex, &pool, &locker)], &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        dex.token_balance(&pool.user_pool_token(&payer)).await,
+        pool_tokens + vested
+    );
+
+    // and the rest after it ends
+    dex.advance_time(1_000).await;
+    dex.refresh_blockhash().await;
+    dex.process(&[unlock_ix(&dex, &pool, &locker)], &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        dex.token_balance(&pool.user_pool_token(&payer)).await,
+        pool_tokens + 1_000_000
+    );
+    let state: Locker = dex.state(&locker).await;
+    assert_eq!(state.released, 1_000_000);
+}
+
+#[tokio::test]
+async fn nothing_unlocks_early() {
+    let mut dex = Dex::start().await;
+    let (pool, locker) = locked_pool(&mut dex, None).await;
+    dex.advance_time(999).await;
+
+    let result = dex.process(&[unlock_ix(&dex, &pool, &locker)], &[]).await;
+    assert_dex_error(result, ChudexError::InvalidInstructionInput);
+    assert_eq!(
+        dex.token_balance(&locker_vault(&pool, &locker)).await,
+        1_000_000
+    );
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use borsh::BorshSerialize;
+use chudex::{
+    error::ChudexError,
+    instruction::ChudexInstruction,
+    state::{GlobalConfig, LpMetadata},
+};
+use common::{assert_dex_error, Dex, PoolKeys};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program,
+};
+
+fn update_ix(dex: &Dex, admin: &Pubkey, pool: &PoolKeys, name: &str) -> Instruction {
+    Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(dex.find(&[GlobalConfig::SEED]), false),
+            AccountMeta::new_readonly(pool.pool, false),
+            AccountMeta::new(pool.lp_metadata, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: ChudexInstruction::UpdateLpMetadata {
+            name: name.to_string(),
+            symbol: "CHU-LP".to_string(),
+            uri: "https://example.com/lp.json".to_string(),
+        }
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn lp_mint_is_named_after_its_pair_and_renamed_by_the_admin() {
+    let mut dex = Dex::start().await;
+    let pool = dex.pool_with_liquidity().await;
+    let metadata: LpMetadata = dex.state(&pool.lp_metadata).await;
+    let default = LpMetadata::new(pool.pool_mint, &pool.mint_a, &pool.mint_b).unwrap();
+    assert_eq!(metadata.mint, pool.pool_mint);
+    assert!(!metadata.name().is_empty());
+    assert_eq!(metadata.name(), default.name());
+    assert_eq!(metadata.symbol(), default.symbol());
+
+    let admin = Keypair::from_bytes(&dex.admin.to_bytes()).unwrap();
+    let update = update_ix(&dex, &admin.pubkey(), &pool, "Chu Pool LP");
+    dex.process(&[update], &[&admin]).await.unwrap();
+    let metadata: LpMetadata = dex.state(&pool.lp_metadata).await;
+    assert_eq!(metadata.name(), "Chu Pool LP");
+    assert_eq!(metadata.symbol(), "CHU-LP");
+    assert_eq!(metadata.uri(), "https://example.com/lp.json");
+}
+
+#[tokio::test]
+async fn names_have_to_fit_and_come_from_the_admin() {
+    let mut dex = Dex::start().await;
+    let pool = dex.pool_with_liquidity().await;
+    let admin = Keypair::from_bytes(&dex.admin.to_bytes()).unwrap();
+
+    let name = "x".repeat(LpMetadata::MAX_NAME_LEN + 1);
+    let update = update_ix(&dex, &admin.pubkey(), &pool, &name);
+    let result = dex.process(&[update], &[&admin]).await;
+    assert_dex_error(result, ChudexError::InvalidInstructionInput);
+
+    let update = update_ix(&dex, &dex.payer(), &pool, "Chu Pool LP");
+    let result = dex.process(&[update], &[]).await;
+    assert_dex_error(result, ChudexError::IncorrectAdmin);
+}
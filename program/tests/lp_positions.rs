@@ -0,0 +1,189 @@
+mod common;
+
+use borsh::BorshSerialize;
+use chudex::{instruction::ChudexInstruction, state::LpPosition};
+use common::{assert_instruction_error, associated_address, Dex, PoolKeys};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+};
+use spl_token::error::TokenError;
+
+/// Addresses of a position opened by the payer with nonce 0.
+struct PositionKeys {
+    mint: Pubkey,
+    position: Pubkey,
+    vault: Pubkey,
+}
+
+impl PositionKeys {
+    fn new(dex: &Dex, pool: &PoolKeys) -> Self {
+        let mint = dex.find(&[
+            LpPosition::MINT_SEED,
+            dex.payer().as_ref(),
+            &0u64.to_le_bytes(),
+        ]);
+        let position = dex.find(&[LpPosition::SEED, mint.as_ref()]);
+        Self {
+            mint,
+            position,
+            vault: associated_address(&position, &pool.pool_mint, &spl_token::id()),
+        }
+    }
+
+    fn holder_token(&self, holder: &Pubkey) -> Pubkey {
+        associated_address(holder, &self.mint, &spl_token::id())
+    }
+}
+
+/// Pool with liquidity, and a million of the payer's pool tokens moved into a position.
+async fn position(dex: &mut Dex) -> (PoolKeys, PositionKeys) {
+    let pool = dex.pool_with_liquidity().await;
+    let payer = dex.payer();
+    let position = PositionKeys::new(dex, &pool);
+    let convert = Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(pool.user_pool_token(&payer), false),
+            AccountMeta::new_readonly(pool.pool, false),
+            AccountMeta::new_readonly(pool.pool_mint, false),
+            AccountMeta::new(position.mint, false),
+            AccountMeta::new(position.holder_token(&payer), false),
+            AccountMeta::new(position.position, false),
+            AccountMeta::new(position.vault, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: ChudexInstruction::ConvertToPosition {
+            nonce: 0,
+            pool_token_amount: 1_000_000,
+        }
+        .try_to_vec()
+        .unwrap(),
+    };
+    dex.process(&[convert], &[]).await.unwrap();
+    (pool, position)
+}
+
+fn convert_from_ix(
+    dex: &Dex,
+    pool: &PoolKeys,
+    position: &PositionKeys,
+    holder: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new(*holder, true),
+            AccountMeta::new(position.holder_token(holder), false),
+            AccountMeta::new(pool.user_pool_token(holder), false),
+            AccountMeta::new_readonly(pool.pool, false),
+            AccountMeta::new_readonly(pool.pool_mint, false),
+            AccountMeta::new(position.mint, false),
+            AccountMeta::new(position.position, false),
+            AccountMeta::new(position.vault, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: ChudexInstruction::ConvertFromPosition.try_to_vec().unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn position_goes_to_whoever_holds_its_nft() {
+    let mut dex = Dex::start().await;
+    let (pool, position) = position(&mut dex).await;
+    let payer = dex.payer();
+    assert_eq!(dex.token_balance(&position.vault).await, 1_000_000);
+    assert_eq!(dex.token_balance(&position.holder_token(&payer)).await, 1);
+    let state: LpPosition = dex.state(&position.position).await;
+    assert_eq!(state.pool_token_amount, 1_000_000);
+    assert_eq!(state.mint, position.mint);
+
+    // hand the nft over, the new holder takes the pool tokens
+    let holder = dex.user(1_000_000_000).await;
+    let holder_token = dex
+        .create_token_account(&holder.pubkey(), &position.mint, 0)
+        .await;
+    let payer_keypair = Keypair::from_bytes(&dex.context.payer.to_bytes()).unwrap();
+    dex.transfer(
+        &position.mint,
+        &position.holder_token(&payer),
+        &holder_token,
+        &payer_keypair,
+        1,
+    )
+    .await;
+    let convert = convert_from_ix(&dex, &pool, &position, &holder.pubkey());
+    dex.process(&[convert], &[&holder]).await.unwrap();
+
+    assert_eq!(
+        dex.token_balance(&pool.user_pool_token(&holder.pubkey()))
+            .await,
+        1_000_000
+    );
+    assert!(dex.account(&position.position).await.is_none());
+    assert_eq!(dex.mint(&position.mint).await.supply, 0);
+}
+
+#[tokio::test]
+async fn only_the_holder_converts_back() {
+    let mut dex = Dex::start().await;
+    let (pool, position) = position(&mut dex).await;
+    let other = dex.user(1_000_000_000).await;
+    dex.create_token_account(&other.pubkey(), &position.mint, 0)
+        .await;
+
+    let convert = convert_from_ix(&dex, &pool, &position, &other.pubkey());
+    let result = dex.process(&[convert], &[&other]).await;
+    assert_instruction_error(
+        result,
+        InstructionError::Custom(TokenError::InsufficientFunds as u32),
+    );
+    assert_eq!(dex.token_balance(&position.vault).await, 1_000_000);
+}
+
+#[tokio::test]
+async fn position_withdraws_in_one_transaction() {
+    let mut dex = Dex::start().await;
+    let (pool, position) = position(&mut dex).await;
+    let payer = dex.payer();
+    let state: LpPosition = dex.state(&position.position).await;
+    let pool_tokens = dex.token_balance(&pool.user_pool_token(&payer)).await;
+    let token_a = dex.token_balance(&pool.user_token_a(&payer)).await;
+    let token_b = dex.token_balance(&pool.user_token_b(&payer)).await;
+
+    let mut accounts = pool.deposit_accounts(&payer);
+    // Withdraw drops Deposit's system, sysvar and associated token programs
+    accounts.drain(9..12);
+    let withdraw = Instruction {
+        program_id: dex.program_id,
+        accounts,
+        data: ChudexInstruction::Withdraw {
+            pool_token_amount: state.pool_token_amount,
+            min_token_a_amount: 1,
+            min_token_b_amount: 1,
+            native_sol: false,
+        }
+        .try_to_vec()
+        .unwrap(),
+    };
+    let convert = convert_from_ix(&dex, &pool, &position, &payer);
+    dex.process(&[convert, withdraw], &[]).await.unwrap();
+
+    assert!(dex.account(&position.position).await.is_none());
+    assert_eq!(
+        dex.token_balance(&pool.user_pool_token(&payer)).await,
+        pool_tokens
+    );
+    assert!(dex.token_balance(&pool.user_token_a(&payer)).await > token_a);
+    assert!(dex.token_balance(&pool.user_token_b(&payer)).await > token_b);
+}
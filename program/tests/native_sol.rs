@@ -0,0 +1,160 @@
+mod common;
+
+use borsh::BorshSerialize;
+use chudex::{curve, error::ChudexError, instruction::ChudexInstruction, state::Pool, token};
+use common::{assert_dex_error, Dex, PoolKeys};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Signer,
+    system_instruction, system_program, sysvar,
+};
+use spl_token::native_mint;
+
+/// Exchange by `user` paying in or out lamports through their wsol account in place of
+/// the native side's token account.
+fn native_exchange_ix(
+    dex: &Dex,
+    user: &Pubkey,
+    pool: &PoolKeys,
+    a_to_b: bool,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let wsol = token::wsol_address(&dex.program_id, user).0;
+    let (user_src, user_dst) = if a_to_b {
+        (wsol, pool.user_token_b(user))
+    } else {
+        (pool.user_token_b(user), wsol)
+    };
+    let mut accounts = vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new(user_src, false),
+        AccountMeta::new(user_dst, false),
+    ];
+    accounts.extend(pool.exchange_accounts(a_to_b));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    Instruction {
+        program_id: dex.program_id,
+        accounts,
+        data: ChudexInstruction::Exchange {
+            amount_in,
+            min_amount_out,
+            native_sol: true,
+        }
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+/// Pool of wrapped SOL and a 6 decimals mint, with liquidity deposited by the payer
+/// straight from lamports.
+async fn native_pool(dex: &mut Dex) -> PoolKeys {
+    dex.initialize_config(vec![30]).await;
+    let mint_b = dex.create_mint(6).await;
+    let payer = dex.payer();
+    dex.create_token_account(&payer, &mint_b, 1_000_000_000)
+        .await;
+    let pool = dex.create_pool(&native_mint::id(), &mint_b, 30).await;
+
+    let mut deposit = dex.deposit_ix(&payer, &pool, 1_000_000_000, 100_000_000);
+    deposit.accounts[1].pubkey = token::wsol_address(&dex.program_id, &payer).0;
+    deposit.data = ChudexInstruction::Deposit {
+        token_a_amount: 1_000_000_000,
+        max_token_b_amount: 100_000_000,
+        native_sol: true,
+    }
+    .try_to_vec()
+    .unwrap();
+    dex.process(&[deposit], &[]).await.unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn swaps_lamports_in_and_out() {
+    let mut dex = Dex::start().await;
+    let pool = native_pool(&mut dex).await;
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!(state.reserve_a, 1_000_000_000);
+
+    let user = dex.user(1_000_000_000).await;
+    dex.create_token_account(&user.pubkey(), &pool.mint_b, 0)
+        .await;
+    let wsol = token::wsol_address(&dex.program_id, &user.pubkey()).0;
+
+    // lamports in, the wsol account's rent comes back on close
+    let (amount_out, _) = curve::swap(
+        100_000_000,
+        state.reserve_a,
+        state.reserve_b,
+        state.fee,
+        state.fee_decimals,
+    )
+    .unwrap();
+    let lamports = dex.account(&user.pubkey()).await.unwrap().lamports;
+    let exchange = native_exchange_ix(&dex, &user.pubkey(), &pool, true, 100_000_000, 1);
+    dex.process(&[exchange], &[&user]).await.unwrap();
+    assert_eq!(
+        dex.account(&user.pubkey()).await.unwrap().lamports,
+        lamports - 100_000_000
+    );
+    assert_eq!(
+        dex.token_balance(&pool.user_token_b(&user.pubkey())).await,
+        amount_out
+    );
+    assert!(dex.account(&wsol).await.is_none());
+
+    // and back out as lamports
+    let state: Pool = dex.state(&pool.pool).await;
+    let (lamports_out, _) = curve::swap(
+        amount_out,
+        state.reserve_b,
+        state.reserve_a,
+        state.fee,
+        state.fee_decimals,
+    )
+    .unwrap();
+    let exchange = native_exchange_ix(&dex, &user.pubkey(), &pool, false, amount_out, 1);
+    dex.process(&[exchange], &[&user]).await.unwrap();
+    assert_eq!(
+        dex.account(&user.pubkey()).await.unwrap().lamports,
+        lamports - 100_000_000 + lamports_out
+    );
+    assert_eq!(
+        dex.token_balance(&pool.user_token_b(&user.pubkey())).await,
+        0
+    );
+    assert!(dex.account(&wsol).await.is_none());
+}
+
+#[tokio::test]
+async fn native_sol_needs_a_native_mint() {
+    let mut dex = Dex::start().await;
+    let pool = dex.pool_with_liquidity().await;
+    let payer = dex.payer();
+
+    let exchange = native_exchange_ix(&dex, &payer, &pool, true, 1_000_000, 1);
+    let result = dex.process(&[exchange], &[]).await;
+    assert_dex_error(result, ChudexError::InvalidInstructionInput);
+}
+
+#[tokio::test]
+async fn lamports_sent_to_the_wsol_address_do_not_block_it() {
+    let mut dex = Dex::start().await;
+    let pool = native_pool(&mut dex).await;
+    let user = dex.user(1_000_000_000).await;
+    dex.create_token_account(&user.pubkey(), &pool.mint_b, 0)
+        .await;
+    let wsol = token::wsol_address(&dex.program_id, &user.pubkey()).0;
+    let payer = dex.payer();
+    // enough to be rent exempt as an empty account, not as a token account
+    let grief = system_instruction::transfer(&payer, &wsol, 1_000_000);
+    dex.process(&[grief], &[]).await.unwrap();
+
+    let exchange = native_exchange_ix(&dex, &user.pubkey(), &pool, true, 100_000_000, 1);
+    dex.process(&[exchange], &[&user]).await.unwrap();
+    assert!(dex.token_balance(&pool.user_token_b(&user.pubkey())).await > 0);
+    assert!(dex.account(&wsol).await.is_none());
+}
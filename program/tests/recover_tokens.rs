@@ -0,0 +1,77 @@
+mod common;
+
+use borsh::BorshSerialize;
+use chudex::{error::ChudexError, instruction::ChudexInstruction, state::GlobalConfig};
+use common::{assert_dex_error, associated_address, Dex, PoolKeys};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+/// RecoverTokens of `mint` from the pool's `token_account` to the payer's.
+fn recover_ix(
+    dex: &Dex,
+    admin: &Pubkey,
+    pool: &PoolKeys,
+    token_account: Pubkey,
+    mint: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(dex.find(&[GlobalConfig::SEED]), false),
+            AccountMeta::new_readonly(pool.pool, false),
+            AccountMeta::new(token_account, false),
+            AccountMeta::new(
+                associated_address(&dex.payer(), &mint, &spl_token::id()),
+                false,
+            ),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: ChudexInstruction::RecoverTokens.try_to_vec().unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn admin_recovers_tokens_sent_to_the_pool() {
+    let mut dex = Dex::start().await;
+    let pool = dex.pool_with_liquidity().await;
+    let payer = dex.payer();
+    let stray_mint = dex.create_mint(6).await;
+    let stray = dex
+        .create_token_account(&pool.pool, &stray_mint, 5_000_000)
+        .await;
+    let recipient = dex.create_token_account(&payer, &stray_mint, 0).await;
+
+    let admin = Keypair::from_bytes(&dex.admin.to_bytes()).unwrap();
+    let recover = recover_ix(&dex, &admin.pubkey(), &pool, stray, stray_mint);
+    dex.process(&[recover], &[&admin]).await.unwrap();
+    assert_eq!(dex.token_balance(&stray).await, 0);
+    assert_eq!(dex.token_balance(&recipient).await, 5_000_000);
+}
+
+#[tokio::test]
+async fn vaults_and_non_admins_are_turned_away() {
+    let mut dex = Dex::start().await;
+    let pool = dex.pool_with_liquidity().await;
+    let payer = dex.payer();
+    let admin = Keypair::from_bytes(&dex.admin.to_bytes()).unwrap();
+
+    let recover = recover_ix(&dex, &admin.pubkey(), &pool, pool.vault_a, pool.mint_a);
+    let result = dex.process(&[recover], &[&admin]).await;
+    assert_dex_error(result, ChudexError::InvalidAccountAddress);
+    assert_eq!(dex.token_balance(&pool.vault_a).await, 100_000_000);
+
+    let stray_mint = dex.create_mint(6).await;
+    let stray = dex
+        .create_token_account(&pool.pool, &stray_mint, 5_000_000)
+        .await;
+    dex.create_token_account(&payer, &stray_mint, 0).await;
+    let recover = recover_ix(&dex, &payer, &pool, stray, stray_mint);
+    let result = dex.process(&[recover], &[]).await;
+    assert_dex_error(result, ChudexError::IncorrectAdmin);
+}
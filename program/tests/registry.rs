@@ -0,0 +1,60 @@
+mod common;
+
+use chudex::{
+    error::ChudexError,
+    state::{Factory, MintIndex, RegistryPage},
+};
+use common::{assert_dex_error, Dex};
+use solana_program_test::tokio;
+
+#[tokio::test]
+async fn pools_are_listed_overall_and_by_mint() {
+    let mut dex = Dex::start().await;
+    dex.initialize_config(vec![30]).await;
+    let mint_a = dex.create_mint(9).await;
+    let mint_b = dex.create_mint(6).await;
+    let mint_c = dex.create_mint(6).await;
+    let first = dex.create_pool(&mint_a, &mint_b, 30).await;
+    let second = dex.create_pool(&mint_a, &mint_c, 30).await;
+
+    let factory: Factory = dex.state(&dex.find(&[Factory::SEED])).await;
+    assert_eq!(factory.pool_count, 2);
+    let page: RegistryPage = dex
+        .state(&dex.find(&[RegistryPage::SEED, &0u64.to_le_bytes()]))
+        .await;
+    assert_eq!(page.pools(), [first.pool, second.pool]);
+
+    // the shared mint lists both, the others just their own
+    for (mint, pools) in [
+        (mint_a, vec![first.pool, second.pool]),
+        (mint_b, vec![first.pool]),
+        (mint_c, vec![second.pool]),
+    ] {
+        let index: MintIndex = dex
+            .state(&dex.find(&[MintIndex::SEED, mint.as_ref()]))
+            .await;
+        assert_eq!(index.mint, mint);
+        assert_eq!(index.pool_count, pools.len() as u64);
+        let page: RegistryPage = dex
+            .state(&dex.find(&[RegistryPage::SEED, mint.as_ref(), &0u64.to_le_bytes()]))
+            .await;
+        assert_eq!(page.pools(), pools);
+    }
+}
+
+#[tokio::test]
+async fn pools_go_on_the_current_page() {
+    let mut dex = Dex::start().await;
+    dex.initialize_config(vec![30]).await;
+    let mint_a = dex.create_mint(9).await;
+    let mint_b = dex.create_mint(6).await;
+    let pool = dex.pool_keys(&mint_a, &mint_b).await;
+    let payer = dex.payer();
+
+    let mut initialize = dex
+        .initialize_pool_ix(&payer, &pool, 30, None, None, None)
+        .await;
+    initialize.accounts[15].pubkey = dex.find(&[RegistryPage::SEED, &1u64.to_le_bytes()]);
+    let result = dex.process(&[initialize], &[]).await;
+    assert_dex_error(result, ChudexError::InvalidProgramAddress);
+}
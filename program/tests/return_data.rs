@@ -0,0 +1,126 @@
+mod common;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use chudex::{
+    client::{DepositResult, ExchangeResult, WithdrawResult},
+    curve,
+    error::ChudexError,
+    instruction::ChudexInstruction,
+    state::Pool,
+};
+use common::{assert_dex_error, Dex, PoolKeys};
+use solana_program_test::tokio;
+use solana_sdk::{instruction::Instruction, transaction_context::TransactionReturnData};
+
+fn withdraw_ix(
+    dex: &Dex,
+    pool: &PoolKeys,
+    pool_token_amount: u64,
+    min_token_a_amount: u64,
+    min_token_b_amount: u64,
+) -> Instruction {
+    let mut accounts = pool.deposit_accounts(&dex.payer());
+    // Withdraw drops Deposit's system, sysvar and associated token programs
+    accounts.drain(9..12);
+    Instruction {
+        program_id: dex.program_id,
+        accounts,
+        data: ChudexInstruction::Withdraw {
+            pool_token_amount,
+            min_token_a_amount,
+            min_token_b_amount,
+            native_sol: false,
+        }
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+/// Balances of the payer's token a, token b and pool token accounts.
+async fn balances(dex: &mut Dex, pool: &PoolKeys) -> [u64; 3] {
+    let payer = dex.payer();
+    [
+        dex.token_balance(&pool.user_token_a(&payer)).await,
+        dex.token_balance(&pool.user_token_b(&payer)).await,
+        dex.token_balance(&pool.user_pool_token(&payer)).await,
+    ]
+}
+
+/// Result returned by the dex.
+fn result<T: BorshDeserialize>(dex: &Dex, return_data: Option<TransactionReturnData>) -> T {
+    let return_data = return_data.unwrap();
+    assert_eq!(return_data.program_id, dex.program_id);
+    T::try_from_slice(&return_data.data).unwrap()
+}
+
+#[tokio::test]
+async fn operations_return_what_they_moved() {
+    let mut dex = Dex::start().await;
+    let pool = dex.pool_with_liquidity().await;
+    let payer = dex.payer();
+
+    let before = balances(&mut dex, &pool).await;
+    let deposit = dex.deposit_ix(&payer, &pool, 10_000_000, 20_000_000);
+    let return_data = dex.process(&[deposit], &[]).await.unwrap();
+    let after = balances(&mut dex, &pool).await;
+    let deposited: DepositResult = result(&dex, return_data);
+    assert_eq!(
+        deposited,
+        DepositResult {
+            token_a_amount: 10_000_000,
+            token_b_amount: before[1] - after[1],
+            pool_token_amount: after[2] - before[2],
+        }
+    );
+    assert_eq!(before[0] - after[0], 10_000_000);
+
+    // amounts are in the order the accounts were passed, here b then a
+    let state: Pool = dex.state(&pool.pool).await;
+    let (amount_out, fee) = curve::swap(
+        1_000_000,
+        state.reserve_b,
+        state.reserve_a,
+        state.fee,
+        state.fee_decimals,
+    )
+    .unwrap();
+    let exchange = dex.exchange_ix(&payer, &pool, false, 1_000_000, 1);
+    let return_data = dex.process(&[exchange], &[]).await.unwrap();
+    let exchanged: ExchangeResult = result(&dex, return_data);
+    assert_eq!(
+        exchanged,
+        ExchangeResult {
+            amount_in: 1_000_000,
+            amount_out,
+            fee,
+        }
+    );
+
+    let before = balances(&mut dex, &pool).await;
+    let withdraw = withdraw_ix(&dex, &pool, deposited.pool_token_amount, 0, 0);
+    let return_data = dex.process(&[withdraw], &[]).await.unwrap();
+    let after = balances(&mut dex, &pool).await;
+    let withdrawn: WithdrawResult = result(&dex, return_data);
+    assert_eq!(
+        withdrawn,
+        WithdrawResult {
+            pool_token_amount: deposited.pool_token_amount,
+            token_a_amount: after[0] - before[0],
+            token_b_amount: after[1] - before[1],
+        }
+    );
+    assert_eq!(before[2] - after[2], deposited.pool_token_amount);
+}
+
+#[tokio::test]
+async fn withdraw_below_its_minimum_fails() {
+    let mut dex = Dex::start().await;
+    let pool = dex.pool_with_liquidity().await;
+    let payer = dex.payer();
+    let pool_tokens = dex.token_balance(&pool.user_pool_token(&payer)).await;
+
+    // a tenth of the pool tokens can't get a fifth of the a reserve
+    let withdraw = withdraw_ix(&dex, &pool, pool_tokens / 10, 20_000_000, 0);
+    let result = dex.process(&[withdraw], &[]).await;
+    assert_dex_error(result, ChudexError::WithdrawAmountBelowMinimum);
+}
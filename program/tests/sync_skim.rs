@@ -0,0 +1,180 @@
+mod common;
+
+use borsh::BorshSerialize;
+use chudex::{instruction::ChudexInstruction, state::Pool};
+use common::{assert_instruction_error, Dex, PoolKeys};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Signer,
+};
+use spl_token::error::TokenError;
+
+fn sync_ix(dex: &Dex, pool: &PoolKeys) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(pool.pool, false),
+        AccountMeta::new(pool.vault_a, false),
+        AccountMeta::new(pool.vault_b, false),
+        AccountMeta::new_readonly(pool.mint_a, false),
+        AccountMeta::new_readonly(pool.mint_b, false),
+        AccountMeta::new_readonly(pool.token_program_a, false),
+        AccountMeta::new_readonly(pool.token_program_b, false),
+    ];
+    accounts.extend(pool.twamm_accounts());
+    Instruction {
+        program_id: dex.program_id,
+        accounts,
+        data: ChudexInstruction::Sync.try_to_vec().unwrap(),
+    }
+}
+
+fn skim_ix(dex: &Dex, pool: &PoolKeys, recipient: &Pubkey) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(pool.pool, false),
+        AccountMeta::new(pool.vault_a, false),
+        AccountMeta::new(pool.vault_b, false),
+        AccountMeta::new(pool.user_token_a(recipient), false),
+        AccountMeta::new(pool.user_token_b(recipient), false),
+        AccountMeta::new_readonly(pool.mint_a, false),
+        AccountMeta::new_readonly(pool.mint_b, false),
+        AccountMeta::new_readonly(pool.token_program_a, false),
+        AccountMeta::new_readonly(pool.token_program_b, false),
+    ];
+    accounts.extend(pool.twamm_accounts());
+    Instruction {
+        program_id: dex.program_id,
+        accounts,
+        data: ChudexInstruction::Skim.try_to_vec().unwrap(),
+    }
+}
+
+/// Asserts the pool's reserves are its vault balances.
+async fn assert_reserves_match_vaults(dex: &mut Dex, pool: &PoolKeys) {
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!(state.reserve_a, dex.token_balance(&pool.vault_a).await);
+    assert_eq!(state.reserve_b, dex.token_balance(&pool.vault_b).await);
+}
+
+#[tokio::test]
+async fn sync_takes_in_a_donation() {
+    let mut dex = Dex::start().await;
+    let pool = dex.pool_with_liquidity().await;
+    let payer = dex.payer();
+    let before: Pool = dex.state(&pool.pool).await;
+
+    // sent straight to the vault, the pool doesn't trade against it yet
+    dex.mint_to(&pool.mint_a, &pool.vault_a, 5_000).await;
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!(state.reserve_a, before.reserve_a);
+
+    dex.process(&[sync_ix(&dex, &pool)], &[]).await.unwrap();
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!(state.reserve_a, before.reserve_a + 5_000);
+    assert_eq!(state.reserve_b, before.reserve_b);
+    assert_reserves_match_vaults(&mut dex, &pool).await;
+
+    // nothing left over to skim
+    let a_before = dex.token_balance(&pool.user_token_a(&payer)).await;
+    dex.process(&[skim_ix(&dex, &pool, &payer)], &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        dex.token_balance(&pool.user_token_a(&payer)).await,
+        a_before
+    );
+}
+
+#[tokio::test]
+async fn skim_sends_a_donation_to_the_recipient() {
+    let mut dex = Dex::start().await;
+    let pool = dex.pool_with_liquidity().await;
+    let recipient = dex.user(1_000_000_000).await;
+    dex.fund(&recipient.pubkey(), &pool, 0, 0).await;
+    let before: Pool = dex.state(&pool.pool).await;
+
+    dex.mint_to(&pool.mint_a, &pool.vault_a, 5_000).await;
+    dex.mint_to(&pool.mint_b, &pool.vault_b, 7_000).await;
+    dex.process(&[skim_ix(&dex, &pool, &recipient.pubkey())], &[])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        dex.token_balance(&pool.user_token_a(&recipient.pubkey()))
+            .await,
+        5_000
+    );
+    assert_eq!(
+        dex.token_balance(&pool.user_token_b(&recipient.pubkey()))
+            .await,
+        7_000
+    );
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!(state.reserve_a, before.reserve_a);
+    assert_eq!(state.reserve_b, before.reserve_b);
+    assert_reserves_match_vaults(&mut dex, &pool).await;
+}
+
+#[tokio::test]
+async fn reserves_track_what_arrives_after_transfer_fees() {
+    let mut dex = Dex::start().await;
+    dex.initialize_config(vec![30]).await;
+    // 1% fee on every transfer of a
+    let mint_a = dex.create_transfer_fee_mint(9, 100, u64::MAX).await;
+    let mint_b = dex.create_mint(6).await;
+    let payer = dex.payer();
+    dex.create_token_account(&payer, &mint_a, 1_000_000_000)
+        .await;
+    dex.create_token_account(&payer, &mint_b, 1_000_000_000)
+        .await;
+    let pool = dex.create_pool(&mint_a, &mint_b, 30).await;
+
+    let deposit = dex.deposit_ix(&payer, &pool, 100_000_000, 100_000_000);
+    dex.process(&[deposit], &[]).await.unwrap();
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!(state.reserve_a, 99_000_000);
+    assert_reserves_match_vaults(&mut dex, &pool).await;
+
+    // both ways, so fees are withheld coming in and going out of the vault
+    let exchange = dex.exchange_ix(&payer, &pool, true, 1_000_000, 1);
+    dex.process(&[exchange], &[]).await.unwrap();
+    assert_reserves_match_vaults(&mut dex, &pool).await;
+    let exchange = dex.exchange_ix(&payer, &pool, false, 1_000_000, 1);
+    dex.process(&[exchange], &[]).await.unwrap();
+    assert_reserves_match_vaults(&mut dex, &pool).await;
+
+    // so there's nothing to sync or skim
+    let before: Pool = dex.state(&pool.pool).await;
+    dex.process(&[sync_ix(&dex, &pool)], &[]).await.unwrap();
+    let a_before = dex.token_balance(&pool.user_token_a(&payer)).await;
+    let b_before = dex.token_balance(&pool.user_token_b(&payer)).await;
+    dex.process(&[skim_ix(&dex, &pool, &payer)], &[])
+        .await
+        .unwrap();
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!(state.reserve_a, before.reserve_a);
+    assert_eq!(state.reserve_b, before.reserve_b);
+    assert_eq!(
+        dex.token_balance(&pool.user_token_a(&payer)).await,
+        a_before
+    );
+    assert_eq!(
+        dex.token_balance(&pool.user_token_b(&payer)).await,
+        b_before
+    );
+}
+
+#[tokio::test]
+async fn skim_rejects_mints_out_of_pool_order() {
+    let mut dex = Dex::start().await;
+    let pool = dex.pool_with_liquidity().await;
+    let payer = dex.payer();
+    let mut skim = skim_ix(&dex, &pool, &payer);
+    skim.accounts.swap(5, 6);
+
+    let result = dex.process(&[skim], &[]).await;
+    assert_instruction_error(
+        result,
+        InstructionError::Custom(TokenError::MintMismatch as u32),
+    );
+}
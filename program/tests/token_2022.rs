@@ -0,0 +1,87 @@
+mod common;
+
+use borsh::BorshDeserialize;
+use chudex::{client::ExchangeResult, curve, error::ChudexError, state::Pool};
+use common::{assert_dex_error, Dex, PoolKeys};
+use solana_program_test::tokio;
+
+/// Pool of a plain 9 decimals mint and a token-2022 6 decimals mint charging 1% on
+/// transfers, with liquidity from the payer.
+async fn transfer_fee_pool(dex: &mut Dex) -> PoolKeys {
+    dex.initialize_config(vec![30]).await;
+    let mint_a = dex.create_mint(9).await;
+    let mint_b = dex.create_transfer_fee_mint(6, 100, u64::MAX).await;
+    let payer = dex.payer();
+    dex.create_token_account(&payer, &mint_a, 1_000_000_000)
+        .await;
+    dex.create_token_account(&payer, &mint_b, 1_000_000_000)
+        .await;
+    let pool = dex.create_pool(&mint_a, &mint_b, 30).await;
+    assert_eq!(pool.token_program_b, spl_token_2022::id());
+    let deposit = dex.deposit_ix(&payer, &pool, 100_000_000, 100_000_000);
+    dex.process(&[deposit], &[]).await.unwrap();
+    pool
+}
+
+/// 1% of `amount`, rounded up like the token program does.
+fn transfer_fee(amount: u64) -> u64 {
+    amount.div_ceil(100)
+}
+
+#[tokio::test]
+async fn swap_pays_out_after_the_transfer_fee() {
+    let mut dex = Dex::start().await;
+    let pool = transfer_fee_pool(&mut dex).await;
+    let payer = dex.payer();
+    let state: Pool = dex.state(&pool.pool).await;
+    let (amount_out, fee) = curve::swap(
+        1_000_000,
+        state.reserve_a,
+        state.reserve_b,
+        state.fee,
+        state.fee_decimals,
+    )
+    .unwrap();
+    let received = amount_out - transfer_fee(amount_out);
+    let b_before = dex.token_balance(&pool.user_token_b(&payer)).await;
+
+    let exchange = dex.exchange_ix(&payer, &pool, true, 1_000_000, received);
+    let return_data = dex.process(&[exchange], &[]).await.unwrap().unwrap();
+
+    assert_eq!(
+        dex.token_balance(&pool.user_token_b(&payer)).await - b_before,
+        received
+    );
+    assert_eq!(
+        ExchangeResult::try_from_slice(&return_data.data).unwrap(),
+        ExchangeResult {
+            amount_in: 1_000_000,
+            amount_out: received,
+            fee,
+        }
+    );
+    // the fee is withheld in the user's account, the vault paid out the full amount
+    let after: Pool = dex.state(&pool.pool).await;
+    assert_eq!(after.reserve_b, state.reserve_b - amount_out);
+}
+
+#[tokio::test]
+async fn minimum_out_applies_after_the_transfer_fee() {
+    let mut dex = Dex::start().await;
+    let pool = transfer_fee_pool(&mut dex).await;
+    let payer = dex.payer();
+    let state: Pool = dex.state(&pool.pool).await;
+    let (amount_out, _) = curve::swap(
+        1_000_000,
+        state.reserve_a,
+        state.reserve_b,
+        state.fee,
+        state.fee_decimals,
+    )
+    .unwrap();
+
+    // what leaves the vault, more than the user gets
+    let exchange = dex.exchange_ix(&payer, &pool, true, 1_000_000, amount_out);
+    let result = dex.process(&[exchange], &[]).await;
+    assert_dex_error(result, ChudexError::ExchangeAmountBelowMinimum);
+}
@@ -0,0 +1,395 @@
+mod common;
+
+use borsh::BorshSerialize;
+use chudex::{
+    curve,
+    error::ChudexError,
+    instruction::ChudexInstruction,
+    state::{ExpiryBucket, LimitOrder, LongTermOrder, Pool, TwammPool},
+    twamm,
+};
+use common::{assert_dex_error, assert_instruction_error, associated_address, Dex, PoolKeys};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+};
+use spl_token_2022::extension::transfer_fee::TransferFee;
+
+fn initialize_twamm_ix(dex: &Dex, pool: &PoolKeys) -> Instruction {
+    Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new(dex.payer(), true),
+            AccountMeta::new(pool.pool, false),
+            AccountMeta::new(pool.twamm, false),
+            AccountMeta::new(pool.twamm_vault_a, false),
+            AccountMeta::new(pool.twamm_vault_b, false),
+            AccountMeta::new_readonly(pool.mint_a, false),
+            AccountMeta::new_readonly(pool.mint_b, false),
+            AccountMeta::new_readonly(pool.token_program_a, false),
+            AccountMeta::new_readonly(pool.token_program_b, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: ChudexInstruction::InitializeTwamm.try_to_vec().unwrap(),
+    }
+}
+
+fn order_address(dex: &Dex, pool: &PoolKeys, owner: &Pubkey, nonce: u64) -> Pubkey {
+    dex.find(&[
+        LongTermOrder::SEED,
+        pool.pool.as_ref(),
+        owner.as_ref(),
+        &nonce.to_le_bytes(),
+    ])
+}
+
+/// The accounts long-term order instructions share after the owner's token accounts.
+fn order_accounts(pool: &PoolKeys, order: Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(pool.pool, false),
+        AccountMeta::new(pool.twamm, false),
+        AccountMeta::new(order, false),
+        AccountMeta::new(pool.twamm_vault_a, false),
+        AccountMeta::new(pool.twamm_vault_b, false),
+        AccountMeta::new(pool.vault_a, false),
+        AccountMeta::new(pool.vault_b, false),
+        AccountMeta::new_readonly(pool.mint_a, false),
+        AccountMeta::new_readonly(pool.mint_b, false),
+        AccountMeta::new_readonly(pool.token_program_a, false),
+        AccountMeta::new_readonly(pool.token_program_b, false),
+    ]
+}
+
+/// PlaceLongTermOrder by `owner` selling a over `num_slots`, starting at `now`.
+fn place_ix(
+    dex: &Dex,
+    pool: &PoolKeys,
+    owner: &Pubkey,
+    amount_in: u64,
+    now: u64,
+    num_slots: u64,
+) -> Instruction {
+    let end_slot =
+        (now + num_slots).div_ceil(TwammPool::ORDER_INTERVAL) * TwammPool::ORDER_INTERVAL;
+    let mut accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(pool.user_token_a(owner), false),
+    ];
+    accounts.extend(order_accounts(pool, order_address(dex, pool, owner, 0)));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.push(AccountMeta::new(
+        dex.find(&[
+            ExpiryBucket::SEED,
+            pool.twamm.as_ref(),
+            &end_slot.to_le_bytes(),
+        ]),
+        false,
+    ));
+    Instruction {
+        program_id: dex.program_id,
+        accounts,
+        data: ChudexInstruction::PlaceLongTermOrder {
+            nonce: 0,
+            amount_in,
+            a_to_b: true,
+            num_slots,
+        }
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+/// Pool with liquidity and a twamm, and an owner with 10 million a.
+async fn twamm_pool(dex: &mut Dex) -> (PoolKeys, Keypair) {
+    let pool = dex.pool_with_liquidity().await;
+    with_twamm(dex, pool).await
+}
+
+/// Initializes `pool`'s twamm and funds an owner with 10 million a.
+async fn with_twamm(dex: &mut Dex, mut pool: PoolKeys) -> (PoolKeys, Keypair) {
+    dex.process(&[initialize_twamm_ix(dex, &pool)], &[])
+        .await
+        .unwrap();
+    pool.has_twamm = true;
+    let owner = dex.user(1_000_000_000).await;
+    dex.fund(&owner.pubkey(), &pool, 10_000_000, 0).await;
+    (pool, owner)
+}
+
+/// Pool reserves once its long-term orders have run up to `now`.
+async fn settled_reserves(dex: &mut Dex, pool: &PoolKeys, now: u64) -> (u64, u64) {
+    let state: Pool = dex.state(&pool.pool).await;
+    let mut twamm_state: TwammPool = dex.state(&pool.twamm).await;
+    let (to_pool_a, to_pool_b) = twamm::advance(
+        &mut twamm_state,
+        now,
+        state.reserve_a,
+        state.reserve_b,
+        state.fee,
+        state.fee_decimals,
+        &TransferFee::default(),
+        &TransferFee::default(),
+    )
+    .unwrap();
+    (
+        (state.reserve_a as i128 + to_pool_a) as u64,
+        (state.reserve_b as i128 + to_pool_b) as u64,
+    )
+}
+
+#[tokio::test]
+async fn limit_fills_trade_after_long_term_orders_settle() {
+    let mut dex = Dex::start().await;
+    let (pool, owner) = twamm_pool(&mut dex).await;
+    let keeper = Keypair::new();
+    dex.fund(&keeper.pubkey(), &pool, 0, 0).await;
+    let start = dex.clock().await.slot;
+
+    // a long-term order selling a to the next interval boundary, and a limit order
+    // selling a that's crossed whatever the long-term order does to the price
+    let limit_order = dex.find(&[
+        LimitOrder::SEED,
+        pool.pool.as_ref(),
+        owner.pubkey().as_ref(),
+        &0u64.to_le_bytes(),
+    ]);
+    let limit_escrow = associated_address(&limit_order, &pool.mint_a, &spl_token::id());
+    let place_limit = Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new(owner.pubkey(), true),
+            AccountMeta::new(pool.user_token_a(&owner.pubkey()), false),
+            AccountMeta::new_readonly(pool.user_token_b(&owner.pubkey()), false),
+            AccountMeta::new_readonly(pool.pool, false),
+            AccountMeta::new(limit_order, false),
+            AccountMeta::new(limit_escrow, false),
+            AccountMeta::new_readonly(pool.mint_a, false),
+            AccountMeta::new_readonly(pool.mint_b, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: ChudexInstruction::PlaceLimitOrder {
+            nonce: 0,
+            amount_in: 1_000_000,
+            min_amount_out: 1,
+        }
+        .try_to_vec()
+        .unwrap(),
+    };
+    dex.process(
+        &[
+            place_ix(&dex, &pool, &owner.pubkey(), 9_000_000, start, 1),
+            place_limit,
+        ],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+
+    // the fill settles the long-term order first and trades against what that leaves
+    dex.advance_slots(3_000).await;
+    let now = dex.clock().await.slot;
+    let (reserve_a, reserve_b) = settled_reserves(&mut dex, &pool, now).await;
+    let state: Pool = dex.state(&pool.pool).await;
+    assert!(reserve_a > state.reserve_a && reserve_b < state.reserve_b);
+    let (amount_out, _) = curve::swap(
+        1_000_000,
+        reserve_a,
+        reserve_b,
+        state.fee,
+        state.fee_decimals,
+    )
+    .unwrap();
+    let keeper_fee = curve::fee_amount(
+        amount_out,
+        LimitOrder::KEEPER_FEE,
+        LimitOrder::KEEPER_FEE_DECIMALS,
+    )
+    .unwrap();
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(keeper.pubkey(), true),
+        AccountMeta::new(pool.user_token_b(&keeper.pubkey()), false),
+        AccountMeta::new(pool.pool, false),
+        AccountMeta::new(pool.vault_a, false),
+        AccountMeta::new(pool.vault_b, false),
+        AccountMeta::new_readonly(pool.mint_a, false),
+        AccountMeta::new_readonly(pool.mint_b, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ];
+    accounts.extend(pool.twamm_accounts());
+    accounts.extend([
+        AccountMeta::new(limit_order, false),
+        AccountMeta::new(limit_escrow, false),
+        AccountMeta::new(pool.user_token_b(&owner.pubkey()), false),
+        AccountMeta::new(owner.pubkey(), false),
+    ]);
+    let fill = Instruction {
+        program_id: dex.program_id,
+        accounts,
+        data: ChudexInstruction::FillOrders.try_to_vec().unwrap(),
+    };
+    dex.process(&[fill], &[&keeper]).await.unwrap();
+
+    assert_eq!(
+        dex.token_balance(&pool.user_token_b(&owner.pubkey())).await,
+        amount_out - keeper_fee
+    );
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!(state.reserve_a, reserve_a + 1_000_000);
+    assert_eq!(state.reserve_b, reserve_b - amount_out);
+    let twamm_state: TwammPool = dex.state(&pool.twamm).await;
+    assert_eq!(twamm_state.last_slot, now);
+
+    // the next touch settles on from the reserves the fill left
+    dex.advance_slots(3_000).await;
+    let now = dex.clock().await.slot;
+    let (reserve_a, reserve_b) = settled_reserves(&mut dex, &pool, now).await;
+    let mut accounts = vec![
+        AccountMeta::new(pool.pool, false),
+        AccountMeta::new(pool.vault_a, false),
+        AccountMeta::new(pool.vault_b, false),
+        AccountMeta::new_readonly(pool.mint_a, false),
+        AccountMeta::new_readonly(pool.mint_b, false),
+        AccountMeta::new_readonly(pool.token_program_a, false),
+        AccountMeta::new_readonly(pool.token_program_b, false),
+    ];
+    accounts.extend(pool.twamm_accounts());
+    let sync = Instruction {
+        program_id: dex.program_id,
+        accounts,
+        data: ChudexInstruction::Sync.try_to_vec().unwrap(),
+    };
+    dex.process(&[sync], &[]).await.unwrap();
+    let state: Pool = dex.state(&pool.pool).await;
+    assert_eq!((state.reserve_a, state.reserve_b), (reserve_a, reserve_b));
+}
+
+/// Places an order selling 9 million a until the next interval boundary and withdraws
+/// it once ended.
+async fn sell_to_the_next_boundary(dex: &mut Dex, pool: &PoolKeys, owner: &Keypair) {
+    let start = dex.clock().await.slot;
+    dex.process(
+        &[place_ix(dex, pool, &owner.pubkey(), 9_000_000, start, 1)],
+        &[owner],
+    )
+    .await
+    .unwrap();
+    let order = order_address(dex, pool, &owner.pubkey(), 0);
+    let end_slot = dex.state::<LongTermOrder>(&order).await.end_slot;
+
+    dex.advance_slots(end_slot - start).await;
+    let mut accounts = vec![
+        AccountMeta::new(owner.pubkey(), true),
+        AccountMeta::new(pool.user_token_a(&owner.pubkey()), false),
+        AccountMeta::new(pool.user_token_b(&owner.pubkey()), false),
+    ];
+    accounts.extend(order_accounts(pool, order));
+    let withdraw = Instruction {
+        program_id: dex.program_id,
+        accounts,
+        data: ChudexInstruction::WithdrawLongTermOrder
+            .try_to_vec()
+            .unwrap(),
+    };
+    dex.process(&[withdraw], &[owner]).await.unwrap();
+}
+
+#[tokio::test]
+async fn ended_order_is_withdrawn_and_closed() {
+    let mut dex = Dex::start().await;
+    let (pool, owner) = twamm_pool(&mut dex).await;
+    let before: Pool = dex.state(&pool.pool).await;
+    sell_to_the_next_boundary(&mut dex, &pool, &owner).await;
+
+    // everything was sold into the pool, bar rounding, and the proceeds paid out
+    let state: Pool = dex.state(&pool.pool).await;
+    let sold = state.reserve_a - before.reserve_a;
+    assert!(sold <= 9_000_000 && sold > 9_000_000 - 10);
+    let proceeds = dex.token_balance(&pool.user_token_b(&owner.pubkey())).await;
+    assert!(proceeds > 0);
+    assert!(proceeds <= before.reserve_b - state.reserve_b);
+    let order = order_address(&dex, &pool, &owner.pubkey(), 0);
+    assert!(dex.account(&order).await.is_none());
+}
+
+#[tokio::test]
+async fn transfer_fees_come_out_of_the_orders() {
+    let mut dex = Dex::start().await;
+    dex.initialize_config(vec![30]).await;
+    let mint_a = dex.create_transfer_fee_mint(9, 100, u64::MAX).await;
+    let mint_b = dex.create_transfer_fee_mint(6, 100, u64::MAX).await;
+    let payer = dex.payer();
+    dex.create_token_account(&payer, &mint_a, 1_000_000_000)
+        .await;
+    dex.create_token_account(&payer, &mint_b, 1_000_000_000)
+        .await;
+    let pool = dex.create_pool(&mint_a, &mint_b, 30).await;
+    let deposit = dex.deposit_ix(&payer, &pool, 100_000_000, 100_000_000);
+    dex.process(&[deposit], &[]).await.unwrap();
+    let (pool, owner) = with_twamm(&mut dex, pool).await;
+    let before: Pool = dex.state(&pool.pool).await;
+
+    // the twamm vault pays out every proceed it credited
+    sell_to_the_next_boundary(&mut dex, &pool, &owner).await;
+    assert!(dex.token_balance(&pool.user_token_b(&owner.pubkey())).await > 0);
+
+    // and the pool was paid for what it swapped out, vaults and reserves in step
+    let state: Pool = dex.state(&pool.pool).await;
+    assert!(
+        state.reserve_a as u128 * state.reserve_b as u128
+            >= before.reserve_a as u128 * before.reserve_b as u128
+    );
+    assert_eq!(dex.token_balance(&pool.vault_a).await, state.reserve_a);
+    assert_eq!(dex.token_balance(&pool.vault_b).await, state.reserve_b);
+}
+
+#[tokio::test]
+async fn orders_cant_outlast_the_bucket_ring() {
+    let mut dex = Dex::start().await;
+    let (pool, owner) = twamm_pool(&mut dex).await;
+    let start = dex.clock().await.slot;
+    let num_slots = TwammPool::MAX_BUCKETS as u64 * TwammPool::ORDER_INTERVAL;
+
+    let result = dex
+        .process(
+            &[place_ix(
+                &dex,
+                &pool,
+                &owner.pubkey(),
+                9_000_000,
+                start,
+                num_slots,
+            )],
+            &[&owner],
+        )
+        .await;
+    assert_dex_error(result, ChudexError::InvalidInstructionInput);
+}
+
+#[tokio::test]
+async fn pools_with_a_twamm_always_settle() {
+    let mut dex = Dex::start().await;
+    let (pool, _) = twamm_pool(&mut dex).await;
+    let payer = dex.payer();
+
+    // leaving out the twamm accounts would swap ahead of the long-term orders
+    let without_twamm = PoolKeys {
+        has_twamm: false,
+        ..pool.clone()
+    };
+    let exchange = dex.exchange_ix(&payer, &without_twamm, true, 1_000_000, 1);
+    let result = dex.process(&[exchange], &[]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+
+    let exchange = dex.exchange_ix(&payer, &pool, true, 1_000_000, 1);
+    dex.process(&[exchange], &[]).await.unwrap();
+}
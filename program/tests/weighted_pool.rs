@@ -0,0 +1,176 @@
+mod common;
+
+use borsh::BorshSerialize;
+use chudex::{curve, error::ChudexError, instruction::ChudexInstruction, state::WeightedPool};
+use common::{assert_dex_error, associated_address, Dex};
+use solana_program_test::tokio;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+/// Addresses of a weighted pool of `mints`, in the order given.
+struct WeightedKeys {
+    pool: Pubkey,
+    pool_mint: Pubkey,
+    mints: Vec<Pubkey>,
+    vaults: Vec<Pubkey>,
+}
+
+impl WeightedKeys {
+    fn new(dex: &Dex, mints: &[Pubkey]) -> Self {
+        let pool = dex.find(&WeightedPool::seeds(mints));
+        Self {
+            pool,
+            pool_mint: dex.find(&[b"chudex_pool_mint", pool.as_ref()]),
+            mints: mints.to_vec(),
+            vaults: mints
+                .iter()
+                .map(|mint| associated_address(&pool, mint, &spl_token::id()))
+                .collect(),
+        }
+    }
+}
+
+fn initialize_ix(dex: &Dex, pool: &WeightedKeys, weights: Vec<u64>) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(dex.payer(), true),
+        AccountMeta::new(pool.pool, false),
+        AccountMeta::new(pool.pool_mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+    ];
+    for (mint, vault) in pool.mints.iter().zip(&pool.vaults) {
+        accounts.push(AccountMeta::new_readonly(*mint, false));
+        accounts.push(AccountMeta::new(*vault, false));
+        accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    }
+    Instruction {
+        program_id: dex.program_id,
+        accounts,
+        data: ChudexInstruction::InitializeWeightedPool {
+            weights,
+            fee: 30,
+            fee_decimals: 4,
+        }
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+/// Three mints sorted by address, with a billion of each in the payer's token accounts.
+async fn sorted_mints(dex: &mut Dex) -> Vec<Pubkey> {
+    let payer = dex.payer();
+    let mut mints = vec![];
+    for _ in 0..3 {
+        let mint = dex.create_mint(6).await;
+        dex.create_token_account(&payer, &mint, 1_000_000_000).await;
+        mints.push(mint);
+    }
+    mints.sort();
+    mints
+}
+
+#[tokio::test]
+async fn deposits_and_swaps_by_weight() {
+    let mut dex = Dex::start().await;
+    let mints = sorted_mints(&mut dex).await;
+    let pool = WeightedKeys::new(&dex, &mints);
+    let payer = dex.payer();
+    dex.process(&[initialize_ix(&dex, &pool, vec![50, 25, 25])], &[])
+        .await
+        .unwrap();
+
+    // the first deposit sets the balances
+    let balances = [100_000_000, 50_000_000, 50_000_000];
+    let mut accounts = vec![
+        AccountMeta::new(payer, true),
+        AccountMeta::new(
+            associated_address(&payer, &pool.pool_mint, &spl_token::id()),
+            false,
+        ),
+        AccountMeta::new_readonly(pool.pool, false),
+        AccountMeta::new(pool.pool_mint, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+    ];
+    for (mint, vault) in mints.iter().zip(&pool.vaults) {
+        accounts.push(AccountMeta::new(
+            associated_address(&payer, mint, &spl_token::id()),
+            false,
+        ));
+        accounts.push(AccountMeta::new(*vault, false));
+        accounts.push(AccountMeta::new_readonly(*mint, false));
+        accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    }
+    let deposit = Instruction {
+        program_id: dex.program_id,
+        accounts,
+        data: ChudexInstruction::WeightedDeposit {
+            pool_token_amount: 1_000_000,
+            max_token_amounts: balances.to_vec(),
+        }
+        .try_to_vec()
+        .unwrap(),
+    };
+    dex.process(&[deposit], &[]).await.unwrap();
+    for (vault, balance) in pool.vaults.iter().zip(balances) {
+        assert_eq!(dex.token_balance(vault).await, balance);
+    }
+
+    // the heavier token in for a lighter one
+    let (amount_out, _) =
+        curve::weighted_swap(1_000_000, balances[0], 50, balances[1], 25, 30, 4).unwrap();
+    let user_src = associated_address(&payer, &mints[0], &spl_token::id());
+    let user_dst = associated_address(&payer, &mints[1], &spl_token::id());
+    let dst_before = dex.token_balance(&user_dst).await;
+    let exchange = Instruction {
+        program_id: dex.program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(payer, true),
+            AccountMeta::new(user_src, false),
+            AccountMeta::new(user_dst, false),
+            AccountMeta::new_readonly(pool.pool, false),
+            AccountMeta::new(pool.vaults[0], false),
+            AccountMeta::new(pool.vaults[1], false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(mints[0], false),
+            AccountMeta::new_readonly(mints[1], false),
+        ],
+        data: ChudexInstruction::WeightedExchange {
+            amount_in: 1_000_000,
+            min_amount_out: amount_out,
+        }
+        .try_to_vec()
+        .unwrap(),
+    };
+    dex.process(&[exchange], &[]).await.unwrap();
+    assert_eq!(dex.token_balance(&user_dst).await - dst_before, amount_out);
+    assert_eq!(
+        dex.token_balance(&pool.vaults[0]).await,
+        balances[0] + 1_000_000
+    );
+    assert_eq!(
+        dex.token_balance(&pool.vaults[1]).await,
+        balances[1] - amount_out
+    );
+}
+
+#[tokio::test]
+async fn mints_have_to_be_sorted() {
+    let mut dex = Dex::start().await;
+    let mut mints = sorted_mints(&mut dex).await;
+    mints.swap(0, 1);
+    let pool = WeightedKeys::new(&dex, &mints);
+
+    let result = dex
+        .process(&[initialize_ix(&dex, &pool, vec![50, 25, 25])], &[])
+        .await;
+    assert_dex_error(result, ChudexError::InvalidInstructionInput);
+}
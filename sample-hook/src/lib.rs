@@ -0,0 +1,89 @@
+//! Sample pool hook program. Rejects exchanges taking more than a tenth of the out
+//! reserve and logs every call.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use borsh::BorshDeserialize;
+
+use chudex::{
+    hook::{HookOperation, HookPayload, HookStage, AUTHORITY_SEED},
+    utils::assert_msg,
+};
+
+/// Largest share of the out reserve one exchange can take, in basis points.
+pub const MAX_EXCHANGE_BPS: u64 = 1_000;
+
+#[cfg(not(feature = "no-entrypoint"))]
+use solana_program::entrypoint;
+#[cfg(not(feature = "no-entrypoint"))]
+entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // FETCH ACCOUNTS
+    let accounts_iter = &mut accounts.iter();
+
+    let hook_authority_ai = next_account_info(accounts_iter)?;
+    let pool_ai = next_account_info(accounts_iter)?;
+
+    // deserialization
+    let payload = HookPayload::try_from_slice(instruction_data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    // ACCOUNT VALIDATION
+
+    // the call comes from the program owning the pool, a real hook would also pin that
+    // program's id
+    let (hook_authority_key, _) =
+        Pubkey::find_program_address(&[AUTHORITY_SEED, pool_ai.key.as_ref()], pool_ai.owner);
+    assert_msg(
+        hook_authority_ai.is_signer && *hook_authority_ai.key == hook_authority_key,
+        ProgramError::MissingRequiredSignature,
+        "Hook authority not signer",
+    )?;
+    assert_msg(
+        payload.pool == *pool_ai.key,
+        ProgramError::InvalidArgument,
+        "Payload pool doesn't match pool",
+    )?;
+
+    // LOGIC
+
+    msg!(
+        "{:?} {:?} by {} - a: {} b: {} pool tokens: {} reserves: {} {}",
+        payload.stage,
+        payload.operation,
+        payload.user,
+        payload.token_a_amount,
+        payload.token_b_amount,
+        payload.pool_token_amount,
+        payload.reserve_a,
+        payload.reserve_b
+    );
+
+    if let (HookStage::Before, HookOperation::Exchange { a_to_b }) =
+        (payload.stage, payload.operation)
+    {
+        let (amount_out, reserve_out) = if a_to_b {
+            (payload.token_b_amount, payload.reserve_b)
+        } else {
+            (payload.token_a_amount, payload.reserve_a)
+        };
+        assert_msg(
+            amount_out as u128 * 10_000 <= reserve_out as u128 * MAX_EXCHANGE_BPS as u128,
+            ProgramError::InvalidArgument,
+            "Exchange too large for the hook",
+        )?;
+    }
+
+    Ok(())
+}
@@ -20,8 +20,9 @@ const { mintAuthority, user } = require("./keys.js");
 
 const mint1Decimals = 9;
 const mint2Decimals = 6;
-const fee = 5;
-const feeDecimals = 3;
+// basis points, has to be one of the config's fee tiers
+const feeTier = 50;
+const feeTiers = [5, 30, 50, 100];
 const poolMintDecimals = 9;
 
 // const programId = new PublicKey("G4QQ465gehN97upZxMh1Z4GWi347nhi9cuoxVRDdUTZf");
@@ -139,6 +140,8 @@ const initTokens = async () => {
   return {
     mint1,
     mint2,
+    firstMintSeed,
+    secondMintSeed,
     userToken1Account,
     userToken2Account,
     poolPubkey,
@@ -199,10 +202,119 @@ const loadTokens = () => {
   }
 }
 
+// create the program config with the allowed fee tiers, once per program
+const initConfig = async () => {
+  const [configPubkey] = await PublicKey.findProgramAddress(
+    [Buffer.from("chudex_config")],
+    programId
+  );
+  if (await connection.getAccountInfo(configPubkey)) {
+    return configPubkey;
+  }
+
+  console.log("Initializing config...");
+
+  // the user deployed the program, only its upgrade authority can become admin
+  const [programDataPubkey] = await PublicKey.findProgramAddress(
+    [programId.toBuffer()],
+    new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+  );
+
+  const initConfigIdx = Buffer.from(new Uint8Array([28]));
+  const feeTiersBuffer = Buffer.concat([
+    Buffer.from(new Uint8Array((new BN(feeTiers.length)).toArray("le", 4))),
+    ...feeTiers.map((tier) => Buffer.from(new Uint8Array((new BN(tier)).toArray("le", 2)))),
+  ]);
+
+  let initConfigIx = new TransactionInstruction({
+    keys: [
+      {
+        pubkey: user.publicKey,
+        isSigner: true,
+        isWritable: true,
+      },
+      {
+        pubkey: configPubkey,
+        isSigner: false,
+        isWritable: true,
+      },
+      {
+        pubkey: SystemProgram.programId,
+        isSigner: false,
+        isWritable: false,
+      },
+      {
+        pubkey: programDataPubkey,
+        isSigner: false,
+        isWritable: false,
+      },
+    ],
+    programId: programId,
+    data: Buffer.concat([
+      initConfigIdx,
+      feeTiersBuffer,
+    ]),
+  });
+
+  let initConfigTx = new Transaction();
+  initConfigTx.add(initConfigIx);
+
+  let initConfigTxid = await sendAndConfirmTransaction(
+    connection,
+    initConfigTx,
+    [user],
+    {
+      skipPreflight: true,
+      preflightCommitment: "confirmed",
+      confirmation: "confirmed",
+    }
+  );
+  console.log(`https://explorer.solana.com/tx/${initConfigTxid}?cluster=devnet`);
+
+  return configPubkey;
+}
+
+// pool registry accounts for a new pool: the factory and the page the pool lands on,
+// then the index and index page of each mint
+const registryPage = async (seeds, countAccount, countOffset) => {
+  const info = await connection.getAccountInfo(countAccount);
+  const count = info ? new BN(info.data.slice(countOffset, countOffset + 8), "le") : new BN(0);
+  const page = count.div(new BN(100));
+  const [pagePubkey] = await PublicKey.findProgramAddress(
+    [...seeds, page.toArrayLike(Buffer, "le", 8)],
+    programId
+  );
+  return pagePubkey;
+}
+
+const registryAccounts = async (mintAPubkey, mintBPubkey) => {
+  const [factoryPubkey] = await PublicKey.findProgramAddress(
+    [Buffer.from("chudex_factory")],
+    programId
+  );
+  const accounts = [
+    factoryPubkey,
+    await registryPage([Buffer.from("chudex_registry")], factoryPubkey, 0),
+  ];
+  for (const mintPubkey of [mintAPubkey, mintBPubkey]) {
+    const [mintIndexPubkey] = await PublicKey.findProgramAddress(
+      [Buffer.from("chudex_mint_index"), mintPubkey.toBuffer()],
+      programId
+    );
+    accounts.push(
+      mintIndexPubkey,
+      await registryPage([Buffer.from("chudex_registry"), mintPubkey.toBuffer()], mintIndexPubkey, 32),
+    );
+  }
+  return accounts;
+}
+
 // init pool
 const initPool = async ({
     mint1,
     mint2,
+    firstMintSeed,
+    secondMintSeed,
     poolPubkey,
     boothVault1Pubkey,
     boothVault2Pubkey,
@@ -212,9 +324,15 @@ const initPool = async ({
   // Initialize exchange booth
   console.log("Initializing pool...");
 
+  const configPubkey = await initConfig();
+  const registryPubkeys = await registryAccounts(firstMintSeed, secondMintSeed);
+  const [lpMetadataPubkey] = await PublicKey.findProgramAddress(
+    [Buffer.from("chudex_lp_metadata"), poolMint.toBuffer()],
+    programId
+  );
+
   const initIdx = Buffer.from(new Uint8Array([0]));
-  const feeBuffer = Buffer.from(new Uint8Array((new BN(fee)).toArray("le", 8)));
-  const feeDecimalsBuffer = Buffer.from(new Uint8Array((new BN(feeDecimals)).toArray("le", 8)));
+  const feeTierBuffer = Buffer.from(new Uint8Array((new BN(feeTier)).toArray("le", 2)));
 
   let initIx = new TransactionInstruction({
     keys: [
@@ -274,12 +392,39 @@ const initPool = async ({
         isSigner: false,
         isWritable: false,
       },
+      {
+        pubkey: TOKEN_PROGRAM_ID,
+        isSigner: false,
+        isWritable: false,
+      },
+      {
+        pubkey: TOKEN_PROGRAM_ID,
+        isSigner: false,
+        isWritable: false,
+      },
+      {
+        pubkey: configPubkey,
+        isSigner: false,
+        isWritable: false,
+      },
+      ...registryPubkeys.map((pubkey) => ({
+        pubkey,
+        isSigner: false,
+        isWritable: true,
+      })),
+      {
+        pubkey: lpMetadataPubkey,
+        isSigner: false,
+        isWritable: true,
+      },
     ],
     programId: programId,
     data: Buffer.concat([
       initIdx,
-      feeBuffer,
-      feeDecimalsBuffer,
+      feeTierBuffer,
+      Buffer.from(new Uint8Array([0])), // no dynamic fee
+      Buffer.from(new Uint8Array([0])), // no allowlist
+      Buffer.from(new Uint8Array([0])), // no hook program
     ]),
   });
 
@@ -321,6 +466,7 @@ const deposit = async ({
   const depositIdx = Buffer.from(new Uint8Array([1]));
   const tokenAAmountBuffer = Buffer.from(new Uint8Array((new BN(tokenAAmount)).toArray("le", 8)));
   const maxTokenBAmountBuffer = Buffer.from(new Uint8Array((new BN(maxTokenBAmount)).toArray("le", 8)));
+  const nativeSolBuffer = Buffer.from(new Uint8Array([0]));
 
   let depositIx = new TransactionInstruction({
     keys: [
@@ -347,7 +493,7 @@ const deposit = async ({
       {
         pubkey: poolPubkey,
         isSigner: false,
-        isWritable: false,
+        isWritable: true,
       },
       {
         pubkey: boothVault1Pubkey,
@@ -385,12 +531,33 @@ const deposit = async ({
         isSigner: false,
         isWritable: false,
       },
+      {
+        pubkey: mint1Pubkey,
+        isSigner: false,
+        isWritable: false,
+      },
+      {
+        pubkey: mint2Pubkey,
+        isSigner: false,
+        isWritable: false,
+      },
+      {
+        pubkey: TOKEN_PROGRAM_ID,
+        isSigner: false,
+        isWritable: false,
+      },
+      {
+        pubkey: TOKEN_PROGRAM_ID,
+        isSigner: false,
+        isWritable: false,
+      },
     ],
     programId: programId,
     data: Buffer.concat([
       depositIdx,
       tokenAAmountBuffer,
       maxTokenBAmountBuffer,
+      nativeSolBuffer,
     ]),
   });
 